@@ -0,0 +1,15 @@
+-- Per-group ELO parameters. Groups without a row use the defaults,
+-- which match the constants in services/elo.rs.
+CREATE TABLE group_settings (
+    group_id uuid PRIMARY KEY REFERENCES groups(id) ON DELETE CASCADE ON UPDATE CASCADE,
+    k_factor DOUBLE PRECISION NOT NULL DEFAULT 100,
+    total_race_size INTEGER NOT NULL DEFAULT 24,
+    position_score_exponent DOUBLE PRECISION NOT NULL DEFAULT 1.5,
+    min_cpu_elo INTEGER NOT NULL DEFAULT 800,
+    cpu_elo_spread INTEGER NOT NULL DEFAULT 50,
+    cpu_elo_decrease INTEGER NOT NULL DEFAULT 25,
+    min_average_elo_for_cpu INTEGER NOT NULL DEFAULT 900,
+    max_average_elo_for_cpu INTEGER NOT NULL DEFAULT 1400,
+    teammate_contribution_ratio DOUBLE PRECISION NOT NULL DEFAULT 0.2,
+    updated_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW()
+);
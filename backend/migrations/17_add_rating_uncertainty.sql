@@ -0,0 +1,26 @@
+-- Rating system selection and Glicko-2 rating uncertainty.
+-- Deviation and volatility defaults match services/rating_system.rs; under ELO
+-- they never change.
+CREATE TYPE rating_system_kind AS ENUM (
+    'elo',
+    'glicko2'
+);
+
+ALTER TABLE group_settings
+    ADD COLUMN rating_system rating_system_kind NOT NULL DEFAULT 'elo';
+
+ALTER TABLE players
+    ADD COLUMN rating_deviation DOUBLE PRECISION NOT NULL DEFAULT 350,
+    ADD COLUMN rating_volatility DOUBLE PRECISION NOT NULL DEFAULT 0.06;
+
+ALTER TABLE player_tournament_scores
+    ADD COLUMN rating_deviation DOUBLE PRECISION NOT NULL DEFAULT 350,
+    ADD COLUMN rating_volatility DOUBLE PRECISION NOT NULL DEFAULT 0.06;
+
+-- Uncertainty going into each race, so amending or undoing a race can restore
+-- it. NULL for races recorded before this migration (treated as the defaults).
+ALTER TABLE player_race_scores
+    ADD COLUMN all_time_rating_deviation_before DOUBLE PRECISION,
+    ADD COLUMN all_time_rating_volatility_before DOUBLE PRECISION,
+    ADD COLUMN tournament_rating_deviation_before DOUBLE PRECISION,
+    ADD COLUMN tournament_rating_volatility_before DOUBLE PRECISION;
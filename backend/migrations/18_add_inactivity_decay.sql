@@ -0,0 +1,23 @@
+-- Provisional status and inactivity decay settings. Defaults match the
+-- constants in services/elo.rs; decay is off (0 per week) until a group
+-- opts in, so inactive players are only flagged.
+ALTER TABLE group_settings
+    ADD COLUMN provisional_race_count INTEGER NOT NULL DEFAULT 10,
+    ADD COLUMN inactivity_weeks INTEGER NOT NULL DEFAULT 8,
+    ADD COLUMN inactivity_decay_per_week INTEGER NOT NULL DEFAULT 0;
+
+-- All-time rating reductions applied to inactive players
+CREATE TABLE player_rating_decays (
+    id uuid PRIMARY KEY DEFAULT gen_random_uuid(),
+    group_id uuid NOT NULL,
+    player_id uuid NOT NULL,
+    weeks INTEGER NOT NULL,
+    elo_change INTEGER NOT NULL,
+    elo_after INTEGER NOT NULL,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+    FOREIGN KEY (group_id) REFERENCES groups (id) ON DELETE CASCADE ON UPDATE CASCADE,
+    FOREIGN KEY (player_id) REFERENCES players (id) ON DELETE CASCADE ON UPDATE CASCADE
+);
+
+CREATE INDEX idx_player_rating_decays_player ON player_rating_decays (player_id, created_at);
+CREATE INDEX idx_player_rating_decays_group ON player_rating_decays (group_id, created_at);
@@ -0,0 +1,29 @@
+-- Points awarded per finishing position for team scores. Built-in tables have
+-- no group and are shared by every group; groups can add their own. Tables
+-- are never edited, so a match's team scores can always be recomputed from
+-- the table it references.
+CREATE TABLE points_tables (
+    id uuid PRIMARY KEY DEFAULT gen_random_uuid(),
+    group_id uuid REFERENCES groups(id) ON DELETE CASCADE ON UPDATE CASCADE,
+    name TEXT NOT NULL,
+    points INTEGER[] NOT NULL,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX idx_points_tables_group_id ON points_tables(group_id);
+
+-- IDs match the constants in models/points_table.rs
+INSERT INTO points_tables (id, group_id, name, points) VALUES
+    ('00000000-0000-0000-0000-000000000001', NULL, 'Mario Kart 8 (12 players)',
+     ARRAY[15, 12, 10, 9, 8, 7, 6, 5, 4, 3, 2, 1]),
+    ('00000000-0000-0000-0000-000000000002', NULL, 'Mario Kart World (24 players)',
+     ARRAY[15, 12, 10, 9, 9, 8, 8, 7, 7, 6, 6, 6, 5, 5, 5, 4, 4, 4, 3, 3, 3, 2, 2, 1]);
+
+-- Tournaments without a table use the Mario Kart 8 table
+ALTER TABLE tournaments
+    ADD COLUMN points_table_id uuid REFERENCES points_tables(id) ON DELETE RESTRICT;
+
+-- Existing matches were scored with the Mario Kart 8 table
+ALTER TABLE matches
+    ADD COLUMN points_table_id uuid NOT NULL DEFAULT '00000000-0000-0000-0000-000000000001'
+        REFERENCES points_tables(id) ON DELETE RESTRICT;
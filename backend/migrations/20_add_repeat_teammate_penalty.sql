@@ -0,0 +1,5 @@
+-- ELO charged per earlier pairing of teammates in the tournament when teams
+-- are allocated. Default matches the constant in services/elo.rs; 0 balances
+-- on ELO alone.
+ALTER TABLE group_settings
+    ADD COLUMN repeat_teammate_penalty INTEGER NOT NULL DEFAULT 50;
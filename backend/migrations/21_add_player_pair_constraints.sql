@@ -0,0 +1,18 @@
+-- Pairs of players who must (together) or must not (apart) share a team in
+-- every match of their group. Each pair is stored once, lowest player ID
+-- first, so a pair cannot be both together and apart.
+CREATE TYPE pair_constraint_kind AS ENUM (
+    'together',
+    'apart'
+);
+
+CREATE TABLE player_pair_constraints (
+    id uuid PRIMARY KEY DEFAULT gen_random_uuid(),
+    group_id uuid NOT NULL REFERENCES groups(id) ON DELETE CASCADE ON UPDATE CASCADE,
+    player_a_id uuid NOT NULL REFERENCES players(id) ON DELETE CASCADE ON UPDATE CASCADE,
+    player_b_id uuid NOT NULL REFERENCES players(id) ON DELETE CASCADE ON UPDATE CASCADE,
+    kind pair_constraint_kind NOT NULL,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+    CHECK (player_a_id < player_b_id),
+    UNIQUE (group_id, player_a_id, player_b_id)
+);
@@ -0,0 +1,16 @@
+-- Free-for-all matches have no teams: every player races every race and
+-- scores individual points, so their round players have no team.
+CREATE TYPE match_mode AS ENUM (
+    'teams',
+    'free_for_all'
+);
+
+ALTER TABLE matches
+    ADD COLUMN mode match_mode NOT NULL DEFAULT 'teams';
+
+ALTER TABLE round_players
+    ALTER COLUMN team_id DROP NOT NULL;
+
+-- Points scored over a completed match, set for free-for-all matches
+ALTER TABLE player_match_scores
+    ADD COLUMN points INTEGER;
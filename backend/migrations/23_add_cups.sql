@@ -0,0 +1,67 @@
+-- Grand Prix cups: four tracks raced in order. Grand Prix matches race whole
+-- cups, and each of their rounds records the cup it belongs to.
+CREATE TABLE cups (
+    id uuid PRIMARY KEY DEFAULT gen_random_uuid(),
+    name VARCHAR(255) NOT NULL UNIQUE
+);
+
+CREATE TABLE cup_tracks (
+    cup_id uuid NOT NULL REFERENCES cups(id) ON DELETE CASCADE ON UPDATE CASCADE,
+    position INTEGER NOT NULL CHECK (position BETWEEN 1 AND 4),
+    track_id uuid NOT NULL REFERENCES tracks(id) ON DELETE CASCADE ON UPDATE CASCADE,
+    PRIMARY KEY (cup_id, position)
+);
+
+ALTER TABLE rounds ADD COLUMN cup_id uuid REFERENCES cups(id);
+
+CREATE INDEX idx_rounds_cup_id ON rounds(cup_id);
+
+-- Mario Kart World cups; a few tracks appear in more than one cup
+INSERT INTO cups (name) VALUES
+    ('Mushroom Cup'),
+    ('Flower Cup'),
+    ('Star Cup'),
+    ('Shell Cup'),
+    ('Banana Cup'),
+    ('Leaf Cup'),
+    ('Lightning Cup'),
+    ('Special Cup');
+
+INSERT INTO cup_tracks (cup_id, position, track_id)
+SELECT c.id, ct.position, t.id
+FROM (VALUES
+    ('Mushroom Cup', 1, 'Mario Bros. Circuit'),
+    ('Mushroom Cup', 2, 'Crown City'),
+    ('Mushroom Cup', 3, 'Whistlestop Summit'),
+    ('Mushroom Cup', 4, 'DK Spaceport'),
+    ('Flower Cup', 1, 'Desert Hills'),
+    ('Flower Cup', 2, 'Shy Guy Bazaar'),
+    ('Flower Cup', 3, 'Wario Stadium'),
+    ('Flower Cup', 4, 'Airship Fortress'),
+    ('Star Cup', 1, 'DK Pass'),
+    ('Star Cup', 2, 'Starview Peak'),
+    ('Star Cup', 3, 'Sky-High Sundae'),
+    ('Star Cup', 4, 'Wario''s Galleon'),
+    ('Shell Cup', 1, 'Koopa Troopa Beach'),
+    ('Shell Cup', 2, 'Faraway Oasis'),
+    ('Shell Cup', 3, 'Crown City'),
+    ('Shell Cup', 4, 'Peach Stadium'),
+    ('Banana Cup', 1, 'Peach Beach'),
+    ('Banana Cup', 2, 'Salty Salty Speedway'),
+    ('Banana Cup', 3, 'Dino Dino Jungle'),
+    ('Banana Cup', 4, 'Great ? Block Ruins'),
+    ('Leaf Cup', 1, 'Cheep Cheep Falls'),
+    ('Leaf Cup', 2, 'Dandelion Depths'),
+    ('Leaf Cup', 3, 'Boo Cinema'),
+    ('Leaf Cup', 4, 'Dry Bones Burnout'),
+    ('Lightning Cup', 1, 'Moo Moo Meadows'),
+    ('Lightning Cup', 2, 'Choco Mountain'),
+    ('Lightning Cup', 3, 'Toad''s Factory'),
+    ('Lightning Cup', 4, 'Bowser''s Castle'),
+    ('Special Cup', 1, 'Acorn Heights'),
+    ('Special Cup', 2, 'Mario Circuit'),
+    ('Special Cup', 3, 'Peach Stadium'),
+    ('Special Cup', 4, 'Rainbow Road')
+) AS ct(cup_name, position, track_name)
+JOIN cups c ON c.name = ct.cup_name
+JOIN tracks t ON t.name = ct.track_name;
@@ -0,0 +1,35 @@
+-- Groups can manage their own tracks alongside the built-in catalogue.
+-- Built-in tracks have no group and are shared by every group.
+ALTER TABLE tracks
+    ADD COLUMN group_id uuid REFERENCES groups(id) ON DELETE CASCADE ON UPDATE CASCADE,
+    ADD COLUMN game_edition TEXT,
+    ADD COLUMN cup TEXT,
+    ADD COLUMN retro BOOLEAN NOT NULL DEFAULT FALSE,
+    ADD COLUMN tags TEXT[] NOT NULL DEFAULT '{}',
+    -- Retired tracks are no longer selected for new matches but still
+    -- resolve for historical rounds
+    ADD COLUMN retired BOOLEAN NOT NULL DEFAULT FALSE;
+
+-- Names are unique within the built-in catalogue and within each group
+ALTER TABLE tracks DROP CONSTRAINT tracks_name_key;
+CREATE UNIQUE INDEX idx_tracks_built_in_name ON tracks(name) WHERE group_id IS NULL;
+CREATE UNIQUE INDEX idx_tracks_group_name ON tracks(group_id, name) WHERE group_id IS NOT NULL;
+
+UPDATE tracks SET game_edition = 'Mario Kart World' WHERE group_id IS NULL;
+
+UPDATE tracks SET retro = TRUE
+WHERE group_id IS NULL
+  AND name IN (
+    'Moo Moo Meadows',
+    'Choco Mountain',
+    'Koopa Troopa Beach',
+    'Peach Beach',
+    'DK Pass',
+    'Wario Stadium',
+    'Toad''s Factory',
+    'Dino Dino Jungle',
+    'Sky-High Sundae',
+    'Shy Guy Bazaar',
+    'Airship Fortress',
+    'Desert Hills'
+  );
@@ -0,0 +1,27 @@
+-- Named subsets of a group's available tracks, e.g. "Retro only". Tracks in
+-- a pool can be weighted so they tend to come up earlier in each cycle of the
+-- track shuffle bag.
+CREATE TABLE track_pools (
+    id uuid PRIMARY KEY DEFAULT gen_random_uuid(),
+    group_id uuid NOT NULL REFERENCES groups(id) ON DELETE CASCADE ON UPDATE CASCADE,
+    name TEXT NOT NULL,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX idx_track_pools_group_id ON track_pools(group_id);
+
+CREATE TABLE track_pool_tracks (
+    track_pool_id uuid NOT NULL REFERENCES track_pools(id) ON DELETE CASCADE,
+    track_id uuid NOT NULL REFERENCES tracks(id) ON DELETE CASCADE,
+    weight INTEGER NOT NULL DEFAULT 1 CHECK (weight > 0),
+    PRIMARY KEY (track_pool_id, track_id)
+);
+
+CREATE INDEX idx_track_pool_tracks_track_id ON track_pool_tracks(track_id);
+
+-- Tournaments and matches without a pool draw from every active track
+ALTER TABLE tournaments
+    ADD COLUMN track_pool_id uuid REFERENCES track_pools(id) ON DELETE RESTRICT;
+
+ALTER TABLE matches
+    ADD COLUMN track_pool_id uuid REFERENCES track_pools(id) ON DELETE RESTRICT;
@@ -0,0 +1,15 @@
+-- Tracks a player would rather not race in a tournament. Match creation
+-- avoids a vetoed track in any race the player is in, unless every remaining
+-- track has been vetoed.
+CREATE TABLE track_vetoes (
+    player_id uuid NOT NULL REFERENCES players(id) ON DELETE CASCADE ON UPDATE CASCADE,
+    tournament_id uuid NOT NULL REFERENCES tournaments(id) ON DELETE CASCADE ON UPDATE CASCADE,
+    track_id uuid NOT NULL REFERENCES tracks(id) ON DELETE CASCADE ON UPDATE CASCADE,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+    PRIMARY KEY (player_id, tournament_id, track_id)
+);
+
+CREATE INDEX idx_track_vetoes_tournament_id ON track_vetoes(tournament_id);
+
+-- Why track selection skipped tracks for a round, for debugging
+ALTER TABLE rounds ADD COLUMN track_selection_notes TEXT[] NOT NULL DEFAULT '{}';
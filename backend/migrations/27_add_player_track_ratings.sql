@@ -0,0 +1,13 @@
+-- A separate ELO rating per player and track, updated whenever a race on the
+-- track is recorded. Ratings for races recorded before this migration are
+-- built by `migrate recompute`.
+CREATE TABLE player_track_ratings (
+    player_id uuid NOT NULL REFERENCES players(id) ON DELETE CASCADE ON UPDATE CASCADE,
+    track_id uuid NOT NULL REFERENCES tracks(id) ON DELETE CASCADE ON UPDATE CASCADE,
+    elo_rating INTEGER NOT NULL DEFAULT 1200,
+    races_played INTEGER NOT NULL DEFAULT 0,
+    updated_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+    PRIMARY KEY (player_id, track_id)
+);
+
+CREATE INDEX idx_player_track_ratings_track_id ON player_track_ratings(track_id);
@@ -0,0 +1,160 @@
+-- Catalogue of characters and vehicle parts a player can race with, and the
+-- build each player used in a race. Builds are optional; any part may be left
+-- out. The catalogue is seeded from Mario Kart 8 Deluxe, the latest game with
+-- separate wheels and gliders.
+CREATE TYPE build_part_kind AS ENUM (
+    'character',
+    'kart',
+    'wheels',
+    'glider'
+);
+
+CREATE TABLE build_parts (
+    id uuid PRIMARY KEY DEFAULT gen_random_uuid(),
+    kind build_part_kind NOT NULL,
+    name VARCHAR(100) NOT NULL,
+    UNIQUE (kind, name)
+);
+
+ALTER TABLE player_race_scores
+    ADD COLUMN character_id uuid REFERENCES build_parts(id) ON UPDATE CASCADE,
+    ADD COLUMN kart_id uuid REFERENCES build_parts(id) ON UPDATE CASCADE,
+    ADD COLUMN wheels_id uuid REFERENCES build_parts(id) ON UPDATE CASCADE,
+    ADD COLUMN glider_id uuid REFERENCES build_parts(id) ON UPDATE CASCADE;
+
+CREATE INDEX idx_player_race_scores_character_id ON player_race_scores(character_id);
+
+INSERT INTO build_parts (kind, name) VALUES
+    ('character', 'Mario'),
+    ('character', 'Luigi'),
+    ('character', 'Peach'),
+    ('character', 'Daisy'),
+    ('character', 'Rosalina'),
+    ('character', 'Tanooki Mario'),
+    ('character', 'Cat Peach'),
+    ('character', 'Yoshi'),
+    ('character', 'Toad'),
+    ('character', 'Koopa Troopa'),
+    ('character', 'Shy Guy'),
+    ('character', 'Lakitu'),
+    ('character', 'Toadette'),
+    ('character', 'King Boo'),
+    ('character', 'Baby Mario'),
+    ('character', 'Baby Luigi'),
+    ('character', 'Baby Peach'),
+    ('character', 'Baby Daisy'),
+    ('character', 'Baby Rosalina'),
+    ('character', 'Metal Mario'),
+    ('character', 'Pink Gold Peach'),
+    ('character', 'Wario'),
+    ('character', 'Waluigi'),
+    ('character', 'Donkey Kong'),
+    ('character', 'Bowser'),
+    ('character', 'Dry Bones'),
+    ('character', 'Bowser Jr.'),
+    ('character', 'Dry Bowser'),
+    ('character', 'Lemmy'),
+    ('character', 'Larry'),
+    ('character', 'Wendy'),
+    ('character', 'Ludwig'),
+    ('character', 'Iggy'),
+    ('character', 'Roy'),
+    ('character', 'Morton'),
+    ('character', 'Inkling Girl'),
+    ('character', 'Inkling Boy'),
+    ('character', 'Link'),
+    ('character', 'Villager'),
+    ('character', 'Isabelle'),
+    ('character', 'Mii'),
+    ('character', 'Birdo'),
+    ('character', 'Petey Piranha'),
+    ('character', 'Wiggler'),
+    ('character', 'Kamek'),
+    ('character', 'Diddy Kong'),
+    ('character', 'Funky Kong'),
+    ('character', 'Pauline'),
+    ('character', 'Peachette');
+
+INSERT INTO build_parts (kind, name) VALUES
+    ('kart', 'Standard Kart'),
+    ('kart', 'Pipe Frame'),
+    ('kart', 'Mach 8'),
+    ('kart', 'Steel Driver'),
+    ('kart', 'Cat Cruiser'),
+    ('kart', 'Circuit Special'),
+    ('kart', 'Tri-Speeder'),
+    ('kart', 'Badwagon'),
+    ('kart', 'Prancer'),
+    ('kart', 'Biddybuggy'),
+    ('kart', 'Landship'),
+    ('kart', 'Sneeker'),
+    ('kart', 'Sports Coupe'),
+    ('kart', 'Gold Standard'),
+    ('kart', 'GLA'),
+    ('kart', 'W 25 Silver Arrow'),
+    ('kart', '300 SL Roadster'),
+    ('kart', 'Blue Falcon'),
+    ('kart', 'Tanooki Kart'),
+    ('kart', 'B Dasher'),
+    ('kart', 'Streetle'),
+    ('kart', 'P-Wing'),
+    ('kart', 'Koopa Clown'),
+    ('kart', 'Standard Bike'),
+    ('kart', 'Comet'),
+    ('kart', 'Sport Bike'),
+    ('kart', 'The Duke'),
+    ('kart', 'Flame Rider'),
+    ('kart', 'Varmint'),
+    ('kart', 'Mr. Scooty'),
+    ('kart', 'Jet Bike'),
+    ('kart', 'Yoshi Bike'),
+    ('kart', 'Master Cycle'),
+    ('kart', 'Master Cycle Zero'),
+    ('kart', 'City Tripper'),
+    ('kart', 'Standard ATV'),
+    ('kart', 'Wild Wiggler'),
+    ('kart', 'Teddy Buggy'),
+    ('kart', 'Bone Rattler'),
+    ('kart', 'Splat Buggy'),
+    ('kart', 'Inkstriker');
+
+INSERT INTO build_parts (kind, name) VALUES
+    ('wheels', 'Standard'),
+    ('wheels', 'Monster'),
+    ('wheels', 'Roller'),
+    ('wheels', 'Slim'),
+    ('wheels', 'Slick'),
+    ('wheels', 'Metal'),
+    ('wheels', 'Button'),
+    ('wheels', 'Off-Road'),
+    ('wheels', 'Sponge'),
+    ('wheels', 'Wood'),
+    ('wheels', 'Cushion'),
+    ('wheels', 'Blue Standard'),
+    ('wheels', 'Hot Monster'),
+    ('wheels', 'Azure Roller'),
+    ('wheels', 'Crimson Slim'),
+    ('wheels', 'Cyber Slick'),
+    ('wheels', 'Retro Off-Road'),
+    ('wheels', 'Gold Tires'),
+    ('wheels', 'GLA Tires'),
+    ('wheels', 'Triforce Tires'),
+    ('wheels', 'Ancient Tires'),
+    ('wheels', 'Leaf Tires');
+
+INSERT INTO build_parts (kind, name) VALUES
+    ('glider', 'Super Glider'),
+    ('glider', 'Cloud Glider'),
+    ('glider', 'Wario Wing'),
+    ('glider', 'Waddle Wing'),
+    ('glider', 'Peach Parasol'),
+    ('glider', 'Parachute'),
+    ('glider', 'Parafoil'),
+    ('glider', 'Flower Glider'),
+    ('glider', 'Bowser Kite'),
+    ('glider', 'Plane Glider'),
+    ('glider', 'MKTV Parafoil'),
+    ('glider', 'Gold Glider'),
+    ('glider', 'Hylian Kite'),
+    ('glider', 'Paraglider'),
+    ('glider', 'Paper Glider');
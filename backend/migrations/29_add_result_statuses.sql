@@ -0,0 +1,15 @@
+-- How a player's race ended. Players who did not finish are stored at the back
+-- of the field (the group's race size) so position-based stats keep working.
+--
+-- - dnf: rated as last place, scores last-place points
+-- - disqualified: rated as last place, scores no points
+-- - disconnected: no rating change, scores no points
+CREATE TYPE result_status AS ENUM (
+    'finished',
+    'dnf',
+    'disqualified',
+    'disconnected'
+);
+
+ALTER TABLE player_race_scores
+    ADD COLUMN status result_status NOT NULL DEFAULT 'finished';
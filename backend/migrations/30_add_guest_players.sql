@@ -0,0 +1,4 @@
+-- Guests race and rate like everyone else, so they count as real opponents,
+-- but their own ratings are left off the leaderboards until they are promoted
+-- to full players.
+ALTER TABLE players ADD COLUMN guest BOOLEAN NOT NULL DEFAULT FALSE;
@@ -0,0 +1,43 @@
+-- Names a player went by before being renamed, so old tournaments can show
+-- who they were at the time.
+CREATE TABLE player_name_changes (
+    id uuid PRIMARY KEY DEFAULT gen_random_uuid(),
+    player_id uuid NOT NULL REFERENCES players(id) ON DELETE CASCADE ON UPDATE CASCADE,
+    previous_name varchar(255) NOT NULL,
+    changed_at timestamptz NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX idx_player_name_changes_player_id ON player_name_changes (player_id, changed_at);
+
+-- Deleting a player must not wipe their race history or the tournaments they
+-- won. NO ACTION is checked at the end of the statement, so deleting a whole
+-- group still cascades through its players and their history.
+ALTER TABLE tournaments
+    DROP CONSTRAINT tournaments_winner_fkey,
+    ADD CONSTRAINT tournaments_winner_fkey
+        FOREIGN KEY (winner) REFERENCES players (id) ON DELETE NO ACTION ON UPDATE CASCADE;
+
+ALTER TABLE match_players
+    DROP CONSTRAINT match_players_player_id_fkey,
+    ADD CONSTRAINT match_players_player_id_fkey
+        FOREIGN KEY (player_id) REFERENCES players (id) ON DELETE NO ACTION ON UPDATE CASCADE;
+
+ALTER TABLE team_players
+    DROP CONSTRAINT team_players_player_id_fkey,
+    ADD CONSTRAINT team_players_player_id_fkey
+        FOREIGN KEY (player_id) REFERENCES players (id) ON DELETE NO ACTION ON UPDATE CASCADE;
+
+ALTER TABLE player_match_scores
+    DROP CONSTRAINT player_match_scores_player_id_fkey,
+    ADD CONSTRAINT player_match_scores_player_id_fkey
+        FOREIGN KEY (player_id) REFERENCES players (id) ON DELETE NO ACTION ON UPDATE CASCADE;
+
+ALTER TABLE player_race_scores
+    DROP CONSTRAINT player_race_scores_player_id_fkey,
+    ADD CONSTRAINT player_race_scores_player_id_fkey
+        FOREIGN KEY (player_id) REFERENCES players (id) ON DELETE NO ACTION ON UPDATE CASCADE;
+
+ALTER TABLE player_tournament_scores
+    DROP CONSTRAINT player_tournament_scores_player_id_fkey,
+    ADD CONSTRAINT player_tournament_scores_player_id_fkey
+        FOREIGN KEY (player_id) REFERENCES players (id) ON DELETE NO ACTION ON UPDATE CASCADE;
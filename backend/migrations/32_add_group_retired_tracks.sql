@@ -0,0 +1,9 @@
+-- Built-in tracks are shared by every group, so a group retires one for
+-- itself here instead of setting tracks.retired. A track is retired for a
+-- group when it is retired globally or listed for the group.
+CREATE TABLE group_retired_tracks (
+    group_id uuid NOT NULL REFERENCES groups(id) ON DELETE CASCADE ON UPDATE CASCADE,
+    track_id uuid NOT NULL REFERENCES tracks(id) ON DELETE CASCADE ON UPDATE CASCADE,
+    retired_at timestamptz NOT NULL DEFAULT NOW(),
+    PRIMARY KEY (group_id, track_id)
+);
@@ -1,7 +1,10 @@
 use clap::{Parser, Subcommand};
 use mario_kart_leaderboard_backend::error::Result;
+use mario_kart_leaderboard_backend::services::elo_replay::{self, RecomputeReport};
+use mario_kart_leaderboard_backend::services::rating_decay::{self, DecayReport};
 use sqlx::postgres::PgPoolOptions;
 use std::env;
+use uuid::Uuid;
 
 #[derive(Parser)]
 #[command(name = "migrate")]
@@ -21,6 +24,67 @@ enum Commands {
     Status,
     /// Create a new migration file
     Add { name: String },
+    /// Recompute all ELO data from recorded race positions
+    Recompute {
+        /// Only recompute this group (defaults to all groups)
+        #[arg(long)]
+        group_id: Option<Uuid>,
+        /// Report what would change without writing anything
+        #[arg(long)]
+        dry_run: bool,
+    },
+    /// Apply inactivity rating decay
+    Decay {
+        /// Only decay this group (defaults to all groups)
+        #[arg(long)]
+        group_id: Option<Uuid>,
+        /// Report what would change without writing anything
+        #[arg(long)]
+        dry_run: bool,
+    },
+}
+
+fn print_report(report: &RecomputeReport) {
+    println!(
+        "Group {}: replayed {} races, {} race score rows changed, {} ratings changed",
+        report.group_id,
+        report.races_replayed,
+        report.race_scores_changed,
+        report.rating_changes.len()
+    );
+    for change in &report.rating_changes {
+        let scope = change
+            .tournament_id
+            .map(|id| format!("tournament {id}"))
+            .unwrap_or_else(|| "all-time".to_string());
+        println!(
+            "  {} ({}): {} -> {} ({:+})",
+            change.player_name,
+            scope,
+            change.before,
+            change.after,
+            change.after - change.before
+        );
+    }
+}
+
+fn print_decay_report(report: &DecayReport) {
+    println!(
+        "Group {}: {} inactive players, {} ratings decayed",
+        report.group_id,
+        report.inactive_players,
+        report.decays.len()
+    );
+    for decay in &report.decays {
+        println!(
+            "  {} ({} weeks): {} -> {} ({:+})",
+            decay.player_name,
+            decay.weeks,
+            decay.before,
+            decay.after,
+            decay.after - decay.before
+        );
+    }
 }
 
 #[tokio::main]
@@ -60,6 +124,35 @@ async fn main() -> Result<()> {
             std::fs::write(&filename, "-- Add your SQL here\n")?;
             println!("Created migration file: {}", filename);
         }
+        Commands::Recompute { group_id, dry_run } => {
+            println!("Recomputing ELO from recorded races...");
+            let reports = match group_id {
+                Some(group_id) => vec![elo_replay::recompute_group(&pool, group_id, dry_run).await?],
+                None => elo_replay::recompute_all_groups(&pool, dry_run).await?,
+            };
+            reports.iter().for_each(print_report);
+            if dry_run {
+                println!("Dry run: no changes were written");
+            } else {
+                println!("ELO recompute completed successfully!");
+            }
+        }
+        Commands::Decay { group_id, dry_run } => {
+            println!("Applying inactivity decay...");
+            let now = chrono::Utc::now();
+            let reports = match group_id {
+                Some(group_id) => {
+                    vec![rating_decay::apply_inactivity_decay(&pool, group_id, now, dry_run).await?]
+                }
+                None => rating_decay::apply_inactivity_decay_all_groups(&pool, now, dry_run).await?,
+            };
+            reports.iter().for_each(print_decay_report);
+            if dry_run {
+                println!("Dry run: no changes were written");
+            } else {
+                println!("Inactivity decay completed successfully!");
+            }
+        }
     }
 
     Ok(())
@@ -75,7 +75,7 @@ async fn main() -> Result<()> {
         }
     };
 
-    let existing_players = Player::find_by_group_id(&pool, group.id).await?;
+    let existing_players = Player::find_by_group_id(&pool, group.id, true, true).await?;
     let existing_names: HashSet<&str> =
         existing_players.iter().map(|p| p.name.as_str()).collect();
 
@@ -92,7 +92,7 @@ async fn main() -> Result<()> {
     if existing_tournaments.is_empty() {
         let today = Local::now().date_naive();
         let end = today + Duration::days(30);
-        let tournament = Tournament::create(&pool, group.id, Some(today), Some(end)).await?;
+        let tournament = Tournament::create(&pool, group.id, Some(today), Some(end), None, None).await?;
         println!(
             "seed: created tournament {} ({} → {})",
             tournament.id, today, end
@@ -12,6 +12,8 @@ pub struct Config {
     pub cors_origins: Vec<String>,
     pub otlp_endpoint: Option<String>,
     pub service_name: String,
+    /// Directory uploaded avatars are stored in
+    pub avatar_dir: String,
 }
 
 impl Config {
@@ -63,6 +65,7 @@ impl Config {
             otlp_endpoint: env::var("OTEL_EXPORTER_OTLP_ENDPOINT").ok(),
             service_name: env::var("OTEL_SERVICE_NAME")
                 .unwrap_or_else(|_| "mario-kart-leaderboard".to_string()),
+            avatar_dir: env::var("AVATAR_DIR").unwrap_or_else(|_| "avatars".to_string()),
         })
     }
 
@@ -0,0 +1,32 @@
+use crate::db::DbPool;
+use crate::models::BuildPart;
+use async_graphql::dataloader::*;
+use std::collections::HashMap;
+use tracing::instrument;
+use uuid::Uuid;
+
+pub struct BuildPartLoader {
+    pool: DbPool,
+}
+
+impl BuildPartLoader {
+    pub fn new(pool: DbPool) -> Self {
+        Self { pool }
+    }
+}
+
+impl Loader<Uuid> for BuildPartLoader {
+    type Value = BuildPart;
+    type Error = std::sync::Arc<sqlx::Error>;
+
+    #[instrument(level = "debug", skip(self), fields(batch_size = keys.len()))]
+    async fn load(&self, keys: &[Uuid]) -> Result<HashMap<Uuid, Self::Value>, Self::Error> {
+        let parts = BuildPart::find_by_ids(&self.pool, keys)
+            .await
+            .map_err(std::sync::Arc::new)?;
+
+        let mapped = parts.into_iter().map(|part| (part.id, part)).collect();
+
+        Ok(mapped)
+    }
+}
@@ -0,0 +1,7 @@
+pub mod loaders;
+pub mod queries;
+pub mod types;
+
+pub use loaders::BuildPartLoader;
+pub use queries::BuildsQuery;
+pub use types::BuildPart;
@@ -0,0 +1,23 @@
+use crate::graphql::builds::types::{BuildPart, BuildPartKind};
+use crate::graphql::context::GraphQLContext;
+use crate::models;
+use async_graphql::*;
+
+#[derive(Default)]
+pub struct BuildsQuery;
+
+#[Object]
+impl BuildsQuery {
+    /// Get the catalogue of characters and vehicle parts
+    async fn build_parts(
+        &self,
+        ctx: &Context<'_>,
+        #[graphql(desc = "Only parts of this kind")] kind: Option<BuildPartKind>,
+    ) -> Result<Vec<BuildPart>> {
+        let gql_ctx = ctx.data::<GraphQLContext>()?;
+
+        let parts = models::BuildPart::find_all(&gql_ctx.pool, kind.map(Into::into)).await?;
+
+        Ok(parts.into_iter().map(BuildPart::from).collect())
+    }
+}
@@ -0,0 +1,210 @@
+use crate::graphql::context::GraphQLContext;
+use crate::models;
+use async_graphql::*;
+use uuid::Uuid;
+
+/// The slot a build part fills
+#[derive(Clone, Copy, Enum, PartialEq, Eq)]
+pub enum BuildPartKind {
+    Character,
+    Kart,
+    Wheels,
+    Glider,
+}
+
+impl From<models::BuildPartKind> for BuildPartKind {
+    fn from(kind: models::BuildPartKind) -> Self {
+        match kind {
+            models::BuildPartKind::Character => Self::Character,
+            models::BuildPartKind::Kart => Self::Kart,
+            models::BuildPartKind::Wheels => Self::Wheels,
+            models::BuildPartKind::Glider => Self::Glider,
+        }
+    }
+}
+
+impl From<BuildPartKind> for models::BuildPartKind {
+    fn from(kind: BuildPartKind) -> Self {
+        match kind {
+            BuildPartKind::Character => Self::Character,
+            BuildPartKind::Kart => Self::Kart,
+            BuildPartKind::Wheels => Self::Wheels,
+            BuildPartKind::Glider => Self::Glider,
+        }
+    }
+}
+
+/// A character or vehicle part from the catalogue
+#[derive(Clone)]
+pub struct BuildPart {
+    pub id: Uuid,
+    pub kind: BuildPartKind,
+    pub name: String,
+}
+
+impl From<models::BuildPart> for BuildPart {
+    fn from(model: models::BuildPart) -> Self {
+        Self {
+            id: model.id,
+            kind: model.kind.into(),
+            name: model.name,
+        }
+    }
+}
+
+#[Object]
+impl BuildPart {
+    async fn id(&self) -> ID {
+        ID(self.id.to_string())
+    }
+
+    async fn kind(&self) -> BuildPartKind {
+        self.kind
+    }
+
+    async fn name(&self) -> &str {
+        &self.name
+    }
+}
+
+/// The character and parts a player raced with; every part is optional
+#[derive(InputObject, Default)]
+pub struct RaceBuildInput {
+    pub character_id: Option<ID>,
+    pub kart_id: Option<ID>,
+    pub wheels_id: Option<ID>,
+    pub glider_id: Option<ID>,
+}
+
+impl RaceBuildInput {
+    /// Parses the part IDs. Whether the parts exist is checked against the
+    /// catalogue with `check_build_parts`.
+    pub fn into_build(self) -> Result<models::RaceBuild> {
+        let parse = |id: Option<ID>| -> Result<Option<Uuid>> {
+            id.map(|id| Uuid::parse_str(&id).map_err(|_| Error::new("Invalid build part ID")))
+                .transpose()
+        };
+
+        Ok(models::RaceBuild {
+            character_id: parse(self.character_id)?,
+            kart_id: parse(self.kart_id)?,
+            wheels_id: parse(self.wheels_id)?,
+            glider_id: parse(self.glider_id)?,
+        })
+    }
+}
+
+/// Checks every part of the (player_id, build) pairs exists with the right
+/// kind, loading the parts in one query.
+pub async fn check_build_parts(
+    pool: &crate::db::DbPool,
+    builds: &[(Uuid, models::RaceBuild)],
+) -> Result<()> {
+    let part_ids: Vec<Uuid> = builds
+        .iter()
+        .flat_map(|(_, build)| build.parts().map(|(id, _)| id))
+        .collect();
+    let parts = models::BuildPart::find_by_ids(pool, &part_ids).await?;
+
+    for (id, kind) in builds.iter().flat_map(|(_, build)| build.parts()) {
+        if !parts.iter().any(|part| part.id == id && part.kind == kind) {
+            return Err(Error::new("Build part not found"));
+        }
+    }
+
+    Ok(())
+}
+
+/// A combination of character and parts and how often it was raced
+#[derive(Clone)]
+pub struct RaceBuild {
+    pub build: models::RaceBuild,
+    pub races_played: i64,
+}
+
+impl From<models::PlayerBuildAggregation> for RaceBuild {
+    fn from(model: models::PlayerBuildAggregation) -> Self {
+        Self {
+            build: model.build,
+            races_played: model.races_played,
+        }
+    }
+}
+
+/// Loads an optional build part by ID.
+async fn load_part(ctx: &Context<'_>, id: Option<Uuid>) -> Result<Option<BuildPart>> {
+    let Some(id) = id else {
+        return Ok(None);
+    };
+
+    let gql_ctx = ctx.data::<GraphQLContext>()?;
+
+    let part = gql_ctx.build_part_loader.load_one(id).await?;
+
+    Ok(part.map(BuildPart::from))
+}
+
+#[Object]
+impl RaceBuild {
+    async fn character(&self, ctx: &Context<'_>) -> Result<Option<BuildPart>> {
+        load_part(ctx, self.build.character_id).await
+    }
+
+    async fn kart(&self, ctx: &Context<'_>) -> Result<Option<BuildPart>> {
+        load_part(ctx, self.build.kart_id).await
+    }
+
+    async fn wheels(&self, ctx: &Context<'_>) -> Result<Option<BuildPart>> {
+        load_part(ctx, self.build.wheels_id).await
+    }
+
+    async fn glider(&self, ctx: &Context<'_>) -> Result<Option<BuildPart>> {
+        load_part(ctx, self.build.glider_id).await
+    }
+
+    async fn races_played(&self) -> i64 {
+        self.races_played
+    }
+}
+
+/// A player's results with one character
+#[derive(Clone)]
+pub struct CharacterStats {
+    pub character_id: Uuid,
+    pub races_played: i64,
+    pub average_position: f64,
+    pub average_elo_change: Option<f64>,
+}
+
+impl From<models::PlayerCharacterAggregation> for CharacterStats {
+    fn from(model: models::PlayerCharacterAggregation) -> Self {
+        Self {
+            character_id: model.character_id,
+            races_played: model.races_played,
+            average_position: model.average_position,
+            average_elo_change: model.average_elo_change,
+        }
+    }
+}
+
+#[Object]
+impl CharacterStats {
+    async fn character(&self, ctx: &Context<'_>) -> Result<BuildPart> {
+        load_part(ctx, Some(self.character_id))
+            .await?
+            .ok_or_else(|| Error::new("Build part not found"))
+    }
+
+    async fn races_played(&self) -> i64 {
+        self.races_played
+    }
+
+    async fn average_position(&self) -> f64 {
+        self.average_position
+    }
+
+    /// Average all-time ELO change per race
+    async fn average_elo_change(&self) -> Option<f64> {
+        self.average_elo_change
+    }
+}
@@ -1,12 +1,18 @@
 use crate::db::DbPool;
+use crate::graphql::builds::BuildPartLoader;
+use crate::graphql::cups::CupLoader;
 use crate::graphql::groups::GroupLoader;
 use crate::graphql::lobby::LobbyByGroupLoader;
 use crate::graphql::matches::MatchesByTournamentLoader;
-use crate::graphql::players::{PlayerActiveTournamentEloLoader, PlayerLoader, PlayersByGroupLoader};
+use crate::graphql::players::{
+    PlayerActiveTournamentEloLoader, PlayerActivityLoader, PlayerLoader, PlayersByGroupLoader,
+};
+use crate::graphql::points_tables::PointsTableLoader;
 use crate::graphql::results::{PlayerMatchScoresByMatchLoader, PlayerRaceScoresByRoundLoader, PlayerTeammateContributionLoader};
 use crate::graphql::rounds::PlayersByRoundLoader;
 use crate::graphql::teams::PlayersByTeamLoader;
-use crate::graphql::tracks::TrackLoader;
+use crate::graphql::track_pools::TrackPoolLoader;
+use crate::graphql::tracks::{TrackChaosLoader, TrackLoader};
 use crate::services::notification_manager::NotificationManager;
 use async_graphql::dataloader::{DataLoader, HashMapCache};
 use async_graphql::ErrorExtensions;
@@ -22,10 +28,16 @@ pub struct GraphQLContext {
     pub players_by_group_loader: Arc<DataLoader<PlayersByGroupLoader, HashMapCache>>,
     pub player_active_tournament_elo_loader:
         Arc<DataLoader<PlayerActiveTournamentEloLoader, HashMapCache>>,
+    pub player_activity_loader: Arc<DataLoader<PlayerActivityLoader, HashMapCache>>,
     pub matches_by_tournament_loader: Arc<DataLoader<MatchesByTournamentLoader, HashMapCache>>,
     pub players_by_round_loader: Arc<DataLoader<PlayersByRoundLoader, HashMapCache>>,
     pub players_by_team_loader: Arc<DataLoader<PlayersByTeamLoader, HashMapCache>>,
     pub track_loader: Arc<DataLoader<TrackLoader, HashMapCache>>,
+    pub track_chaos_loader: Arc<DataLoader<TrackChaosLoader, HashMapCache>>,
+    pub cup_loader: Arc<DataLoader<CupLoader, HashMapCache>>,
+    pub build_part_loader: Arc<DataLoader<BuildPartLoader, HashMapCache>>,
+    pub track_pool_loader: Arc<DataLoader<TrackPoolLoader, HashMapCache>>,
+    pub points_table_loader: Arc<DataLoader<PointsTableLoader, HashMapCache>>,
     pub player_race_scores_by_round_loader:
         Arc<DataLoader<PlayerRaceScoresByRoundLoader, HashMapCache>>,
     pub player_match_scores_by_match_loader:
@@ -62,6 +74,11 @@ impl GraphQLContext {
                 tokio::spawn,
                 HashMapCache::default(),
             )),
+            player_activity_loader: Arc::new(DataLoader::with_cache(
+                PlayerActivityLoader::new(pool.clone()),
+                tokio::spawn,
+                HashMapCache::default(),
+            )),
             matches_by_tournament_loader: Arc::new(DataLoader::with_cache(
                 MatchesByTournamentLoader::new(pool.clone()),
                 tokio::spawn,
@@ -78,7 +95,32 @@ impl GraphQLContext {
                 HashMapCache::default(),
             )),
             track_loader: Arc::new(DataLoader::with_cache(
-                TrackLoader::new(pool.clone()),
+                TrackLoader::new(pool.clone(), group_id),
+                tokio::spawn,
+                HashMapCache::default(),
+            )),
+            track_chaos_loader: Arc::new(DataLoader::with_cache(
+                TrackChaosLoader::new(pool.clone(), group_id),
+                tokio::spawn,
+                HashMapCache::default(),
+            )),
+            cup_loader: Arc::new(DataLoader::with_cache(
+                CupLoader::new(pool.clone()),
+                tokio::spawn,
+                HashMapCache::default(),
+            )),
+            build_part_loader: Arc::new(DataLoader::with_cache(
+                BuildPartLoader::new(pool.clone()),
+                tokio::spawn,
+                HashMapCache::default(),
+            )),
+            track_pool_loader: Arc::new(DataLoader::with_cache(
+                TrackPoolLoader::new(pool.clone()),
+                tokio::spawn,
+                HashMapCache::default(),
+            )),
+            points_table_loader: Arc::new(DataLoader::with_cache(
+                PointsTableLoader::new(pool.clone()),
                 tokio::spawn,
                 HashMapCache::default(),
             )),
@@ -0,0 +1,32 @@
+use crate::db::DbPool;
+use crate::models::Cup;
+use async_graphql::dataloader::*;
+use std::collections::HashMap;
+use tracing::instrument;
+use uuid::Uuid;
+
+pub struct CupLoader {
+    pool: DbPool,
+}
+
+impl CupLoader {
+    pub fn new(pool: DbPool) -> Self {
+        Self { pool }
+    }
+}
+
+impl Loader<Uuid> for CupLoader {
+    type Value = Cup;
+    type Error = std::sync::Arc<sqlx::Error>;
+
+    #[instrument(level = "debug", skip(self), fields(batch_size = keys.len()))]
+    async fn load(&self, keys: &[Uuid]) -> Result<HashMap<Uuid, Self::Value>, Self::Error> {
+        let cups = Cup::find_by_ids(&self.pool, keys)
+            .await
+            .map_err(std::sync::Arc::new)?;
+
+        let mapped = cups.into_iter().map(|cup| (cup.id, cup)).collect();
+
+        Ok(mapped)
+    }
+}
@@ -0,0 +1,7 @@
+pub mod loaders;
+pub mod queries;
+pub mod types;
+
+pub use loaders::CupLoader;
+pub use queries::CupsQuery;
+pub use types::Cup;
@@ -0,0 +1,19 @@
+use crate::graphql::context::GraphQLContext;
+use crate::graphql::cups::types::Cup;
+use crate::models;
+use async_graphql::*;
+
+#[derive(Default)]
+pub struct CupsQuery;
+
+#[Object]
+impl CupsQuery {
+    /// Get all Grand Prix cups
+    async fn cups(&self, ctx: &Context<'_>) -> Result<Vec<Cup>> {
+        let gql_ctx = ctx.data::<GraphQLContext>()?;
+
+        let cups = models::Cup::find_all(&gql_ctx.pool).await?;
+
+        Ok(cups.into_iter().map(Cup::from).collect())
+    }
+}
@@ -0,0 +1,128 @@
+use async_graphql::*;
+use uuid::Uuid;
+
+use crate::graphql::context::GraphQLContext;
+use crate::graphql::players::types::Player;
+use crate::graphql::tracks::types::Track;
+use crate::services::score_calculation;
+
+#[derive(Clone)]
+pub struct Cup {
+    pub id: Uuid,
+    pub name: String,
+}
+
+impl From<crate::models::Cup> for Cup {
+    fn from(model: crate::models::Cup) -> Self {
+        Self {
+            id: model.id,
+            name: model.name,
+        }
+    }
+}
+
+#[Object]
+impl Cup {
+    async fn id(&self) -> ID {
+        ID(self.id.to_string())
+    }
+
+    async fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// The cup's tracks in race order
+    async fn tracks(&self, ctx: &Context<'_>) -> Result<Vec<Track>> {
+        let gql_ctx = ctx.data::<GraphQLContext>()?;
+
+        let tracks = crate::models::Cup::find_tracks_by_cup_ids(
+            &gql_ctx.pool,
+            &[self.id],
+            gql_ctx.group_id,
+        )
+        .await?;
+
+        Ok(tracks
+            .into_iter()
+            .map(|(_, track)| Track::from(track))
+            .collect())
+    }
+}
+
+/// A cup raced in a Grand Prix match, with its standings so far
+#[derive(Clone)]
+pub struct MatchCup {
+    pub cup_id: Uuid,
+    pub standings: Vec<CupStanding>,
+}
+
+impl From<score_calculation::CupStandings> for MatchCup {
+    fn from(model: score_calculation::CupStandings) -> Self {
+        Self {
+            cup_id: model.cup_id,
+            standings: model.players.into_iter().map(CupStanding::from).collect(),
+        }
+    }
+}
+
+#[Object]
+impl MatchCup {
+    async fn cup(&self, ctx: &Context<'_>) -> Result<Cup> {
+        let gql_ctx = ctx.data::<GraphQLContext>()?;
+
+        let cup = gql_ctx
+            .cup_loader
+            .load_one(self.cup_id)
+            .await?
+            .ok_or_else(|| Error::new("Cup not found"))?;
+
+        Ok(Cup::from(cup))
+    }
+
+    /// Players ranked by points scored in the cup's recorded races
+    async fn standings(&self) -> &[CupStanding] {
+        &self.standings
+    }
+}
+
+#[derive(Clone)]
+pub struct CupStanding {
+    pub player_id: Uuid,
+    pub position: i32,
+    pub points: i32,
+}
+
+impl From<score_calculation::CupPlayerStanding> for CupStanding {
+    fn from(model: score_calculation::CupPlayerStanding) -> Self {
+        Self {
+            player_id: model.player_id,
+            position: model.position,
+            points: model.points,
+        }
+    }
+}
+
+#[Object]
+impl CupStanding {
+    async fn player(&self, ctx: &Context<'_>) -> Result<Player> {
+        let gql_ctx = ctx.data::<GraphQLContext>()?;
+
+        let player = gql_ctx
+            .player_loader
+            .load_one(self.player_id)
+            .await?
+            .ok_or_else(|| Error::new("Player not found"))?;
+
+        Ok(Player::from(player))
+    }
+
+    /// Rank in the cup; players on equal points share a position
+    async fn position(&self) -> i32 {
+        self.position
+    }
+
+    /// Points under the match's points table
+    async fn points(&self) -> i32 {
+        self.points
+    }
+}
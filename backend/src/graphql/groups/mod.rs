@@ -1,7 +1,9 @@
 pub mod loaders;
+pub mod mutations;
 pub mod queries;
 pub mod types;
 
 pub use loaders::GroupLoader;
+pub use mutations::GroupsMutation;
 pub use queries::GroupsQuery;
 pub use types::Group;
@@ -0,0 +1,101 @@
+use crate::graphql::context::GraphQLContext;
+use crate::graphql::groups::types::{GroupSettings, RatingSystem};
+use crate::models;
+use crate::services::elo::EloSettings;
+use crate::services::elo_replay;
+use crate::services::validation::validate_elo_settings;
+use async_graphql::*;
+
+#[derive(Default)]
+pub struct GroupsMutation;
+
+/// ELO settings to change; omitted fields keep their current value.
+#[derive(InputObject)]
+pub struct GroupSettingsInput {
+    pub rating_system: Option<RatingSystem>,
+    pub k_factor: Option<f64>,
+    pub total_race_size: Option<i32>,
+    pub position_score_exponent: Option<f64>,
+    pub min_cpu_elo: Option<i32>,
+    pub cpu_elo_spread: Option<i32>,
+    pub cpu_elo_decrease: Option<i32>,
+    pub min_average_elo_for_cpu: Option<i32>,
+    pub max_average_elo_for_cpu: Option<i32>,
+    pub teammate_contribution_ratio: Option<f64>,
+    pub provisional_race_count: Option<i32>,
+    pub inactivity_weeks: Option<i32>,
+    pub inactivity_decay_per_week: Option<i32>,
+    pub repeat_teammate_penalty: Option<i32>,
+}
+
+#[Object]
+impl GroupsMutation {
+    /// Change the current group's ELO settings.
+    ///
+    /// New settings apply to races recorded from now on. With `recompute`,
+    /// every recorded race of the group is re-rated with the new settings in
+    /// the same transaction; switching the rating system usually wants this.
+    async fn update_group_settings(
+        &self,
+        ctx: &Context<'_>,
+        #[graphql(desc = "The settings to change")] input: GroupSettingsInput,
+        #[graphql(
+            desc = "Recompute all ELO history with the new settings",
+            default = false
+        )]
+        recompute: bool,
+    ) -> Result<GroupSettings> {
+        let gql_ctx = ctx.data::<GraphQLContext>()?;
+        let group_id = gql_ctx.authenticated_group_id()?;
+
+        let current = models::GroupSettings::find_elo_settings(&gql_ctx.pool, group_id).await?;
+
+        let settings = EloSettings {
+            rating_system: input
+                .rating_system
+                .map(Into::into)
+                .unwrap_or(current.rating_system),
+            k_factor: input.k_factor.unwrap_or(current.k_factor),
+            total_race_size: input.total_race_size.unwrap_or(current.total_race_size),
+            position_score_exponent: input
+                .position_score_exponent
+                .unwrap_or(current.position_score_exponent),
+            min_cpu_elo: input.min_cpu_elo.unwrap_or(current.min_cpu_elo),
+            cpu_elo_spread: input.cpu_elo_spread.unwrap_or(current.cpu_elo_spread),
+            cpu_elo_decrease: input.cpu_elo_decrease.unwrap_or(current.cpu_elo_decrease),
+            min_average_elo_for_cpu: input
+                .min_average_elo_for_cpu
+                .unwrap_or(current.min_average_elo_for_cpu),
+            max_average_elo_for_cpu: input
+                .max_average_elo_for_cpu
+                .unwrap_or(current.max_average_elo_for_cpu),
+            teammate_contribution_ratio: input
+                .teammate_contribution_ratio
+                .unwrap_or(current.teammate_contribution_ratio),
+            provisional_race_count: input
+                .provisional_race_count
+                .unwrap_or(current.provisional_race_count),
+            inactivity_weeks: input.inactivity_weeks.unwrap_or(current.inactivity_weeks),
+            inactivity_decay_per_week: input
+                .inactivity_decay_per_week
+                .unwrap_or(current.inactivity_decay_per_week),
+            repeat_teammate_penalty: input
+                .repeat_teammate_penalty
+                .unwrap_or(current.repeat_teammate_penalty),
+        };
+
+        validate_elo_settings(&settings)?;
+
+        let mut tx = gql_ctx.pool.begin().await?;
+
+        let stored = models::GroupSettings::upsert(&mut *tx, group_id, &settings).await?;
+
+        if recompute {
+            elo_replay::recompute_group_in_transaction(&mut tx, group_id).await?;
+        }
+
+        tx.commit().await?;
+
+        Ok(GroupSettings::from(EloSettings::from(stored)))
+    }
+}
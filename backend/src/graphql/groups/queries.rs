@@ -1,5 +1,5 @@
 use crate::graphql::context::GraphQLContext;
-use crate::graphql::groups::types::Group;
+use crate::graphql::groups::types::{Group, GroupSettings};
 use crate::models;
 use async_graphql::*;
 
@@ -18,4 +18,14 @@ impl GroupsQuery {
 
         Ok(Group::from(group))
     }
+
+    /// ELO settings of the current group (defaults if never changed)
+    async fn group_settings(&self, ctx: &Context<'_>) -> Result<GroupSettings> {
+        let gql_ctx = ctx.data::<GraphQLContext>()?;
+        let group_id = gql_ctx.authenticated_group_id()?;
+
+        let settings = models::GroupSettings::find_elo_settings(&gql_ctx.pool, group_id).await?;
+
+        Ok(GroupSettings::from(settings))
+    }
 }
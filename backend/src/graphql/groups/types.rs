@@ -1,5 +1,7 @@
 use crate::graphql::context::GraphQLContext;
 use crate::graphql::players::types::Player;
+use crate::services::elo::EloSettings;
+use crate::services::rating_system::RatingSystemKind;
 use async_graphql::*;
 use uuid::Uuid;
 
@@ -28,6 +30,7 @@ impl Group {
         &self.name
     }
 
+    /// Active players ranked by all-time ELO, leaving out guests
     async fn players(&self, ctx: &Context<'_>) -> Result<Vec<Player>> {
         let gql_ctx = ctx.data::<GraphQLContext>()?;
 
@@ -52,3 +55,74 @@ impl Group {
         Ok(players.into_iter().map(Player::from).collect())
     }
 }
+
+#[derive(Clone, Copy, Enum, PartialEq, Eq)]
+pub enum RatingSystem {
+    Elo,
+    #[graphql(name = "GLICKO2")]
+    Glicko2,
+}
+
+impl From<RatingSystemKind> for RatingSystem {
+    fn from(kind: RatingSystemKind) -> Self {
+        match kind {
+            RatingSystemKind::Elo => Self::Elo,
+            RatingSystemKind::Glicko2 => Self::Glicko2,
+        }
+    }
+}
+
+impl From<RatingSystem> for RatingSystemKind {
+    fn from(system: RatingSystem) -> Self {
+        match system {
+            RatingSystem::Elo => Self::Elo,
+            RatingSystem::Glicko2 => Self::Glicko2,
+        }
+    }
+}
+
+/// ELO parameters used when rating this group's races.
+#[derive(Clone, SimpleObject)]
+pub struct GroupSettings {
+    /// Rating system that drives the leaderboard
+    pub rating_system: RatingSystem,
+    pub k_factor: f64,
+    pub total_race_size: i32,
+    pub position_score_exponent: f64,
+    pub min_cpu_elo: i32,
+    pub cpu_elo_spread: i32,
+    pub cpu_elo_decrease: i32,
+    pub min_average_elo_for_cpu: i32,
+    pub max_average_elo_for_cpu: i32,
+    pub teammate_contribution_ratio: f64,
+    /// Players with fewer recorded races are provisional
+    pub provisional_race_count: i32,
+    /// Weeks without a race after which a player is inactive
+    pub inactivity_weeks: i32,
+    /// All-time ELO removed per inactive week by the decay job
+    pub inactivity_decay_per_week: i32,
+    /// ELO charged per earlier tournament pairing of teammates when teams are
+    /// allocated
+    pub repeat_teammate_penalty: i32,
+}
+
+impl From<EloSettings> for GroupSettings {
+    fn from(settings: EloSettings) -> Self {
+        Self {
+            rating_system: settings.rating_system.into(),
+            k_factor: settings.k_factor,
+            total_race_size: settings.total_race_size,
+            position_score_exponent: settings.position_score_exponent,
+            min_cpu_elo: settings.min_cpu_elo,
+            cpu_elo_spread: settings.cpu_elo_spread,
+            cpu_elo_decrease: settings.cpu_elo_decrease,
+            min_average_elo_for_cpu: settings.min_average_elo_for_cpu,
+            max_average_elo_for_cpu: settings.max_average_elo_for_cpu,
+            teammate_contribution_ratio: settings.teammate_contribution_ratio,
+            provisional_race_count: settings.provisional_race_count,
+            inactivity_weeks: settings.inactivity_weeks,
+            inactivity_decay_per_week: settings.inactivity_decay_per_week,
+            repeat_teammate_penalty: settings.repeat_teammate_penalty,
+        }
+    }
+}
@@ -1,7 +1,12 @@
 use crate::graphql::context::GraphQLContext;
-use crate::graphql::matches::types::Match;
+use crate::graphql::matches::types::{BalancingStrategy, Match, MatchMode};
+use crate::graphql::players::types::PlayerPairInput;
+use crate::graphql::points_tables::types::find_available_points_table;
+use crate::graphql::track_pools::types::find_group_track_pool;
 use crate::models;
-use crate::services::match_service;
+use crate::services::match_service::{self, TeamMatchOptions};
+use crate::services::team_allocation::PairConstraints;
+use crate::services::track_selection::{TrackFormat, TrackOptions};
 use async_graphql::*;
 use sqlx;
 use uuid::Uuid;
@@ -19,11 +24,31 @@ impl MatchesMutation {
         #[graphql(desc = "The tournament ID")] tournament_id: ID,
         #[graphql(desc = "The player IDs participating in this match")] player_ids: Vec<ID>,
         #[graphql(desc = "The number of races")] num_races: i32,
+        #[graphql(desc = "Teams or free-for-all (default: TEAMS)")] mode: Option<MatchMode>,
         #[graphql(desc = "The number of players per race (default: 4)")] players_per_race: Option<
             i32,
         >,
-        #[graphql(desc = "Whether to assign teams randomly instead of by ELO balance (default: false)")]
+        #[graphql(desc = "The number of teams (default: playersPerRace, or enough teams of teamSize)")]
+        num_teams: Option<i32>,
+        #[graphql(desc = "The number of players per team; 1 makes every player their own team")]
+        team_size: Option<i32>,
+        #[graphql(
+            desc = "Whether to assign teams randomly instead of by ELO balance (default: false)",
+            deprecation = "Use balancingStrategy: RANDOM"
+        )]
         random_teams: Option<bool>,
+        #[graphql(desc = "How players are split into teams (default: GREEDY, or RANDOM with randomTeams)")]
+        balancing_strategy: Option<BalancingStrategy>,
+        #[graphql(desc = "Pairs of players who must share a team in this match")]
+        keep_together: Option<Vec<PlayerPairInput>>,
+        #[graphql(desc = "Pairs of players who must not share a team in this match")]
+        keep_apart: Option<Vec<PlayerPairInput>>,
+        #[graphql(desc = "Points table for team scores (default: the tournament's table)")]
+        points_table_id: Option<ID>,
+        #[graphql(desc = "Race whole cups of four tracks; numRaces must be a multiple of 4 (default: false)")]
+        grand_prix: Option<bool>,
+        #[graphql(desc = "Track pool to draw tracks from (default: the tournament's pool)")]
+        track_pool_id: Option<ID>,
     ) -> Result<Match> {
         let gql_ctx = ctx.data::<GraphQLContext>()?;
         let group_id = gql_ctx.authenticated_group_id()?;
@@ -37,8 +62,36 @@ impl MatchesMutation {
             .collect();
         let player_uuids = player_uuids?;
 
+        let mode = mode.unwrap_or(MatchMode::Teams);
+        if mode == MatchMode::FreeForAll {
+            let has_team_options = num_teams.is_some()
+                || team_size.is_some()
+                || random_teams.is_some()
+                || balancing_strategy.is_some()
+                || keep_together.is_some()
+                || keep_apart.is_some();
+            if has_team_options {
+                return Err(Error::new("Free-for-all matches have no teams"));
+            }
+            if players_per_race.is_some_and(|count| count as usize != player_uuids.len()) {
+                return Err(Error::new(
+                    "Every player races every race in a free-for-all match",
+                ));
+            }
+        }
+
         let players_per_race = players_per_race.unwrap_or(DEFAULT_PLAYERS_PER_RACE);
-        let random_teams = random_teams.unwrap_or(false);
+        let num_teams = match_service::resolve_num_teams(
+            player_uuids.len(),
+            players_per_race,
+            num_teams,
+            team_size,
+        )?;
+        let balancing_strategy = balancing_strategy.unwrap_or(if random_teams.unwrap_or(false) {
+            BalancingStrategy::Random
+        } else {
+            BalancingStrategy::Greedy
+        });
 
         let tournament = models::Tournament::find_by_id(&gql_ctx.pool, tournament_uuid)
             .await?
@@ -58,17 +111,75 @@ impl MatchesMutation {
             return Err(Error::new("One or more players are disabled"));
         }
 
-        let match_result = match_service::create_match_with_rounds(
-            &gql_ctx.pool,
-            group_id,
-            tournament_uuid,
-            &player_uuids,
-            num_races,
-            players_per_race,
-            random_teams,
-            &gql_ctx.notification_manager,
-        )
-        .await?;
+        let mut constraints = PairConstraints::default();
+        for pair in keep_together.iter().flatten() {
+            let (player_a, player_b) = parse_match_pair(pair, &player_uuids)?;
+            constraints.keep_together(player_a, player_b);
+        }
+        for pair in keep_apart.iter().flatten() {
+            let (player_a, player_b) = parse_match_pair(pair, &player_uuids)?;
+            constraints.keep_apart(player_a, player_b);
+        }
+
+        let points_table_id = match points_table_id {
+            Some(id) => Some(find_available_points_table(&gql_ctx.pool, group_id, &id).await?.id),
+            None => None,
+        };
+
+        let track_format = if grand_prix.unwrap_or(false) {
+            TrackFormat::GrandPrix
+        } else {
+            TrackFormat::Shuffled
+        };
+
+        if track_format == TrackFormat::GrandPrix && track_pool_id.is_some() {
+            return Err(Error::new(
+                "Grand Prix matches race whole cups and cannot use a track pool",
+            ));
+        }
+
+        let track_options = TrackOptions {
+            format: track_format,
+            track_pool_id: match track_pool_id {
+                Some(id) => Some(find_group_track_pool(&gql_ctx.pool, group_id, &id).await?.id),
+                None => None,
+            },
+        };
+
+        let match_result = match mode {
+            MatchMode::Teams => {
+                let options = TeamMatchOptions {
+                    num_races,
+                    players_per_race,
+                    num_teams,
+                    balancing_strategy: balancing_strategy.into(),
+                    constraints: &constraints,
+                    points_table_id,
+                    track_options,
+                };
+                match_service::create_match_with_rounds(
+                    &gql_ctx.pool,
+                    group_id,
+                    tournament_uuid,
+                    &player_uuids,
+                    &options,
+                    &gql_ctx.notification_manager,
+                )
+                .await?
+            }
+            MatchMode::FreeForAll => {
+                match_service::create_free_for_all_match(
+                    &gql_ctx.pool,
+                    &tournament,
+                    &player_uuids,
+                    num_races,
+                    points_table_id,
+                    track_options,
+                    &gql_ctx.notification_manager,
+                )
+                .await?
+            }
+        };
 
         Ok(Match::from(match_result))
     }
@@ -101,7 +212,7 @@ impl MatchesMutation {
 
         if has_results {
             return Err(Error::new(
-                "Cannot cancel match: race results have been recorded",
+                "Cannot cancel match: race results have been recorded; undo them with undoLastRound first",
             ));
         }
 
@@ -113,3 +224,15 @@ impl MatchesMutation {
         Ok(true)
     }
 }
+
+/// Parses a keep-together or keep-apart pair, whose players must both be in
+/// the match.
+fn parse_match_pair(pair: &PlayerPairInput, player_ids: &[Uuid]) -> Result<(Uuid, Uuid)> {
+    let (player_a, player_b) = pair.parse()?;
+    if !player_ids.contains(&player_a) || !player_ids.contains(&player_b) {
+        return Err(Error::new(
+            "Players kept together or apart must be in the match",
+        ));
+    }
+    Ok((player_a, player_b))
+}
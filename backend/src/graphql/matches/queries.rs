@@ -1,6 +1,7 @@
 use crate::graphql::context::GraphQLContext;
-use crate::graphql::matches::types::Match;
+use crate::graphql::matches::types::{Match, RoundPrediction};
 use crate::models;
+use crate::services::prediction;
 use async_graphql::*;
 use uuid::Uuid;
 
@@ -31,4 +32,32 @@ impl MatchesQuery {
 
         Ok(Match::from(match_record))
     }
+
+    /// Predicts a round's results and each team's chance of winning the match
+    async fn predict_round(
+        &self,
+        ctx: &Context<'_>,
+        #[graphql(desc = "The match ID")] match_id: ID,
+        #[graphql(desc = "The round number")] round_number: i32,
+    ) -> Result<RoundPrediction> {
+        let gql_ctx = ctx.data::<GraphQLContext>()?;
+        let group_id = gql_ctx.authenticated_group_id()?;
+
+        let match_uuid = Uuid::parse_str(&match_id).map_err(|_| Error::new("Invalid match ID"))?;
+
+        let match_record = models::Match::find_by_id(&gql_ctx.pool, match_uuid)
+            .await?
+            .ok_or_else(|| Error::new("Match not found"))?;
+
+        if match_record.group_id != group_id {
+            return Err(Error::new("Unauthorized").extend_with(|_, e| {
+                e.set("code", "UNAUTHORIZED");
+            }));
+        }
+
+        let prediction =
+            prediction::predict_round(&gql_ctx.pool, &match_record, round_number).await?;
+
+        Ok(RoundPrediction::from(prediction))
+    }
 }
@@ -2,7 +2,51 @@ use async_graphql::*;
 use chrono::{DateTime, Utc};
 use uuid::Uuid;
 
+use crate::graphql::cups::types::MatchCup;
+use crate::graphql::players::types::Player;
+use crate::graphql::points_tables::types::PointsTable;
+use crate::graphql::track_pools::types::TrackPool;
 use crate::graphql::results::types::PlayerMatchResult;
+use crate::services::{match_service, prediction, score_calculation, team_allocation};
+
+/// How players are split into teams when a match is created
+#[derive(Clone, Copy, Enum, PartialEq, Eq)]
+pub enum BalancingStrategy {
+    /// Highest ELO first onto the team with the lowest total ELO
+    Greedy,
+    /// Smallest spread of team average ELO and expected race points
+    Optimal,
+    /// Random teams of balanced sizes
+    Random,
+}
+
+impl From<BalancingStrategy> for team_allocation::BalancingStrategy {
+    fn from(strategy: BalancingStrategy) -> Self {
+        match strategy {
+            BalancingStrategy::Greedy => Self::Greedy,
+            BalancingStrategy::Optimal => Self::Optimal,
+            BalancingStrategy::Random => Self::Random,
+        }
+    }
+}
+
+/// Whether a match is raced in teams or every player for themselves
+#[derive(Clone, Copy, Enum, PartialEq, Eq)]
+pub enum MatchMode {
+    /// Players are split into teams scored together
+    Teams,
+    /// No teams: every player races every race and scores individual points
+    FreeForAll,
+}
+
+impl From<crate::models::MatchMode> for MatchMode {
+    fn from(mode: crate::models::MatchMode) -> Self {
+        match mode {
+            crate::models::MatchMode::Teams => Self::Teams,
+            crate::models::MatchMode::FreeForAll => Self::FreeForAll,
+        }
+    }
+}
 
 #[derive(Clone)]
 pub struct Match {
@@ -12,6 +56,9 @@ pub struct Match {
     pub time: DateTime<Utc>,
     pub num_of_rounds: i32,
     pub completed: bool,
+    pub points_table_id: Uuid,
+    pub mode: MatchMode,
+    pub track_pool_id: Option<Uuid>,
 }
 
 impl From<crate::models::Match> for Match {
@@ -23,6 +70,9 @@ impl From<crate::models::Match> for Match {
             time: model.time,
             num_of_rounds: model.num_of_rounds,
             completed: model.completed,
+            points_table_id: model.points_table_id,
+            mode: model.mode.into(),
+            track_pool_id: model.track_pool_id,
         }
     }
 }
@@ -45,12 +95,48 @@ impl Match {
         self.num_of_rounds
     }
 
+    async fn mode(&self) -> MatchMode {
+        self.mode
+    }
+
+    /// The points table team scores are calculated with
+    async fn points_table(&self, ctx: &Context<'_>) -> Result<PointsTable> {
+        let context = ctx.data_unchecked::<crate::graphql::GraphQLContext>();
+
+        let table = context
+            .points_table_loader
+            .load_one(self.points_table_id)
+            .await?
+            .ok_or_else(|| Error::new("Points table not found"))?;
+
+        Ok(PointsTable::from(table))
+    }
+
+    /// The track pool the match's tracks were drawn from; null for every
+    /// active track
+    async fn track_pool(&self, ctx: &Context<'_>) -> Result<Option<TrackPool>> {
+        let Some(track_pool_id) = self.track_pool_id else {
+            return Ok(None);
+        };
+
+        let context = ctx.data_unchecked::<crate::graphql::GraphQLContext>();
+
+        let track_pool = context
+            .track_pool_loader
+            .load_one(track_pool_id)
+            .await?
+            .map(TrackPool::from);
+
+        Ok(track_pool)
+    }
+
     async fn rounds(&self, ctx: &Context<'_>) -> Result<Vec<crate::graphql::rounds::Round>> {
         let context = ctx.data_unchecked::<crate::graphql::GraphQLContext>();
 
         let rounds_with_data = crate::models::Round::get_by_match_with_tracks_and_results(
             &context.pool,
             self.id,
+            self.group_id,
         )
         .await?;
 
@@ -67,6 +153,16 @@ impl Match {
             .collect())
     }
 
+    /// Cups raced in a Grand Prix match, in race order, with standings under
+    /// the match's points table; empty for other matches
+    async fn cups(&self, ctx: &Context<'_>) -> Result<Vec<MatchCup>> {
+        let context = ctx.data_unchecked::<crate::graphql::GraphQLContext>();
+
+        let standings = score_calculation::calculate_cup_standings(&context.pool, self.id).await?;
+
+        Ok(standings.into_iter().map(MatchCup::from).collect())
+    }
+
     async fn teams(&self, ctx: &Context<'_>) -> Result<Vec<crate::graphql::teams::Team>> {
         let context = ctx.data_unchecked::<crate::graphql::GraphQLContext>();
 
@@ -82,6 +178,24 @@ impl Match {
         self.completed
     }
 
+    /// Share of teammate pairs who had not been teammates in earlier matches
+    /// of the tournament, from 0.0 (all repeats) to 1.0 (all new pairings)
+    async fn pairing_freshness(&self, ctx: &Context<'_>) -> Result<f64> {
+        let context = ctx.data_unchecked::<crate::graphql::GraphQLContext>();
+
+        let teams = crate::models::Team::get_by_match_with_players(&context.pool, self.id).await?;
+        let history =
+            match_service::find_pairing_history(&context.pool, self.tournament_id, Some(self.time))
+                .await?;
+
+        let player_ids: Vec<Vec<Uuid>> = teams
+            .iter()
+            .map(|(_, players)| players.iter().map(|p| p.id).collect())
+            .collect();
+
+        Ok(history.freshness(&player_ids))
+    }
+
     async fn player_results(&self, ctx: &Context<'_>) -> Result<Vec<PlayerMatchResult>> {
         let context = ctx.data_unchecked::<crate::graphql::GraphQLContext>();
 
@@ -108,3 +222,147 @@ impl Match {
             .collect())
     }
 }
+
+/// Predicted outcome of a round and of the rest of its match
+#[derive(Clone)]
+pub struct RoundPrediction {
+    pub match_id: Uuid,
+    pub round_number: i32,
+    pub players: Vec<PlayerRacePrediction>,
+    pub teams: Vec<TeamWinProbability>,
+}
+
+impl From<prediction::RoundPrediction> for RoundPrediction {
+    fn from(model: prediction::RoundPrediction) -> Self {
+        Self {
+            match_id: model.match_id,
+            round_number: model.round_number,
+            players: model.players.into_iter().map(PlayerRacePrediction::from).collect(),
+            teams: model.teams.into_iter().map(TeamWinProbability::from).collect(),
+        }
+    }
+}
+
+#[Object]
+impl RoundPrediction {
+    async fn match_id(&self) -> ID {
+        ID(self.match_id.to_string())
+    }
+
+    async fn round_number(&self) -> i32 {
+        self.round_number
+    }
+
+    /// Predictions for each player assigned to the round
+    async fn players(&self) -> &[PlayerRacePrediction] {
+        &self.players
+    }
+
+    /// Win probability of each team in the match
+    async fn teams(&self) -> &[TeamWinProbability] {
+        &self.teams
+    }
+}
+
+#[derive(Clone)]
+pub struct PlayerRacePrediction {
+    pub player_id: Uuid,
+    pub expected_position: f64,
+    pub expected_points: f64,
+    pub elo_change_if_first: i32,
+    pub elo_change_if_median: i32,
+    pub elo_change_if_last: i32,
+}
+
+impl From<prediction::PlayerPrediction> for PlayerRacePrediction {
+    fn from(model: prediction::PlayerPrediction) -> Self {
+        Self {
+            player_id: model.player_id,
+            expected_position: model.expected_position,
+            expected_points: model.expected_points,
+            elo_change_if_first: model.elo_change_if_first,
+            elo_change_if_median: model.elo_change_if_median,
+            elo_change_if_last: model.elo_change_if_last,
+        }
+    }
+}
+
+#[Object]
+impl PlayerRacePrediction {
+    async fn player(&self, ctx: &Context<'_>) -> Result<Player> {
+        let context = ctx.data_unchecked::<crate::graphql::GraphQLContext>();
+
+        let player = context
+            .player_loader
+            .load_one(self.player_id)
+            .await?
+            .ok_or_else(|| Error::new("Player not found"))?;
+
+        Ok(Player::from(player))
+    }
+
+    /// Average finishing position, including CPUs
+    async fn expected_position(&self) -> f64 {
+        self.expected_position
+    }
+
+    async fn expected_points(&self) -> f64 {
+        self.expected_points
+    }
+
+    /// All-time ELO change for finishing first
+    async fn elo_change_if_first(&self) -> i32 {
+        self.elo_change_if_first
+    }
+
+    /// All-time ELO change for finishing mid-field
+    async fn elo_change_if_median(&self) -> i32 {
+        self.elo_change_if_median
+    }
+
+    /// All-time ELO change for finishing last
+    async fn elo_change_if_last(&self) -> i32 {
+        self.elo_change_if_last
+    }
+}
+
+#[derive(Clone)]
+pub struct TeamWinProbability {
+    pub team_id: Uuid,
+    pub current_points: i32,
+    pub expected_points: f64,
+    pub win_probability: f64,
+}
+
+impl From<prediction::TeamPrediction> for TeamWinProbability {
+    fn from(model: prediction::TeamPrediction) -> Self {
+        Self {
+            team_id: model.team_id,
+            current_points: model.current_points,
+            expected_points: model.expected_points,
+            win_probability: model.win_probability,
+        }
+    }
+}
+
+#[Object]
+impl TeamWinProbability {
+    async fn team_id(&self) -> ID {
+        ID(self.team_id.to_string())
+    }
+
+    /// Points scored in recorded rounds
+    async fn current_points(&self) -> i32 {
+        self.current_points
+    }
+
+    /// Points expected once every round is raced
+    async fn expected_points(&self) -> f64 {
+        self.expected_points
+    }
+
+    /// Probability (0 to 1) of winning the match
+    async fn win_probability(&self) -> f64 {
+        self.win_probability
+    }
+}
@@ -1,15 +1,19 @@
 pub mod auth;
+pub mod builds;
 pub mod context;
+pub mod cups;
 pub mod groups;
 pub mod lobby;
 pub mod matches;
 pub mod players;
+pub mod points_tables;
 pub mod results;
 pub mod rounds;
 pub mod schema;
 pub mod subscriptions;
 pub mod teams;
 pub mod tournaments;
+pub mod track_pools;
 pub mod tracks;
 
 pub use context::GraphQLContext;
@@ -1,6 +1,6 @@
 
 use crate::db::DbPool;
-use crate::models::Player;
+use crate::models::{Player, PlayerActivity};
 use async_graphql::dataloader::*;
 use std::collections::HashMap;
 use tracing::instrument;
@@ -66,6 +66,33 @@ impl Loader<Uuid> for PlayersByGroupLoader {
     }
 }
 
+pub struct PlayerActivityLoader {
+    pool: DbPool,
+}
+
+impl PlayerActivityLoader {
+    pub fn new(pool: DbPool) -> Self {
+        Self { pool }
+    }
+}
+
+impl Loader<Uuid> for PlayerActivityLoader {
+    type Value = PlayerActivity;
+    type Error = std::sync::Arc<sqlx::Error>;
+
+    #[instrument(level = "debug", skip(self), fields(batch_size = keys.len()))]
+    async fn load(&self, keys: &[Uuid]) -> Result<HashMap<Uuid, Self::Value>, Self::Error> {
+        let activity = Player::find_activity_by_ids(&self.pool, keys)
+            .await
+            .map_err(std::sync::Arc::new)?;
+
+        Ok(activity
+            .into_iter()
+            .map(|activity| (activity.player_id, activity))
+            .collect())
+    }
+}
+
 pub struct PlayerActiveTournamentEloLoader {
     pool: DbPool,
 }
@@ -3,7 +3,9 @@ pub mod mutations;
 pub mod queries;
 pub mod types;
 
-pub use loaders::{PlayerActiveTournamentEloLoader, PlayerLoader, PlayersByGroupLoader};
+pub use loaders::{
+    PlayerActiveTournamentEloLoader, PlayerActivityLoader, PlayerLoader, PlayersByGroupLoader,
+};
 pub use mutations::PlayersMutation;
 pub use queries::PlayersQuery;
 pub use types::Player;
@@ -1,8 +1,13 @@
+use crate::config::Config;
 use crate::graphql::context::GraphQLContext;
-use crate::graphql::players::types::Player;
+use crate::graphql::players::types::{
+    PairConstraintKind, Player, PlayerPairConstraint, PlayerPairInput, find_group_player,
+};
 use crate::models;
+use crate::services::{avatar, avatar_storage, player_merge, tournament_completion};
 use crate::services::validation::validate_name;
 use async_graphql::*;
+use uuid::Uuid;
 
 #[derive(Default)]
 pub struct PlayersMutation;
@@ -13,6 +18,11 @@ impl PlayersMutation {
         &self,
         ctx: &Context<'_>,
         #[graphql(desc = "The player name")] name: String,
+        #[graphql(
+            desc = "Whether the player is a guest who stays off the leaderboards",
+            default = false
+        )]
+        guest: bool,
     ) -> Result<Player> {
         let gql_ctx = ctx.data::<GraphQLContext>()?;
         let group_id = gql_ctx.authenticated_group_id()?;
@@ -20,8 +30,230 @@ impl PlayersMutation {
         // Validate input
         validate_name(&name, "Player name")?;
 
-        let player = models::Player::create(&gql_ctx.pool, group_id, name.trim()).await?;
+        let player = if guest {
+            models::Player::create_guest(&gql_ctx.pool, group_id, name.trim()).await?
+        } else {
+            models::Player::create(&gql_ctx.pool, group_id, name.trim()).await?
+        };
 
         Ok(Player::from(player))
     }
+
+    /// Make a guest a full player, keeping their races and ratings. Completed
+    /// tournaments the guest raced in have their winner and stats recalculated
+    /// to include them.
+    async fn promote_guest_player(
+        &self,
+        ctx: &Context<'_>,
+        #[graphql(desc = "The guest player ID")] player_id: ID,
+    ) -> Result<Player> {
+        let gql_ctx = ctx.data::<GraphQLContext>()?;
+        let group_id = gql_ctx.authenticated_group_id()?;
+
+        let player = find_group_player(&gql_ctx.pool, group_id, &player_id).await?;
+
+        if !player.guest {
+            return Err(Error::new("Player is not a guest"));
+        }
+
+        let mut tx = gql_ctx.pool.begin().await?;
+
+        let player = models::Player::promote_guest(&mut *tx, player.id).await?;
+
+        let tournament_ids =
+            tournament_completion::find_completed_tournaments_with_player(&mut tx, player.id)
+                .await?;
+        for tournament_id in tournament_ids {
+            tournament_completion::recalculate_completion(&mut tx, tournament_id).await?;
+        }
+
+        tx.commit().await?;
+
+        Ok(Player::from(player))
+    }
+
+    /// Rename one of the current group's players, keeping the old name in
+    /// their name history
+    async fn rename_player(
+        &self,
+        ctx: &Context<'_>,
+        #[graphql(desc = "The player ID")] player_id: ID,
+        #[graphql(desc = "The player's new name")] name: String,
+    ) -> Result<Player> {
+        let gql_ctx = ctx.data::<GraphQLContext>()?;
+        let group_id = gql_ctx.authenticated_group_id()?;
+
+        let player = find_group_player(&gql_ctx.pool, group_id, &player_id).await?;
+
+        validate_name(&name, "Player name")?;
+        let name = name.trim();
+
+        if name == player.name {
+            return Ok(Player::from(player));
+        }
+
+        if models::Player::is_name_taken(&gql_ctx.pool, group_id, name, Some(player.id)).await? {
+            return Err(Error::new("A player with this name already exists"));
+        }
+
+        let player = models::Player::rename(&gql_ctx.pool, player.id, name).await?;
+
+        Ok(Player::from(player))
+    }
+
+    /// Disable or re-enable one of the current group's players.
+    ///
+    /// Disabled players are hidden from player selection, but their matches
+    /// and tournaments keep showing them.
+    async fn set_player_disabled(
+        &self,
+        ctx: &Context<'_>,
+        #[graphql(desc = "The player ID")] player_id: ID,
+        #[graphql(desc = "Whether the player is disabled")] disabled: bool,
+    ) -> Result<Player> {
+        let gql_ctx = ctx.data::<GraphQLContext>()?;
+        let group_id = gql_ctx.authenticated_group_id()?;
+
+        let player = find_group_player(&gql_ctx.pool, group_id, &player_id).await?;
+
+        let player = models::Player::set_disabled(&gql_ctx.pool, player.id, disabled).await?;
+
+        Ok(Player::from(player))
+    }
+
+    /// Delete one of the current group's players who has never raced
+    async fn delete_player(
+        &self,
+        ctx: &Context<'_>,
+        #[graphql(desc = "The player ID")] player_id: ID,
+    ) -> Result<bool> {
+        let gql_ctx = ctx.data::<GraphQLContext>()?;
+        let group_id = gql_ctx.authenticated_group_id()?;
+
+        let player = find_group_player(&gql_ctx.pool, group_id, &player_id).await?;
+
+        if models::Player::has_history(&gql_ctx.pool, player.id).await? {
+            return Err(Error::new(
+                "Cannot delete player: they have match history. Disable them instead",
+            ));
+        }
+
+        models::Player::delete(&gql_ctx.pool, player.id).await?;
+
+        Ok(true)
+    }
+
+    /// Upload a PNG, JPEG or WebP image (up to 5 MB) as a player's avatar,
+    /// replacing any earlier upload.
+    ///
+    /// The image is cropped to a square and served at
+    /// `/avatars/{size}/{avatarFilename}` for sizes 64, 128 and 256.
+    async fn upload_player_avatar(
+        &self,
+        ctx: &Context<'_>,
+        #[graphql(desc = "The player ID")] player_id: ID,
+        #[graphql(desc = "The image file")] file: Upload,
+    ) -> Result<Player> {
+        let gql_ctx = ctx.data::<GraphQLContext>()?;
+        let group_id = gql_ctx.authenticated_group_id()?;
+        let storage = avatar_storage::from_config(ctx.data::<Config>()?);
+
+        let player = find_group_player(&gql_ctx.pool, group_id, &player_id).await?;
+
+        let upload = file.value(ctx)?.into_read();
+
+        let player =
+            avatar::set_player_avatar(&gql_ctx.pool, storage.as_ref(), &player, upload).await?;
+
+        Ok(Player::from(player))
+    }
+
+    /// Remove a player's avatar
+    async fn remove_player_avatar(
+        &self,
+        ctx: &Context<'_>,
+        #[graphql(desc = "The player ID")] player_id: ID,
+    ) -> Result<Player> {
+        let gql_ctx = ctx.data::<GraphQLContext>()?;
+        let group_id = gql_ctx.authenticated_group_id()?;
+        let storage = avatar_storage::from_config(ctx.data::<Config>()?);
+
+        let player = find_group_player(&gql_ctx.pool, group_id, &player_id).await?;
+
+        let player = avatar::remove_player_avatar(&gql_ctx.pool, storage.as_ref(), &player).await?;
+
+        Ok(Player::from(player))
+    }
+
+    /// Merge a duplicate player into another player of the current group.
+    ///
+    /// The source player's history moves to the target, the source is
+    /// deleted and the group's ratings are recomputed. In matches both
+    /// players took part in, the target's results are kept.
+    async fn merge_players(
+        &self,
+        ctx: &Context<'_>,
+        #[graphql(desc = "The duplicate player to merge and delete")] source_id: ID,
+        #[graphql(desc = "The player to keep")] target_id: ID,
+    ) -> Result<Player> {
+        let gql_ctx = ctx.data::<GraphQLContext>()?;
+        let group_id = gql_ctx.authenticated_group_id()?;
+
+        let source = find_group_player(&gql_ctx.pool, group_id, &source_id).await?;
+        let target = find_group_player(&gql_ctx.pool, group_id, &target_id).await?;
+
+        let player =
+            player_merge::merge_players(&gql_ctx.pool, group_id, source.id, target.id).await?;
+
+        Ok(Player::from(player))
+    }
+
+    /// Keep two players together or apart in every match of the group,
+    /// replacing any earlier constraint on the pair.
+    async fn set_player_pair_constraint(
+        &self,
+        ctx: &Context<'_>,
+        #[graphql(desc = "The two players")] pair: PlayerPairInput,
+        #[graphql(desc = "Whether the players must or must not share a team")]
+        kind: PairConstraintKind,
+    ) -> Result<PlayerPairConstraint> {
+        let gql_ctx = ctx.data::<GraphQLContext>()?;
+        let group_id = gql_ctx.authenticated_group_id()?;
+
+        let (player_a, player_b) = pair.parse()?;
+        let players = models::Player::find_by_ids(&gql_ctx.pool, &[player_a, player_b]).await?;
+        if players.len() != 2 || players.iter().any(|p| p.group_id != group_id) {
+            return Err(Error::new("Player not found"));
+        }
+
+        let constraint = models::PlayerPairConstraint::upsert(
+            &gql_ctx.pool,
+            group_id,
+            player_a,
+            player_b,
+            kind.into(),
+        )
+        .await?;
+
+        Ok(PlayerPairConstraint::from(constraint))
+    }
+
+    async fn remove_player_pair_constraint(
+        &self,
+        ctx: &Context<'_>,
+        #[graphql(desc = "The constraint ID")] constraint_id: ID,
+    ) -> Result<bool> {
+        let gql_ctx = ctx.data::<GraphQLContext>()?;
+        let group_id = gql_ctx.authenticated_group_id()?;
+
+        let constraint_uuid =
+            Uuid::parse_str(&constraint_id).map_err(|_| Error::new("Invalid constraint ID"))?;
+
+        let constraint = models::PlayerPairConstraint::find_by_id(&gql_ctx.pool, constraint_uuid)
+            .await?
+            .filter(|constraint| constraint.group_id == group_id)
+            .ok_or_else(|| Error::new("Constraint not found"))?;
+
+        Ok(models::PlayerPairConstraint::delete(&gql_ctx.pool, constraint.id).await?)
+    }
 }
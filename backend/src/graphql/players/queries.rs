@@ -1,5 +1,5 @@
 use crate::graphql::context::GraphQLContext;
-use crate::graphql::players::types::Player;
+use crate::graphql::players::types::{Player, PlayerPairConstraint};
 use crate::models;
 use async_graphql::*;
 use uuid::Uuid;
@@ -9,15 +9,39 @@ pub struct PlayersQuery;
 
 #[Object]
 impl PlayersQuery {
-    async fn players(&self, ctx: &Context<'_>) -> Result<Vec<Player>> {
+    async fn players(
+        &self,
+        ctx: &Context<'_>,
+        #[graphql(desc = "Whether to include disabled players", default = false)]
+        include_disabled: bool,
+        #[graphql(desc = "Whether to include guest players", default = false)]
+        include_guests: bool,
+    ) -> Result<Vec<Player>> {
         let gql_ctx = ctx.data::<GraphQLContext>()?;
         let group_id = gql_ctx.authenticated_group_id()?;
 
-        let players = models::Player::find_by_group_id(&gql_ctx.pool, group_id).await?;
+        let players = models::Player::find_by_group_id(
+            &gql_ctx.pool,
+            group_id,
+            include_disabled,
+            include_guests,
+        )
+        .await?;
 
         Ok(players.into_iter().map(Player::from).collect())
     }
 
+    /// Pairs of players the current group always keeps together or apart
+    async fn player_pair_constraints(&self, ctx: &Context<'_>) -> Result<Vec<PlayerPairConstraint>> {
+        let gql_ctx = ctx.data::<GraphQLContext>()?;
+        let group_id = gql_ctx.authenticated_group_id()?;
+
+        let constraints =
+            models::PlayerPairConstraint::find_by_group_id(&gql_ctx.pool, group_id).await?;
+
+        Ok(constraints.into_iter().map(PlayerPairConstraint::from).collect())
+    }
+
     async fn player_by_id(&self, ctx: &Context<'_>, player_id: ID) -> Result<Option<Player>> {
         let gql_ctx = ctx.data::<GraphQLContext>()?;
         let group_id = gql_ctx.authenticated_group_id()?;
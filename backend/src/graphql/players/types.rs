@@ -1,3 +1,4 @@
+use crate::graphql::builds::types::{CharacterStats, RaceBuild};
 use crate::graphql::context::GraphQLContext;
 use crate::graphql::tournaments::types::PlayerTournamentPlacing;
 use crate::models::{self, PlayerMatchScore, PlayerRaceScore, Tournament};
@@ -11,7 +12,10 @@ pub struct Player {
     pub group_id: Uuid,
     pub name: String,
     pub elo_rating: i32,
+    pub rating_deviation: f64,
     pub avatar_filename: Option<String>,
+    pub disabled: bool,
+    pub guest: bool,
 }
 
 impl From<crate::models::Player> for Player {
@@ -21,16 +25,56 @@ impl From<crate::models::Player> for Player {
             group_id: model.group_id,
             name: model.name,
             elo_rating: model.elo_rating,
+            rating_deviation: model.rating_deviation,
             avatar_filename: model.avatar_filename,
+            disabled: model.disabled,
+            guest: model.guest,
+        }
+    }
+}
+
+/// A name a player went by before being renamed
+#[derive(Clone, SimpleObject)]
+pub struct PlayerNameChange {
+    pub previous_name: String,
+    /// When the player stopped going by this name
+    pub changed_at: DateTime<Utc>,
+}
+
+impl From<models::PlayerNameChange> for PlayerNameChange {
+    fn from(model: models::PlayerNameChange) -> Self {
+        Self {
+            previous_name: model.previous_name,
+            changed_at: model.changed_at,
         }
     }
 }
 
 #[derive(Clone, SimpleObject)]
 pub struct PlayerTrackStats {
+    pub track_id: ID,
     pub track_name: String,
     pub average_position: f64,
     pub races_played: i64,
+    /// The player's ELO rating on this track
+    pub track_elo: Option<i32>,
+    /// The track's chaos index (see `Track.chaosIndex`)
+    pub chaos_index: Option<f64>,
+}
+
+/// What produced a point in a player's ELO history
+#[derive(Clone, Copy, Enum, PartialEq, Eq)]
+pub enum EloHistoryEventKind {
+    Race,
+    /// Inactivity decay
+    Decay,
+}
+
+#[derive(Clone, SimpleObject)]
+pub struct PlayerEloHistoryPoint {
+    pub timestamp: String,
+    pub elo: i32,
+    pub kind: EloHistoryEventKind,
 }
 
 #[derive(Clone, SimpleObject)]
@@ -60,6 +104,80 @@ impl Player {
         self.elo_rating
     }
 
+    /// Earlier names, most recent first
+    async fn name_history(&self, ctx: &Context<'_>) -> Result<Vec<PlayerNameChange>> {
+        let gql_ctx = ctx.data::<GraphQLContext>()?;
+
+        let changes = models::PlayerNameChange::find_by_player_id(&gql_ctx.pool, self.id).await?;
+
+        Ok(changes.into_iter().map(PlayerNameChange::from).collect())
+    }
+
+    /// Whether the player is hidden from player selection
+    async fn disabled(&self) -> bool {
+        self.disabled
+    }
+
+    /// Whether the player is a guest, rated as an opponent but left off the
+    /// leaderboards
+    async fn is_guest(&self) -> bool {
+        self.guest
+    }
+
+    /// How uncertain the all-time rating is (Glicko-2 rating deviation)
+    async fn rating_deviation(&self) -> f64 {
+        self.rating_deviation
+    }
+
+    /// All-time recorded races
+    async fn races_played(&self, ctx: &Context<'_>) -> Result<i64> {
+        let gql_ctx = ctx.data::<GraphQLContext>()?;
+
+        let activity = gql_ctx.player_activity_loader.load_one(self.id).await?;
+
+        Ok(activity.map_or(0, |activity| activity.races_played))
+    }
+
+    /// Whether the player has fewer races than the group's provisional race count
+    async fn provisional(&self, ctx: &Context<'_>) -> Result<bool> {
+        let gql_ctx = ctx.data::<GraphQLContext>()?;
+
+        let activity = gql_ctx.player_activity_loader.load_one(self.id).await?;
+
+        Ok(activity.is_none_or(|activity| activity.is_provisional()))
+    }
+
+    /// Whether the player has not raced for the group's inactivity weeks
+    async fn inactive(&self, ctx: &Context<'_>) -> Result<bool> {
+        let gql_ctx = ctx.data::<GraphQLContext>()?;
+
+        let activity = gql_ctx.player_activity_loader.load_one(self.id).await?;
+
+        Ok(activity.is_some_and(|activity| activity.is_inactive(Utc::now())))
+    }
+
+    /// All-time ELO after every race and inactivity decay
+    async fn elo_history(&self, ctx: &Context<'_>) -> Result<Vec<PlayerEloHistoryPoint>> {
+        let gql_ctx = ctx.data::<GraphQLContext>()?;
+
+        let history =
+            PlayerRaceScore::find_all_time_elo_history_by_player_id(&gql_ctx.pool, self.id)
+                .await?;
+
+        Ok(history
+            .into_iter()
+            .map(|(elo, timestamp, is_decay)| PlayerEloHistoryPoint {
+                timestamp,
+                elo,
+                kind: if is_decay {
+                    EloHistoryEventKind::Decay
+                } else {
+                    EloHistoryEventKind::Race
+                },
+            })
+            .collect())
+    }
+
     async fn current_tournament_elo(&self, ctx: &Context<'_>) -> Result<Option<i32>> {
         let gql_ctx = ctx.data::<GraphQLContext>()?;
 
@@ -76,16 +194,44 @@ impl Player {
 
         let stats = PlayerRaceScore::find_track_stats_by_player(&gql_ctx.pool, self.id).await?;
 
+        let chaos_indexes = gql_ctx
+            .track_chaos_loader
+            .load_many(stats.iter().map(|s| s.track_id))
+            .await?;
+
         Ok(stats
             .into_iter()
             .map(|s| PlayerTrackStats {
+                track_id: ID(s.track_id.to_string()),
+                chaos_index: chaos_indexes.get(&s.track_id).map(|c| c.chaos_index),
                 track_name: s.track_name,
                 average_position: s.average_position,
                 races_played: s.races_played,
+                track_elo: s.track_elo,
             })
             .collect())
     }
 
+    /// Results by character, most raced first
+    async fn character_stats(&self, ctx: &Context<'_>) -> Result<Vec<CharacterStats>> {
+        let gql_ctx = ctx.data::<GraphQLContext>()?;
+
+        let stats =
+            PlayerRaceScore::find_character_stats_by_player(&gql_ctx.pool, self.id).await?;
+
+        Ok(stats.into_iter().map(CharacterStats::from).collect())
+    }
+
+    /// The character and parts the player has raced with most often
+    async fn most_picked_build(&self, ctx: &Context<'_>) -> Result<Option<RaceBuild>> {
+        let gql_ctx = ctx.data::<GraphQLContext>()?;
+
+        let build =
+            PlayerRaceScore::find_most_picked_build_by_player(&gql_ctx.pool, self.id).await?;
+
+        Ok(build.map(RaceBuild::from))
+    }
+
     async fn match_history(&self, ctx: &Context<'_>) -> Result<Vec<PlayerMatchHistoryEntry>> {
         let gql_ctx = ctx.data::<GraphQLContext>()?;
 
@@ -129,3 +275,112 @@ impl Player {
         Ok(placings.into_iter().map(PlayerTournamentPlacing::from).collect())
     }
 }
+
+/// Finds one of the group's players by ID.
+pub(crate) async fn find_group_player(
+    pool: &crate::db::DbPool,
+    group_id: Uuid,
+    id: &ID,
+) -> Result<models::Player> {
+    let uuid = Uuid::parse_str(id).map_err(|_| Error::new("Invalid player ID"))?;
+
+    models::Player::find_by_id(pool, uuid)
+        .await?
+        .filter(|player| player.group_id == group_id)
+        .ok_or_else(|| Error::new("Player not found"))
+}
+
+/// Whether a pair of players must or must not share a team
+#[derive(Clone, Copy, Enum, PartialEq, Eq)]
+pub enum PairConstraintKind {
+    Together,
+    Apart,
+}
+
+impl From<models::PairConstraintKind> for PairConstraintKind {
+    fn from(model: models::PairConstraintKind) -> Self {
+        match model {
+            models::PairConstraintKind::Together => Self::Together,
+            models::PairConstraintKind::Apart => Self::Apart,
+        }
+    }
+}
+
+impl From<PairConstraintKind> for models::PairConstraintKind {
+    fn from(kind: PairConstraintKind) -> Self {
+        match kind {
+            PairConstraintKind::Together => Self::Together,
+            PairConstraintKind::Apart => Self::Apart,
+        }
+    }
+}
+
+/// Two players to keep together or apart
+#[derive(InputObject)]
+pub struct PlayerPairInput {
+    pub first_player_id: ID,
+    pub second_player_id: ID,
+}
+
+impl PlayerPairInput {
+    /// Parses the pair's player IDs, rejecting a player paired with themselves.
+    pub fn parse(&self) -> Result<(Uuid, Uuid)> {
+        let parse = |id: &ID| Uuid::parse_str(id).map_err(|_| Error::new("Invalid player ID"));
+        let pair = (parse(&self.first_player_id)?, parse(&self.second_player_id)?);
+        if pair.0 == pair.1 {
+            return Err(Error::new("A player cannot be paired with themselves"));
+        }
+        Ok(pair)
+    }
+}
+
+/// A pair of players the group always keeps together or apart
+#[derive(Clone)]
+pub struct PlayerPairConstraint {
+    pub id: Uuid,
+    pub player_a_id: Uuid,
+    pub player_b_id: Uuid,
+    pub kind: PairConstraintKind,
+}
+
+impl From<models::PlayerPairConstraint> for PlayerPairConstraint {
+    fn from(model: models::PlayerPairConstraint) -> Self {
+        Self {
+            id: model.id,
+            player_a_id: model.player_a_id,
+            player_b_id: model.player_b_id,
+            kind: model.kind.into(),
+        }
+    }
+}
+
+#[Object]
+impl PlayerPairConstraint {
+    async fn id(&self) -> ID {
+        ID(self.id.to_string())
+    }
+
+    async fn kind(&self) -> PairConstraintKind {
+        self.kind
+    }
+
+    async fn players(&self, ctx: &Context<'_>) -> Result<Vec<Player>> {
+        let context = ctx.data::<GraphQLContext>()?;
+
+        let players = context
+            .player_loader
+            .load_many([self.player_a_id, self.player_b_id])
+            .await?;
+
+        [self.player_a_id, self.player_b_id]
+            .iter()
+            .map(|id| {
+                players
+                    .get(id)
+                    .cloned()
+                    .map(Player::from)
+                    .ok_or_else(|| Error::new("Player not found"))
+            })
+            .collect()
+    }
+}
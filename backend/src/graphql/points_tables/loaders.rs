@@ -0,0 +1,32 @@
+use crate::db::DbPool;
+use crate::models::PointsTable;
+use async_graphql::dataloader::*;
+use std::collections::HashMap;
+use tracing::instrument;
+use uuid::Uuid;
+
+pub struct PointsTableLoader {
+    pool: DbPool,
+}
+
+impl PointsTableLoader {
+    pub fn new(pool: DbPool) -> Self {
+        Self { pool }
+    }
+}
+
+impl Loader<Uuid> for PointsTableLoader {
+    type Value = PointsTable;
+    type Error = std::sync::Arc<sqlx::Error>;
+
+    #[instrument(level = "debug", skip(self), fields(batch_size = keys.len()))]
+    async fn load(&self, keys: &[Uuid]) -> Result<HashMap<Uuid, Self::Value>, Self::Error> {
+        let tables = PointsTable::find_by_ids(&self.pool, keys)
+            .await
+            .map_err(std::sync::Arc::new)?;
+
+        let mapped = tables.into_iter().map(|table| (table.id, table)).collect();
+
+        Ok(mapped)
+    }
+}
@@ -0,0 +1,9 @@
+pub mod loaders;
+pub mod mutations;
+pub mod queries;
+pub mod types;
+
+pub use loaders::PointsTableLoader;
+pub use mutations::PointsTablesMutation;
+pub use queries::PointsTablesQuery;
+pub use types::PointsTable;
@@ -0,0 +1,62 @@
+use crate::graphql::context::GraphQLContext;
+use crate::graphql::points_tables::types::{PointsTable, find_available_points_table};
+use crate::models;
+use crate::services::validation::{validate_name, validate_points_table};
+use async_graphql::*;
+
+#[derive(Default)]
+pub struct PointsTablesMutation;
+
+#[Object]
+impl PointsTablesMutation {
+    /// Create a custom points table for the current group.
+    ///
+    /// Points tables cannot be edited once created, so matches scored with
+    /// them keep reproducible team scores.
+    async fn create_points_table(
+        &self,
+        ctx: &Context<'_>,
+        #[graphql(desc = "The table's name")] name: String,
+        #[graphql(desc = "Points per finishing position, starting with first place")]
+        points: Vec<i32>,
+    ) -> Result<PointsTable> {
+        let gql_ctx = ctx.data::<GraphQLContext>()?;
+        let group_id = gql_ctx.authenticated_group_id()?;
+
+        validate_name(&name, "Points table name")?;
+        validate_points_table(&points)?;
+
+        let table =
+            models::PointsTable::create(&gql_ctx.pool, group_id, name.trim(), &points).await?;
+
+        Ok(PointsTable::from(table))
+    }
+
+    /// Delete one of the current group's points tables that no tournament or
+    /// match uses.
+    async fn delete_points_table(
+        &self,
+        ctx: &Context<'_>,
+        #[graphql(desc = "The points table ID")] points_table_id: ID,
+    ) -> Result<bool> {
+        let gql_ctx = ctx.data::<GraphQLContext>()?;
+        let group_id = gql_ctx.authenticated_group_id()?;
+
+        let table =
+            find_available_points_table(&gql_ctx.pool, group_id, &points_table_id).await?;
+
+        if table.is_built_in() {
+            return Err(Error::new("Built-in points tables cannot be deleted"));
+        }
+
+        if models::PointsTable::is_in_use(&gql_ctx.pool, table.id).await? {
+            return Err(Error::new(
+                "Cannot delete points table: it is used by a tournament or match",
+            ));
+        }
+
+        models::PointsTable::delete(&gql_ctx.pool, table.id).await?;
+
+        Ok(true)
+    }
+}
@@ -0,0 +1,20 @@
+use crate::graphql::context::GraphQLContext;
+use crate::graphql::points_tables::types::PointsTable;
+use crate::models;
+use async_graphql::*;
+
+#[derive(Default)]
+pub struct PointsTablesQuery;
+
+#[Object]
+impl PointsTablesQuery {
+    /// Get the built-in points tables and the current group's own tables
+    async fn points_tables(&self, ctx: &Context<'_>) -> Result<Vec<PointsTable>> {
+        let gql_ctx = ctx.data::<GraphQLContext>()?;
+        let group_id = gql_ctx.authenticated_group_id()?;
+
+        let tables = models::PointsTable::find_available_to_group(&gql_ctx.pool, group_id).await?;
+
+        Ok(tables.into_iter().map(PointsTable::from).collect())
+    }
+}
@@ -0,0 +1,56 @@
+use async_graphql::*;
+use uuid::Uuid;
+
+#[derive(Clone)]
+pub struct PointsTable {
+    pub id: Uuid,
+    pub name: String,
+    pub points: Vec<i32>,
+    pub built_in: bool,
+}
+
+impl From<crate::models::PointsTable> for PointsTable {
+    fn from(model: crate::models::PointsTable) -> Self {
+        Self {
+            built_in: model.is_built_in(),
+            id: model.id,
+            name: model.name,
+            points: model.points,
+        }
+    }
+}
+
+#[Object]
+impl PointsTable {
+    async fn id(&self) -> ID {
+        ID(self.id.to_string())
+    }
+
+    async fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Points per finishing position, starting with first place
+    async fn points(&self) -> &[i32] {
+        &self.points
+    }
+
+    /// Whether this is a built-in table shared by every group
+    async fn built_in(&self) -> bool {
+        self.built_in
+    }
+}
+
+/// Loads a points table visible to a group, by GraphQL ID.
+pub(crate) async fn find_available_points_table(
+    pool: &crate::db::DbPool,
+    group_id: Uuid,
+    id: &ID,
+) -> Result<crate::models::PointsTable> {
+    let uuid = Uuid::parse_str(id).map_err(|_| Error::new("Invalid points table ID"))?;
+
+    crate::models::PointsTable::find_by_id(pool, uuid)
+        .await?
+        .filter(|table| table.is_available_to(group_id))
+        .ok_or_else(|| Error::new("Points table not found"))
+}
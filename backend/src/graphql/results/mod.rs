@@ -2,4 +2,4 @@ pub mod loaders;
 pub mod types;
 
 pub use loaders::{PlayerMatchScoresByMatchLoader, PlayerRaceScoresByRoundLoader, PlayerTeammateContributionLoader};
-pub use types::{PlayerMatchResult, PlayerRaceResult};
+pub use types::{PlayerMatchResult, PlayerRaceResult, ResultStatus};
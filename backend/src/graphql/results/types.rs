@@ -3,12 +3,48 @@ use crate::graphql::players::types::Player;
 use async_graphql::*;
 use uuid::Uuid;
 
+/// How a player's race ended
+#[derive(Clone, Copy, Enum, PartialEq, Eq)]
+pub enum ResultStatus {
+    Finished,
+    /// Did not finish; rated as last place and scores the points table's
+    /// last-place points
+    Dnf,
+    /// Rated as last place and scores no points
+    Disqualified,
+    /// The race does not change the player's ratings and scores no points
+    Disconnected,
+}
+
+impl From<crate::models::ResultStatus> for ResultStatus {
+    fn from(status: crate::models::ResultStatus) -> Self {
+        match status {
+            crate::models::ResultStatus::Finished => Self::Finished,
+            crate::models::ResultStatus::Dnf => Self::Dnf,
+            crate::models::ResultStatus::Disqualified => Self::Disqualified,
+            crate::models::ResultStatus::Disconnected => Self::Disconnected,
+        }
+    }
+}
+
+impl From<ResultStatus> for crate::models::ResultStatus {
+    fn from(status: ResultStatus) -> Self {
+        match status {
+            ResultStatus::Finished => Self::Finished,
+            ResultStatus::Dnf => Self::Dnf,
+            ResultStatus::Disqualified => Self::Disqualified,
+            ResultStatus::Disconnected => Self::Disconnected,
+        }
+    }
+}
+
 #[derive(Clone)]
 pub struct PlayerRaceResult {
     pub match_id: Uuid,
     pub round_number: i32,
     pub player_id: Uuid,
     pub position: i32,
+    pub status: crate::models::ResultStatus,
     pub all_time_elo_change: Option<i32>,
     pub all_time_elo_after: Option<i32>,
     pub tournament_elo_change: Option<i32>,
@@ -23,6 +59,7 @@ impl From<crate::models::PlayerRaceScore> for PlayerRaceResult {
             round_number: model.round_number,
             player_id: model.player_id,
             position: model.position,
+            status: model.status,
             all_time_elo_change: model.all_time_elo_change,
             all_time_elo_after: model.all_time_elo_after,
             tournament_elo_change: model.tournament_elo_change,
@@ -42,6 +79,7 @@ impl PlayerRaceResult {
             round_number: score.round_number,
             player_id: score.player_id,
             position: score.position,
+            status: score.status,
             all_time_elo_change: score.all_time_elo_change,
             all_time_elo_after: score.all_time_elo_after,
             tournament_elo_change: score.tournament_elo_change,
@@ -73,6 +111,11 @@ impl PlayerRaceResult {
         self.position
     }
 
+    /// How the race ended; players who did not finish are placed last
+    async fn status(&self) -> ResultStatus {
+        self.status.into()
+    }
+
     async fn tournament_elo_change(&self) -> Option<i32> {
         self.tournament_elo_change
     }
@@ -87,6 +130,7 @@ pub struct PlayerMatchResult {
     pub tournament_elo_change: i32,
     pub tournament_elo_from_races: i32,
     pub tournament_elo_from_contributions: i32,
+    pub points: Option<i32>,
     pub cached_player: Option<crate::models::Player>,
 }
 
@@ -100,6 +144,7 @@ impl From<crate::models::PlayerMatchScore> for PlayerMatchResult {
             tournament_elo_change: model.tournament_elo_change,
             tournament_elo_from_races: model.tournament_elo_from_races,
             tournament_elo_from_contributions: model.tournament_elo_from_contributions,
+            points: model.points,
             cached_player: None,
         }
     }
@@ -118,6 +163,7 @@ impl PlayerMatchResult {
             tournament_elo_change: score.tournament_elo_change,
             tournament_elo_from_races: score.tournament_elo_from_races,
             tournament_elo_from_contributions: score.tournament_elo_from_contributions,
+            points: score.points,
             cached_player: Some(player),
         }
     }
@@ -161,6 +207,11 @@ impl PlayerMatchResult {
         self.tournament_elo_from_contributions
     }
 
+    /// Points scored over the match; set once a free-for-all match completes
+    async fn points(&self) -> Option<i32> {
+        self.points
+    }
+
     async fn teammate_contribution(&self, ctx: &Context<'_>) -> Result<i32> {
         let gql_ctx = ctx.data::<GraphQLContext>()?;
 
@@ -1,7 +1,9 @@
 pub mod loaders;
 pub mod mutations;
+pub mod queries;
 pub mod types;
 
 pub use loaders::PlayersByRoundLoader;
 pub use mutations::RoundsMutation;
+pub use queries::RoundsQuery;
 pub use types::Round;
@@ -1,5 +1,7 @@
+use crate::graphql::builds::types::{RaceBuildInput, check_build_parts};
 use crate::graphql::context::GraphQLContext;
 use crate::graphql::matches::types::Match;
+use crate::graphql::results::types::ResultStatus;
 use crate::models;
 use crate::services::notification_manager::SlotAssignmentNotification;
 use crate::services::result_recording;
@@ -13,7 +15,90 @@ pub struct RoundsMutation;
 #[derive(InputObject)]
 pub struct PlayerResultInput {
     pub player_id: ID,
-    pub position: i32,
+    /// Finishing position; left out for players who did not finish
+    pub position: Option<i32>,
+    /// How the race ended for the player (defaults to finished)
+    pub status: Option<ResultStatus>,
+    /// The character and parts the player raced with
+    pub build: Option<RaceBuildInput>,
+}
+
+/// (player_id, position) pairs for players who finished and (player_id,
+/// status) pairs for players who did not.
+type ParsedResults = (Vec<(Uuid, i32)>, Vec<(Uuid, models::ResultStatus)>);
+
+/// Parses result inputs into finishers and non-finishers and validates positions.
+pub(crate) fn parse_player_results(results: &[PlayerResultInput]) -> Result<ParsedResults> {
+    let mut player_uuids_with_positions = Vec::new();
+    let mut non_finishers = Vec::new();
+
+    for r in results {
+        let uuid = Uuid::parse_str(&r.player_id).map_err(|_| Error::new("Invalid player ID"))?;
+        let status = r.status.map(models::ResultStatus::from).unwrap_or_default();
+
+        match (status, r.position) {
+            (models::ResultStatus::Finished, Some(position)) => {
+                player_uuids_with_positions.push((uuid, position))
+            }
+            (models::ResultStatus::Finished, None) => {
+                return Err(Error::new("Players who finished need a position"));
+            }
+            (_, Some(_)) => {
+                return Err(Error::new("Players who did not finish cannot have a position"));
+            }
+            (status, None) => non_finishers.push((uuid, status)),
+        }
+    }
+
+    let positions: Vec<i32> = player_uuids_with_positions
+        .iter()
+        .map(|(_, pos)| *pos)
+        .collect();
+    let unique_positions: std::collections::HashSet<i32> = positions.iter().copied().collect();
+    if unique_positions.len() != positions.len() {
+        return Err(Error::new("Duplicate positions are not allowed"));
+    }
+    if player_uuids_with_positions.is_empty() && non_finishers.is_empty() {
+        return Err(Error::new("At least one player result is required"));
+    }
+
+    Ok((player_uuids_with_positions, non_finishers))
+}
+
+/// IDs of every player in parsed results, finishers first.
+pub(crate) fn result_player_ids(
+    positions: &[(Uuid, i32)],
+    non_finishers: &[(Uuid, models::ResultStatus)],
+) -> Vec<Uuid> {
+    positions
+        .iter()
+        .map(|(uuid, _)| *uuid)
+        .chain(non_finishers.iter().map(|(uuid, _)| *uuid))
+        .collect()
+}
+
+/// Parses and validates the builds of result inputs that have one.
+async fn parse_race_builds(
+    pool: &crate::db::DbPool,
+    results: Vec<PlayerResultInput>,
+) -> Result<Vec<(Uuid, models::RaceBuild)>> {
+    let mut builds = Vec::new();
+
+    for result in results {
+        let Some(build) = result.build else {
+            continue;
+        };
+        let player_id =
+            Uuid::parse_str(&result.player_id).map_err(|_| Error::new("Invalid player ID"))?;
+        let build = build.into_build()?;
+        if !build.is_empty() {
+            builds.push((player_id, build));
+        }
+    }
+
+    check_build_parts(pool, &builds).await?;
+
+    Ok(builds)
 }
 
 #[Object]
@@ -30,30 +115,8 @@ impl RoundsMutation {
 
         let match_uuid = Uuid::parse_str(&match_id).map_err(|_| Error::new("Invalid match ID"))?;
 
-        let player_uuids_with_positions: Result<Vec<(Uuid, i32)>> = results
-            .iter()
-            .map(|r| {
-                Uuid::parse_str(&r.player_id)
-                    .map_err(|_| Error::new("Invalid player ID"))
-                    .map(|uuid| (uuid, r.position))
-            })
-            .collect();
-        let player_uuids_with_positions = player_uuids_with_positions?;
-
-        let positions: Vec<i32> = player_uuids_with_positions
-            .iter()
-            .map(|(_, pos)| *pos)
-            .collect();
-        if positions.iter().any(|&p| !(1..=24).contains(&p)) {
-            return Err(Error::new("Positions must be between 1 and 24"));
-        }
-        let unique_positions: std::collections::HashSet<i32> = positions.iter().copied().collect();
-        if unique_positions.len() != positions.len() {
-            return Err(Error::new("Duplicate positions are not allowed"));
-        }
-        if player_uuids_with_positions.is_empty() {
-            return Err(Error::new("At least one player result is required"));
-        }
+        let (player_uuids_with_positions, non_finishers) = parse_player_results(&results)?;
+        let builds = parse_race_builds(&gql_ctx.pool, results).await?;
 
         let match_record = models::Match::find_by_id(&gql_ctx.pool, match_uuid)
             .await?
@@ -79,18 +142,113 @@ impl RoundsMutation {
 
         let round_players =
             result_recording::get_round_players(&gql_ctx.pool, match_uuid, round_number).await?;
-        let player_uuids: Vec<Uuid> = player_uuids_with_positions
-            .iter()
-            .map(|(uuid, _)| *uuid)
-            .collect();
+        let player_uuids = result_player_ids(&player_uuids_with_positions, &non_finishers);
         result_recording::validate_players_in_round(&player_uuids, &round_players)?;
 
         let updated_match = result_recording::record_race_results(
             &gql_ctx.pool,
             group_id,
-            match_uuid,
-            round_number,
-            &player_uuids_with_positions,
+            &result_recording::RoundResults {
+                round_number,
+                results: &player_uuids_with_positions,
+                non_finishers: &non_finishers,
+                builds: &builds,
+            },
+            &match_record,
+            &gql_ctx.notification_manager,
+        )
+        .await?;
+
+        Ok(Match::from(updated_match))
+    }
+
+    /// Replace the positions and result statuses of an already recorded round.
+    ///
+    /// ELO is replayed for this round and every later race in the group, so
+    /// ratings, per-race ELO history, teammate contributions and match
+    /// aggregates end up as if the corrected positions had been recorded.
+    /// Builds given with the results replace the recorded ones; players
+    /// without a build keep theirs.
+    async fn amend_round_results(
+        &self,
+        ctx: &Context<'_>,
+        #[graphql(desc = "The match ID")] match_id: ID,
+        #[graphql(desc = "The round number")] round_number: i32,
+        #[graphql(desc = "Corrected player results for this round")] results: Vec<PlayerResultInput>,
+    ) -> Result<Match> {
+        let gql_ctx = ctx.data::<GraphQLContext>()?;
+        let group_id = gql_ctx.authenticated_group_id()?;
+
+        let match_uuid = Uuid::parse_str(&match_id).map_err(|_| Error::new("Invalid match ID"))?;
+
+        let (player_uuids_with_positions, non_finishers) = parse_player_results(&results)?;
+        let builds = parse_race_builds(&gql_ctx.pool, results).await?;
+
+        let match_record = models::Match::find_by_id(&gql_ctx.pool, match_uuid)
+            .await?
+            .ok_or_else(|| Error::new("Match not found"))?;
+
+        if match_record.group_id != group_id {
+            return Err(Error::new("Match not found"));
+        }
+
+        let round = models::Round::find_one(&gql_ctx.pool, match_uuid, round_number)
+            .await?
+            .ok_or_else(|| Error::new("Round not found"))?;
+
+        if !round.completed {
+            return Err(Error::new(
+                "Round has not been recorded yet; use recordRoundResults instead",
+            ));
+        }
+
+        let round_players =
+            result_recording::get_round_players(&gql_ctx.pool, match_uuid, round_number).await?;
+        let player_uuids = result_player_ids(&player_uuids_with_positions, &non_finishers);
+        result_recording::validate_players_in_round(&player_uuids, &round_players)?;
+
+        let updated_match = result_recording::amend_race_results(
+            &gql_ctx.pool,
+            group_id,
+            &result_recording::RoundResults {
+                round_number,
+                results: &player_uuids_with_positions,
+                non_finishers: &non_finishers,
+                builds: &builds,
+            },
+            &match_record,
+            &gql_ctx.notification_manager,
+        )
+        .await?;
+
+        Ok(Match::from(updated_match))
+    }
+
+    /// Undo the most recently recorded round of a match.
+    ///
+    /// Removes the round's results, reverts ELO by the stored deltas and
+    /// reopens the round (and the match, if it was completed).
+    async fn undo_last_round(
+        &self,
+        ctx: &Context<'_>,
+        #[graphql(desc = "The match ID")] match_id: ID,
+    ) -> Result<Match> {
+        let gql_ctx = ctx.data::<GraphQLContext>()?;
+        let group_id = gql_ctx.authenticated_group_id()?;
+
+        let match_uuid = Uuid::parse_str(&match_id).map_err(|_| Error::new("Invalid match ID"))?;
+
+        let match_record = models::Match::find_by_id(&gql_ctx.pool, match_uuid)
+            .await?
+            .ok_or_else(|| Error::new("Match not found"))?;
+
+        if match_record.group_id != group_id {
+            return Err(Error::new("Match not found"));
+        }
+
+        let updated_match = result_recording::undo_last_round(
+            &gql_ctx.pool,
+            group_id,
             &match_record,
             &gql_ctx.notification_manager,
         )
@@ -125,6 +283,12 @@ impl RoundsMutation {
             return Err(Error::new("Match not found"));
         }
 
+        if match_record.mode == models::MatchMode::FreeForAll {
+            return Err(Error::new(
+                "Every player races every race in a free-for-all match, so there is no one to swap in",
+            ));
+        }
+
         let round = models::Round::find_one(&gql_ctx.pool, match_uuid, round_number)
             .await?
             .ok_or_else(|| Error::new("Round not found"))?;
@@ -133,7 +297,7 @@ impl RoundsMutation {
             return Err(Error::new("Cannot swap players in a completed round"));
         }
 
-        let current_round_player: Option<(Uuid, i32)> = sqlx::query_as(
+        let current_round_player: Option<(Option<Uuid>, i32)> = sqlx::query_as(
             "SELECT team_id, player_position FROM round_players
              WHERE match_id = $1 AND round_number = $2 AND player_id = $3",
         )
@@ -0,0 +1,73 @@
+use crate::graphql::context::GraphQLContext;
+use crate::graphql::rounds::mutations::{
+    PlayerResultInput, parse_player_results, result_player_ids,
+};
+use crate::graphql::rounds::types::RoundSimulation;
+use crate::models;
+use crate::services::result_recording;
+use async_graphql::*;
+use uuid::Uuid;
+
+#[derive(Default)]
+pub struct RoundsQuery;
+
+#[Object]
+impl RoundsQuery {
+    /// Preview the outcome of recording a round's results.
+    ///
+    /// Runs the same pipeline as recordRoundResults in a transaction that is
+    /// always rolled back, so nothing is stored and no update is published.
+    async fn simulate_round_results(
+        &self,
+        ctx: &Context<'_>,
+        #[graphql(desc = "The match ID")] match_id: ID,
+        #[graphql(desc = "The round number")] round_number: i32,
+        #[graphql(desc = "Hypothetical player results for this round")] results: Vec<PlayerResultInput>,
+    ) -> Result<RoundSimulation> {
+        let gql_ctx = ctx.data::<GraphQLContext>()?;
+        let group_id = gql_ctx.authenticated_group_id()?;
+
+        let match_uuid = Uuid::parse_str(&match_id).map_err(|_| Error::new("Invalid match ID"))?;
+
+        let (player_uuids_with_positions, non_finishers) = parse_player_results(&results)?;
+
+        let match_record = models::Match::find_by_id(&gql_ctx.pool, match_uuid)
+            .await?
+            .ok_or_else(|| Error::new("Match not found"))?;
+
+        if match_record.group_id != group_id {
+            return Err(Error::new("Unauthorized").extend_with(|_, e| {
+                e.set("code", "UNAUTHORIZED");
+            }));
+        }
+
+        if match_record.completed {
+            return Err(Error::new("Match is already completed"));
+        }
+
+        let round = models::Round::find_one(&gql_ctx.pool, match_uuid, round_number)
+            .await?
+            .ok_or_else(|| Error::new("Round not found"))?;
+
+        if round.completed {
+            return Err(Error::new("Round is already completed"));
+        }
+
+        let round_players =
+            result_recording::get_round_players(&gql_ctx.pool, match_uuid, round_number).await?;
+        let player_uuids = result_player_ids(&player_uuids_with_positions, &non_finishers);
+        result_recording::validate_players_in_round(&player_uuids, &round_players)?;
+
+        let simulation = result_recording::simulate_race_results(
+            &gql_ctx.pool,
+            group_id,
+            round_number,
+            &player_uuids_with_positions,
+            &non_finishers,
+            &match_record,
+        )
+        .await?;
+
+        Ok(RoundSimulation::new(match_uuid, round_number, simulation))
+    }
+}
@@ -1,6 +1,10 @@
 use crate::graphql::context::GraphQLContext;
-use crate::graphql::results::types::PlayerRaceResult;
+use crate::graphql::cups::types::Cup;
+use crate::graphql::players::types::Player;
+use crate::graphql::results::types::{PlayerRaceResult, ResultStatus};
+use crate::graphql::tournaments::types::{LeaderboardEntry, ProvisionalFilter, filter_provisional};
 use crate::graphql::tracks::types::Track;
+use crate::services::result_recording;
 use async_graphql::*;
 use sqlx;
 use uuid::Uuid;
@@ -8,7 +12,7 @@ use uuid::Uuid;
 #[derive(Clone)]
 pub struct RoundPlayer {
     pub player: crate::models::Player,
-    pub team_id: Uuid,
+    pub team_id: Option<Uuid>,
 }
 
 #[Object]
@@ -25,8 +29,9 @@ impl RoundPlayer {
         self.player.avatar_filename.as_deref()
     }
 
-    async fn team_id(&self) -> ID {
-        self.team_id.to_string().into()
+    /// Null in free-for-all matches
+    async fn team_id(&self) -> Option<ID> {
+        self.team_id.map(|team_id| team_id.to_string().into())
     }
 }
 
@@ -36,6 +41,8 @@ pub struct Round {
     pub round_number: i32,
     pub track_id: Option<Uuid>,
     pub completed: bool,
+    pub cup_id: Option<Uuid>,
+    pub track_selection_notes: Vec<String>,
     pub cached_track: Option<Option<crate::models::Track>>,
     pub cached_result_player_ids: Option<Vec<Uuid>>,
     pub cached_results: Option<Vec<crate::models::PlayerRaceScore>>,
@@ -48,6 +55,8 @@ impl From<crate::models::Round> for Round {
             round_number: model.round_number,
             track_id: model.track_id,
             completed: model.completed,
+            cup_id: model.cup_id,
+            track_selection_notes: model.track_selection_notes,
             cached_track: None,
             cached_result_player_ids: None,
             cached_results: None,
@@ -67,6 +76,8 @@ impl Round {
             round_number: model.round_number,
             track_id: model.track_id,
             completed: model.completed,
+            cup_id: model.cup_id,
+            track_selection_notes: model.track_selection_notes,
             cached_track: Some(track),
             cached_result_player_ids: Some(result_player_ids),
             cached_results: Some(results),
@@ -104,10 +115,33 @@ impl Round {
         Ok(track)
     }
 
+    /// Debug information: tracks skipped for this round and why, such as
+    /// player vetoes
+    async fn track_selection_notes(&self) -> &[String] {
+        &self.track_selection_notes
+    }
+
+    /// The cup the round belongs to in Grand Prix matches
+    async fn cup(&self, ctx: &Context<'_>) -> Result<Option<Cup>> {
+        let Some(cup_id) = self.cup_id else {
+            return Ok(None);
+        };
+
+        let gql_ctx = ctx.data::<GraphQLContext>()?;
+
+        let cup = gql_ctx
+            .cup_loader
+            .load_one(cup_id)
+            .await?
+            .map(Cup::from);
+
+        Ok(cup)
+    }
+
     async fn players(&self, ctx: &Context<'_>) -> Result<Vec<RoundPlayer>> {
         let gql_ctx = ctx.data::<GraphQLContext>()?;
 
-        let round_player_records: Vec<(Uuid, Uuid)> = sqlx::query_as(
+        let round_player_records: Vec<(Uuid, Option<Uuid>)> = sqlx::query_as(
             "SELECT player_id, team_id FROM round_players
              WHERE match_id = $1 AND round_number = $2
              ORDER BY player_position",
@@ -165,3 +199,160 @@ impl Round {
             .collect())
     }
 }
+
+/// Would-be outcome of recording a round's results
+#[derive(Clone)]
+pub struct RoundSimulation {
+    pub match_id: Uuid,
+    pub round_number: i32,
+    pub players: Vec<SimulatedPlayerResult>,
+    pub leaderboard: Vec<LeaderboardEntry>,
+    pub teams: Vec<SimulatedTeamScore>,
+    pub match_completed: bool,
+}
+
+impl RoundSimulation {
+    pub fn new(match_id: Uuid, round_number: i32, model: result_recording::SimulatedRound) -> Self {
+        Self {
+            match_id,
+            round_number,
+            players: model.players.into_iter().map(SimulatedPlayerResult::from).collect(),
+            leaderboard: model.leaderboard.into_iter().map(LeaderboardEntry::from).collect(),
+            teams: model.teams.into_iter().map(SimulatedTeamScore::from).collect(),
+            match_completed: model.match_completed,
+        }
+    }
+}
+
+#[Object]
+impl RoundSimulation {
+    async fn match_id(&self) -> ID {
+        ID(self.match_id.to_string())
+    }
+
+    async fn round_number(&self) -> i32 {
+        self.round_number
+    }
+
+    /// Rating changes of each submitted player
+    async fn players(&self) -> &[SimulatedPlayerResult] {
+        &self.players
+    }
+
+    /// Tournament leaderboard as it would be after the round
+    async fn leaderboard(
+        &self,
+        #[graphql(default)] provisional: ProvisionalFilter,
+    ) -> Vec<LeaderboardEntry> {
+        filter_provisional(self.leaderboard.iter().cloned(), provisional)
+    }
+
+    /// Average points per round of each team
+    async fn teams(&self) -> &[SimulatedTeamScore] {
+        &self.teams
+    }
+
+    /// Whether recording the round would complete the match
+    async fn match_completed(&self) -> bool {
+        self.match_completed
+    }
+}
+
+#[derive(Clone)]
+pub struct SimulatedPlayerResult {
+    pub player_id: Uuid,
+    pub position: i32,
+    pub status: crate::models::ResultStatus,
+    pub all_time_elo_change: i32,
+    pub all_time_elo_after: i32,
+    pub tournament_elo_change: i32,
+    pub teammate_contribution: i32,
+    pub tournament_elo_after: i32,
+}
+
+impl From<result_recording::SimulatedPlayerResult> for SimulatedPlayerResult {
+    fn from(model: result_recording::SimulatedPlayerResult) -> Self {
+        Self {
+            player_id: model.player_id,
+            position: model.position,
+            status: model.status,
+            all_time_elo_change: model.all_time_elo_change,
+            all_time_elo_after: model.all_time_elo_after,
+            tournament_elo_change: model.tournament_elo_change,
+            teammate_contribution: model.teammate_contribution,
+            tournament_elo_after: model.tournament_elo_after,
+        }
+    }
+}
+
+#[Object]
+impl SimulatedPlayerResult {
+    async fn player(&self, ctx: &Context<'_>) -> Result<Player> {
+        let context = ctx.data_unchecked::<GraphQLContext>();
+
+        let player = context
+            .player_loader
+            .load_one(self.player_id)
+            .await?
+            .ok_or_else(|| Error::new("Player not found"))?;
+
+        Ok(Player::from(player))
+    }
+
+    async fn position(&self) -> i32 {
+        self.position
+    }
+
+    async fn status(&self) -> ResultStatus {
+        self.status.into()
+    }
+
+    async fn all_time_elo_change(&self) -> i32 {
+        self.all_time_elo_change
+    }
+
+    async fn all_time_elo_after(&self) -> i32 {
+        self.all_time_elo_after
+    }
+
+    /// Tournament ELO change from the race, before teammate contributions
+    async fn tournament_elo_change(&self) -> i32 {
+        self.tournament_elo_change
+    }
+
+    /// Tournament ELO received from teammates' results
+    async fn teammate_contribution(&self) -> i32 {
+        self.teammate_contribution
+    }
+
+    async fn tournament_elo_after(&self) -> i32 {
+        self.tournament_elo_after
+    }
+}
+
+#[derive(Clone)]
+pub struct SimulatedTeamScore {
+    pub team_id: Uuid,
+    pub score: f64,
+}
+
+impl From<result_recording::SimulatedTeamScore> for SimulatedTeamScore {
+    fn from(model: result_recording::SimulatedTeamScore) -> Self {
+        Self {
+            team_id: model.team_id,
+            score: model.score,
+        }
+    }
+}
+
+#[Object]
+impl SimulatedTeamScore {
+    async fn team_id(&self) -> ID {
+        ID(self.team_id.to_string())
+    }
+
+    /// Average points per round; rounded when stored at match completion
+    async fn score(&self) -> f64 {
+        self.score
+    }
+}
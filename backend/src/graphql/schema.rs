@@ -1,7 +1,10 @@
 use async_graphql::*;
 use async_graphql::extensions::OpenTelemetry;
 
-use crate::graphql::{auth, groups, lobby, matches, players, rounds, subscriptions, tournaments, tracks};
+use crate::graphql::{
+    auth, builds, cups, groups, lobby, matches, players, points_tables, rounds, subscriptions,
+    tournaments, track_pools, tracks,
+};
 
 /// Root Query combining all feature queries
 #[derive(MergedObject, Default)]
@@ -11,18 +14,27 @@ pub struct Query(
     players::PlayersQuery,
     tournaments::TournamentsQuery,
     matches::MatchesQuery,
+    rounds::RoundsQuery,
     tracks::TracksQuery,
+    track_pools::TrackPoolsQuery,
+    cups::CupsQuery,
+    builds::BuildsQuery,
+    points_tables::PointsTablesQuery,
 );
 
 /// Root Mutation combining all feature mutations
 #[derive(MergedObject, Default)]
 pub struct Mutation(
     auth::AuthMutation,
+    groups::GroupsMutation,
     players::PlayersMutation,
     tournaments::TournamentsMutation,
     matches::MatchesMutation,
     rounds::RoundsMutation,
+    tracks::TracksMutation,
+    track_pools::TrackPoolsMutation,
     lobby::LobbyMutation,
+    points_tables::PointsTablesMutation,
 );
 
 /// Root Subscription for real-time updates
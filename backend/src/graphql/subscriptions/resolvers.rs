@@ -311,7 +311,7 @@ async fn fetch_race_results(
     round_number: i32,
 ) -> Result<Vec<PlayerRaceResult>> {
     let scores = sqlx::query_as::<_, models::PlayerRaceScore>(
-        "SELECT group_id, match_id, round_number, player_id, position,
+        "SELECT group_id, match_id, round_number, player_id, position, status,
                 all_time_elo_change, all_time_elo_after,
                 tournament_elo_change, tournament_elo_after, created_at
          FROM player_race_scores
@@ -333,7 +333,7 @@ async fn fetch_player_aggregates(
     let scores = sqlx::query_as::<_, models::PlayerMatchScore>(
         "SELECT group_id, match_id, player_id, position, elo_change,
                 tournament_elo_change, tournament_elo_from_races,
-                tournament_elo_from_contributions, created_at
+                tournament_elo_from_contributions, points, created_at
          FROM player_match_scores
          WHERE match_id = $1
          ORDER BY position ASC",
@@ -353,15 +353,7 @@ async fn fetch_tournament_leaderboard(
 
     Ok(entries
         .into_iter()
-        .map(|(player_id, player_name, elo_rating, all_time_elo, avatar_filename)| {
-            LeaderboardEntry {
-                player_id,
-                player_name,
-                elo_rating,
-                all_time_elo,
-                avatar_filename,
-            }
-        })
+        .map(LeaderboardEntry::from)
         .collect())
 }
 
@@ -1,5 +1,7 @@
 use crate::graphql::context::GraphQLContext;
+use crate::graphql::points_tables::types::find_available_points_table;
 use crate::graphql::tournaments::types::Tournament;
+use crate::graphql::track_pools::types::find_group_track_pool;
 use crate::models;
 use crate::services::tournament_completion;
 use async_graphql::*;
@@ -16,6 +18,10 @@ impl TournamentsMutation {
         ctx: &Context<'_>,
         #[graphql(desc = "The tournament start date (YYYY-MM-DD)")] start_date: Option<String>,
         #[graphql(desc = "The tournament end date (YYYY-MM-DD)")] end_date: Option<String>,
+        #[graphql(desc = "Points table for the tournament's matches (default: Mario Kart 8)")]
+        points_table_id: Option<ID>,
+        #[graphql(desc = "Track pool for the tournament's matches (default: every active track)")]
+        track_pool_id: Option<ID>,
     ) -> Result<Tournament> {
         let gql_ctx = ctx.data::<GraphQLContext>()?;
         let group_id = gql_ctx.authenticated_group_id()?;
@@ -30,7 +36,95 @@ impl TournamentsMutation {
             .transpose()
             .map_err(|_| Error::new("Invalid end date format. Use YYYY-MM-DD"))?;
 
-        let tournament = models::Tournament::create(&gql_ctx.pool, group_id, start, end).await?;
+        let points_table_id = match points_table_id {
+            Some(id) => Some(find_available_points_table(&gql_ctx.pool, group_id, &id).await?.id),
+            None => None,
+        };
+
+        let track_pool_id = match track_pool_id {
+            Some(id) => Some(find_group_track_pool(&gql_ctx.pool, group_id, &id).await?.id),
+            None => None,
+        };
+
+        let tournament = models::Tournament::create(
+            &gql_ctx.pool,
+            group_id,
+            start,
+            end,
+            points_table_id,
+            track_pool_id,
+        )
+        .await?;
+
+        Ok(Tournament::from(tournament))
+    }
+
+    /// Change the points table used for a tournament's future matches.
+    ///
+    /// Matches already created keep the table they were created with.
+    async fn set_tournament_points_table(
+        &self,
+        ctx: &Context<'_>,
+        #[graphql(desc = "The tournament ID")] tournament_id: ID,
+        #[graphql(desc = "The points table ID, or null for the Mario Kart 8 table")]
+        points_table_id: Option<ID>,
+    ) -> Result<Tournament> {
+        let gql_ctx = ctx.data::<GraphQLContext>()?;
+        let group_id = gql_ctx.authenticated_group_id()?;
+
+        let tournament_uuid = Uuid::parse_str(&tournament_id)
+            .map_err(|_| Error::new("Invalid tournament ID"))?;
+
+        let tournament = models::Tournament::find_by_id(&gql_ctx.pool, tournament_uuid)
+            .await?
+            .ok_or_else(|| Error::new("Tournament not found"))?;
+
+        if tournament.group_id != group_id {
+            return Err(Error::new("Tournament not found"));
+        }
+
+        let points_table_id = match points_table_id {
+            Some(id) => Some(find_available_points_table(&gql_ctx.pool, group_id, &id).await?.id),
+            None => None,
+        };
+
+        let tournament =
+            models::Tournament::set_points_table(&gql_ctx.pool, tournament_uuid, points_table_id)
+                .await?;
+
+        Ok(Tournament::from(tournament))
+    }
+
+    /// Change the track pool used for a tournament's future matches.
+    async fn set_tournament_track_pool(
+        &self,
+        ctx: &Context<'_>,
+        #[graphql(desc = "The tournament ID")] tournament_id: ID,
+        #[graphql(desc = "The track pool ID, or null for every active track")]
+        track_pool_id: Option<ID>,
+    ) -> Result<Tournament> {
+        let gql_ctx = ctx.data::<GraphQLContext>()?;
+        let group_id = gql_ctx.authenticated_group_id()?;
+
+        let tournament_uuid = Uuid::parse_str(&tournament_id)
+            .map_err(|_| Error::new("Invalid tournament ID"))?;
+
+        let tournament = models::Tournament::find_by_id(&gql_ctx.pool, tournament_uuid)
+            .await?
+            .ok_or_else(|| Error::new("Tournament not found"))?;
+
+        if tournament.group_id != group_id {
+            return Err(Error::new("Tournament not found"));
+        }
+
+        let track_pool_id = match track_pool_id {
+            Some(id) => Some(find_group_track_pool(&gql_ctx.pool, group_id, &id).await?.id),
+            None => None,
+        };
+
+        let tournament =
+            models::Tournament::set_track_pool(&gql_ctx.pool, tournament_uuid, track_pool_id)
+                .await?;
 
         Ok(Tournament::from(tournament))
     }
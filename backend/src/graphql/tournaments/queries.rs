@@ -65,7 +65,7 @@ impl TournamentsQuery {
         let group_id = gql_ctx.authenticated_group_id()?;
 
         let tournament = sqlx::query_as::<_, models::Tournament>(
-            "SELECT id, group_id, start_date, end_date, winner
+            "SELECT id, group_id, start_date, end_date, winner, points_table_id, track_pool_id
              FROM tournaments
              WHERE group_id = $1 AND winner IS NULL
              ORDER BY start_date DESC NULLS LAST
@@ -85,17 +85,7 @@ impl TournamentsQuery {
 
                 let leaderboard = entries
                     .into_iter()
-                    .map(
-                        |(player_id, player_name, elo_rating, all_time_elo, avatar_filename)| {
-                            LeaderboardEntry {
-                                player_id,
-                                player_name,
-                                elo_rating,
-                                all_time_elo,
-                                avatar_filename,
-                            }
-                        },
-                    )
+                    .map(LeaderboardEntry::from)
                     .collect();
 
                 Ok(Some(ActiveTournamentWithLeaderboard {
@@ -131,22 +121,12 @@ impl TournamentsQuery {
 
                 let all_players: Vec<(Uuid, String)> = leaderboard_entries
                     .iter()
-                    .map(|(player_id, player_name, _, _, _)| (*player_id, player_name.clone()))
+                    .map(|entry| (entry.player_id, entry.player_name.clone()))
                     .collect();
 
                 let leaderboard: Vec<LeaderboardEntry> = leaderboard_entries
                     .into_iter()
-                    .map(
-                        |(player_id, player_name, elo_rating, all_time_elo, avatar_filename)| {
-                            LeaderboardEntry {
-                                player_id,
-                                player_name,
-                                elo_rating,
-                                all_time_elo,
-                                avatar_filename,
-                            }
-                        },
-                    )
+                    .map(LeaderboardEntry::from)
                     .collect();
 
                 let stats_models =
@@ -1,5 +1,8 @@
 use crate::graphql::context::GraphQLContext;
 use crate::graphql::matches::types::Match;
+use crate::graphql::points_tables::types::PointsTable;
+use crate::graphql::track_pools::types::TrackPool;
+use crate::graphql::tracks::types::TrackVeto;
 use crate::models;
 use crate::models::TournamentStatType as ModelStatType;
 use async_graphql::*;
@@ -36,6 +39,33 @@ impl From<ModelStatType> for TournamentStatType {
     }
 }
 
+/// How leaderboards treat provisional players
+#[derive(Clone, Copy, Default, Enum, PartialEq, Eq)]
+pub enum ProvisionalFilter {
+    /// Keep provisional players in rating order
+    #[default]
+    Include,
+    /// Leave provisional players out
+    Exclude,
+    /// List provisional players after everyone else
+    Last,
+}
+
+/// Applies a provisional filter to leaderboard entries in rating order.
+pub fn filter_provisional(
+    entries: impl IntoIterator<Item = LeaderboardEntry>,
+    filter: ProvisionalFilter,
+) -> Vec<LeaderboardEntry> {
+    let mut entries: Vec<LeaderboardEntry> = entries
+        .into_iter()
+        .filter(|entry| filter != ProvisionalFilter::Exclude || !entry.provisional)
+        .collect();
+    if filter == ProvisionalFilter::Last {
+        entries.sort_by_key(|entry| entry.provisional);
+    }
+    entries
+}
+
 #[derive(Clone)]
 pub struct TournamentStat {
     pub id: Uuid,
@@ -89,6 +119,8 @@ pub struct Tournament {
     pub start_date: Option<NaiveDate>,
     pub end_date: Option<NaiveDate>,
     pub winner: Option<Uuid>,
+    pub points_table_id: Option<Uuid>,
+    pub track_pool_id: Option<Uuid>,
 }
 
 impl From<crate::models::Tournament> for Tournament {
@@ -99,6 +131,8 @@ impl From<crate::models::Tournament> for Tournament {
             start_date: model.start_date,
             end_date: model.end_date,
             winner: model.winner,
+            points_table_id: model.points_table_id,
+            track_pool_id: model.track_pool_id,
         }
     }
 }
@@ -121,6 +155,48 @@ impl Tournament {
         self.winner.map(|id| ID(id.to_string()))
     }
 
+    /// The points table new matches are scored with
+    async fn points_table(&self, ctx: &Context<'_>) -> Result<PointsTable> {
+        let gql_ctx = ctx.data::<GraphQLContext>()?;
+
+        let table = gql_ctx
+            .points_table_loader
+            .load_one(
+                self.points_table_id
+                    .unwrap_or(models::points_table::MK8_POINTS_TABLE_ID),
+            )
+            .await?
+            .ok_or_else(|| Error::new("Points table not found"))?;
+
+        Ok(PointsTable::from(table))
+    }
+
+    /// The track pool new matches draw from; null for every active track
+    async fn track_pool(&self, ctx: &Context<'_>) -> Result<Option<TrackPool>> {
+        let Some(track_pool_id) = self.track_pool_id else {
+            return Ok(None);
+        };
+
+        let gql_ctx = ctx.data::<GraphQLContext>()?;
+
+        let track_pool = gql_ctx
+            .track_pool_loader
+            .load_one(track_pool_id)
+            .await?
+            .map(TrackPool::from);
+
+        Ok(track_pool)
+    }
+
+    /// Tracks players have vetoed; new matches avoid them where possible
+    async fn track_vetoes(&self, ctx: &Context<'_>) -> Result<Vec<TrackVeto>> {
+        let gql_ctx = ctx.data::<GraphQLContext>()?;
+
+        let vetoes = models::TrackVeto::find_by_tournament_id(&gql_ctx.pool, self.id).await?;
+
+        Ok(vetoes.into_iter().map(TrackVeto::from).collect())
+    }
+
     async fn matches(&self, ctx: &Context<'_>) -> Result<Vec<Match>> {
         let gql_ctx = ctx.data::<GraphQLContext>()?;
 
@@ -133,23 +209,21 @@ impl Tournament {
         Ok(matches.into_iter().map(Match::from).collect())
     }
 
-    async fn leaderboard(&self, ctx: &Context<'_>) -> Result<Vec<LeaderboardEntry>> {
+    async fn leaderboard(
+        &self,
+        ctx: &Context<'_>,
+        #[graphql(default)] provisional: ProvisionalFilter,
+    ) -> Result<Vec<LeaderboardEntry>> {
         let gql_ctx = ctx.data::<GraphQLContext>()?;
 
         let entries =
             models::PlayerTournamentScore::get_tournament_leaderboard(&gql_ctx.pool, self.id)
                 .await?;
 
-        Ok(entries
-            .into_iter()
-            .map(|(player_id, player_name, elo_rating, all_time_elo, avatar_filename)| LeaderboardEntry {
-                player_id,
-                player_name,
-                elo_rating,
-                all_time_elo,
-                avatar_filename,
-            })
-            .collect())
+        Ok(filter_provisional(
+            entries.into_iter().map(LeaderboardEntry::from),
+            provisional,
+        ))
     }
 
     async fn stats(&self, ctx: &Context<'_>) -> Result<Vec<TournamentStat>> {
@@ -166,8 +240,28 @@ pub struct LeaderboardEntry {
     pub player_id: Uuid,
     pub player_name: String,
     pub elo_rating: i32,
+    pub rating_deviation: f64,
     pub all_time_elo: i32,
     pub avatar_filename: Option<String>,
+    pub races_played: i32,
+    pub provisional: bool,
+    pub formerly_known_as: Option<String>,
+}
+
+impl From<models::TournamentLeaderboardRow> for LeaderboardEntry {
+    fn from(row: models::TournamentLeaderboardRow) -> Self {
+        Self {
+            player_id: row.player_id,
+            player_name: row.player_name,
+            elo_rating: row.elo_rating,
+            rating_deviation: row.rating_deviation,
+            all_time_elo: row.all_time_elo,
+            avatar_filename: row.avatar_filename,
+            races_played: row.races_played,
+            provisional: row.provisional,
+            formerly_known_as: row.formerly_known_as,
+        }
+    }
 }
 
 #[Object]
@@ -185,6 +279,11 @@ impl LeaderboardEntry {
         self.elo_rating
     }
 
+    /// How uncertain the tournament rating is (Glicko-2 rating deviation)
+    async fn rating_deviation(&self) -> f64 {
+        self.rating_deviation
+    }
+
     async fn all_time_elo(&self) -> i32 {
         self.all_time_elo
     }
@@ -193,6 +292,22 @@ impl LeaderboardEntry {
         self.avatar_filename.as_deref()
     }
 
+    /// All-time recorded races
+    async fn races_played(&self) -> i32 {
+        self.races_played
+    }
+
+    /// Whether the player has fewer races than the group's provisional race count
+    async fn provisional(&self) -> bool {
+        self.provisional
+    }
+
+    /// The player's name during the tournament, if they have been renamed
+    /// since it was completed
+    async fn formerly_known_as(&self) -> Option<&str> {
+        self.formerly_known_as.as_deref()
+    }
+
     async fn past_tournament_placings(
         &self,
         ctx: &Context<'_>,
@@ -235,8 +350,11 @@ impl ActiveTournamentWithLeaderboard {
         self.tournament.winner.map(|id| ID(id.to_string()))
     }
 
-    async fn leaderboard(&self) -> &[LeaderboardEntry] {
-        &self.leaderboard
+    async fn leaderboard(
+        &self,
+        #[graphql(default)] provisional: ProvisionalFilter,
+    ) -> Vec<LeaderboardEntry> {
+        filter_provisional(self.leaderboard.iter().cloned(), provisional)
     }
 
     async fn matches(&self, ctx: &Context<'_>) -> Result<Vec<Match>> {
@@ -250,6 +368,11 @@ impl ActiveTournamentWithLeaderboard {
 
         Ok(matches.into_iter().map(Match::from).collect())
     }
+
+    /// Tracks players have vetoed; new matches avoid them where possible
+    async fn track_vetoes(&self, ctx: &Context<'_>) -> Result<Vec<TrackVeto>> {
+        self.tournament.track_vetoes(ctx).await
+    }
 }
 
 #[derive(Clone, SimpleObject)]
@@ -370,8 +493,11 @@ impl TournamentDetail {
         self.winner.map(|id| ID(id.to_string()))
     }
 
-    async fn leaderboard(&self) -> &[LeaderboardEntry] {
-        &self.leaderboard
+    async fn leaderboard(
+        &self,
+        #[graphql(default)] provisional: ProvisionalFilter,
+    ) -> Vec<LeaderboardEntry> {
+        filter_provisional(self.leaderboard.iter().cloned(), provisional)
     }
 
     async fn stats(&self) -> &[TournamentStat] {
@@ -0,0 +1,35 @@
+use crate::db::DbPool;
+use crate::models::TrackPool;
+use async_graphql::dataloader::*;
+use std::collections::HashMap;
+use tracing::instrument;
+use uuid::Uuid;
+
+pub struct TrackPoolLoader {
+    pool: DbPool,
+}
+
+impl TrackPoolLoader {
+    pub fn new(pool: DbPool) -> Self {
+        Self { pool }
+    }
+}
+
+impl Loader<Uuid> for TrackPoolLoader {
+    type Value = TrackPool;
+    type Error = std::sync::Arc<sqlx::Error>;
+
+    #[instrument(level = "debug", skip(self), fields(batch_size = keys.len()))]
+    async fn load(&self, keys: &[Uuid]) -> Result<HashMap<Uuid, Self::Value>, Self::Error> {
+        let track_pools = TrackPool::find_by_ids(&self.pool, keys)
+            .await
+            .map_err(std::sync::Arc::new)?;
+
+        let mapped = track_pools
+            .into_iter()
+            .map(|track_pool| (track_pool.id, track_pool))
+            .collect();
+
+        Ok(mapped)
+    }
+}
@@ -0,0 +1,9 @@
+pub mod loaders;
+pub mod mutations;
+pub mod queries;
+pub mod types;
+
+pub use loaders::TrackPoolLoader;
+pub use mutations::TrackPoolsMutation;
+pub use queries::TrackPoolsQuery;
+pub use types::TrackPool;
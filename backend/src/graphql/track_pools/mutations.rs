@@ -0,0 +1,82 @@
+use crate::graphql::context::GraphQLContext;
+use crate::graphql::track_pools::types::{
+    TrackPool, TrackPoolTrackInput, find_group_track_pool, parse_track_pool_tracks,
+};
+use crate::models;
+use crate::services::validation::validate_name;
+use async_graphql::*;
+
+#[derive(Default)]
+pub struct TrackPoolsMutation;
+
+#[Object]
+impl TrackPoolsMutation {
+    /// Create a named track pool for the current group
+    async fn create_track_pool(
+        &self,
+        ctx: &Context<'_>,
+        #[graphql(desc = "The pool's name")] name: String,
+        #[graphql(desc = "The pool's tracks and their weights")] tracks: Vec<TrackPoolTrackInput>,
+    ) -> Result<TrackPool> {
+        let gql_ctx = ctx.data::<GraphQLContext>()?;
+        let group_id = gql_ctx.authenticated_group_id()?;
+
+        validate_name(&name, "Track pool name")?;
+        let tracks = parse_track_pool_tracks(&gql_ctx.pool, group_id, &tracks).await?;
+
+        let mut tx = gql_ctx.pool.begin().await?;
+        let track_pool = models::TrackPool::create(&mut tx, group_id, name.trim(), &tracks).await?;
+        tx.commit().await?;
+
+        Ok(TrackPool::from(track_pool))
+    }
+
+    /// Rename one of the current group's track pools and replace its tracks.
+    ///
+    /// Matches already created keep the tracks they were given.
+    async fn update_track_pool(
+        &self,
+        ctx: &Context<'_>,
+        #[graphql(desc = "The track pool ID")] track_pool_id: ID,
+        #[graphql(desc = "The pool's name")] name: String,
+        #[graphql(desc = "The pool's tracks and their weights")] tracks: Vec<TrackPoolTrackInput>,
+    ) -> Result<TrackPool> {
+        let gql_ctx = ctx.data::<GraphQLContext>()?;
+        let group_id = gql_ctx.authenticated_group_id()?;
+
+        let track_pool = find_group_track_pool(&gql_ctx.pool, group_id, &track_pool_id).await?;
+
+        validate_name(&name, "Track pool name")?;
+        let tracks = parse_track_pool_tracks(&gql_ctx.pool, group_id, &tracks).await?;
+
+        let mut tx = gql_ctx.pool.begin().await?;
+        let track_pool =
+            models::TrackPool::update(&mut tx, track_pool.id, name.trim(), &tracks).await?;
+        tx.commit().await?;
+
+        Ok(TrackPool::from(track_pool))
+    }
+
+    /// Delete one of the current group's track pools that no tournament or
+    /// match uses.
+    async fn delete_track_pool(
+        &self,
+        ctx: &Context<'_>,
+        #[graphql(desc = "The track pool ID")] track_pool_id: ID,
+    ) -> Result<bool> {
+        let gql_ctx = ctx.data::<GraphQLContext>()?;
+        let group_id = gql_ctx.authenticated_group_id()?;
+
+        let track_pool = find_group_track_pool(&gql_ctx.pool, group_id, &track_pool_id).await?;
+
+        if models::TrackPool::is_in_use(&gql_ctx.pool, track_pool.id).await? {
+            return Err(Error::new(
+                "Cannot delete track pool: it is used by a tournament or match",
+            ));
+        }
+
+        models::TrackPool::delete(&gql_ctx.pool, track_pool.id).await?;
+
+        Ok(true)
+    }
+}
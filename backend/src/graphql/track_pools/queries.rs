@@ -0,0 +1,20 @@
+use crate::graphql::context::GraphQLContext;
+use crate::graphql::track_pools::types::TrackPool;
+use crate::models;
+use async_graphql::*;
+
+#[derive(Default)]
+pub struct TrackPoolsQuery;
+
+#[Object]
+impl TrackPoolsQuery {
+    /// Get the current group's track pools
+    async fn track_pools(&self, ctx: &Context<'_>) -> Result<Vec<TrackPool>> {
+        let gql_ctx = ctx.data::<GraphQLContext>()?;
+        let group_id = gql_ctx.authenticated_group_id()?;
+
+        let track_pools = models::TrackPool::find_by_group_id(&gql_ctx.pool, group_id).await?;
+
+        Ok(track_pools.into_iter().map(TrackPool::from).collect())
+    }
+}
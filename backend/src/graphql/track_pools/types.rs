@@ -0,0 +1,121 @@
+use crate::graphql::context::GraphQLContext;
+use crate::graphql::tracks::types::Track;
+use async_graphql::*;
+use uuid::Uuid;
+
+#[derive(Clone)]
+pub struct TrackPool {
+    pub id: Uuid,
+    pub name: String,
+}
+
+impl From<crate::models::TrackPool> for TrackPool {
+    fn from(model: crate::models::TrackPool) -> Self {
+        Self {
+            id: model.id,
+            name: model.name,
+        }
+    }
+}
+
+#[Object]
+impl TrackPool {
+    async fn id(&self) -> ID {
+        ID(self.id.to_string())
+    }
+
+    async fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// The pool's tracks with their weights, including retired tracks
+    async fn tracks(&self, ctx: &Context<'_>) -> Result<Vec<TrackPoolTrack>> {
+        let gql_ctx = ctx.data::<GraphQLContext>()?;
+
+        let tracks =
+            crate::models::TrackPool::find_tracks_by_pool_ids(&gql_ctx.pool, &[self.id]).await?;
+
+        Ok(tracks.into_iter().map(TrackPoolTrack::from).collect())
+    }
+}
+
+#[derive(Clone)]
+pub struct TrackPoolTrack {
+    pub track: crate::models::Track,
+    pub weight: i32,
+}
+
+impl From<crate::models::TrackPoolTrack> for TrackPoolTrack {
+    fn from(model: crate::models::TrackPoolTrack) -> Self {
+        Self {
+            track: model.track,
+            weight: model.weight,
+        }
+    }
+}
+
+#[Object]
+impl TrackPoolTrack {
+    async fn track(&self) -> Track {
+        Track::from(self.track.clone())
+    }
+
+    /// Relative chance of the track being drawn early in each cycle
+    async fn weight(&self) -> i32 {
+        self.weight
+    }
+}
+
+#[derive(InputObject)]
+pub struct TrackPoolTrackInput {
+    pub track_id: ID,
+    /// Relative selection weight (default: 1)
+    #[graphql(default = 1)]
+    pub weight: i32,
+}
+
+/// Loads one of a group's track pools, by GraphQL ID.
+pub(crate) async fn find_group_track_pool(
+    pool: &crate::db::DbPool,
+    group_id: Uuid,
+    id: &ID,
+) -> Result<crate::models::TrackPool> {
+    let uuid = Uuid::parse_str(id).map_err(|_| Error::new("Invalid track pool ID"))?;
+
+    crate::models::TrackPool::find_by_id(pool, uuid)
+        .await?
+        .filter(|track_pool| track_pool.group_id == group_id)
+        .ok_or_else(|| Error::new("Track pool not found"))
+}
+
+/// Parses a pool's track inputs, checking that every track is available to
+/// the group.
+pub(crate) async fn parse_track_pool_tracks(
+    pool: &crate::db::DbPool,
+    group_id: Uuid,
+    tracks: &[TrackPoolTrackInput],
+) -> Result<Vec<(Uuid, i32)>> {
+    let entries = tracks
+        .iter()
+        .map(|input| {
+            Uuid::parse_str(&input.track_id)
+                .map(|track_id| (track_id, input.weight))
+                .map_err(|_| Error::new("Invalid track ID"))
+        })
+        .collect::<Result<Vec<(Uuid, i32)>>>()?;
+
+    crate::services::validation::validate_track_pool(&entries)?;
+
+    let track_ids: Vec<Uuid> = entries.iter().map(|(track_id, _)| *track_id).collect();
+    let available = crate::models::Track::find_by_ids(pool, &track_ids, Some(group_id))
+        .await?
+        .into_iter()
+        .filter(|track| track.is_available_to(group_id))
+        .count();
+
+    if available != track_ids.len() {
+        return Err(Error::new("Track not found"));
+    }
+
+    Ok(entries)
+}
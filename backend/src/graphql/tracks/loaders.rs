@@ -1,18 +1,20 @@
 
 use crate::db::DbPool;
-use crate::models::Track;
+use crate::models::{PlayerRaceScore, Track, TrackChaosIndex};
 use async_graphql::dataloader::*;
 use std::collections::HashMap;
 use tracing::instrument;
 use uuid::Uuid;
 
+/// Loads tracks, retired as seen by the authenticated group when there is one.
 pub struct TrackLoader {
     pool: DbPool,
+    group_id: Option<Uuid>,
 }
 
 impl TrackLoader {
-    pub fn new(pool: DbPool) -> Self {
-        Self { pool }
+    pub fn new(pool: DbPool, group_id: Option<Uuid>) -> Self {
+        Self { pool, group_id }
     }
 }
 
@@ -22,7 +24,7 @@ impl Loader<Uuid> for TrackLoader {
 
     #[instrument(level = "debug", skip(self), fields(batch_size = keys.len()))]
     async fn load(&self, keys: &[Uuid]) -> Result<HashMap<Uuid, Self::Value>, Self::Error> {
-        let tracks = Track::find_by_ids(&self.pool, keys)
+        let tracks = Track::find_by_ids(&self.pool, keys, self.group_id)
             .await
             .map_err(std::sync::Arc::new)?;
 
@@ -31,3 +33,35 @@ impl Loader<Uuid> for TrackLoader {
         Ok(mapped)
     }
 }
+
+/// Loads track chaos indexes, from the authenticated group's races only when
+/// there is one.
+pub struct TrackChaosLoader {
+    pool: DbPool,
+    group_id: Option<Uuid>,
+}
+
+impl TrackChaosLoader {
+    pub fn new(pool: DbPool, group_id: Option<Uuid>) -> Self {
+        Self { pool, group_id }
+    }
+}
+
+impl Loader<Uuid> for TrackChaosLoader {
+    type Value = TrackChaosIndex;
+    type Error = std::sync::Arc<sqlx::Error>;
+
+    #[instrument(level = "debug", skip(self), fields(batch_size = keys.len()))]
+    async fn load(&self, keys: &[Uuid]) -> Result<HashMap<Uuid, Self::Value>, Self::Error> {
+        let indexes = PlayerRaceScore::find_track_chaos_indexes(&self.pool, keys, self.group_id)
+            .await
+            .map_err(std::sync::Arc::new)?;
+
+        let mapped = indexes
+            .into_iter()
+            .map(|index| (index.track_id, index))
+            .collect();
+
+        Ok(mapped)
+    }
+}
@@ -1,7 +1,9 @@
 pub mod loaders;
+pub mod mutations;
 pub mod queries;
 pub mod types;
 
-pub use loaders::TrackLoader;
+pub use loaders::{TrackChaosLoader, TrackLoader};
+pub use mutations::TracksMutation;
 pub use queries::TracksQuery;
 pub use types::Track;
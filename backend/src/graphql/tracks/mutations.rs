@@ -0,0 +1,214 @@
+use crate::graphql::context::GraphQLContext;
+use crate::graphql::tracks::types::{
+    Track, TrackInput, TrackVeto, find_available_track, find_group_track,
+};
+use crate::models;
+use crate::services::track_selection::MAX_TRACK_VETOES;
+use async_graphql::*;
+use uuid::Uuid;
+
+#[derive(Default)]
+pub struct TracksMutation;
+
+#[Object]
+impl TracksMutation {
+    /// Add a track to the current group's catalogue
+    async fn create_track(
+        &self,
+        ctx: &Context<'_>,
+        #[graphql(desc = "The track's details")] input: TrackInput,
+    ) -> Result<Track> {
+        let gql_ctx = ctx.data::<GraphQLContext>()?;
+        let group_id = gql_ctx.authenticated_group_id()?;
+
+        let details = input.into_details()?;
+
+        if models::Track::is_name_taken(&gql_ctx.pool, group_id, &details.name, None).await? {
+            return Err(Error::new("A track with this name already exists"));
+        }
+
+        let track = models::Track::create(&gql_ctx.pool, group_id, &details).await?;
+
+        Ok(Track::from(track))
+    }
+
+    /// Rename one of the current group's tracks or change its details
+    async fn update_track(
+        &self,
+        ctx: &Context<'_>,
+        #[graphql(desc = "The track ID")] track_id: ID,
+        #[graphql(desc = "The track's new details")] input: TrackInput,
+    ) -> Result<Track> {
+        let gql_ctx = ctx.data::<GraphQLContext>()?;
+        let group_id = gql_ctx.authenticated_group_id()?;
+
+        let track = find_group_track(&gql_ctx.pool, group_id, &track_id).await?;
+        let details = input.into_details()?;
+
+        if models::Track::is_name_taken(&gql_ctx.pool, group_id, &details.name, Some(track.id))
+            .await?
+        {
+            return Err(Error::new("A track with this name already exists"));
+        }
+
+        let track = models::Track::update(&gql_ctx.pool, track.id, &details).await?;
+
+        Ok(Track::from(track))
+    }
+
+    /// Retire or restore one of the current group's tracks, or a built-in
+    /// track for the current group only.
+    ///
+    /// Retired tracks are no longer selected for new matches, but rounds
+    /// already raced on them keep showing the track.
+    async fn set_track_retired(
+        &self,
+        ctx: &Context<'_>,
+        #[graphql(desc = "The track ID")] track_id: ID,
+        #[graphql(desc = "Whether the track is retired")] retired: bool,
+    ) -> Result<Track> {
+        let gql_ctx = ctx.data::<GraphQLContext>()?;
+        let group_id = gql_ctx.authenticated_group_id()?;
+
+        let track = find_available_track(&gql_ctx.pool, group_id, &track_id).await?;
+
+        if !track.is_built_in() {
+            let track = models::Track::set_retired(&gql_ctx.pool, track.id, retired).await?;
+            return Ok(Track::from(track));
+        }
+
+        models::Track::set_retired_for_group(&gql_ctx.pool, track.id, group_id, retired).await?;
+
+        let track = models::Track::find_by_id(&gql_ctx.pool, track.id, Some(group_id))
+            .await?
+            .ok_or_else(|| Error::new("Track not found"))?;
+
+        Ok(Track::from(track))
+    }
+
+    /// Delete one of the current group's tracks that no round uses
+    async fn delete_track(
+        &self,
+        ctx: &Context<'_>,
+        #[graphql(desc = "The track ID")] track_id: ID,
+    ) -> Result<bool> {
+        let gql_ctx = ctx.data::<GraphQLContext>()?;
+        let group_id = gql_ctx.authenticated_group_id()?;
+
+        let track = find_group_track(&gql_ctx.pool, group_id, &track_id).await?;
+
+        if models::Track::is_in_use(&gql_ctx.pool, track.id).await? {
+            return Err(Error::new(
+                "Cannot delete track: it has been raced. Retire it instead",
+            ));
+        }
+
+        models::Track::delete(&gql_ctx.pool, track.id).await?;
+
+        Ok(true)
+    }
+
+    /// Veto a track for a player in a tournament, so that new matches avoid
+    /// it in races the player is in. Vetoing the same track twice is a no-op.
+    async fn veto_track(
+        &self,
+        ctx: &Context<'_>,
+        #[graphql(desc = "The tournament ID")] tournament_id: ID,
+        #[graphql(desc = "The player ID")] player_id: ID,
+        #[graphql(desc = "The track ID")] track_id: ID,
+    ) -> Result<TrackVeto> {
+        let gql_ctx = ctx.data::<GraphQLContext>()?;
+        let group_id = gql_ctx.authenticated_group_id()?;
+
+        let (tournament_uuid, player_uuid, track_uuid) =
+            find_veto_ids(&gql_ctx.pool, group_id, &tournament_id, &player_id, &track_id).await?;
+
+        let mut tx = gql_ctx.pool.begin().await?;
+
+        // Serialise vetoes per tournament so concurrent requests cannot
+        // exceed the limit
+        sqlx::query("SELECT id FROM tournaments WHERE id = $1 FOR UPDATE")
+            .bind(tournament_uuid)
+            .execute(&mut *tx)
+            .await?;
+
+        if let Some(veto) =
+            models::TrackVeto::find(&mut *tx, tournament_uuid, player_uuid, track_uuid).await?
+        {
+            return Ok(TrackVeto::from(veto));
+        }
+
+        let vetoed =
+            models::TrackVeto::count_for_player(&mut *tx, tournament_uuid, player_uuid).await?;
+
+        if vetoed >= MAX_TRACK_VETOES {
+            return Err(Error::new(format!(
+                "A player can veto at most {MAX_TRACK_VETOES} tracks per tournament"
+            )));
+        }
+
+        let veto =
+            models::TrackVeto::create(&mut *tx, tournament_uuid, player_uuid, track_uuid).await?;
+
+        tx.commit().await?;
+
+        Ok(TrackVeto::from(veto))
+    }
+
+    /// Withdraw a player's veto of a track in a tournament
+    async fn remove_track_veto(
+        &self,
+        ctx: &Context<'_>,
+        #[graphql(desc = "The tournament ID")] tournament_id: ID,
+        #[graphql(desc = "The player ID")] player_id: ID,
+        #[graphql(desc = "The track ID")] track_id: ID,
+    ) -> Result<bool> {
+        let gql_ctx = ctx.data::<GraphQLContext>()?;
+        let group_id = gql_ctx.authenticated_group_id()?;
+
+        let (tournament_uuid, player_uuid, track_uuid) =
+            find_veto_ids(&gql_ctx.pool, group_id, &tournament_id, &player_id, &track_id).await?;
+
+        let removed =
+            models::TrackVeto::delete(&gql_ctx.pool, tournament_uuid, player_uuid, track_uuid)
+                .await?;
+
+        Ok(removed)
+    }
+}
+
+/// Checks that a veto's tournament is in progress and that its tournament,
+/// player and track all belong to the group.
+async fn find_veto_ids(
+    pool: &crate::db::DbPool,
+    group_id: Uuid,
+    tournament_id: &ID,
+    player_id: &ID,
+    track_id: &ID,
+) -> Result<(Uuid, Uuid, Uuid)> {
+    let tournament_uuid =
+        Uuid::parse_str(tournament_id).map_err(|_| Error::new("Invalid tournament ID"))?;
+    let player_uuid = Uuid::parse_str(player_id).map_err(|_| Error::new("Invalid player ID"))?;
+    let track_uuid = Uuid::parse_str(track_id).map_err(|_| Error::new("Invalid track ID"))?;
+
+    let tournament = models::Tournament::find_by_id(pool, tournament_uuid)
+        .await?
+        .filter(|tournament| tournament.group_id == group_id)
+        .ok_or_else(|| Error::new("Tournament not found"))?;
+
+    if tournament.winner.is_some() {
+        return Err(Error::new("Tournament already completed"));
+    }
+
+    models::Player::find_by_id(pool, player_uuid)
+        .await?
+        .filter(|player| player.group_id == group_id)
+        .ok_or_else(|| Error::new("Player not found"))?;
+
+    models::Track::find_by_id(pool, track_uuid, Some(group_id))
+        .await?
+        .filter(|track| track.is_available_to(group_id))
+        .ok_or_else(|| Error::new("Track not found"))?;
+
+    Ok((tournament_uuid, player_uuid, track_uuid))
+}
@@ -8,11 +8,21 @@ pub struct TracksQuery;
 
 #[Object]
 impl TracksQuery {
-    /// Get all available tracks
-    async fn tracks(&self, ctx: &Context<'_>) -> Result<Vec<Track>> {
+    /// Get the built-in tracks and the current group's own tracks
+    async fn tracks(
+        &self,
+        ctx: &Context<'_>,
+        #[graphql(desc = "Include retired tracks (default: false)")]
+        include_retired: Option<bool>,
+    ) -> Result<Vec<Track>> {
         let gql_ctx = ctx.data::<GraphQLContext>()?;
 
-        let tracks = models::Track::find_all(&gql_ctx.pool).await?;
+        let tracks = models::Track::find_available_to_group(
+            &gql_ctx.pool,
+            gql_ctx.group_id,
+            include_retired.unwrap_or(false),
+        )
+        .await?;
 
         Ok(tracks.into_iter().map(Track::from).collect())
     }
@@ -1,3 +1,6 @@
+use crate::graphql::context::GraphQLContext;
+use crate::graphql::players::types::Player;
+use crate::services::validation::{validate_track_name, validate_track_tags};
 use async_graphql::*;
 use uuid::Uuid;
 
@@ -5,13 +8,25 @@ use uuid::Uuid;
 pub struct Track {
     pub id: Uuid,
     pub name: String,
+    pub game_edition: Option<String>,
+    pub cup: Option<String>,
+    pub retro: bool,
+    pub tags: Vec<String>,
+    pub retired: bool,
+    pub built_in: bool,
 }
 
 impl From<crate::models::Track> for Track {
     fn from(model: crate::models::Track) -> Self {
         Self {
+            built_in: model.is_built_in(),
             id: model.id,
             name: model.name,
+            game_edition: model.game_edition,
+            cup: model.cup,
+            retro: model.retro,
+            tags: model.tags,
+            retired: model.retired,
         }
     }
 }
@@ -25,4 +40,230 @@ impl Track {
     async fn name(&self) -> &str {
         &self.name
     }
+
+    /// The game the track is raced in
+    async fn game_edition(&self) -> Option<&str> {
+        self.game_edition.as_deref()
+    }
+
+    /// The cup the track belongs to in its game
+    async fn cup(&self) -> Option<&str> {
+        self.cup.as_deref()
+    }
+
+    /// Whether the track is a remake of a track from an earlier game
+    async fn retro(&self) -> bool {
+        self.retro
+    }
+
+    async fn tags(&self) -> &[String] {
+        &self.tags
+    }
+
+    /// Retired tracks are no longer selected for new matches
+    async fn retired(&self) -> bool {
+        self.retired
+    }
+
+    /// Whether this is a built-in track shared by every group
+    async fn built_in(&self) -> bool {
+        self.built_in
+    }
+
+    /// How far finishing orders on the track stray from the order of the
+    /// players' ratings, from 0 (always as expected) to 1 (always reversed).
+    /// Null until the track has been raced by at least two players
+    async fn chaos_index(&self, ctx: &Context<'_>) -> Result<Option<f64>> {
+        let gql_ctx = ctx.data::<GraphQLContext>()?;
+
+        let chaos = gql_ctx.track_chaos_loader.load_one(self.id).await?;
+
+        Ok(chaos.map(|chaos| chaos.chaos_index))
+    }
+
+    /// The current group's best rated players on the track
+    async fn specialists(
+        &self,
+        ctx: &Context<'_>,
+        #[graphql(default = 5, desc = "Maximum number of players")] limit: i32,
+    ) -> Result<Vec<PlayerTrackRating>> {
+        let gql_ctx = ctx.data::<GraphQLContext>()?;
+        let group_id = gql_ctx.authenticated_group_id()?;
+
+        let ratings = crate::models::PlayerTrackRating::find_top_by_track(
+            &gql_ctx.pool,
+            self.id,
+            group_id,
+            i64::from(limit.clamp(1, 50)),
+        )
+        .await?;
+
+        Ok(ratings.into_iter().map(PlayerTrackRating::from).collect())
+    }
+}
+
+/// A player's ELO rating on a single track
+#[derive(Clone)]
+pub struct PlayerTrackRating {
+    pub player_id: Uuid,
+    pub elo_rating: i32,
+    pub races_played: i32,
+}
+
+impl From<crate::models::PlayerTrackRating> for PlayerTrackRating {
+    fn from(model: crate::models::PlayerTrackRating) -> Self {
+        Self {
+            player_id: model.player_id,
+            elo_rating: model.elo_rating,
+            races_played: model.races_played,
+        }
+    }
+}
+
+#[Object]
+impl PlayerTrackRating {
+    async fn player(&self, ctx: &Context<'_>) -> Result<Player> {
+        let gql_ctx = ctx.data::<GraphQLContext>()?;
+
+        let player = gql_ctx
+            .player_loader
+            .load_one(self.player_id)
+            .await?
+            .ok_or_else(|| Error::new("Player not found"))?;
+
+        Ok(Player::from(player))
+    }
+
+    async fn elo_rating(&self) -> i32 {
+        self.elo_rating
+    }
+
+    async fn races_played(&self) -> i32 {
+        self.races_played
+    }
+}
+
+/// Track details for creating or updating a group's track
+#[derive(InputObject)]
+pub struct TrackInput {
+    pub name: String,
+    pub game_edition: Option<String>,
+    pub cup: Option<String>,
+    #[graphql(default)]
+    pub retro: bool,
+    #[graphql(default)]
+    pub tags: Vec<String>,
+}
+
+impl TrackInput {
+    /// Validates the input and trims its text, dropping blank optional fields
+    /// and duplicate tags.
+    pub fn into_details(self) -> Result<crate::models::TrackDetails> {
+        validate_track_name(&self.name)?;
+        validate_track_tags(&self.tags)?;
+
+        let non_blank = |value: Option<String>| {
+            value
+                .map(|v| v.trim().to_string())
+                .filter(|v| !v.is_empty())
+        };
+
+        let tags = self.tags.iter().map(|tag| tag.trim().to_string()).fold(
+            Vec::new(),
+            |mut acc: Vec<String>, tag| {
+                if !acc.contains(&tag) {
+                    acc.push(tag);
+                }
+                acc
+            },
+        );
+
+        Ok(crate::models::TrackDetails {
+            name: self.name.trim().to_string(),
+            game_edition: non_blank(self.game_edition),
+            cup: non_blank(self.cup),
+            retro: self.retro,
+            tags,
+        })
+    }
+}
+
+/// Loads a built-in track or one of the group's own tracks, by GraphQL ID,
+/// retired as seen by the group.
+pub(crate) async fn find_available_track(
+    pool: &crate::db::DbPool,
+    group_id: Uuid,
+    id: &ID,
+) -> Result<crate::models::Track> {
+    let uuid = Uuid::parse_str(id).map_err(|_| Error::new("Invalid track ID"))?;
+
+    crate::models::Track::find_by_id(pool, uuid, Some(group_id))
+        .await?
+        .filter(|track| track.is_available_to(group_id))
+        .ok_or_else(|| Error::new("Track not found"))
+}
+
+/// Loads one of a group's own tracks, by GraphQL ID. Built-in tracks can only
+/// be retired by a group, not changed.
+pub(crate) async fn find_group_track(
+    pool: &crate::db::DbPool,
+    group_id: Uuid,
+    id: &ID,
+) -> Result<crate::models::Track> {
+    let track = find_available_track(pool, group_id, id).await?;
+
+    if track.is_built_in() {
+        return Err(Error::new("Built-in tracks cannot be changed"));
+    }
+
+    Ok(track)
+}
+
+/// A track a player would rather not race in a tournament
+#[derive(Clone)]
+pub struct TrackVeto {
+    pub player_id: Uuid,
+    pub tournament_id: Uuid,
+    pub track_id: Uuid,
+}
+
+impl From<crate::models::TrackVeto> for TrackVeto {
+    fn from(model: crate::models::TrackVeto) -> Self {
+        Self {
+            player_id: model.player_id,
+            tournament_id: model.tournament_id,
+            track_id: model.track_id,
+        }
+    }
+}
+
+#[Object]
+impl TrackVeto {
+    async fn tournament_id(&self) -> ID {
+        ID(self.tournament_id.to_string())
+    }
+
+    async fn player(&self, ctx: &Context<'_>) -> Result<Player> {
+        let gql_ctx = ctx.data::<GraphQLContext>()?;
+
+        let player = gql_ctx
+            .player_loader
+            .load_one(self.player_id)
+            .await?
+            .ok_or_else(|| Error::new("Player not found"))?;
+
+        Ok(Player::from(player))
+    }
+
+    async fn track(&self, ctx: &Context<'_>) -> Result<Track> {
+        let gql_ctx = ctx.data::<GraphQLContext>()?;
+
+        let track = gql_ctx
+            .track_loader
+            .load_one(self.track_id)
+            .await?
+            .ok_or_else(|| Error::new("Track not found"))?;
+
+        Ok(Track::from(track))
+    }
 }
@@ -0,0 +1,52 @@
+use crate::config::Config;
+use crate::services::avatar::{self, AVATAR_SIZES, DEFAULT_AVATAR_SIZE};
+use crate::services::avatar_storage;
+use axum::{
+    extract::{Extension as AxumExtension, Path},
+    http::{StatusCode, header},
+    response::{IntoResponse, Response},
+};
+
+/// Uploaded avatars never change under the same filename, so clients and
+/// proxies may cache them for good.
+const AVATAR_CACHE_CONTROL: &str = "public, max-age=31536000, immutable";
+
+/// Serves an uploaded avatar at one of the stored sizes
+pub async fn avatar_handler(
+    config: AxumExtension<Config>,
+    Path((size, filename)): Path<(u32, String)>,
+) -> Response {
+    serve_avatar(&config, size, &filename).await
+}
+
+/// Serves an uploaded avatar at the default size
+pub async fn default_avatar_handler(
+    config: AxumExtension<Config>,
+    Path(filename): Path<String>,
+) -> Response {
+    serve_avatar(&config, DEFAULT_AVATAR_SIZE, &filename).await
+}
+
+async fn serve_avatar(config: &Config, size: u32, filename: &str) -> Response {
+    if !AVATAR_SIZES.contains(&size) || !avatar::is_uploaded_avatar(filename) {
+        return StatusCode::NOT_FOUND.into_response();
+    }
+
+    let storage = avatar_storage::from_config(config);
+
+    match storage.get(&avatar::storage_key(size, filename)).await {
+        Ok(Some(bytes)) => (
+            [
+                (header::CONTENT_TYPE, "image/jpeg"),
+                (header::CACHE_CONTROL, AVATAR_CACHE_CONTROL),
+            ],
+            bytes,
+        )
+            .into_response(),
+        Ok(None) => StatusCode::NOT_FOUND.into_response(),
+        Err(e) => {
+            tracing::error!("Failed to read avatar {}: {}", filename, e);
+            StatusCode::INTERNAL_SERVER_ERROR.into_response()
+        }
+    }
+}
@@ -1,3 +1,5 @@
+pub mod avatars;
 pub mod graphql;
 
+pub use avatars::{avatar_handler, default_avatar_handler};
 pub use graphql::{graphql_handler, graphql_playground, graphql_sse_handler, unified_graphql_handler};
@@ -7,7 +7,7 @@ use mario_kart_leaderboard_backend::{
     config::Config,
     db::create_pool,
     graphql::build_schema,
-    handlers::{graphql_playground, unified_graphql_handler},
+    handlers::{avatar_handler, default_avatar_handler, graphql_playground, unified_graphql_handler},
     middleware::auth::auth_middleware,
     observability::{init_telemetry, shutdown_telemetry},
     services::notification_manager::NotificationManager,
@@ -72,6 +72,8 @@ async fn main() -> Result<(), AppError> {
             "/graphql",
             post(unified_graphql_handler).get(unified_graphql_handler),
         )
+        .route("/avatars/{filename}", get(default_avatar_handler))
+        .route("/avatars/{size}/{filename}", get(avatar_handler))
         .layer(middleware::from_fn_with_state(
             config.clone(),
             auth_middleware,
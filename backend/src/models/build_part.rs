@@ -0,0 +1,79 @@
+use sqlx::{FromRow, PgExecutor, Type};
+use uuid::Uuid;
+
+/// The slot a build part fills.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Type)]
+#[sqlx(type_name = "build_part_kind", rename_all = "snake_case")]
+pub enum BuildPartKind {
+    Character,
+    Kart,
+    Wheels,
+    Glider,
+}
+
+/// A character or vehicle part from the catalogue
+#[derive(Debug, Clone, FromRow)]
+pub struct BuildPart {
+    pub id: Uuid,
+    pub kind: BuildPartKind,
+    pub name: String,
+}
+
+/// The character and parts a player raced with; any of them may be unknown.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, FromRow)]
+pub struct RaceBuild {
+    pub character_id: Option<Uuid>,
+    pub kart_id: Option<Uuid>,
+    pub wheels_id: Option<Uuid>,
+    pub glider_id: Option<Uuid>,
+}
+
+impl RaceBuild {
+    pub fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+
+    /// The parts of the build with the kind each slot requires.
+    pub fn parts(&self) -> impl Iterator<Item = (Uuid, BuildPartKind)> {
+        [
+            (self.character_id, BuildPartKind::Character),
+            (self.kart_id, BuildPartKind::Kart),
+            (self.wheels_id, BuildPartKind::Wheels),
+            (self.glider_id, BuildPartKind::Glider),
+        ]
+        .into_iter()
+        .filter_map(|(id, kind)| id.map(|id| (id, kind)))
+    }
+}
+
+impl BuildPart {
+    pub async fn find_all<'e>(
+        executor: impl PgExecutor<'e>,
+        kind: Option<BuildPartKind>,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as::<_, Self>(
+            "SELECT id, kind, name
+             FROM build_parts
+             WHERE $1::build_part_kind IS NULL OR kind = $1
+             ORDER BY kind, name",
+        )
+        .bind(kind)
+        .fetch_all(executor)
+        .await
+    }
+
+    pub async fn find_by_ids<'e>(
+        executor: impl PgExecutor<'e>,
+        ids: &[Uuid],
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as::<_, Self>(
+            "SELECT id, kind, name
+             FROM build_parts
+             WHERE id = ANY($1)
+             ORDER BY kind, name",
+        )
+        .bind(ids)
+        .fetch_all(executor)
+        .await
+    }
+}
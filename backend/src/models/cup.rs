@@ -0,0 +1,61 @@
+use crate::db::DbPool;
+use sqlx::FromRow;
+use uuid::Uuid;
+
+use super::Track;
+
+/// A Grand Prix cup: four tracks raced in order.
+#[derive(Debug, Clone, FromRow)]
+pub struct Cup {
+    pub id: Uuid,
+    pub name: String,
+}
+
+#[derive(FromRow)]
+struct CupTrackRow {
+    cup_id: Uuid,
+    #[sqlx(flatten)]
+    track: Track,
+}
+
+impl Cup {
+    pub async fn find_all(pool: &DbPool) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as::<_, Self>("SELECT id, name FROM cups ORDER BY name")
+            .fetch_all(pool)
+            .await
+    }
+
+    pub async fn find_by_ids(pool: &DbPool, ids: &[Uuid]) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as::<_, Self>("SELECT id, name FROM cups WHERE id = ANY($1) ORDER BY name")
+            .bind(ids)
+            .fetch_all(pool)
+            .await
+    }
+
+    /// Loads the tracks of each cup in race order, keyed by cup ID, retired as
+    /// seen by the group when one is given.
+    pub async fn find_tracks_by_cup_ids(
+        pool: &DbPool,
+        cup_ids: &[Uuid],
+        group_id: Option<Uuid>,
+    ) -> Result<Vec<(Uuid, Track)>, sqlx::Error> {
+        let rows = sqlx::query_as::<_, CupTrackRow>(
+            "SELECT ct.cup_id, t.id, t.group_id, t.name, t.game_edition, t.cup, t.retro,
+                    t.tags,
+                    t.retired OR EXISTS(
+                        SELECT 1 FROM group_retired_tracks grt
+                        WHERE grt.track_id = t.id AND grt.group_id = $2
+                    ) AS retired
+             FROM cup_tracks ct
+             JOIN tracks t ON t.id = ct.track_id
+             WHERE ct.cup_id = ANY($1)
+             ORDER BY ct.cup_id, ct.position",
+        )
+        .bind(cup_ids)
+        .bind(group_id)
+        .fetch_all(pool)
+        .await?;
+
+        Ok(rows.into_iter().map(|row| (row.cup_id, row.track)).collect())
+    }
+}
@@ -0,0 +1,133 @@
+use crate::services::elo::EloSettings;
+use crate::services::rating_system::RatingSystemKind;
+use chrono::{DateTime, Utc};
+use sqlx::{FromRow, PgExecutor};
+use uuid::Uuid;
+
+#[derive(Debug, Clone, FromRow)]
+pub struct GroupSettings {
+    pub group_id: Uuid,
+    pub rating_system: RatingSystemKind,
+    pub k_factor: f64,
+    pub total_race_size: i32,
+    pub position_score_exponent: f64,
+    pub min_cpu_elo: i32,
+    pub cpu_elo_spread: i32,
+    pub cpu_elo_decrease: i32,
+    pub min_average_elo_for_cpu: i32,
+    pub max_average_elo_for_cpu: i32,
+    pub teammate_contribution_ratio: f64,
+    pub provisional_race_count: i32,
+    pub inactivity_weeks: i32,
+    pub inactivity_decay_per_week: i32,
+    pub repeat_teammate_penalty: i32,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl GroupSettings {
+    /// Fetch the stored settings of a group, if it has overridden the defaults.
+    pub async fn find_by_group_id<'e>(
+        executor: impl PgExecutor<'e>,
+        group_id: Uuid,
+    ) -> Result<Option<Self>, sqlx::Error> {
+        sqlx::query_as::<_, Self>(
+            "SELECT group_id, rating_system, k_factor, total_race_size, position_score_exponent,
+                    min_cpu_elo, cpu_elo_spread, cpu_elo_decrease,
+                    min_average_elo_for_cpu, max_average_elo_for_cpu,
+                    teammate_contribution_ratio, provisional_race_count,
+                    inactivity_weeks, inactivity_decay_per_week, repeat_teammate_penalty, updated_at
+             FROM group_settings
+             WHERE group_id = $1",
+        )
+        .bind(group_id)
+        .fetch_optional(executor)
+        .await
+    }
+
+    /// ELO settings of a group, falling back to the defaults when none are stored.
+    pub async fn find_elo_settings<'e>(
+        executor: impl PgExecutor<'e>,
+        group_id: Uuid,
+    ) -> Result<EloSettings, sqlx::Error> {
+        Ok(Self::find_by_group_id(executor, group_id)
+            .await?
+            .map(EloSettings::from)
+            .unwrap_or_default())
+    }
+
+    /// Insert or replace the settings of a group.
+    pub async fn upsert<'e>(
+        executor: impl PgExecutor<'e>,
+        group_id: Uuid,
+        settings: &EloSettings,
+    ) -> Result<Self, sqlx::Error> {
+        sqlx::query_as::<_, Self>(
+            "INSERT INTO group_settings (
+                group_id, rating_system, k_factor, total_race_size, position_score_exponent,
+                min_cpu_elo, cpu_elo_spread, cpu_elo_decrease,
+                min_average_elo_for_cpu, max_average_elo_for_cpu,
+                teammate_contribution_ratio, provisional_race_count,
+                inactivity_weeks, inactivity_decay_per_week, repeat_teammate_penalty
+             ) VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, $15)
+             ON CONFLICT (group_id) DO UPDATE SET
+                rating_system = EXCLUDED.rating_system,
+                k_factor = EXCLUDED.k_factor,
+                total_race_size = EXCLUDED.total_race_size,
+                position_score_exponent = EXCLUDED.position_score_exponent,
+                min_cpu_elo = EXCLUDED.min_cpu_elo,
+                cpu_elo_spread = EXCLUDED.cpu_elo_spread,
+                cpu_elo_decrease = EXCLUDED.cpu_elo_decrease,
+                min_average_elo_for_cpu = EXCLUDED.min_average_elo_for_cpu,
+                max_average_elo_for_cpu = EXCLUDED.max_average_elo_for_cpu,
+                teammate_contribution_ratio = EXCLUDED.teammate_contribution_ratio,
+                provisional_race_count = EXCLUDED.provisional_race_count,
+                inactivity_weeks = EXCLUDED.inactivity_weeks,
+                inactivity_decay_per_week = EXCLUDED.inactivity_decay_per_week,
+                repeat_teammate_penalty = EXCLUDED.repeat_teammate_penalty,
+                updated_at = NOW()
+             RETURNING group_id, rating_system, k_factor, total_race_size, position_score_exponent,
+                       min_cpu_elo, cpu_elo_spread, cpu_elo_decrease,
+                       min_average_elo_for_cpu, max_average_elo_for_cpu,
+                       teammate_contribution_ratio, provisional_race_count,
+                       inactivity_weeks, inactivity_decay_per_week, repeat_teammate_penalty, updated_at",
+        )
+        .bind(group_id)
+        .bind(settings.rating_system)
+        .bind(settings.k_factor)
+        .bind(settings.total_race_size)
+        .bind(settings.position_score_exponent)
+        .bind(settings.min_cpu_elo)
+        .bind(settings.cpu_elo_spread)
+        .bind(settings.cpu_elo_decrease)
+        .bind(settings.min_average_elo_for_cpu)
+        .bind(settings.max_average_elo_for_cpu)
+        .bind(settings.teammate_contribution_ratio)
+        .bind(settings.provisional_race_count)
+        .bind(settings.inactivity_weeks)
+        .bind(settings.inactivity_decay_per_week)
+        .bind(settings.repeat_teammate_penalty)
+        .fetch_one(executor)
+        .await
+    }
+}
+
+impl From<GroupSettings> for EloSettings {
+    fn from(model: GroupSettings) -> Self {
+        Self {
+            rating_system: model.rating_system,
+            k_factor: model.k_factor,
+            total_race_size: model.total_race_size,
+            position_score_exponent: model.position_score_exponent,
+            min_cpu_elo: model.min_cpu_elo,
+            cpu_elo_spread: model.cpu_elo_spread,
+            cpu_elo_decrease: model.cpu_elo_decrease,
+            min_average_elo_for_cpu: model.min_average_elo_for_cpu,
+            max_average_elo_for_cpu: model.max_average_elo_for_cpu,
+            teammate_contribution_ratio: model.teammate_contribution_ratio,
+            provisional_race_count: model.provisional_race_count,
+            inactivity_weeks: model.inactivity_weeks,
+            inactivity_decay_per_week: model.inactivity_decay_per_week,
+            repeat_teammate_penalty: model.repeat_teammate_penalty,
+        }
+    }
+}
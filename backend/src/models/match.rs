@@ -1,9 +1,19 @@
 use crate::db::DbPool;
 use chrono::{DateTime, Utc};
-use sqlx::FromRow;
+use sqlx::{FromRow, Type};
 use tracing::instrument;
 use uuid::Uuid;
 
+/// Whether a match is raced in teams or every player for themselves.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Type)]
+#[sqlx(type_name = "match_mode", rename_all = "snake_case")]
+pub enum MatchMode {
+    #[default]
+    Teams,
+    /// No teams: every player races every race and scores individual points
+    FreeForAll,
+}
+
 #[derive(Debug, Clone, FromRow)]
 pub struct Match {
     pub id: Uuid,
@@ -13,13 +23,19 @@ pub struct Match {
     #[sqlx(rename = "rounds")]
     pub num_of_rounds: i32,
     pub completed: bool,
+    /// Points table the match's team scores are calculated with
+    pub points_table_id: Uuid,
+    pub mode: MatchMode,
+    /// Track pool the match's tracks were drawn from; `None` for every active
+    /// track
+    pub track_pool_id: Option<Uuid>,
 }
 
 impl Match {
     #[instrument(level = "debug", skip(pool))]
     pub async fn find_by_id(pool: &DbPool, id: Uuid) -> Result<Option<Self>, sqlx::Error> {
         sqlx::query_as::<_, Self>(
-            "SELECT id, group_id, tournament_id, time, rounds, completed
+            "SELECT id, group_id, tournament_id, time, rounds, completed, points_table_id, mode, track_pool_id
              FROM matches
              WHERE id = $1",
         )
@@ -31,7 +47,7 @@ impl Match {
     #[instrument(level = "debug", skip(pool), fields(batch_size = ids.len()))]
     pub async fn find_by_ids(pool: &DbPool, ids: &[Uuid]) -> Result<Vec<Self>, sqlx::Error> {
         sqlx::query_as::<_, Self>(
-            "SELECT id, group_id, tournament_id, time, rounds, completed
+            "SELECT id, group_id, tournament_id, time, rounds, completed, points_table_id, mode, track_pool_id
              FROM matches
              WHERE id = ANY($1)",
         )
@@ -46,7 +62,7 @@ impl Match {
         tournament_id: Uuid,
     ) -> Result<Vec<Self>, sqlx::Error> {
         sqlx::query_as::<_, Self>(
-            "SELECT id, group_id, tournament_id, time, rounds, completed
+            "SELECT id, group_id, tournament_id, time, rounds, completed, points_table_id, mode, track_pool_id
              FROM matches
              WHERE tournament_id = $1
              ORDER BY time DESC",
@@ -62,7 +78,7 @@ impl Match {
         tournament_ids: &[Uuid],
     ) -> Result<Vec<Self>, sqlx::Error> {
         sqlx::query_as::<_, Self>(
-            "SELECT id, group_id, tournament_id, time, rounds, completed
+            "SELECT id, group_id, tournament_id, time, rounds, completed, points_table_id, mode, track_pool_id
              FROM matches
              WHERE tournament_id = ANY($1)
              ORDER BY time DESC",
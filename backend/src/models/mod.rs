@@ -1,27 +1,52 @@
+pub mod build_part;
+pub mod cup;
 pub mod group;
+pub mod group_settings;
 pub mod lobby_entry;
 pub mod r#match;
 pub mod player;
 pub mod player_match_score;
+pub mod player_name_change;
+pub mod player_pair_constraint;
 pub mod player_race_score;
+pub mod player_rating_decay;
 pub mod player_teammate_elo_contribution;
 pub mod player_tournament_score;
+pub mod player_track_rating;
+pub mod points_table;
 pub mod round;
 pub mod team;
 pub mod tournament;
 pub mod tournament_stat;
 pub mod track;
+pub mod track_pool;
+pub mod track_veto;
 
+pub use build_part::{BuildPart, BuildPartKind, RaceBuild};
+pub use cup::Cup;
 pub use group::Group;
+pub use group_settings::GroupSettings;
 pub use lobby_entry::LobbyEntry;
-pub use r#match::Match;
-pub use player::Player;
+pub use r#match::{Match, MatchMode};
+pub use player::{Player, PlayerActivity};
 pub use player_match_score::{PlayerMatchScore, PlayerMatchWithTime};
-pub use player_race_score::{PlayerRaceScore, PlayerTrackAggregation};
+pub use player_name_change::PlayerNameChange;
+pub use player_pair_constraint::{PairConstraintKind, PlayerPairConstraint};
+pub use player_race_score::{
+    PlayerBuildAggregation, PlayerCharacterAggregation, PlayerRaceScore, PlayerTrackAggregation,
+    ResultStatus, TrackChaosIndex,
+};
+pub use player_rating_decay::PlayerRatingDecay;
 pub use player_teammate_elo_contribution::PlayerTeammateEloContribution;
-pub use player_tournament_score::{PlayerTournamentPlacingRow, PlayerTournamentScore};
+pub use player_tournament_score::{
+    PlayerTournamentPlacingRow, PlayerTournamentScore, TournamentLeaderboardRow,
+};
+pub use player_track_rating::PlayerTrackRating;
+pub use points_table::PointsTable;
 pub use round::Round;
 pub use team::Team;
 pub use tournament::{CompletedTournamentRow, Tournament};
 pub use tournament_stat::{BiggestSwingData, TournamentStat, TournamentStatType};
-pub use track::Track;
+pub use track::{Track, TrackDetails};
+pub use track_pool::{TrackPool, TrackPoolTrack};
+pub use track_veto::TrackVeto;
@@ -1,5 +1,7 @@
 use crate::db::DbPool;
-use sqlx::FromRow;
+use crate::services::elo::{DEFAULT_INACTIVITY_WEEKS, DEFAULT_PROVISIONAL_RACE_COUNT};
+use chrono::{DateTime, Duration, Utc};
+use sqlx::{FromRow, PgExecutor};
 use tracing::instrument;
 use uuid::Uuid;
 
@@ -9,15 +11,42 @@ pub struct Player {
     pub group_id: Uuid,
     pub name: String,
     pub elo_rating: i32,
+    pub rating_deviation: f64,
+    pub rating_volatility: f64,
     pub avatar_filename: Option<String>,
     pub disabled: bool,
+    /// Rated as an opponent but left off the leaderboards
+    pub guest: bool,
+}
+
+/// How much a player has raced, with the thresholds of their group's settings.
+#[derive(Debug, Clone, FromRow)]
+pub struct PlayerActivity {
+    pub player_id: Uuid,
+    pub races_played: i64,
+    pub last_raced_at: Option<DateTime<Utc>>,
+    pub provisional_race_count: i32,
+    pub inactivity_weeks: i32,
+}
+
+impl PlayerActivity {
+    pub fn is_provisional(&self) -> bool {
+        self.races_played < i64::from(self.provisional_race_count)
+    }
+
+    /// Players who have never raced are provisional, not inactive.
+    pub fn is_inactive(&self, now: DateTime<Utc>) -> bool {
+        self.last_raced_at.is_some_and(|last_raced_at| {
+            now - last_raced_at >= Duration::weeks(i64::from(self.inactivity_weeks))
+        })
+    }
 }
 
 impl Player {
     #[instrument(level = "debug", skip(pool))]
     pub async fn find_by_id(pool: &DbPool, id: Uuid) -> Result<Option<Self>, sqlx::Error> {
         sqlx::query_as::<_, Self>(
-            "SELECT id, group_id, name, elo_rating, avatar_filename, disabled FROM players WHERE id = $1",
+            "SELECT id, group_id, name, elo_rating, rating_deviation, rating_volatility, avatar_filename, disabled, guest FROM players WHERE id = $1",
         )
         .bind(id)
         .fetch_optional(pool)
@@ -27,7 +56,7 @@ impl Player {
     #[instrument(level = "debug", skip(pool), fields(batch_size = ids.len()))]
     pub async fn find_by_ids(pool: &DbPool, ids: &[Uuid]) -> Result<Vec<Self>, sqlx::Error> {
         sqlx::query_as::<_, Self>(
-            "SELECT id, group_id, name, elo_rating, avatar_filename, disabled FROM players WHERE id = ANY($1)",
+            "SELECT id, group_id, name, elo_rating, rating_deviation, rating_volatility, avatar_filename, disabled, guest FROM players WHERE id = ANY($1)",
         )
         .bind(ids)
         .fetch_all(pool)
@@ -38,11 +67,15 @@ impl Player {
     pub async fn find_by_group_id(
         pool: &DbPool,
         group_id: Uuid,
+        include_disabled: bool,
+        include_guests: bool,
     ) -> Result<Vec<Self>, sqlx::Error> {
         sqlx::query_as::<_, Self>(
-            "SELECT id, group_id, name, elo_rating, avatar_filename, disabled FROM players WHERE group_id = $1 AND disabled = FALSE ORDER BY elo_rating DESC"
+            "SELECT id, group_id, name, elo_rating, rating_deviation, rating_volatility, avatar_filename, disabled, guest FROM players WHERE group_id = $1 AND (disabled = FALSE OR $2) AND (guest = FALSE OR $3) ORDER BY elo_rating DESC"
         )
         .bind(group_id)
+        .bind(include_disabled)
+        .bind(include_guests)
         .fetch_all(pool)
         .await
     }
@@ -53,13 +86,37 @@ impl Player {
         group_ids: &[Uuid],
     ) -> Result<Vec<Self>, sqlx::Error> {
         sqlx::query_as::<_, Self>(
-            "SELECT id, group_id, name, elo_rating, avatar_filename, disabled FROM players WHERE group_id = ANY($1) AND disabled = FALSE ORDER BY elo_rating DESC"
+            "SELECT id, group_id, name, elo_rating, rating_deviation, rating_volatility, avatar_filename, disabled, guest FROM players WHERE group_id = ANY($1) AND disabled = FALSE AND guest = FALSE ORDER BY elo_rating DESC"
         )
         .bind(group_ids)
         .fetch_all(pool)
         .await
     }
 
+    #[instrument(level = "debug", skip(pool), fields(batch_size = ids.len()))]
+    pub async fn find_activity_by_ids(
+        pool: &DbPool,
+        ids: &[Uuid],
+    ) -> Result<Vec<PlayerActivity>, sqlx::Error> {
+        sqlx::query_as::<_, PlayerActivity>(
+            "SELECT p.id AS player_id,
+                    COUNT(prs.player_id) AS races_played,
+                    MAX(prs.created_at) AS last_raced_at,
+                    COALESCE(gs.provisional_race_count, $2) AS provisional_race_count,
+                    COALESCE(gs.inactivity_weeks, $3) AS inactivity_weeks
+             FROM players p
+             LEFT JOIN group_settings gs ON gs.group_id = p.group_id
+             LEFT JOIN player_race_scores prs ON prs.player_id = p.id
+             WHERE p.id = ANY($1)
+             GROUP BY p.id, gs.provisional_race_count, gs.inactivity_weeks",
+        )
+        .bind(ids)
+        .bind(DEFAULT_PROVISIONAL_RACE_COUNT)
+        .bind(DEFAULT_INACTIVITY_WEEKS)
+        .fetch_all(pool)
+        .await
+    }
+
     #[instrument(level = "debug", skip(pool))]
     pub async fn create(
         pool: &DbPool,
@@ -67,21 +124,151 @@ impl Player {
         name: &str,
     ) -> Result<Self, sqlx::Error> {
         sqlx::query_as::<_, Self>(
-            "INSERT INTO players (group_id, name) VALUES ($1, $2) RETURNING id, group_id, name, elo_rating, avatar_filename, disabled"
+            "INSERT INTO players (group_id, name) VALUES ($1, $2) RETURNING id, group_id, name, elo_rating, rating_deviation, rating_volatility, avatar_filename, disabled, guest"
+        )
+        .bind(group_id)
+        .bind(name)
+        .fetch_one(pool)
+        .await
+    }
+
+    /// Creates a player who races and rates normally but stays off the
+    /// leaderboards until promoted.
+    #[instrument(level = "debug", skip(pool))]
+    pub async fn create_guest(
+        pool: &DbPool,
+        group_id: Uuid,
+        name: &str,
+    ) -> Result<Self, sqlx::Error> {
+        sqlx::query_as::<_, Self>(
+            "INSERT INTO players (group_id, name, guest) VALUES ($1, $2, TRUE) RETURNING id, group_id, name, elo_rating, rating_deviation, rating_volatility, avatar_filename, disabled, guest"
+        )
+        .bind(group_id)
+        .bind(name)
+        .fetch_one(pool)
+        .await
+    }
+
+    /// Whether another player in the group already has the name, ignoring case.
+    #[instrument(level = "debug", skip(pool))]
+    pub async fn is_name_taken(
+        pool: &DbPool,
+        group_id: Uuid,
+        name: &str,
+        exclude_id: Option<Uuid>,
+    ) -> Result<bool, sqlx::Error> {
+        sqlx::query_scalar(
+            "SELECT EXISTS(
+                SELECT 1 FROM players
+                WHERE group_id = $1
+                  AND LOWER(name) = LOWER($2)
+                  AND id IS DISTINCT FROM $3
+             )",
         )
         .bind(group_id)
         .bind(name)
+        .bind(exclude_id)
         .fetch_one(pool)
         .await
     }
 
+    /// Renames a player, keeping their current name in the name history.
+    #[instrument(level = "debug", skip(pool))]
+    pub async fn rename(pool: &DbPool, id: Uuid, name: &str) -> Result<Self, sqlx::Error> {
+        // Every CTE sees the row as it was before the update, so the history
+        // records the name being replaced.
+        sqlx::query_as::<_, Self>(
+            "WITH history AS (
+                INSERT INTO player_name_changes (player_id, previous_name)
+                SELECT id, name FROM players WHERE id = $1
+             )
+             UPDATE players SET name = $2
+             WHERE id = $1
+             RETURNING id, group_id, name, elo_rating, rating_deviation, rating_volatility, avatar_filename, disabled, guest",
+        )
+        .bind(id)
+        .bind(name)
+        .fetch_one(pool)
+        .await
+    }
+
+    #[instrument(level = "debug", skip(pool))]
+    pub async fn set_disabled(
+        pool: &DbPool,
+        id: Uuid,
+        disabled: bool,
+    ) -> Result<Self, sqlx::Error> {
+        sqlx::query_as::<_, Self>(
+            "UPDATE players SET disabled = $2 WHERE id = $1 RETURNING id, group_id, name, elo_rating, rating_deviation, rating_volatility, avatar_filename, disabled, guest"
+        )
+        .bind(id)
+        .bind(disabled)
+        .fetch_one(pool)
+        .await
+    }
+
+    #[instrument(level = "debug", skip(pool))]
+    pub async fn set_avatar_filename(
+        pool: &DbPool,
+        id: Uuid,
+        avatar_filename: Option<&str>,
+    ) -> Result<Self, sqlx::Error> {
+        sqlx::query_as::<_, Self>(
+            "UPDATE players SET avatar_filename = $2 WHERE id = $1 RETURNING id, group_id, name, elo_rating, rating_deviation, rating_volatility, avatar_filename, disabled, guest"
+        )
+        .bind(id)
+        .bind(avatar_filename)
+        .fetch_one(pool)
+        .await
+    }
+
+    /// Whether the player has been in a match or won a tournament.
+    #[instrument(level = "debug", skip(pool))]
+    pub async fn has_history(pool: &DbPool, id: Uuid) -> Result<bool, sqlx::Error> {
+        sqlx::query_scalar(
+            "SELECT EXISTS(SELECT 1 FROM match_players WHERE player_id = $1)
+                 OR EXISTS(SELECT 1 FROM round_players WHERE player_id = $1)
+                 OR EXISTS(SELECT 1 FROM player_race_scores WHERE player_id = $1)
+                 OR EXISTS(SELECT 1 FROM player_tournament_scores WHERE player_id = $1)
+                 OR EXISTS(SELECT 1 FROM tournaments WHERE winner = $1)",
+        )
+        .bind(id)
+        .fetch_one(pool)
+        .await
+    }
+
+    #[instrument(level = "debug", skip(pool))]
+    pub async fn delete(pool: &DbPool, id: Uuid) -> Result<(), sqlx::Error> {
+        sqlx::query("DELETE FROM players WHERE id = $1")
+            .bind(id)
+            .execute(pool)
+            .await?;
+
+        Ok(())
+    }
+
+    /// Makes a guest a full player. Their races and ratings are kept, so they
+    /// join the leaderboards where they stand.
+    #[instrument(level = "debug", skip(executor))]
+    pub async fn promote_guest<'e>(
+        executor: impl PgExecutor<'e>,
+        id: Uuid,
+    ) -> Result<Self, sqlx::Error> {
+        sqlx::query_as::<_, Self>(
+            "UPDATE players SET guest = FALSE WHERE id = $1 RETURNING id, group_id, name, elo_rating, rating_deviation, rating_volatility, avatar_filename, disabled, guest"
+        )
+        .bind(id)
+        .fetch_one(executor)
+        .await
+    }
+
     #[instrument(level = "debug", skip(pool), fields(batch_size = team_ids.len()))]
     pub async fn find_by_team_ids(
         pool: &DbPool,
         team_ids: &[Uuid],
     ) -> Result<Vec<(Uuid, Self)>, sqlx::Error> {
-        let rows = sqlx::query_as::<_, (Uuid, Uuid, Uuid, String, i32, Option<String>, bool)>(
-            "SELECT tp.team_id, p.id, p.group_id, p.name, p.elo_rating, p.avatar_filename, p.disabled
+        let rows = sqlx::query_as::<_, (Uuid, Uuid, Uuid, String, i32, f64, f64, Option<String>, bool, bool)>(
+            "SELECT tp.team_id, p.id, p.group_id, p.name, p.elo_rating, p.rating_deviation, p.rating_volatility, p.avatar_filename, p.disabled, p.guest
              FROM team_players tp
              JOIN players p ON tp.player_id = p.id
              WHERE tp.team_id = ANY($1)
@@ -94,7 +281,7 @@ impl Player {
         Ok(rows
             .into_iter()
             .map(
-                |(team_id, id, group_id, name, elo_rating, avatar_filename, disabled)| {
+                |(team_id, id, group_id, name, elo_rating, rating_deviation, rating_volatility, avatar_filename, disabled, guest)| {
                     (
                         team_id,
                         Player {
@@ -102,8 +289,11 @@ impl Player {
                             group_id,
                             name,
                             elo_rating,
+                            rating_deviation,
+                            rating_volatility,
                             avatar_filename,
                             disabled,
+                            guest,
                         },
                     )
                 },
@@ -122,8 +312,8 @@ impl Player {
             .map(|(_, round_number)| *round_number)
             .collect();
 
-        let rows = sqlx::query_as::<_, (Uuid, i32, Uuid, Uuid, String, i32, Option<String>, bool)>(
-            "SELECT rp.match_id, rp.round_number, p.id, p.group_id, p.name, p.elo_rating, p.avatar_filename, p.disabled
+        let rows = sqlx::query_as::<_, (Uuid, i32, Uuid, Uuid, String, i32, f64, f64, Option<String>, bool, bool)>(
+            "SELECT rp.match_id, rp.round_number, p.id, p.group_id, p.name, p.elo_rating, p.rating_deviation, p.rating_volatility, p.avatar_filename, p.disabled, p.guest
              FROM round_players rp
              JOIN players p ON rp.player_id = p.id
              WHERE rp.match_id = ANY($1) AND rp.round_number = ANY($2)
@@ -137,7 +327,7 @@ impl Player {
         Ok(rows
             .into_iter()
             .map(
-                |(match_id, round_number, id, group_id, name, elo_rating, avatar_filename, disabled)| {
+                |(match_id, round_number, id, group_id, name, elo_rating, rating_deviation, rating_volatility, avatar_filename, disabled, guest)| {
                     (
                         (match_id, round_number),
                         Player {
@@ -145,8 +335,11 @@ impl Player {
                             group_id,
                             name,
                             elo_rating,
+                            rating_deviation,
+                            rating_volatility,
                             avatar_filename,
                             disabled,
+                            guest,
                         },
                     )
                 },
@@ -24,6 +24,8 @@ pub struct PlayerMatchScore {
     pub tournament_elo_change: i32,
     pub tournament_elo_from_races: i32,
     pub tournament_elo_from_contributions: i32,
+    /// Points scored over a completed free-for-all match
+    pub points: Option<i32>,
     pub created_at: DateTime<Utc>,
 }
 
@@ -37,7 +39,7 @@ impl PlayerMatchScore {
             "SELECT group_id, match_id, player_id, position,
                     elo_change, tournament_elo_change,
                     tournament_elo_from_races, tournament_elo_from_contributions,
-                    created_at
+                    points, created_at
              FROM player_match_scores
              WHERE match_id = $1
              ORDER BY position ASC",
@@ -56,7 +58,7 @@ impl PlayerMatchScore {
             "SELECT group_id, match_id, player_id, position,
                     elo_change, tournament_elo_change,
                     tournament_elo_from_races, tournament_elo_from_contributions,
-                    created_at
+                    points, created_at
              FROM player_match_scores
              WHERE match_id = ANY($1)
              ORDER BY match_id, position ASC",
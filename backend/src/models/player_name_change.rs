@@ -0,0 +1,33 @@
+use crate::db::DbPool;
+use chrono::{DateTime, Utc};
+use sqlx::FromRow;
+use tracing::instrument;
+use uuid::Uuid;
+
+/// A name a player went by until they were renamed.
+#[derive(Debug, Clone, FromRow)]
+pub struct PlayerNameChange {
+    pub id: Uuid,
+    pub player_id: Uuid,
+    pub previous_name: String,
+    pub changed_at: DateTime<Utc>,
+}
+
+impl PlayerNameChange {
+    /// Fetches a player's earlier names, most recent first.
+    #[instrument(level = "debug", skip(pool))]
+    pub async fn find_by_player_id(
+        pool: &DbPool,
+        player_id: Uuid,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as::<_, Self>(
+            "SELECT id, player_id, previous_name, changed_at
+             FROM player_name_changes
+             WHERE player_id = $1
+             ORDER BY changed_at DESC",
+        )
+        .bind(player_id)
+        .fetch_all(pool)
+        .await
+    }
+}
@@ -0,0 +1,87 @@
+use chrono::{DateTime, Utc};
+use sqlx::{FromRow, PgExecutor, Type};
+use uuid::Uuid;
+
+/// Whether a pair of players must or must not share a team.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Type)]
+#[sqlx(type_name = "pair_constraint_kind", rename_all = "snake_case")]
+pub enum PairConstraintKind {
+    Together,
+    Apart,
+}
+
+/// A group-level team constraint between two players, stored lowest player
+/// ID first.
+#[derive(Debug, Clone, FromRow)]
+pub struct PlayerPairConstraint {
+    pub id: Uuid,
+    pub group_id: Uuid,
+    pub player_a_id: Uuid,
+    pub player_b_id: Uuid,
+    pub kind: PairConstraintKind,
+    pub created_at: DateTime<Utc>,
+}
+
+impl PlayerPairConstraint {
+    pub async fn find_by_id<'e>(
+        executor: impl PgExecutor<'e>,
+        id: Uuid,
+    ) -> Result<Option<Self>, sqlx::Error> {
+        sqlx::query_as::<_, Self>(
+            "SELECT id, group_id, player_a_id, player_b_id, kind, created_at
+             FROM player_pair_constraints
+             WHERE id = $1",
+        )
+        .bind(id)
+        .fetch_optional(executor)
+        .await
+    }
+
+    pub async fn find_by_group_id<'e>(
+        executor: impl PgExecutor<'e>,
+        group_id: Uuid,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as::<_, Self>(
+            "SELECT id, group_id, player_a_id, player_b_id, kind, created_at
+             FROM player_pair_constraints
+             WHERE group_id = $1
+             ORDER BY created_at ASC, id ASC",
+        )
+        .bind(group_id)
+        .fetch_all(executor)
+        .await
+    }
+
+    /// Stores a constraint, replacing any existing constraint on the pair.
+    pub async fn upsert<'e>(
+        executor: impl PgExecutor<'e>,
+        group_id: Uuid,
+        player_a_id: Uuid,
+        player_b_id: Uuid,
+        kind: PairConstraintKind,
+    ) -> Result<Self, sqlx::Error> {
+        sqlx::query_as::<_, Self>(
+            "INSERT INTO player_pair_constraints (group_id, player_a_id, player_b_id, kind)
+             VALUES ($1, $2, $3, $4)
+             ON CONFLICT (group_id, player_a_id, player_b_id) DO UPDATE SET
+                kind = EXCLUDED.kind,
+                created_at = NOW()
+             RETURNING id, group_id, player_a_id, player_b_id, kind, created_at",
+        )
+        .bind(group_id)
+        .bind(player_a_id.min(player_b_id))
+        .bind(player_a_id.max(player_b_id))
+        .bind(kind)
+        .fetch_one(executor)
+        .await
+    }
+
+    pub async fn delete<'e>(executor: impl PgExecutor<'e>, id: Uuid) -> Result<bool, sqlx::Error> {
+        let result = sqlx::query("DELETE FROM player_pair_constraints WHERE id = $1")
+            .bind(id)
+            .execute(executor)
+            .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+}
@@ -1,9 +1,38 @@
 use crate::db::DbPool;
 use chrono::{DateTime, Utc};
-use sqlx::FromRow;
+use sqlx::{FromRow, PgExecutor, Type};
 use tracing::instrument;
 use uuid::Uuid;
 
+use super::RaceBuild;
+
+/// How a player's race ended.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Type)]
+#[sqlx(type_name = "result_status", rename_all = "snake_case")]
+pub enum ResultStatus {
+    #[default]
+    Finished,
+    /// Did not finish; rated as last place and scores the points table's
+    /// last-place points
+    Dnf,
+    /// Rated as last place and scores no points
+    Disqualified,
+    /// The race does not change the player's ratings and scores no points
+    Disconnected,
+}
+
+impl ResultStatus {
+    /// Whether the race changes the player's ratings.
+    pub fn is_rated(self) -> bool {
+        self != Self::Disconnected
+    }
+
+    /// Whether the player's position scores points.
+    pub fn scores_points(self) -> bool {
+        matches!(self, Self::Finished | Self::Dnf)
+    }
+}
+
 #[derive(Debug, Clone, FromRow)]
 pub struct PlayerRaceScore {
     pub group_id: Uuid,
@@ -11,6 +40,7 @@ pub struct PlayerRaceScore {
     pub round_number: i32,
     pub player_id: Uuid,
     pub position: i32,
+    pub status: ResultStatus,
     pub all_time_elo_change: Option<i32>,
     pub all_time_elo_after: Option<i32>,
     pub tournament_elo_change: Option<i32>,
@@ -20,9 +50,42 @@ pub struct PlayerRaceScore {
 
 #[derive(Debug, Clone, FromRow)]
 pub struct PlayerTrackAggregation {
+    pub track_id: Uuid,
     pub track_name: String,
     pub average_position: f64,
     pub races_played: i64,
+    /// The player's ELO on the track; `None` until ratings are recomputed for
+    /// races recorded before track ratings existed
+    pub track_elo: Option<i32>,
+}
+
+/// A player's results with one character.
+#[derive(Debug, Clone, FromRow)]
+pub struct PlayerCharacterAggregation {
+    pub character_id: Uuid,
+    pub races_played: i64,
+    pub average_position: f64,
+    /// Average all-time ELO change per race
+    pub average_elo_change: Option<f64>,
+}
+
+/// A build a player has raced with and how often.
+#[derive(Debug, Clone, FromRow)]
+pub struct PlayerBuildAggregation {
+    #[sqlx(flatten)]
+    pub build: RaceBuild,
+    pub races_played: i64,
+}
+
+/// How unpredictable finishing orders are on a track.
+#[derive(Debug, Clone, FromRow)]
+pub struct TrackChaosIndex {
+    pub track_id: Uuid,
+    /// Average displacement between the finishing order and the order of
+    /// pre-race ELO, from 0 (always as expected) to 1 (always reversed)
+    pub chaos_index: f64,
+    /// Races with at least two players that the index is based on
+    pub races: i64,
 }
 
 impl PlayerRaceScore {
@@ -32,7 +95,7 @@ impl PlayerRaceScore {
         match_id: Uuid,
     ) -> Result<Vec<Self>, sqlx::Error> {
         sqlx::query_as::<_, Self>(
-            "SELECT group_id, match_id, round_number, player_id, position,
+            "SELECT group_id, match_id, round_number, player_id, position, status,
                     all_time_elo_change, all_time_elo_after,
                     tournament_elo_change, tournament_elo_after, created_at
              FROM player_race_scores
@@ -51,7 +114,7 @@ impl PlayerRaceScore {
         round_number: i32,
     ) -> Result<Vec<Self>, sqlx::Error> {
         sqlx::query_as::<_, Self>(
-            "SELECT group_id, match_id, round_number, player_id, position,
+            "SELECT group_id, match_id, round_number, player_id, position, status,
                     all_time_elo_change, all_time_elo_after,
                     tournament_elo_change, tournament_elo_after, created_at
              FROM player_race_scores
@@ -73,7 +136,7 @@ impl PlayerRaceScore {
         let round_numbers: Vec<i32> = rounds.iter().map(|(_, round_num)| *round_num).collect();
 
         sqlx::query_as::<_, Self>(
-            "SELECT group_id, match_id, round_number, player_id, position,
+            "SELECT group_id, match_id, round_number, player_id, position, status,
                     all_time_elo_change, all_time_elo_after,
                     tournament_elo_change, tournament_elo_after, created_at
              FROM player_race_scores
@@ -109,6 +172,32 @@ impl PlayerRaceScore {
         .await
     }
 
+    /// All-time ELO after each of a player's races and inactivity decays, as
+    /// (elo, timestamp, is_decay) in chronological order.
+    #[instrument(level = "debug", skip(pool))]
+    pub async fn find_all_time_elo_history_by_player_id(
+        pool: &DbPool,
+        player_id: Uuid,
+    ) -> Result<Vec<(i32, String, bool)>, sqlx::Error> {
+        sqlx::query_as::<_, (i32, String, bool)>(
+            "SELECT elo, created_at::text AS timestamp, is_decay
+             FROM (
+                 SELECT prs.all_time_elo_after AS elo, prs.created_at, FALSE AS is_decay
+                 FROM player_race_scores prs
+                 WHERE prs.player_id = $1
+                   AND prs.all_time_elo_after IS NOT NULL
+                 UNION ALL
+                 SELECT d.elo_after, d.created_at, TRUE
+                 FROM player_rating_decays d
+                 WHERE d.player_id = $1
+             ) history
+             ORDER BY created_at ASC",
+        )
+        .bind(player_id)
+        .fetch_all(pool)
+        .await
+    }
+
     #[instrument(level = "debug", skip(pool))]
     pub async fn find_track_stats_by_player(
         pool: &DbPool,
@@ -116,12 +205,16 @@ impl PlayerRaceScore {
     ) -> Result<Vec<PlayerTrackAggregation>, sqlx::Error> {
         sqlx::query_as::<_, PlayerTrackAggregation>(
             "SELECT
+                t.id AS track_id,
                 t.name AS track_name,
                 AVG(prs.position)::float8 AS average_position,
-                COUNT(*)::bigint AS races_played
+                COUNT(*)::bigint AS races_played,
+                MAX(ptr.elo_rating) AS track_elo
              FROM player_race_scores prs
              INNER JOIN rounds r ON prs.match_id = r.match_id AND prs.round_number = r.round_number
              INNER JOIN tracks t ON r.track_id = t.id
+             LEFT JOIN player_track_ratings ptr
+                 ON ptr.player_id = prs.player_id AND ptr.track_id = t.id
              WHERE prs.player_id = $1
              GROUP BY t.id, t.name
              ORDER BY average_position ASC",
@@ -130,4 +223,143 @@ impl PlayerRaceScore {
         .fetch_all(pool)
         .await
     }
+
+    /// Calculates the chaos index of each given track that has been raced,
+    /// optionally only from one group's races.
+    ///
+    /// Each race's expected order ranks its players by all-time ELO going into
+    /// the race. Its chaos is the total rank displacement of the finishing
+    /// order, divided by the largest displacement possible for that many
+    /// players; the index averages this over every race on the track.
+    /// Disconnected players are left out of the race.
+    #[instrument(level = "debug", skip(pool))]
+    pub async fn find_track_chaos_indexes(
+        pool: &DbPool,
+        track_ids: &[Uuid],
+        group_id: Option<Uuid>,
+    ) -> Result<Vec<TrackChaosIndex>, sqlx::Error> {
+        sqlx::query_as::<_, TrackChaosIndex>(
+            "WITH ranked AS (
+                 SELECT
+                     r.track_id,
+                     prs.match_id,
+                     prs.round_number,
+                     ROW_NUMBER() OVER race_order AS actual_rank,
+                     ROW_NUMBER() OVER (
+                         PARTITION BY prs.match_id, prs.round_number
+                         ORDER BY prs.all_time_elo_after - prs.all_time_elo_change DESC,
+                                  prs.position ASC
+                     ) AS expected_rank,
+                     COUNT(*) OVER (PARTITION BY prs.match_id, prs.round_number) AS racers
+                 FROM player_race_scores prs
+                 INNER JOIN rounds r
+                     ON prs.match_id = r.match_id AND prs.round_number = r.round_number
+                 WHERE r.track_id = ANY($1)
+                   AND ($2::uuid IS NULL OR prs.group_id = $2)
+                   AND prs.status <> 'disconnected'
+                   AND prs.all_time_elo_after IS NOT NULL
+                   AND prs.all_time_elo_change IS NOT NULL
+                 WINDOW race_order AS (
+                     PARTITION BY prs.match_id, prs.round_number
+                     ORDER BY prs.position ASC
+                 )
+             ),
+             races AS (
+                 SELECT
+                     track_id,
+                     SUM(ABS(actual_rank - expected_rank))::float8
+                         / (MAX(racers) * MAX(racers) / 2) AS chaos
+                 FROM ranked
+                 GROUP BY track_id, match_id, round_number
+                 HAVING MAX(racers) >= 2
+             )
+             SELECT track_id, AVG(chaos)::float8 AS chaos_index, COUNT(*)::bigint AS races
+             FROM races
+             GROUP BY track_id",
+        )
+        .bind(track_ids)
+        .bind(group_id)
+        .fetch_all(pool)
+        .await
+    }
+
+    /// Stores the builds players raced with in a recorded race.
+    #[instrument(level = "debug", skip(executor, builds))]
+    pub async fn set_builds<'e>(
+        executor: impl PgExecutor<'e>,
+        match_id: Uuid,
+        round_number: i32,
+        builds: &[(Uuid, RaceBuild)],
+    ) -> Result<(), sqlx::Error> {
+        let player_ids: Vec<Uuid> = builds.iter().map(|(id, _)| *id).collect();
+        let character_ids: Vec<Option<Uuid>> = builds.iter().map(|(_, b)| b.character_id).collect();
+        let kart_ids: Vec<Option<Uuid>> = builds.iter().map(|(_, b)| b.kart_id).collect();
+        let wheels_ids: Vec<Option<Uuid>> = builds.iter().map(|(_, b)| b.wheels_id).collect();
+        let glider_ids: Vec<Option<Uuid>> = builds.iter().map(|(_, b)| b.glider_id).collect();
+
+        sqlx::query(
+            "UPDATE player_race_scores prs
+             SET character_id = u.character_id,
+                 kart_id = u.kart_id,
+                 wheels_id = u.wheels_id,
+                 glider_id = u.glider_id
+             FROM UNNEST($3::uuid[], $4::uuid[], $5::uuid[], $6::uuid[], $7::uuid[])
+                 AS u(player_id, character_id, kart_id, wheels_id, glider_id)
+             WHERE prs.match_id = $1 AND prs.round_number = $2 AND prs.player_id = u.player_id",
+        )
+        .bind(match_id)
+        .bind(round_number)
+        .bind(&player_ids)
+        .bind(&character_ids)
+        .bind(&kart_ids)
+        .bind(&wheels_ids)
+        .bind(&glider_ids)
+        .execute(executor)
+        .await?;
+
+        Ok(())
+    }
+
+    #[instrument(level = "debug", skip(pool))]
+    pub async fn find_character_stats_by_player(
+        pool: &DbPool,
+        player_id: Uuid,
+    ) -> Result<Vec<PlayerCharacterAggregation>, sqlx::Error> {
+        sqlx::query_as::<_, PlayerCharacterAggregation>(
+            "SELECT
+                character_id,
+                COUNT(*)::bigint AS races_played,
+                AVG(position)::float8 AS average_position,
+                AVG(all_time_elo_change)::float8 AS average_elo_change
+             FROM player_race_scores
+             WHERE player_id = $1 AND character_id IS NOT NULL
+             GROUP BY character_id
+             ORDER BY races_played DESC, average_position ASC",
+        )
+        .bind(player_id)
+        .fetch_all(pool)
+        .await
+    }
+
+    /// Fetches the build a player has raced with most often, counting only
+    /// races where at least one part was recorded.
+    #[instrument(level = "debug", skip(pool))]
+    pub async fn find_most_picked_build_by_player(
+        pool: &DbPool,
+        player_id: Uuid,
+    ) -> Result<Option<PlayerBuildAggregation>, sqlx::Error> {
+        sqlx::query_as::<_, PlayerBuildAggregation>(
+            "SELECT character_id, kart_id, wheels_id, glider_id, COUNT(*)::bigint AS races_played
+             FROM player_race_scores
+             WHERE player_id = $1
+               AND (character_id IS NOT NULL OR kart_id IS NOT NULL
+                    OR wheels_id IS NOT NULL OR glider_id IS NOT NULL)
+             GROUP BY character_id, kart_id, wheels_id, glider_id
+             ORDER BY races_played DESC, MAX(created_at) DESC
+             LIMIT 1",
+        )
+        .bind(player_id)
+        .fetch_optional(pool)
+        .await
+    }
 }
@@ -0,0 +1,95 @@
+use chrono::{DateTime, Utc};
+use sqlx::{FromRow, Postgres, Transaction};
+use uuid::Uuid;
+
+#[derive(Debug, Clone, FromRow)]
+pub struct PlayerRatingDecay {
+    pub id: Uuid,
+    pub group_id: Uuid,
+    pub player_id: Uuid,
+    pub weeks: i32,
+    pub elo_change: i32,
+    pub elo_after: i32,
+    pub created_at: DateTime<Utc>,
+}
+
+impl PlayerRatingDecay {
+    /// Inserts decay events as (player_id, weeks, elo_change, elo_after), all
+    /// recorded at `created_at`.
+    pub async fn insert_batch(
+        tx: &mut Transaction<'_, Postgres>,
+        group_id: Uuid,
+        created_at: DateTime<Utc>,
+        decays: &[(Uuid, i32, i32, i32)],
+    ) -> Result<(), sqlx::Error> {
+        if decays.is_empty() {
+            return Ok(());
+        }
+
+        let player_ids: Vec<Uuid> = decays.iter().map(|(pid, _, _, _)| *pid).collect();
+        let weeks: Vec<i32> = decays.iter().map(|(_, weeks, _, _)| *weeks).collect();
+        let elo_changes: Vec<i32> = decays.iter().map(|(_, _, change, _)| *change).collect();
+        let elo_afters: Vec<i32> = decays.iter().map(|(_, _, _, after)| *after).collect();
+
+        sqlx::query(
+            "INSERT INTO player_rating_decays
+             (group_id, player_id, weeks, elo_change, elo_after, created_at)
+             SELECT $1, u.player_id, u.weeks, u.elo_change, u.elo_after, $2
+             FROM UNNEST($3::uuid[], $4::int[], $5::int[], $6::int[])
+                 AS u(player_id, weeks, elo_change, elo_after)",
+        )
+        .bind(group_id)
+        .bind(created_at)
+        .bind(&player_ids)
+        .bind(&weeks)
+        .bind(&elo_changes)
+        .bind(&elo_afters)
+        .execute(&mut **tx)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Fetches a group's decay events in chronological order, optionally only
+    /// those recorded at or after `since`.
+    pub async fn find_by_group_id(
+        tx: &mut Transaction<'_, Postgres>,
+        group_id: Uuid,
+        since: Option<DateTime<Utc>>,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as::<_, Self>(
+            "SELECT id, group_id, player_id, weeks, elo_change, elo_after, created_at
+             FROM player_rating_decays
+             WHERE group_id = $1 AND ($2::timestamptz IS NULL OR created_at >= $2)
+             ORDER BY created_at ASC, id ASC",
+        )
+        .bind(group_id)
+        .bind(since)
+        .fetch_all(&mut **tx)
+        .await
+    }
+
+    pub async fn update_elo_after_batch(
+        tx: &mut Transaction<'_, Postgres>,
+        updates: &[(Uuid, i32)],
+    ) -> Result<(), sqlx::Error> {
+        if updates.is_empty() {
+            return Ok(());
+        }
+
+        let (ids, elo_afters): (Vec<Uuid>, Vec<i32>) = updates.iter().copied().unzip();
+
+        sqlx::query(
+            "UPDATE player_rating_decays d
+             SET elo_after = u.elo_after
+             FROM UNNEST($1::uuid[], $2::int[]) AS u(id, elo_after)
+             WHERE d.id = u.id",
+        )
+        .bind(&ids)
+        .bind(&elo_afters)
+        .execute(&mut **tx)
+        .await?;
+
+        Ok(())
+    }
+}
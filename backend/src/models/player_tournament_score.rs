@@ -1,6 +1,8 @@
 use crate::db::DbPool;
+use crate::services::elo::DEFAULT_PROVISIONAL_RACE_COUNT;
+use crate::services::rating_system::Uncertainty;
 use chrono::{DateTime, NaiveDate, Utc};
-use sqlx::{FromRow, Postgres, Transaction};
+use sqlx::{FromRow, PgExecutor, Postgres, Transaction};
 use std::collections::HashMap;
 use uuid::Uuid;
 
@@ -118,19 +120,100 @@ impl PlayerTournamentScore {
         Ok(())
     }
 
-    pub async fn get_tournament_leaderboard(
-        pool: &DbPool,
+    pub async fn get_uncertainty_batch(
+        tx: &mut Transaction<'_, Postgres>,
+        player_ids: &[Uuid],
+        tournament_id: Uuid,
+    ) -> Result<HashMap<Uuid, Uncertainty>, sqlx::Error> {
+        let rows = sqlx::query_as::<_, (Uuid, f64, f64)>(
+            "SELECT player_id, rating_deviation, rating_volatility FROM player_tournament_scores
+             WHERE player_id = ANY($1) AND tournament_id = $2",
+        )
+        .bind(player_ids)
+        .bind(tournament_id)
+        .fetch_all(&mut **tx)
+        .await?;
+
+        Ok(rows
+            .into_iter()
+            .map(|(player_id, deviation, volatility)| {
+                (
+                    player_id,
+                    Uncertainty {
+                        deviation,
+                        volatility,
+                    },
+                )
+            })
+            .collect())
+    }
+
+    pub async fn update_uncertainty_batch(
+        tx: &mut Transaction<'_, Postgres>,
+        updates: &[(Uuid, Uuid, Uncertainty)],
+    ) -> Result<(), sqlx::Error> {
+        let player_ids: Vec<Uuid> = updates.iter().map(|(pid, _, _)| *pid).collect();
+        let tournament_ids: Vec<Uuid> = updates.iter().map(|(_, tid, _)| *tid).collect();
+        let deviations: Vec<f64> = updates.iter().map(|(_, _, u)| u.deviation).collect();
+        let volatilities: Vec<f64> = updates.iter().map(|(_, _, u)| u.volatility).collect();
+
+        sqlx::query(
+            "UPDATE player_tournament_scores pts
+             SET rating_deviation = u.rating_deviation,
+                 rating_volatility = u.rating_volatility,
+                 updated_at = NOW()
+             FROM UNNEST($1::uuid[], $2::uuid[], $3::float8[], $4::float8[])
+                 AS u(player_id, tournament_id, rating_deviation, rating_volatility)
+             WHERE pts.player_id = u.player_id AND pts.tournament_id = u.tournament_id",
+        )
+        .bind(&player_ids)
+        .bind(&tournament_ids)
+        .bind(&deviations)
+        .bind(&volatilities)
+        .execute(&mut **tx)
+        .await?;
+
+        Ok(())
+    }
+
+    pub async fn get_tournament_leaderboard<'e>(
+        executor: impl PgExecutor<'e>,
         tournament_id: Uuid,
-    ) -> Result<Vec<(Uuid, String, i32, i32, Option<String>)>, sqlx::Error> {
-        sqlx::query_as::<_, (Uuid, String, i32, i32, Option<String>)>(
-            "SELECT p.id, p.name, pts.elo_rating, p.elo_rating, p.avatar_filename
+    ) -> Result<Vec<TournamentLeaderboardRow>, sqlx::Error> {
+        sqlx::query_as::<_, TournamentLeaderboardRow>(
+            "SELECT p.id AS player_id, p.name AS player_name, pts.elo_rating,
+                    pts.rating_deviation, p.elo_rating AS all_time_elo, p.avatar_filename,
+                    rc.races_played::int AS races_played,
+                    rc.races_played < COALESCE(gs.provisional_race_count, $2) AS provisional,
+                    former.previous_name AS formerly_known_as
              FROM player_tournament_scores pts
              JOIN players p ON p.id = pts.player_id
-             WHERE pts.tournament_id = $1
+             JOIN tournaments t ON t.id = pts.tournament_id
+             LEFT JOIN group_settings gs ON gs.group_id = pts.group_id
+             CROSS JOIN LATERAL (
+                 SELECT COUNT(*) AS races_played
+                 FROM player_race_scores prs
+                 WHERE prs.player_id = p.id
+             ) rc
+             -- The name the player went by when a completed tournament's last
+             -- match was played, if they have been renamed since
+             LEFT JOIN LATERAL (
+                 SELECT pnc.previous_name
+                 FROM player_name_changes pnc
+                 WHERE pnc.player_id = p.id
+                   AND t.winner IS NOT NULL
+                   AND pnc.changed_at > (
+                       SELECT MAX(m.time) FROM matches m WHERE m.tournament_id = t.id
+                   )
+                 ORDER BY pnc.changed_at ASC
+                 LIMIT 1
+             ) former ON TRUE
+             WHERE pts.tournament_id = $1 AND NOT p.guest
              ORDER BY pts.elo_rating DESC",
         )
         .bind(tournament_id)
-        .fetch_all(pool)
+        .bind(DEFAULT_PROVISIONAL_RACE_COUNT)
+        .fetch_all(executor)
         .await
     }
 
@@ -141,8 +224,8 @@ impl PlayerTournamentScore {
     ) -> Result<Vec<PlayerTournamentPlacingRow>, sqlx::Error> {
         // Rank every participant in completed tournaments first, then filter to the
         // requested player. Filtering before the window functions would make every
-        // placing 1/1. "placing" is also a reserved word in PostgreSQL, so it must
-        // be quoted as an identifier.
+        // placing 1/1. Guests are not ranked, so they have no placings. "placing" is
+        // also a reserved word in PostgreSQL, so it must be quoted as an identifier.
         sqlx::query_as::<_, PlayerTournamentPlacingRow>(
             r#"WITH ranked AS (
                 SELECT
@@ -158,8 +241,10 @@ impl PlayerTournamentScore {
                     COUNT(*) OVER (PARTITION BY pts.tournament_id)::int AS total_players
                 FROM player_tournament_scores pts
                 JOIN tournaments t ON t.id = pts.tournament_id
+                JOIN players p ON p.id = pts.player_id
                 WHERE t.group_id = $2
                   AND t.winner IS NOT NULL
+                  AND NOT p.guest
             )
             SELECT tournament_id, start_date, end_date, elo_rating, "placing", total_players
             FROM ranked
@@ -173,6 +258,22 @@ impl PlayerTournamentScore {
     }
 }
 
+#[derive(Debug, Clone, FromRow)]
+pub struct TournamentLeaderboardRow {
+    pub player_id: Uuid,
+    pub player_name: String,
+    pub elo_rating: i32,
+    pub rating_deviation: f64,
+    pub all_time_elo: i32,
+    pub avatar_filename: Option<String>,
+    /// All-time recorded races
+    pub races_played: i32,
+    /// Fewer recorded races than the group's provisional race count
+    pub provisional: bool,
+    /// The player's name during a completed tournament, if it has changed since
+    pub formerly_known_as: Option<String>,
+}
+
 #[derive(Debug, Clone, FromRow)]
 pub struct PlayerTournamentPlacingRow {
     pub tournament_id: Uuid,
@@ -0,0 +1,125 @@
+use chrono::{DateTime, Utc};
+use sqlx::{FromRow, PgExecutor, Postgres, Transaction};
+use std::collections::HashMap;
+use uuid::Uuid;
+
+/// A player's ELO rating on a single track
+#[derive(Debug, Clone, FromRow)]
+pub struct PlayerTrackRating {
+    pub player_id: Uuid,
+    pub track_id: Uuid,
+    pub elo_rating: i32,
+    pub races_played: i32,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl PlayerTrackRating {
+    /// Fetches the best rated players of a group on a track, leaving out guests.
+    pub async fn find_top_by_track<'e>(
+        executor: impl PgExecutor<'e>,
+        track_id: Uuid,
+        group_id: Uuid,
+        limit: i64,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as::<_, Self>(
+            "SELECT ptr.player_id, ptr.track_id, ptr.elo_rating, ptr.races_played, ptr.updated_at
+             FROM player_track_ratings ptr
+             JOIN players p ON p.id = ptr.player_id
+             WHERE ptr.track_id = $1 AND p.group_id = $2 AND NOT p.guest
+             ORDER BY ptr.elo_rating DESC, ptr.races_played DESC
+             LIMIT $3",
+        )
+        .bind(track_id)
+        .bind(group_id)
+        .bind(limit)
+        .fetch_all(executor)
+        .await
+    }
+
+    /// Fetches the track ratings of the given players, keyed by player ID.
+    /// Players who have not raced the track yet are missing.
+    pub async fn get_batch(
+        tx: &mut Transaction<'_, Postgres>,
+        player_ids: &[Uuid],
+        track_id: Uuid,
+    ) -> Result<HashMap<Uuid, i32>, sqlx::Error> {
+        let ratings = sqlx::query_as::<_, (Uuid, i32)>(
+            "SELECT player_id, elo_rating FROM player_track_ratings
+             WHERE player_id = ANY($1) AND track_id = $2",
+        )
+        .bind(player_ids)
+        .bind(track_id)
+        .fetch_all(&mut **tx)
+        .await?;
+
+        Ok(ratings.into_iter().collect())
+    }
+
+    /// Stores the ratings after one race on a track, counting the race for
+    /// each player.
+    pub async fn record_race_batch(
+        tx: &mut Transaction<'_, Postgres>,
+        track_id: Uuid,
+        new_elos: &[(Uuid, i32)],
+    ) -> Result<(), sqlx::Error> {
+        let (player_ids, elos): (Vec<Uuid>, Vec<i32>) = new_elos.iter().copied().unzip();
+
+        sqlx::query(
+            "INSERT INTO player_track_ratings (player_id, track_id, elo_rating, races_played)
+             SELECT u.player_id, $1, u.elo_rating, 1
+             FROM UNNEST($2::uuid[], $3::int[]) AS u(player_id, elo_rating)
+             ON CONFLICT (player_id, track_id) DO UPDATE
+             SET elo_rating = EXCLUDED.elo_rating,
+                 races_played = player_track_ratings.races_played + 1,
+                 updated_at = NOW()",
+        )
+        .bind(track_id)
+        .bind(&player_ids)
+        .bind(&elos)
+        .execute(&mut **tx)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Replaces every track rating of a group's players with the given
+    /// (player_id, track_id, elo_rating, races_played) rows.
+    pub async fn replace_for_group(
+        tx: &mut Transaction<'_, Postgres>,
+        group_id: Uuid,
+        ratings: &[(Uuid, Uuid, i32, i32)],
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query(
+            "DELETE FROM player_track_ratings
+             WHERE player_id IN (SELECT id FROM players WHERE group_id = $1)",
+        )
+        .bind(group_id)
+        .execute(&mut **tx)
+        .await?;
+
+        let (player_ids, track_ids, elos, races_played): (Vec<_>, Vec<_>, Vec<_>, Vec<_>) =
+            ratings.iter().fold(
+                (Vec::new(), Vec::new(), Vec::new(), Vec::new()),
+                |(mut pids, mut tids, mut elos, mut races), (pid, tid, elo, count)| {
+                    pids.push(*pid);
+                    tids.push(*tid);
+                    elos.push(*elo);
+                    races.push(*count);
+                    (pids, tids, elos, races)
+                },
+            );
+
+        sqlx::query(
+            "INSERT INTO player_track_ratings (player_id, track_id, elo_rating, races_played)
+             SELECT * FROM UNNEST($1::uuid[], $2::uuid[], $3::int[], $4::int[])",
+        )
+        .bind(&player_ids)
+        .bind(&track_ids)
+        .bind(&elos)
+        .bind(&races_played)
+        .execute(&mut **tx)
+        .await?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,128 @@
+use chrono::{DateTime, Utc};
+use sqlx::{FromRow, PgExecutor};
+use uuid::Uuid;
+
+/// Built-in Mario Kart 8 12-player table, used when no table is chosen
+pub const MK8_POINTS_TABLE_ID: Uuid = Uuid::from_u128(1);
+
+/// Built-in Mario Kart World 24-player table
+pub const MK_WORLD_POINTS_TABLE_ID: Uuid = Uuid::from_u128(2);
+
+#[derive(Debug, Clone, FromRow)]
+pub struct PointsTable {
+    pub id: Uuid,
+    /// `None` for built-in tables shared by every group
+    pub group_id: Option<Uuid>,
+    pub name: String,
+    /// Points per position, starting with first place
+    pub points: Vec<i32>,
+    pub created_at: DateTime<Utc>,
+}
+
+impl PointsTable {
+    pub fn is_built_in(&self) -> bool {
+        self.group_id.is_none()
+    }
+
+    /// Whether a group may use this table.
+    pub fn is_available_to(&self, group_id: Uuid) -> bool {
+        self.group_id.is_none_or(|owner| owner == group_id)
+    }
+
+    pub async fn find_by_id<'e>(
+        executor: impl PgExecutor<'e>,
+        id: Uuid,
+    ) -> Result<Option<Self>, sqlx::Error> {
+        sqlx::query_as::<_, Self>(
+            "SELECT id, group_id, name, points, created_at FROM points_tables WHERE id = $1",
+        )
+        .bind(id)
+        .fetch_optional(executor)
+        .await
+    }
+
+    pub async fn find_by_ids<'e>(
+        executor: impl PgExecutor<'e>,
+        ids: &[Uuid],
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as::<_, Self>(
+            "SELECT id, group_id, name, points, created_at FROM points_tables WHERE id = ANY($1)",
+        )
+        .bind(ids)
+        .fetch_all(executor)
+        .await
+    }
+
+    /// Fetches the built-in tables followed by the group's own tables.
+    pub async fn find_available_to_group<'e>(
+        executor: impl PgExecutor<'e>,
+        group_id: Uuid,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as::<_, Self>(
+            "SELECT id, group_id, name, points, created_at
+             FROM points_tables
+             WHERE group_id IS NULL OR group_id = $1
+             ORDER BY group_id NULLS FIRST, created_at ASC, name ASC",
+        )
+        .bind(group_id)
+        .fetch_all(executor)
+        .await
+    }
+
+    /// Fetches the points of the table a match is scored with.
+    pub async fn find_points_by_match_id<'e>(
+        executor: impl PgExecutor<'e>,
+        match_id: Uuid,
+    ) -> Result<Vec<i32>, sqlx::Error> {
+        sqlx::query_scalar(
+            "SELECT pt.points
+             FROM matches m
+             JOIN points_tables pt ON pt.id = m.points_table_id
+             WHERE m.id = $1",
+        )
+        .bind(match_id)
+        .fetch_one(executor)
+        .await
+    }
+
+    pub async fn create<'e>(
+        executor: impl PgExecutor<'e>,
+        group_id: Uuid,
+        name: &str,
+        points: &[i32],
+    ) -> Result<Self, sqlx::Error> {
+        sqlx::query_as::<_, Self>(
+            "INSERT INTO points_tables (group_id, name, points)
+             VALUES ($1, $2, $3)
+             RETURNING id, group_id, name, points, created_at",
+        )
+        .bind(group_id)
+        .bind(name)
+        .bind(points)
+        .fetch_one(executor)
+        .await
+    }
+
+    /// Whether any tournament or match references the table.
+    pub async fn is_in_use<'e>(
+        executor: impl PgExecutor<'e>,
+        id: Uuid,
+    ) -> Result<bool, sqlx::Error> {
+        sqlx::query_scalar(
+            "SELECT EXISTS(SELECT 1 FROM matches WHERE points_table_id = $1)
+                 OR EXISTS(SELECT 1 FROM tournaments WHERE points_table_id = $1)",
+        )
+        .bind(id)
+        .fetch_one(executor)
+        .await
+    }
+
+    pub async fn delete<'e>(executor: impl PgExecutor<'e>, id: Uuid) -> Result<(), sqlx::Error> {
+        sqlx::query("DELETE FROM points_tables WHERE id = $1")
+            .bind(id)
+            .execute(executor)
+            .await?;
+
+        Ok(())
+    }
+}
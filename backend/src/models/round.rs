@@ -4,7 +4,7 @@ use std::collections::HashMap;
 use tracing::instrument;
 use uuid::Uuid;
 
-use super::{PlayerRaceScore, Track};
+use super::{PlayerRaceScore, ResultStatus, Track};
 
 #[derive(Debug, Clone, FromRow)]
 pub struct Round {
@@ -12,6 +12,10 @@ pub struct Round {
     pub round_number: i32,
     pub track_id: Option<Uuid>,
     pub completed: bool,
+    /// Cup the round belongs to in Grand Prix matches
+    pub cup_id: Option<Uuid>,
+    /// Why track selection skipped tracks for the round, such as player vetoes
+    pub track_selection_notes: Vec<String>,
 }
 
 impl Round {
@@ -21,7 +25,7 @@ impl Round {
         round_number: i32,
     ) -> Result<Option<Self>, sqlx::Error> {
         sqlx::query_as::<_, Self>(
-            "SELECT match_id, round_number, track_id, completed
+            "SELECT match_id, round_number, track_id, completed, cup_id, track_selection_notes
              FROM rounds
              WHERE match_id = $1 AND round_number = $2",
         )
@@ -36,7 +40,7 @@ impl Round {
         match_id: Uuid,
     ) -> Result<Vec<Self>, sqlx::Error> {
         sqlx::query_as::<_, Self>(
-            "SELECT match_id, round_number, track_id, completed
+            "SELECT match_id, round_number, track_id, completed, cup_id, track_selection_notes
              FROM rounds
              WHERE match_id = $1
              ORDER BY round_number ASC",
@@ -50,23 +54,22 @@ impl Round {
     pub async fn get_by_match_with_tracks_and_results(
         pool: &DbPool,
         match_id: Uuid,
+        group_id: Uuid,
     ) -> Result<Vec<RoundWithTracksAndResults>, sqlx::Error> {
         let rows = sqlx::query_as::<_, (
-            Uuid, i32, Option<Uuid>, bool,
-            Option<Uuid>, Option<String>,
-            Option<Uuid>, Option<i32>, Option<i32>, Option<i32>,
+            Uuid, i32, Option<Uuid>, bool, Option<Uuid>, Vec<String>,
+            Option<Uuid>, Option<i32>, Option<ResultStatus>, Option<i32>, Option<i32>,
             Option<Uuid>,
         )>(
             "SELECT
-                r.match_id, r.round_number, r.track_id, r.completed,
-                t.id as track_db_id, t.name as track_name,
+                r.match_id, r.round_number, r.track_id, r.completed, r.cup_id, r.track_selection_notes,
                 prs.player_id as result_player_id,
                 prs.position as result_position,
+                prs.status as result_status,
                 prs.all_time_elo_change,
                 prs.tournament_elo_change,
                 rp.player_id as round_player_id
              FROM rounds r
-             LEFT JOIN tracks t ON t.id = r.track_id
              LEFT JOIN player_race_scores prs
                ON prs.match_id = r.match_id
                AND prs.round_number = r.round_number
@@ -82,15 +85,21 @@ impl Round {
 
         let grouped = rows.into_iter().fold(
             HashMap::<i32, RoundWithTracksAndResults>::new(),
-            |mut acc, (match_id, round_number, track_id, completed,
-                       opt_track_id, opt_track_name,
-                       opt_player_id, opt_position, opt_all_time_elo, opt_tournament_elo,
+            |mut acc, (match_id, round_number, track_id, completed, cup_id, track_selection_notes,
+                       opt_player_id, opt_position, opt_status, opt_all_time_elo, opt_tournament_elo,
                        opt_round_player_id)| {
 
                 let entry = acc.entry(round_number).or_insert_with(|| {
                     RoundWithTracksAndResults {
-                        round: Round { match_id, round_number, track_id, completed },
-                        track: opt_track_id.zip(opt_track_name).map(|(id, name)| Track { id, name }),
+                        round: Round {
+                            match_id,
+                            round_number,
+                            track_id,
+                            completed,
+                            cup_id,
+                            track_selection_notes,
+                        },
+                        track: None,
                         result_player_ids: Vec::new(),
                         results: Vec::new(),
                     }
@@ -105,6 +114,7 @@ impl Round {
                             round_number,
                             player_id,
                             position: opt_position.unwrap(),
+                            status: opt_status.unwrap_or_default(),
                             all_time_elo_change: opt_all_time_elo,
                             all_time_elo_after: None,
                             tournament_elo_change: opt_tournament_elo,
@@ -122,7 +132,26 @@ impl Round {
             },
         );
 
-        let mut result: Vec<RoundWithTracksAndResults> = grouped.into_values().collect();
+        let track_ids: Vec<Uuid> = grouped
+            .values()
+            .filter_map(|entry| entry.round.track_id)
+            .collect();
+        let tracks_by_id: HashMap<Uuid, Track> = Track::find_by_ids(pool, &track_ids, Some(group_id))
+            .await?
+            .into_iter()
+            .map(|track| (track.id, track))
+            .collect();
+
+        let mut result: Vec<RoundWithTracksAndResults> = grouped
+            .into_values()
+            .map(|entry| RoundWithTracksAndResults {
+                track: entry
+                    .round
+                    .track_id
+                    .and_then(|track_id| tracks_by_id.get(&track_id).cloned()),
+                ..entry
+            })
+            .collect();
         result.sort_by_key(|r| r.round.round_number);
 
         Ok(result)
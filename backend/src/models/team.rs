@@ -1,5 +1,6 @@
 use crate::db::DbPool;
-use sqlx::FromRow;
+use chrono::{DateTime, Utc};
+use sqlx::{FromRow, PgExecutor};
 use std::collections::HashMap;
 use tracing::instrument;
 use uuid::Uuid;
@@ -37,10 +38,11 @@ impl Team {
         pool: &DbPool,
         match_id: Uuid,
     ) -> Result<Vec<(Team, Vec<Player>)>, sqlx::Error> {
-        let rows = sqlx::query_as::<_, (Uuid, Uuid, Uuid, i32, Option<i32>, Uuid, Uuid, String, i32, Option<String>, bool)>(
+        let rows = sqlx::query_as::<_, (Uuid, Uuid, Uuid, i32, Option<i32>, Uuid, Uuid, String, i32, f64, f64, Option<String>, bool, bool)>(
             "SELECT
                 t.id, t.group_id, t.match_id, t.team_num, t.score,
-                p.id, p.group_id, p.name, p.elo_rating, p.avatar_filename, p.disabled
+                p.id, p.group_id, p.name, p.elo_rating, p.rating_deviation, p.rating_volatility,
+                p.avatar_filename, p.disabled, p.guest
              FROM teams t
              JOIN team_players tp ON tp.team_id = t.id
              JOIN players p ON p.id = tp.player_id
@@ -54,7 +56,8 @@ impl Team {
         let grouped = rows.into_iter().fold(
             HashMap::<Uuid, (Team, Vec<Player>)>::new(),
             |mut acc, (team_id, team_group_id, team_match_id, team_num, score,
-                       player_id, player_group_id, player_name, player_elo, player_avatar, disabled)| {
+                       player_id, player_group_id, player_name, player_elo, player_deviation, player_volatility,
+                       player_avatar, disabled, guest)| {
                 let team = Team {
                     id: team_id,
                     group_id: team_group_id,
@@ -67,8 +70,11 @@ impl Team {
                     group_id: player_group_id,
                     name: player_name,
                     elo_rating: player_elo,
+                    rating_deviation: player_deviation,
+                    rating_volatility: player_volatility,
                     avatar_filename: player_avatar,
                     disabled,
+                    guest,
                 };
 
                 acc.entry(team_id)
@@ -83,4 +89,28 @@ impl Team {
 
         Ok(result)
     }
+
+    /// Counts how often pairs of players were teammates in a tournament's
+    /// matches, as (player, player, times), optionally only in matches before
+    /// `before`.
+    #[instrument(level = "debug", skip(executor))]
+    pub async fn find_teammate_pair_counts<'e>(
+        executor: impl PgExecutor<'e>,
+        tournament_id: Uuid,
+        before: Option<DateTime<Utc>>,
+    ) -> Result<Vec<(Uuid, Uuid, i64)>, sqlx::Error> {
+        sqlx::query_as::<_, (Uuid, Uuid, i64)>(
+            "SELECT a.player_id, b.player_id, COUNT(*)
+             FROM team_players a
+             JOIN team_players b ON b.team_id = a.team_id AND b.player_id > a.player_id
+             JOIN teams t ON t.id = a.team_id
+             JOIN matches m ON m.id = t.match_id
+             WHERE m.tournament_id = $1 AND ($2::timestamptz IS NULL OR m.time < $2)
+             GROUP BY a.player_id, b.player_id",
+        )
+        .bind(tournament_id)
+        .bind(before)
+        .fetch_all(executor)
+        .await
+    }
 }
@@ -11,6 +11,10 @@ pub struct Tournament {
     pub start_date: Option<NaiveDate>,
     pub end_date: Option<NaiveDate>,
     pub winner: Option<Uuid>,
+    /// Points table for new matches; `None` uses the Mario Kart 8 table
+    pub points_table_id: Option<Uuid>,
+    /// Track pool for new matches; `None` draws from every active track
+    pub track_pool_id: Option<Uuid>,
 }
 
 #[derive(Debug, Clone, FromRow)]
@@ -28,7 +32,8 @@ impl Tournament {
     #[instrument(level = "debug", skip(pool))]
     pub async fn find_by_id(pool: &DbPool, id: Uuid) -> Result<Option<Self>, sqlx::Error> {
         sqlx::query_as::<_, Self>(
-            "SELECT id, group_id, start_date, end_date, winner FROM tournaments WHERE id = $1",
+            "SELECT id, group_id, start_date, end_date, winner, points_table_id, track_pool_id
+             FROM tournaments WHERE id = $1",
         )
         .bind(id)
         .fetch_optional(pool)
@@ -38,7 +43,8 @@ impl Tournament {
     #[instrument(level = "debug", skip(pool), fields(batch_size = ids.len()))]
     pub async fn find_by_ids(pool: &DbPool, ids: &[Uuid]) -> Result<Vec<Self>, sqlx::Error> {
         sqlx::query_as::<_, Self>(
-            "SELECT id, group_id, start_date, end_date, winner FROM tournaments WHERE id = ANY($1)",
+            "SELECT id, group_id, start_date, end_date, winner, points_table_id, track_pool_id
+             FROM tournaments WHERE id = ANY($1)",
         )
         .bind(ids)
         .fetch_all(pool)
@@ -51,7 +57,7 @@ impl Tournament {
         group_id: Uuid,
     ) -> Result<Vec<Self>, sqlx::Error> {
         sqlx::query_as::<_, Self>(
-            "SELECT id, group_id, start_date, end_date, winner
+            "SELECT id, group_id, start_date, end_date, winner, points_table_id, track_pool_id
              FROM tournaments
              WHERE group_id = $1
              ORDER BY start_date DESC NULLS LAST",
@@ -105,15 +111,57 @@ impl Tournament {
         group_id: Uuid,
         start_date: Option<NaiveDate>,
         end_date: Option<NaiveDate>,
+        points_table_id: Option<Uuid>,
+        track_pool_id: Option<Uuid>,
     ) -> Result<Self, sqlx::Error> {
         sqlx::query_as::<_, Self>(
-            "INSERT INTO tournaments (group_id, start_date, end_date)
-             VALUES ($1, $2, $3)
-             RETURNING id, group_id, start_date, end_date, winner",
+            "INSERT INTO tournaments (group_id, start_date, end_date, points_table_id, track_pool_id)
+             VALUES ($1, $2, $3, $4, $5)
+             RETURNING id, group_id, start_date, end_date, winner, points_table_id, track_pool_id",
         )
         .bind(group_id)
         .bind(start_date)
         .bind(end_date)
+        .bind(points_table_id)
+        .bind(track_pool_id)
+        .fetch_one(pool)
+        .await
+    }
+
+    /// Sets the points table used for the tournament's future matches.
+    #[instrument(level = "debug", skip(pool))]
+    pub async fn set_points_table(
+        pool: &DbPool,
+        tournament_id: Uuid,
+        points_table_id: Option<Uuid>,
+    ) -> Result<Self, sqlx::Error> {
+        sqlx::query_as::<_, Self>(
+            "UPDATE tournaments
+             SET points_table_id = $1
+             WHERE id = $2
+             RETURNING id, group_id, start_date, end_date, winner, points_table_id, track_pool_id",
+        )
+        .bind(points_table_id)
+        .bind(tournament_id)
+        .fetch_one(pool)
+        .await
+    }
+
+    /// Sets the track pool used for the tournament's future matches.
+    #[instrument(level = "debug", skip(pool))]
+    pub async fn set_track_pool(
+        pool: &DbPool,
+        tournament_id: Uuid,
+        track_pool_id: Option<Uuid>,
+    ) -> Result<Self, sqlx::Error> {
+        sqlx::query_as::<_, Self>(
+            "UPDATE tournaments
+             SET track_pool_id = $1
+             WHERE id = $2
+             RETURNING id, group_id, start_date, end_date, winner, points_table_id, track_pool_id",
+        )
+        .bind(track_pool_id)
+        .bind(tournament_id)
         .fetch_one(pool)
         .await
     }
@@ -145,7 +193,7 @@ impl Tournament {
             "UPDATE tournaments
              SET winner = $1
              WHERE id = $2
-             RETURNING id, group_id, start_date, end_date, winner",
+             RETURNING id, group_id, start_date, end_date, winner, points_table_id, track_pool_id",
         )
         .bind(winner_id)
         .bind(tournament_id)
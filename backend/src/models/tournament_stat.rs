@@ -50,6 +50,18 @@ impl TournamentStat {
         .await
     }
 
+    pub async fn delete_by_tournament_id(
+        tx: &mut Transaction<'_, Postgres>,
+        tournament_id: Uuid,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query("DELETE FROM tournament_stats WHERE tournament_id = $1")
+            .bind(tournament_id)
+            .execute(&mut **tx)
+            .await?;
+
+        Ok(())
+    }
+
     pub async fn insert_batch(
         tx: &mut Transaction<'_, Postgres>,
         stats: &[(Uuid, TournamentStatType, Uuid, i32, Option<serde_json::Value>)],
@@ -1,31 +1,272 @@
 use crate::db::DbPool;
-use sqlx::FromRow;
+use sqlx::{FromRow, PgExecutor};
 use uuid::Uuid;
 
 #[derive(Debug, Clone, FromRow)]
 pub struct Track {
     pub id: Uuid,
+    /// `None` for built-in tracks shared by every group
+    pub group_id: Option<Uuid>,
     pub name: String,
+    /// The game the track is raced in, e.g. "Mario Kart World"
+    pub game_edition: Option<String>,
+    /// The cup the track belongs to in its game
+    pub cup: Option<String>,
+    /// Whether the track is a remake of a track from an earlier game
+    pub retro: bool,
+    pub tags: Vec<String>,
+    /// Retired tracks are never selected for new matches. Built-in tracks
+    /// can also be retired by a single group; queries that know the group
+    /// include that here.
+    pub retired: bool,
+}
+
+/// Editable track metadata
+#[derive(Debug, Clone)]
+pub struct TrackDetails {
+    pub name: String,
+    pub game_edition: Option<String>,
+    pub cup: Option<String>,
+    pub retro: bool,
+    pub tags: Vec<String>,
 }
 
 impl Track {
+    pub fn is_built_in(&self) -> bool {
+        self.group_id.is_none()
+    }
+
+    /// Whether a group may use this track.
+    pub fn is_available_to(&self, group_id: Uuid) -> bool {
+        self.group_id.is_none_or(|owner| owner == group_id)
+    }
+
     pub async fn find_all(pool: &DbPool) -> Result<Vec<Self>, sqlx::Error> {
-        sqlx::query_as::<_, Self>("SELECT id, name FROM tracks ORDER BY name")
-            .fetch_all(pool)
-            .await
+        sqlx::query_as::<_, Self>(
+            "SELECT id, group_id, name, game_edition, cup, retro, tags, retired
+             FROM tracks
+             ORDER BY name",
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    /// Fetches a track, retired as seen by the group when one is given.
+    pub async fn find_by_id<'e>(
+        executor: impl PgExecutor<'e>,
+        id: Uuid,
+        group_id: Option<Uuid>,
+    ) -> Result<Option<Self>, sqlx::Error> {
+        sqlx::query_as::<_, Self>(
+            "SELECT t.id, t.group_id, t.name, t.game_edition, t.cup, t.retro, t.tags,
+                    t.retired OR EXISTS(
+                        SELECT 1 FROM group_retired_tracks grt
+                        WHERE grt.track_id = t.id AND grt.group_id = $2
+                    ) AS retired
+             FROM tracks t
+             WHERE t.id = $1",
+        )
+        .bind(id)
+        .bind(group_id)
+        .fetch_optional(executor)
+        .await
+    }
+
+    /// Fetches tracks by ID, including retired ones, retired as seen by the
+    /// group when one is given.
+    pub async fn find_by_ids<'e>(
+        executor: impl PgExecutor<'e>,
+        ids: &[Uuid],
+        group_id: Option<Uuid>,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as::<_, Self>(
+            "SELECT t.id, t.group_id, t.name, t.game_edition, t.cup, t.retro, t.tags,
+                    t.retired OR EXISTS(
+                        SELECT 1 FROM group_retired_tracks grt
+                        WHERE grt.track_id = t.id AND grt.group_id = $2
+                    ) AS retired
+             FROM tracks t
+             WHERE t.id = ANY($1)
+             ORDER BY t.name",
+        )
+        .bind(ids)
+        .bind(group_id)
+        .fetch_all(executor)
+        .await
+    }
+
+    /// Fetches the built-in tracks and, when a group is given, the group's own
+    /// tracks, retired as seen by the group.
+    pub async fn find_available_to_group<'e>(
+        executor: impl PgExecutor<'e>,
+        group_id: Option<Uuid>,
+        include_retired: bool,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as::<_, Self>(
+            "SELECT * FROM (
+                 SELECT t.id, t.group_id, t.name, t.game_edition, t.cup, t.retro, t.tags,
+                        t.retired OR EXISTS(
+                            SELECT 1 FROM group_retired_tracks grt
+                            WHERE grt.track_id = t.id AND grt.group_id = $1
+                        ) AS retired
+                 FROM tracks t
+                 WHERE t.group_id IS NULL OR t.group_id = $1
+             ) available
+             WHERE $2 OR NOT retired
+             ORDER BY name",
+        )
+        .bind(group_id)
+        .bind(include_retired)
+        .fetch_all(executor)
+        .await
+    }
+
+    /// Fetches the tracks that can be selected for a tournament's matches:
+    /// the built-in tracks and the tournament group's own tracks that neither
+    /// are retired nor the group has retired.
+    pub async fn find_active_for_tournament<'e>(
+        executor: impl PgExecutor<'e>,
+        tournament_id: Uuid,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as::<_, Self>(
+            "SELECT t.id, t.group_id, t.name, t.game_edition, t.cup, t.retro, t.tags, t.retired
+             FROM tracks t
+             JOIN tournaments tour ON tour.id = $1
+             WHERE NOT t.retired
+               AND (t.group_id IS NULL OR t.group_id = tour.group_id)
+               AND NOT EXISTS(
+                   SELECT 1 FROM group_retired_tracks grt
+                   WHERE grt.track_id = t.id AND grt.group_id = tour.group_id
+               )
+             ORDER BY t.name",
+        )
+        .bind(tournament_id)
+        .fetch_all(executor)
+        .await
     }
 
-    pub async fn find_by_id(pool: &DbPool, id: Uuid) -> Result<Option<Self>, sqlx::Error> {
-        sqlx::query_as::<_, Self>("SELECT id, name FROM tracks WHERE id = $1")
+    /// Whether a track with this name is already available to the group,
+    /// ignoring case and optionally one track being renamed.
+    pub async fn is_name_taken<'e>(
+        executor: impl PgExecutor<'e>,
+        group_id: Uuid,
+        name: &str,
+        exclude_id: Option<Uuid>,
+    ) -> Result<bool, sqlx::Error> {
+        sqlx::query_scalar(
+            "SELECT EXISTS(
+                SELECT 1 FROM tracks
+                WHERE (group_id IS NULL OR group_id = $1)
+                  AND LOWER(name) = LOWER($2)
+                  AND id IS DISTINCT FROM $3
+             )",
+        )
+        .bind(group_id)
+        .bind(name)
+        .bind(exclude_id)
+        .fetch_one(executor)
+        .await
+    }
+
+    pub async fn create<'e>(
+        executor: impl PgExecutor<'e>,
+        group_id: Uuid,
+        details: &TrackDetails,
+    ) -> Result<Self, sqlx::Error> {
+        sqlx::query_as::<_, Self>(
+            "INSERT INTO tracks (group_id, name, game_edition, cup, retro, tags)
+             VALUES ($1, $2, $3, $4, $5, $6)
+             RETURNING id, group_id, name, game_edition, cup, retro, tags, retired",
+        )
+        .bind(group_id)
+        .bind(&details.name)
+        .bind(&details.game_edition)
+        .bind(&details.cup)
+        .bind(details.retro)
+        .bind(&details.tags)
+        .fetch_one(executor)
+        .await
+    }
+
+    pub async fn update<'e>(
+        executor: impl PgExecutor<'e>,
+        id: Uuid,
+        details: &TrackDetails,
+    ) -> Result<Self, sqlx::Error> {
+        sqlx::query_as::<_, Self>(
+            "UPDATE tracks
+             SET name = $2, game_edition = $3, cup = $4, retro = $5, tags = $6
+             WHERE id = $1
+             RETURNING id, group_id, name, game_edition, cup, retro, tags, retired",
+        )
+        .bind(id)
+        .bind(&details.name)
+        .bind(&details.game_edition)
+        .bind(&details.cup)
+        .bind(details.retro)
+        .bind(&details.tags)
+        .fetch_one(executor)
+        .await
+    }
+
+    pub async fn set_retired<'e>(
+        executor: impl PgExecutor<'e>,
+        id: Uuid,
+        retired: bool,
+    ) -> Result<Self, sqlx::Error> {
+        sqlx::query_as::<_, Self>(
+            "UPDATE tracks
+             SET retired = $2
+             WHERE id = $1
+             RETURNING id, group_id, name, game_edition, cup, retro, tags, retired",
+        )
+        .bind(id)
+        .bind(retired)
+        .fetch_one(executor)
+        .await
+    }
+
+    /// Retires or restores a built-in track for one group only.
+    pub async fn set_retired_for_group<'e>(
+        executor: impl PgExecutor<'e>,
+        id: Uuid,
+        group_id: Uuid,
+        retired: bool,
+    ) -> Result<(), sqlx::Error> {
+        let query = if retired {
+            "INSERT INTO group_retired_tracks (group_id, track_id)
+             VALUES ($1, $2)
+             ON CONFLICT (group_id, track_id) DO NOTHING"
+        } else {
+            "DELETE FROM group_retired_tracks WHERE group_id = $1 AND track_id = $2"
+        };
+
+        sqlx::query(query)
+            .bind(group_id)
             .bind(id)
-            .fetch_optional(pool)
-            .await
+            .execute(executor)
+            .await?;
+
+        Ok(())
     }
 
-    pub async fn find_by_ids(pool: &DbPool, ids: &[Uuid]) -> Result<Vec<Self>, sqlx::Error> {
-        sqlx::query_as::<_, Self>("SELECT id, name FROM tracks WHERE id = ANY($1) ORDER BY name")
-            .bind(ids)
-            .fetch_all(pool)
+    /// Whether any round has been played or scheduled on the track.
+    pub async fn is_in_use<'e>(
+        executor: impl PgExecutor<'e>,
+        id: Uuid,
+    ) -> Result<bool, sqlx::Error> {
+        sqlx::query_scalar("SELECT EXISTS(SELECT 1 FROM rounds WHERE track_id = $1)")
+            .bind(id)
+            .fetch_one(executor)
             .await
     }
+
+    pub async fn delete<'e>(executor: impl PgExecutor<'e>, id: Uuid) -> Result<(), sqlx::Error> {
+        sqlx::query("DELETE FROM tracks WHERE id = $1")
+            .bind(id)
+            .execute(executor)
+            .await?;
+
+        Ok(())
+    }
 }
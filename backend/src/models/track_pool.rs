@@ -0,0 +1,182 @@
+use chrono::{DateTime, Utc};
+use sqlx::{FromRow, PgExecutor, Postgres, Transaction};
+use uuid::Uuid;
+
+use super::Track;
+
+/// A named subset of the tracks available to a group
+#[derive(Debug, Clone, FromRow)]
+pub struct TrackPool {
+    pub id: Uuid,
+    pub group_id: Uuid,
+    pub name: String,
+    pub created_at: DateTime<Utc>,
+}
+
+/// A track in a pool with its selection weight
+#[derive(Debug, Clone, FromRow)]
+pub struct TrackPoolTrack {
+    pub track_pool_id: Uuid,
+    /// Relative chance of the track being drawn early in a cycle
+    pub weight: i32,
+    #[sqlx(flatten)]
+    pub track: Track,
+}
+
+impl TrackPool {
+    pub async fn find_by_id<'e>(
+        executor: impl PgExecutor<'e>,
+        id: Uuid,
+    ) -> Result<Option<Self>, sqlx::Error> {
+        sqlx::query_as::<_, Self>(
+            "SELECT id, group_id, name, created_at FROM track_pools WHERE id = $1",
+        )
+        .bind(id)
+        .fetch_optional(executor)
+        .await
+    }
+
+    pub async fn find_by_ids<'e>(
+        executor: impl PgExecutor<'e>,
+        ids: &[Uuid],
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as::<_, Self>(
+            "SELECT id, group_id, name, created_at FROM track_pools WHERE id = ANY($1)",
+        )
+        .bind(ids)
+        .fetch_all(executor)
+        .await
+    }
+
+    pub async fn find_by_group_id<'e>(
+        executor: impl PgExecutor<'e>,
+        group_id: Uuid,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as::<_, Self>(
+            "SELECT id, group_id, name, created_at
+             FROM track_pools
+             WHERE group_id = $1
+             ORDER BY name ASC",
+        )
+        .bind(group_id)
+        .fetch_all(executor)
+        .await
+    }
+
+    /// Fetches the tracks of the given pools, including retired ones, retired
+    /// as seen by each pool's group.
+    pub async fn find_tracks_by_pool_ids<'e>(
+        executor: impl PgExecutor<'e>,
+        pool_ids: &[Uuid],
+    ) -> Result<Vec<TrackPoolTrack>, sqlx::Error> {
+        sqlx::query_as::<_, TrackPoolTrack>(
+            "SELECT tpt.track_pool_id, tpt.weight, t.id, t.group_id, t.name, t.game_edition,
+                    t.cup, t.retro, t.tags,
+                    t.retired OR EXISTS(
+                        SELECT 1 FROM group_retired_tracks grt
+                        WHERE grt.track_id = t.id AND grt.group_id = tp.group_id
+                    ) AS retired
+             FROM track_pool_tracks tpt
+             JOIN track_pools tp ON tp.id = tpt.track_pool_id
+             JOIN tracks t ON t.id = tpt.track_id
+             WHERE tpt.track_pool_id = ANY($1)
+             ORDER BY t.name ASC",
+        )
+        .bind(pool_ids)
+        .fetch_all(executor)
+        .await
+    }
+
+    pub async fn create(
+        tx: &mut Transaction<'_, Postgres>,
+        group_id: Uuid,
+        name: &str,
+        tracks: &[(Uuid, i32)],
+    ) -> Result<Self, sqlx::Error> {
+        let track_pool = sqlx::query_as::<_, Self>(
+            "INSERT INTO track_pools (group_id, name)
+             VALUES ($1, $2)
+             RETURNING id, group_id, name, created_at",
+        )
+        .bind(group_id)
+        .bind(name)
+        .fetch_one(&mut **tx)
+        .await?;
+
+        Self::insert_tracks(tx, track_pool.id, tracks).await?;
+
+        Ok(track_pool)
+    }
+
+    /// Renames a pool and replaces its tracks.
+    pub async fn update(
+        tx: &mut Transaction<'_, Postgres>,
+        id: Uuid,
+        name: &str,
+        tracks: &[(Uuid, i32)],
+    ) -> Result<Self, sqlx::Error> {
+        let track_pool = sqlx::query_as::<_, Self>(
+            "UPDATE track_pools
+             SET name = $2
+             WHERE id = $1
+             RETURNING id, group_id, name, created_at",
+        )
+        .bind(id)
+        .bind(name)
+        .fetch_one(&mut **tx)
+        .await?;
+
+        sqlx::query("DELETE FROM track_pool_tracks WHERE track_pool_id = $1")
+            .bind(id)
+            .execute(&mut **tx)
+            .await?;
+
+        Self::insert_tracks(tx, id, tracks).await?;
+
+        Ok(track_pool)
+    }
+
+    async fn insert_tracks(
+        tx: &mut Transaction<'_, Postgres>,
+        id: Uuid,
+        tracks: &[(Uuid, i32)],
+    ) -> Result<(), sqlx::Error> {
+        let (track_ids, weights): (Vec<Uuid>, Vec<i32>) = tracks.iter().copied().unzip();
+
+        sqlx::query(
+            "INSERT INTO track_pool_tracks (track_pool_id, track_id, weight)
+             SELECT $1, track_id, weight
+             FROM UNNEST($2::uuid[], $3::int[]) AS t(track_id, weight)",
+        )
+        .bind(id)
+        .bind(&track_ids)
+        .bind(&weights)
+        .execute(&mut **tx)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Whether any tournament or match draws from the pool.
+    pub async fn is_in_use<'e>(
+        executor: impl PgExecutor<'e>,
+        id: Uuid,
+    ) -> Result<bool, sqlx::Error> {
+        sqlx::query_scalar(
+            "SELECT EXISTS(SELECT 1 FROM matches WHERE track_pool_id = $1)
+                 OR EXISTS(SELECT 1 FROM tournaments WHERE track_pool_id = $1)",
+        )
+        .bind(id)
+        .fetch_one(executor)
+        .await
+    }
+
+    pub async fn delete<'e>(executor: impl PgExecutor<'e>, id: Uuid) -> Result<(), sqlx::Error> {
+        sqlx::query("DELETE FROM track_pools WHERE id = $1")
+            .bind(id)
+            .execute(executor)
+            .await?;
+
+        Ok(())
+    }
+}
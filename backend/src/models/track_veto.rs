@@ -0,0 +1,100 @@
+use chrono::{DateTime, Utc};
+use sqlx::{FromRow, PgExecutor};
+use uuid::Uuid;
+
+/// A track a player would rather not race in a tournament
+#[derive(Debug, Clone, FromRow)]
+pub struct TrackVeto {
+    pub player_id: Uuid,
+    pub tournament_id: Uuid,
+    pub track_id: Uuid,
+    pub created_at: DateTime<Utc>,
+}
+
+impl TrackVeto {
+    pub async fn find_by_tournament_id<'e>(
+        executor: impl PgExecutor<'e>,
+        tournament_id: Uuid,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as::<_, Self>(
+            "SELECT player_id, tournament_id, track_id, created_at
+             FROM track_vetoes
+             WHERE tournament_id = $1
+             ORDER BY created_at ASC",
+        )
+        .bind(tournament_id)
+        .fetch_all(executor)
+        .await
+    }
+
+    /// Counts the tracks a player has vetoed in a tournament.
+    pub async fn count_for_player<'e>(
+        executor: impl PgExecutor<'e>,
+        tournament_id: Uuid,
+        player_id: Uuid,
+    ) -> Result<i64, sqlx::Error> {
+        sqlx::query_scalar(
+            "SELECT COUNT(*) FROM track_vetoes WHERE tournament_id = $1 AND player_id = $2",
+        )
+        .bind(tournament_id)
+        .bind(player_id)
+        .fetch_one(executor)
+        .await
+    }
+
+    pub async fn create<'e>(
+        executor: impl PgExecutor<'e>,
+        tournament_id: Uuid,
+        player_id: Uuid,
+        track_id: Uuid,
+    ) -> Result<Self, sqlx::Error> {
+        sqlx::query_as::<_, Self>(
+            "INSERT INTO track_vetoes (tournament_id, player_id, track_id)
+             VALUES ($1, $2, $3)
+             RETURNING player_id, tournament_id, track_id, created_at",
+        )
+        .bind(tournament_id)
+        .bind(player_id)
+        .bind(track_id)
+        .fetch_one(executor)
+        .await
+    }
+
+    /// Removes a veto, returning whether there was one.
+    pub async fn delete<'e>(
+        executor: impl PgExecutor<'e>,
+        tournament_id: Uuid,
+        player_id: Uuid,
+        track_id: Uuid,
+    ) -> Result<bool, sqlx::Error> {
+        let result = sqlx::query(
+            "DELETE FROM track_vetoes
+             WHERE tournament_id = $1 AND player_id = $2 AND track_id = $3",
+        )
+        .bind(tournament_id)
+        .bind(player_id)
+        .bind(track_id)
+        .execute(executor)
+        .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    pub async fn find<'e>(
+        executor: impl PgExecutor<'e>,
+        tournament_id: Uuid,
+        player_id: Uuid,
+        track_id: Uuid,
+    ) -> Result<Option<Self>, sqlx::Error> {
+        sqlx::query_as::<_, Self>(
+            "SELECT player_id, tournament_id, track_id, created_at
+             FROM track_vetoes
+             WHERE tournament_id = $1 AND player_id = $2 AND track_id = $3",
+        )
+        .bind(tournament_id)
+        .bind(player_id)
+        .bind(track_id)
+        .fetch_optional(executor)
+        .await
+    }
+}
@@ -0,0 +1,222 @@
+//! Avatar Service
+//!
+//! Validates uploaded avatar images and turns them into square JPEGs in every
+//! size in `AVATAR_SIZES`, stored with `avatar_storage` under `<size>/<filename>`.
+//!
+//! Each upload gets a new filename, so a filename always refers to the same
+//! image and can be cached indefinitely. Avatars that are replaced or removed
+//! are deleted from storage. Filenames that do not look like uploads (e.g. the
+//! bundled avatars the frontend ships with) are left alone.
+
+use crate::db::DbPool;
+use crate::error::{AppError, Result};
+use crate::models;
+use crate::services::avatar_storage::AvatarStorage;
+use image::codecs::jpeg::JpegEncoder;
+use image::imageops::FilterType;
+use image::{ImageFormat, ImageReader, Limits};
+use std::io::{Cursor, Read};
+use uuid::Uuid;
+
+/// Largest accepted upload (5 MB).
+pub const MAX_AVATAR_BYTES: usize = 5 * 1024 * 1024;
+
+/// Largest accepted width or height, to reject images that would take too
+/// much memory to decode.
+pub const MAX_AVATAR_DIMENSION: u32 = 4096;
+
+/// Side lengths in pixels of the stored avatar images, smallest first.
+pub const AVATAR_SIZES: [u32; 3] = [64, 128, 256];
+
+/// Size served when no size is requested.
+pub const DEFAULT_AVATAR_SIZE: u32 = 256;
+
+const JPEG_QUALITY: u8 = 85;
+
+/// One stored size of an avatar.
+#[derive(Debug, Clone)]
+pub struct ProcessedAvatar {
+    pub size: u32,
+    pub bytes: Vec<u8>,
+}
+
+/// Validates an uploaded image and resizes it to every avatar size.
+///
+/// Images are cropped to a centred square before resizing and re-encoded as
+/// JPEG, which also strips any metadata the upload carried.
+///
+/// # Arguments
+///
+/// * `bytes` - The uploaded file
+///
+/// # Returns
+///
+/// Result containing the JPEG for each size in `AVATAR_SIZES`
+///
+/// # Errors
+///
+/// Returns an error if the file is larger than `MAX_AVATAR_BYTES`, is not a
+/// PNG, JPEG or WebP image, is wider or taller than `MAX_AVATAR_DIMENSION`,
+/// or cannot be decoded
+pub fn process_avatar(bytes: &[u8]) -> Result<Vec<ProcessedAvatar>> {
+    if bytes.len() > MAX_AVATAR_BYTES {
+        return Err(AppError::InvalidInput(format!(
+            "Avatar must be at most {} MB",
+            MAX_AVATAR_BYTES / (1024 * 1024)
+        )));
+    }
+
+    let format = image::guess_format(bytes)
+        .ok()
+        .filter(|format| {
+            matches!(format, ImageFormat::Png | ImageFormat::Jpeg | ImageFormat::WebP)
+        })
+        .ok_or_else(|| {
+            AppError::InvalidInput("Avatar must be a PNG, JPEG or WebP image".to_string())
+        })?;
+
+    let mut limits = Limits::default();
+    limits.max_image_width = Some(MAX_AVATAR_DIMENSION);
+    limits.max_image_height = Some(MAX_AVATAR_DIMENSION);
+
+    let mut reader = ImageReader::with_format(Cursor::new(bytes), format);
+    reader.limits(limits);
+    let image = reader.decode().map_err(|e| {
+        AppError::InvalidInput(format!("Avatar could not be read as an image: {e}"))
+    })?;
+
+    AVATAR_SIZES
+        .iter()
+        .map(|&size| {
+            let resized = image
+                .resize_to_fill(size, size, FilterType::Lanczos3)
+                .to_rgb8();
+
+            let mut bytes = Vec::new();
+            JpegEncoder::new_with_quality(&mut bytes, JPEG_QUALITY)
+                .encode_image(&resized)
+                .map_err(|e| AppError::Internal(format!("Failed to encode avatar: {e}")))?;
+
+            Ok(ProcessedAvatar { size, bytes })
+        })
+        .collect()
+}
+
+/// A new, unique filename for an avatar of the player.
+pub fn new_avatar_filename(player_id: Uuid) -> String {
+    format!("{player_id}-{}.jpg", Uuid::new_v4().simple())
+}
+
+/// Whether the filename was produced by `new_avatar_filename`.
+///
+/// Only such filenames are served or deleted, which also keeps arbitrary
+/// paths out of storage keys.
+pub fn is_uploaded_avatar(filename: &str) -> bool {
+    filename
+        .strip_suffix(".jpg")
+        .and_then(|stem| stem.rsplit_once('-'))
+        .is_some_and(|(player_id, token)| {
+            Uuid::try_parse(player_id).is_ok()
+                && token.len() == 32
+                && token.bytes().all(|b| b.is_ascii_hexdigit())
+        })
+}
+
+/// The storage key of one size of an avatar.
+pub fn storage_key(size: u32, filename: &str) -> String {
+    format!("{size}/{filename}")
+}
+
+/// Processes an upload, stores it and makes it the player's avatar.
+///
+/// # Arguments
+///
+/// * `pool` - Database connection pool
+/// * `storage` - Where avatar images are stored
+/// * `player` - The player whose avatar is replaced
+/// * `upload` - Reader over the uploaded file
+///
+/// # Returns
+///
+/// Result containing the player with their new avatar filename
+///
+/// # Errors
+///
+/// Returns an error if the upload cannot be read or is rejected by
+/// `process_avatar`, or if storage or the database fails
+pub async fn set_player_avatar(
+    pool: &DbPool,
+    storage: &dyn AvatarStorage,
+    player: &models::Player,
+    upload: impl Read + Send + 'static,
+) -> Result<models::Player> {
+    // Reading the upload blocks and decoding and resizing is CPU bound, so
+    // keep both off the async workers. Reading one byte past the limit lets
+    // `process_avatar` reject oversized files.
+    let images = tokio::task::spawn_blocking(move || {
+        let mut bytes = Vec::new();
+        upload
+            .take(MAX_AVATAR_BYTES as u64 + 1)
+            .read_to_end(&mut bytes)?;
+        process_avatar(&bytes)
+    })
+    .await
+        .map_err(|e| AppError::Internal(format!("Avatar processing failed: {e}")))??;
+
+    let filename = new_avatar_filename(player.id);
+    for image in images {
+        storage
+            .put(&storage_key(image.size, &filename), image.bytes)
+            .await?;
+    }
+
+    let updated = models::Player::set_avatar_filename(pool, player.id, Some(&filename)).await?;
+
+    if let Some(previous) = &player.avatar_filename {
+        delete_avatar(storage, previous).await?;
+    }
+
+    Ok(updated)
+}
+
+/// Clears the player's avatar and deletes its images.
+///
+/// # Arguments
+///
+/// * `pool` - Database connection pool
+/// * `storage` - Where avatar images are stored
+/// * `player` - The player whose avatar is removed
+///
+/// # Returns
+///
+/// Result containing the player without an avatar
+///
+/// # Errors
+///
+/// Returns an error if storage or the database fails
+pub async fn remove_player_avatar(
+    pool: &DbPool,
+    storage: &dyn AvatarStorage,
+    player: &models::Player,
+) -> Result<models::Player> {
+    let updated = models::Player::set_avatar_filename(pool, player.id, None).await?;
+
+    if let Some(previous) = &player.avatar_filename {
+        delete_avatar(storage, previous).await?;
+    }
+
+    Ok(updated)
+}
+
+/// Deletes every size of an uploaded avatar. Other filenames are ignored.
+async fn delete_avatar(storage: &dyn AvatarStorage, filename: &str) -> Result<()> {
+    if !is_uploaded_avatar(filename) {
+        return Ok(());
+    }
+
+    for size in AVATAR_SIZES {
+        storage.delete(&storage_key(size, filename)).await?;
+    }
+
+    Ok(())
+}
@@ -0,0 +1,74 @@
+//! Avatar Storage Service
+//!
+//! Stores processed avatar images behind the `AvatarStorage` trait, so the
+//! local disk store can be swapped for an S3-compatible one without touching
+//! the upload or serving code. Keys are relative paths such as
+//! `128/<filename>` and are validated by the avatar service before they get
+//! here.
+
+use crate::config::Config;
+use crate::error::Result;
+use async_trait::async_trait;
+use std::io::ErrorKind;
+use std::path::PathBuf;
+use std::sync::Arc;
+
+#[async_trait]
+pub trait AvatarStorage: Send + Sync {
+    /// Stores the bytes under the key, replacing anything already there.
+    async fn put(&self, key: &str, bytes: Vec<u8>) -> Result<()>;
+
+    /// Fetches the bytes stored under the key, or `None` if there are none.
+    async fn get(&self, key: &str) -> Result<Option<Vec<u8>>>;
+
+    /// Removes the bytes stored under the key. Missing keys are ignored.
+    async fn delete(&self, key: &str) -> Result<()>;
+}
+
+/// Stores avatars as files under a root directory.
+pub struct LocalDiskStorage {
+    root: PathBuf,
+}
+
+impl LocalDiskStorage {
+    pub fn new(root: impl Into<PathBuf>) -> Self {
+        Self { root: root.into() }
+    }
+}
+
+#[async_trait]
+impl AvatarStorage for LocalDiskStorage {
+    async fn put(&self, key: &str, bytes: Vec<u8>) -> Result<()> {
+        let path = self.root.join(key);
+        if let Some(parent) = path.parent() {
+            tokio::fs::create_dir_all(parent).await?;
+        }
+
+        // Write to a temporary file first so readers never see a partial image
+        let temp_path = path.with_extension("tmp");
+        tokio::fs::write(&temp_path, bytes).await?;
+        tokio::fs::rename(&temp_path, &path).await?;
+
+        Ok(())
+    }
+
+    async fn get(&self, key: &str) -> Result<Option<Vec<u8>>> {
+        match tokio::fs::read(self.root.join(key)).await {
+            Ok(bytes) => Ok(Some(bytes)),
+            Err(e) if e.kind() == ErrorKind::NotFound => Ok(None),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    async fn delete(&self, key: &str) -> Result<()> {
+        match tokio::fs::remove_file(self.root.join(key)).await {
+            Err(e) if e.kind() != ErrorKind::NotFound => Err(e.into()),
+            _ => Ok(()),
+        }
+    }
+}
+
+/// The avatar storage configured for the server.
+pub fn from_config(config: &Config) -> Arc<dyn AvatarStorage> {
+    Arc::new(LocalDiskStorage::new(&config.avatar_dir))
+}
@@ -17,34 +17,94 @@
 //!    against all other opponents using the standard ELO formula
 //! 3. **Calculate Actual Score**: Convert race position (1-24) to a normalized score using
 //!    a power function that rewards top positions more
-//! 4. **Apply K-Factor**: Calculate rating change using K=100 and the difference between
-//!    expected and actual scores
+//! 4. **Apply K-Factor**: Calculate rating change using the K-factor (default 100) and the
+//!    difference between expected and actual scores
 //!
 //! ## Algorithm Characteristics
 //!
 //! - **Dynamic CPU ELO**: CPUs scale with race skill level (based on average human ELO)
-//! - **CPU ELO floor**: Minimum CPU ELO of 800 for bottom positions
-//! - **Non-linear scoring**: Power function (exponent 1.5) steepens the curve so that
+//! - **CPU ELO floor**: Minimum CPU ELO (default 800) for bottom positions
+//! - **Non-linear scoring**: Power function (default exponent 1.5) steepens the curve so that
 //!   lower positions score significantly worse than top positions
 //!
-//! ## Constants
+//! ## Settings
+//!
+//! Every parameter lives in `EloSettings`, which groups can override through
+//! the `group_settings` table. The constants below are the defaults.
 
+use crate::services::rating_system::RatingSystemKind;
 use std::cmp::max;
 use tracing::instrument;
 use uuid::Uuid;
 
 /// K-factor determines the maximum rating change per race
-const K_FACTOR: f64 = 100.0;
+pub const DEFAULT_K_FACTOR: f64 = 100.0;
 
 /// Total number of racers in a Mario Kart race (including CPUs)
-const TOTAL_RACE_SIZE: usize = 24;
+pub const DEFAULT_TOTAL_RACE_SIZE: i32 = 24;
+
+pub const DEFAULT_MIN_CPU_ELO: i32 = 800;
+pub const DEFAULT_CPU_ELO_SPREAD: i32 = 50;
+pub const DEFAULT_CPU_ELO_DECREASE: i32 = 25;
+pub const DEFAULT_POSITION_SCORE_EXPONENT: f64 = 1.5;
+pub const DEFAULT_MIN_AVERAGE_ELO_FOR_CPU: i32 = 900;
+pub const DEFAULT_MAX_AVERAGE_ELO_FOR_CPU: i32 = 1400;
+
+/// Share of a player's tournament ELO change given to each teammate
+pub const DEFAULT_TEAMMATE_CONTRIBUTION_RATIO: f64 = 0.2;
+
+/// Players with fewer recorded races than this are provisional
+pub const DEFAULT_PROVISIONAL_RACE_COUNT: i32 = 10;
+
+/// Weeks without a race after which a player counts as inactive
+pub const DEFAULT_INACTIVITY_WEEKS: i32 = 8;
+
+/// All-time ELO removed per inactive week (0 only flags inactive players)
+pub const DEFAULT_INACTIVITY_DECAY_PER_WEEK: i32 = 0;
+
+/// ELO charged per earlier tournament pairing of teammates in team allocation
+pub const DEFAULT_REPEAT_TEAMMATE_PENALTY: i32 = 50;
+
+/// Tunable parameters of the ELO and teammate ELO calculations.
+#[derive(Debug, Clone, PartialEq)]
+pub struct EloSettings {
+    /// Rating system that drives the leaderboard
+    pub rating_system: RatingSystemKind,
+    pub k_factor: f64,
+    pub total_race_size: i32,
+    pub position_score_exponent: f64,
+    pub min_cpu_elo: i32,
+    pub cpu_elo_spread: i32,
+    pub cpu_elo_decrease: i32,
+    pub min_average_elo_for_cpu: i32,
+    pub max_average_elo_for_cpu: i32,
+    pub teammate_contribution_ratio: f64,
+    pub provisional_race_count: i32,
+    pub inactivity_weeks: i32,
+    pub inactivity_decay_per_week: i32,
+    pub repeat_teammate_penalty: i32,
+}
 
-const MIN_CPU_ELO: i32 = 800;
-const CPU_ELO_SPREAD: i32 = 50;
-const CPU_ELO_DECREASE: i32 = 25;
-const POSITION_SCORE_EXPONENT: f64 = 1.5;
-const MIN_AVERAGE_ELO_FOR_CPU: i32 = 900;
-const MAX_AVERAGE_ELO_FOR_CPU: i32 = 1400;
+impl Default for EloSettings {
+    fn default() -> Self {
+        Self {
+            rating_system: RatingSystemKind::default(),
+            k_factor: DEFAULT_K_FACTOR,
+            total_race_size: DEFAULT_TOTAL_RACE_SIZE,
+            position_score_exponent: DEFAULT_POSITION_SCORE_EXPONENT,
+            min_cpu_elo: DEFAULT_MIN_CPU_ELO,
+            cpu_elo_spread: DEFAULT_CPU_ELO_SPREAD,
+            cpu_elo_decrease: DEFAULT_CPU_ELO_DECREASE,
+            min_average_elo_for_cpu: DEFAULT_MIN_AVERAGE_ELO_FOR_CPU,
+            max_average_elo_for_cpu: DEFAULT_MAX_AVERAGE_ELO_FOR_CPU,
+            teammate_contribution_ratio: DEFAULT_TEAMMATE_CONTRIBUTION_RATIO,
+            provisional_race_count: DEFAULT_PROVISIONAL_RACE_COUNT,
+            inactivity_weeks: DEFAULT_INACTIVITY_WEEKS,
+            inactivity_decay_per_week: DEFAULT_INACTIVITY_DECAY_PER_WEEK,
+            repeat_teammate_penalty: DEFAULT_REPEAT_TEAMMATE_PENALTY,
+        }
+    }
+}
 
 /// Represents a player's result in a single race
 #[derive(Debug, Clone)]
@@ -71,6 +131,7 @@ pub struct EloChange {
 /// # Arguments
 ///
 /// * `results` - Slice of PlayerResult structs representing human players' race results
+/// * `settings` - ELO parameters to calculate with
 ///
 /// # Returns
 ///
@@ -85,20 +146,20 @@ pub struct EloChange {
 ///     PlayerResult { player_id: Uuid::new_v4(), position: 1, current_elo: 1400 },
 ///     PlayerResult { player_id: Uuid::new_v4(), position: 10, current_elo: 1200 },
 /// ];
-/// let changes = calculate_elo_changes(&results);
+/// let changes = calculate_elo_changes(&results, &EloSettings::default());
 /// // changes[0] will have a positive elo_change (1st place)
 /// // changes[1] will have a smaller positive or negative change (10th place)
 /// ```
-#[instrument(level = "info", fields(player_count = results.len()))]
-pub fn calculate_elo_changes(results: &[PlayerResult]) -> Vec<EloChange> {
-    let full_field = create_full_field(results);
+#[instrument(level = "info", skip(settings), fields(player_count = results.len()))]
+pub fn calculate_elo_changes(results: &[PlayerResult], settings: &EloSettings) -> Vec<EloChange> {
+    let full_field = create_full_field(results, settings);
 
     results
         .iter()
         .map(|player| {
             let expected_score = calculate_expected_score(player, &full_field);
-            let actual_score = position_to_score(player.position);
-            let elo_change = (K_FACTOR * (actual_score - expected_score)).round() as i32;
+            let actual_score = position_to_score(player.position, settings);
+            let elo_change = (settings.k_factor * (actual_score - expected_score)).round() as i32;
             let new_elo = player.current_elo + elo_change;
 
             EloChange {
@@ -110,11 +171,11 @@ pub fn calculate_elo_changes(results: &[PlayerResult]) -> Vec<EloChange> {
         .collect()
 }
 
-/// Internal function: Creates a full 24-player field by filling empty positions with CPU opponents.
-/// CPU ELO is scaled based on the average human ELO in the race.
+/// Internal function: Creates a full field (24 players by default) by filling empty positions
+/// with CPU opponents. CPU ELO is scaled based on the average human ELO in the race.
 /// Exposed for testing purposes.
-#[instrument(level = "debug", fields(human_count = human_results.len()))]
-pub fn create_full_field(human_results: &[PlayerResult]) -> Vec<PlayerResult> {
+#[instrument(level = "debug", skip(settings), fields(human_count = human_results.len()))]
+pub fn create_full_field(human_results: &[PlayerResult], settings: &EloSettings) -> Vec<PlayerResult> {
     let average_human_elo = human_results
         .iter()
         .map(|r| r.current_elo)
@@ -122,18 +183,23 @@ pub fn create_full_field(human_results: &[PlayerResult]) -> Vec<PlayerResult> {
         .checked_div(human_results.len() as i32)
         .unwrap_or(1200);
 
-    let clamped_average_elo =
-        average_human_elo.clamp(MIN_AVERAGE_ELO_FOR_CPU, MAX_AVERAGE_ELO_FOR_CPU);
-    let base_cpu_elo = clamped_average_elo + CPU_ELO_SPREAD / 2;
+    let clamped_average_elo = average_human_elo.clamp(
+        settings.min_average_elo_for_cpu,
+        settings.max_average_elo_for_cpu,
+    );
+    let base_cpu_elo = clamped_average_elo + settings.cpu_elo_spread / 2;
 
     let human_positions: std::collections::HashSet<i32> =
         human_results.iter().map(|r| r.position).collect();
 
-    (1..=TOTAL_RACE_SIZE as i32).fold(
+    (1..=settings.total_race_size).fold(
         human_results.to_vec(),
         |mut field, position| {
             if !human_positions.contains(&position) {
-                let cpu_elo = max(MIN_CPU_ELO, base_cpu_elo - ((position - 1) * CPU_ELO_DECREASE));
+                let cpu_elo = max(
+                    settings.min_cpu_elo,
+                    base_cpu_elo - ((position - 1) * settings.cpu_elo_decrease),
+                );
                 field.push(PlayerResult {
                     player_id: Uuid::nil(),
                     position,
@@ -145,8 +211,11 @@ pub fn create_full_field(human_results: &[PlayerResult]) -> Vec<PlayerResult> {
     )
 }
 
+/// Internal function: Calculates a player's expected score against the rest of the field,
+/// i.e. the average probability of finishing ahead of each opponent.
+/// Exposed for race predictions and testing.
 #[instrument(level = "debug", skip(all_results), fields(opponent_count = all_results.len().saturating_sub(1), player_elo = player.current_elo))]
-fn calculate_expected_score(player: &PlayerResult, all_results: &[PlayerResult]) -> f64 {
+pub fn calculate_expected_score(player: &PlayerResult, all_results: &[PlayerResult]) -> f64 {
     let opponent_count = all_results.len().saturating_sub(1);
     if opponent_count == 0 {
         return 0.5;
@@ -163,15 +232,17 @@ fn calculate_expected_score(player: &PlayerResult, all_results: &[PlayerResult])
         / opponent_count as f64
 }
 
-/// Internal function: Converts race position (1-24) to normalized score using a power function.
-/// The power function compresses bottom positions and expands top positions,
-/// naturally protecting top finishers from losing ELO.
+/// Internal function: Converts race position (1 to the race size) to normalized score using a
+/// power function. The power function compresses bottom positions and expands top positions,
+/// naturally protecting top finishers from losing ELO. Positions beyond the race size score
+/// the same as last place.
 /// Exposed for testing purposes.
-pub fn position_to_score(position: i32) -> f64 {
-    if TOTAL_RACE_SIZE <= 1 {
+pub fn position_to_score(position: i32, settings: &EloSettings) -> f64 {
+    if settings.total_race_size <= 1 {
         return 0.5;
     }
-    let linear_score =
-        (TOTAL_RACE_SIZE as i32 - position) as f64 / (TOTAL_RACE_SIZE - 1) as f64;
-    linear_score.powf(POSITION_SCORE_EXPONENT)
+    let linear_score = ((settings.total_race_size - position) as f64
+        / (settings.total_race_size - 1) as f64)
+        .max(0.0);
+    linear_score.powf(settings.position_score_exponent)
 }
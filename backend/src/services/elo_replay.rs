@@ -0,0 +1,1134 @@
+//! ELO Replay Service
+//!
+//! This module rewrites everything derived from race positions when a recorded
+//! race changes. Correcting one race also changes every later race its players
+//! took part in, because those races were rated from the ratings it produced.
+//!
+//! Two entry points share the same replay:
+//! - `replay_from_round`: replays from one race onwards, starting from the
+//!   current ratings rewound by the stored deltas (used when amending results)
+//! - `recompute_group`: rebuilds a whole group from the starting rating, e.g.
+//!   after the ELO rules or the group's ELO settings change (exposed as
+//!   `migrate recompute` and `updateGroupSettings`)
+//!
+//! ## Replay Workflow
+//!
+//! 1. Load the group's races in chronological order (`matches.time`, then `round_number`)
+//! 2. Determine the ratings before the first replayed race (rewound or reset)
+//! 3. Discard teammate contributions recorded for the replayed races
+//! 4. Replay each race with the group's rating system and the teammate ELO
+//!    service, using the group's ELO settings. Inactivity decay events recorded
+//!    before a race are applied to the all-time ratings first, with their
+//!    recorded change. Disconnected players are left out of the race and keep
+//!    their ratings
+//! 5. Persist the results in the caller's transaction:
+//!    - Per-race ELO changes, `*_elo_after` values and the rating uncertainty
+//!      going into each race in `player_race_scores`
+//!    - Teammate contributions
+//!    - The replayed `elo_after` of each inactivity decay event
+//!    - Ratings and rating uncertainty in `players` and `player_tournament_scores`
+//!    - `player_match_scores` aggregates for every affected match
+//!    - Every per-track rating of the group (see `track_rating`)
+
+use crate::db::DbPool;
+use crate::error::{AppError, Result};
+use crate::models;
+use crate::services::elo::EloSettings;
+use crate::services::rating_system::{self, RatedPlayer, RatingChange, Uncertainty};
+use crate::services::score_calculation;
+use crate::services::teammate_elo::{self, TeammateContribution};
+use crate::services::track_rating;
+use chrono::{DateTime, Utc};
+use sqlx::{FromRow, Postgres, Transaction};
+use std::collections::{HashMap, HashSet};
+use std::iter::Peekable;
+use uuid::Uuid;
+
+/// Rating used for players with no rating yet (matches the column defaults).
+pub const STARTING_ELO: i32 = 1200;
+
+/// All-time and tournament ratings at a point in the replay.
+#[derive(Debug, Clone, Default)]
+pub struct RatingState {
+    pub all_time: HashMap<Uuid, i32>,
+    /// Keyed by (player_id, tournament_id)
+    pub tournament: HashMap<(Uuid, Uuid), i32>,
+    pub all_time_uncertainty: HashMap<Uuid, Uncertainty>,
+    /// Keyed by (player_id, tournament_id)
+    pub tournament_uncertainty: HashMap<(Uuid, Uuid), Uncertainty>,
+}
+
+impl RatingState {
+    pub fn all_time_elo(&self, player_id: Uuid) -> i32 {
+        self.all_time
+            .get(&player_id)
+            .copied()
+            .unwrap_or(STARTING_ELO)
+    }
+
+    pub fn tournament_elo(&self, player_id: Uuid, tournament_id: Uuid) -> i32 {
+        self.tournament
+            .get(&(player_id, tournament_id))
+            .copied()
+            .unwrap_or(STARTING_ELO)
+    }
+
+    pub fn all_time_uncertainty(&self, player_id: Uuid) -> Uncertainty {
+        self.all_time_uncertainty
+            .get(&player_id)
+            .copied()
+            .unwrap_or_default()
+    }
+
+    pub fn tournament_uncertainty(&self, player_id: Uuid, tournament_id: Uuid) -> Uncertainty {
+        self.tournament_uncertainty
+            .get(&(player_id, tournament_id))
+            .copied()
+            .unwrap_or_default()
+    }
+}
+
+/// Everything a single replayed race produces.
+#[derive(Debug, Clone)]
+pub struct ReplayedRace {
+    pub all_time_elo_changes: Vec<RatingChange>,
+    pub tournament_elo_changes: Vec<RatingChange>,
+    pub teammate_contributions: Vec<TeammateContribution>,
+}
+
+/// A recorded race, identified by match and round.
+#[derive(Debug, Clone, FromRow)]
+struct RecordedRace {
+    match_id: Uuid,
+    round_number: i32,
+    tournament_id: Uuid,
+    /// When the race's results were first recorded
+    recorded_at: DateTime<Utc>,
+}
+
+/// A single player's stored result for a recorded race.
+#[derive(Debug, Clone, FromRow)]
+struct RecordedResult {
+    match_id: Uuid,
+    round_number: i32,
+    player_id: Uuid,
+    position: i32,
+    status: models::ResultStatus,
+    all_time_elo_change: Option<i32>,
+    tournament_elo_change: Option<i32>,
+    all_time_rating_deviation_before: Option<f64>,
+    all_time_rating_volatility_before: Option<f64>,
+    tournament_rating_deviation_before: Option<f64>,
+    tournament_rating_volatility_before: Option<f64>,
+}
+
+impl RecordedResult {
+    fn all_time_uncertainty_before(&self) -> Uncertainty {
+        uncertainty_or_default(
+            self.all_time_rating_deviation_before,
+            self.all_time_rating_volatility_before,
+        )
+    }
+
+    fn tournament_uncertainty_before(&self) -> Uncertainty {
+        uncertainty_or_default(
+            self.tournament_rating_deviation_before,
+            self.tournament_rating_volatility_before,
+        )
+    }
+}
+
+/// Stored uncertainty, or the starting uncertainty for races recorded before
+/// it was tracked.
+fn uncertainty_or_default(deviation: Option<f64>, volatility: Option<f64>) -> Uncertainty {
+    let default = Uncertainty::default();
+    Uncertainty {
+        deviation: deviation.unwrap_or(default.deviation),
+        volatility: volatility.unwrap_or(default.volatility),
+    }
+}
+
+/// Replayed values of one `player_race_scores` row.
+#[derive(Debug, Clone)]
+struct RaceScoreUpdate {
+    match_id: Uuid,
+    round_number: i32,
+    player_id: Uuid,
+    all_time_elo_change: i32,
+    all_time_elo_after: i32,
+    tournament_elo_change: i32,
+    tournament_elo_after: i32,
+    all_time_uncertainty_before: Uncertainty,
+    tournament_uncertainty_before: Uncertainty,
+}
+
+/// Team membership for a single match.
+#[derive(Debug, Clone, Default)]
+struct MatchTeams {
+    player_to_team: HashMap<Uuid, Uuid>,
+    team_to_players: HashMap<Uuid, Vec<Uuid>>,
+}
+
+/// Replays a single race against the given rating state.
+///
+/// Computes all-time and tournament rating changes from the ratings in `state`
+/// with the rating system selected in `settings`, derives teammate
+/// contributions from the tournament changes, then advances `state` to the
+/// ratings after the race. This mirrors what
+/// `result_recording::record_race_results` does for a newly recorded race.
+///
+/// # Arguments
+///
+/// * `state` - Ratings before the race; updated in place to the ratings after it
+/// * `tournament_id` - UUID of the tournament the race belongs to
+/// * `results` - Slice of (player_id, position) tuples for race participants
+/// * `player_to_team` - Map of player IDs to their team IDs for the match
+/// * `team_to_players` - Map of team IDs to the players on that team
+/// * `settings` - The group's ELO settings
+///
+/// # Returns
+///
+/// The ELO changes and teammate contributions produced by the race
+pub fn replay_race(
+    state: &mut RatingState,
+    tournament_id: Uuid,
+    results: &[(Uuid, i32)],
+    player_to_team: &HashMap<Uuid, Uuid>,
+    team_to_players: &HashMap<Uuid, Vec<Uuid>>,
+    settings: &EloSettings,
+) -> ReplayedRace {
+    let all_time_players: Vec<RatedPlayer> = results
+        .iter()
+        .map(|&(player_id, position)| RatedPlayer {
+            player_id,
+            position,
+            rating: state.all_time_elo(player_id),
+            uncertainty: state.all_time_uncertainty(player_id),
+        })
+        .collect();
+    let all_time_elo_changes = rating_system::calculate_rating_changes(&all_time_players, settings);
+
+    let tournament_players: Vec<RatedPlayer> = results
+        .iter()
+        .map(|&(player_id, position)| RatedPlayer {
+            player_id,
+            position,
+            rating: state.tournament_elo(player_id, tournament_id),
+            uncertainty: state.tournament_uncertainty(player_id, tournament_id),
+        })
+        .collect();
+    let tournament_elo_changes =
+        rating_system::calculate_rating_changes(&tournament_players, settings);
+
+    let tournament_elo_change_map: HashMap<Uuid, i32> = tournament_elo_changes
+        .iter()
+        .map(|change| (change.player_id, change.elo_change))
+        .collect();
+
+    let (teammate_contributions, tournament_elo_adjustments) =
+        teammate_elo::calculate_teammate_contributions(
+            results,
+            player_to_team,
+            team_to_players,
+            &tournament_elo_change_map,
+            settings,
+        );
+
+    all_time_elo_changes.iter().for_each(|change| {
+        state.all_time.insert(change.player_id, change.new_elo);
+        state
+            .all_time_uncertainty
+            .insert(change.player_id, change.new_uncertainty);
+    });
+    tournament_elo_changes.iter().for_each(|change| {
+        state
+            .tournament
+            .insert((change.player_id, tournament_id), change.new_elo);
+        state
+            .tournament_uncertainty
+            .insert((change.player_id, tournament_id), change.new_uncertainty);
+    });
+    tournament_elo_adjustments
+        .into_iter()
+        .for_each(|(player_id, adjustment)| {
+            *state
+                .tournament
+                .entry((player_id, tournament_id))
+                .or_insert(STARTING_ELO) += adjustment;
+        });
+
+    ReplayedRace {
+        all_time_elo_changes,
+        tournament_elo_changes,
+        teammate_contributions,
+    }
+}
+
+/// Replays every race of a group from the given round onwards.
+///
+/// Positions are read from `player_race_scores`, so callers correcting results
+/// should update positions in the same transaction before calling this. The
+/// transaction is not committed; callers decide when to commit.
+///
+/// # Arguments
+///
+/// * `tx` - Active database transaction
+/// * `group_id` - UUID of the group
+/// * `match_id` - UUID of the match containing the first race to replay
+/// * `round_number` - Round number of the first race to replay
+///
+/// # Returns
+///
+/// Result containing the number of races replayed
+///
+/// # Errors
+///
+/// Returns an error if:
+/// - The round has no recorded results
+/// - Any database operation fails
+pub async fn replay_from_round(
+    tx: &mut Transaction<'_, Postgres>,
+    group_id: Uuid,
+    match_id: Uuid,
+    round_number: i32,
+) -> Result<usize> {
+    let races = fetch_recorded_races(tx, group_id).await?;
+
+    let start_index = races
+        .iter()
+        .position(|race| race.match_id == match_id && race.round_number == round_number)
+        .ok_or_else(|| AppError::NotFound("No recorded results for this round".to_string()))?;
+
+    let races_to_replay = &races[start_index..];
+    let results = fetch_recorded_results(tx, races_to_replay).await?;
+    let replay_start = races_to_replay.iter().map(|race| race.recorded_at).min();
+    let decays = models::PlayerRatingDecay::find_by_group_id(tx, group_id, replay_start).await?;
+    let initial_state = rewind_ratings(tx, races_to_replay, &results, &decays).await?;
+
+    replay_races(tx, group_id, races_to_replay, &results, &decays, initial_state).await?;
+
+    Ok(races_to_replay.len())
+}
+
+/// Fetches every recorded race of a group in replay order.
+async fn fetch_recorded_races(
+    tx: &mut Transaction<'_, Postgres>,
+    group_id: Uuid,
+) -> Result<Vec<RecordedRace>> {
+    let races = sqlx::query_as::<_, RecordedRace>(
+        "SELECT prs.match_id, prs.round_number, m.tournament_id,
+                MIN(prs.created_at) AS recorded_at
+         FROM player_race_scores prs
+         JOIN matches m ON m.id = prs.match_id
+         WHERE prs.group_id = $1
+         GROUP BY prs.match_id, prs.round_number, m.tournament_id, m.time
+         ORDER BY m.time ASC, prs.round_number ASC, prs.match_id ASC",
+    )
+    .bind(group_id)
+    .fetch_all(&mut **tx)
+    .await?;
+
+    Ok(races)
+}
+
+/// Splits races into parallel (match_id, round_number) arrays for UNNEST binds.
+fn race_keys(races: &[RecordedRace]) -> (Vec<Uuid>, Vec<i32>) {
+    races
+        .iter()
+        .map(|race| (race.match_id, race.round_number))
+        .unzip()
+}
+
+/// Fetches stored results for the given races.
+async fn fetch_recorded_results(
+    tx: &mut Transaction<'_, Postgres>,
+    races: &[RecordedRace],
+) -> Result<Vec<RecordedResult>> {
+    let (match_ids, round_numbers) = race_keys(races);
+
+    let results = sqlx::query_as::<_, RecordedResult>(
+        "SELECT prs.match_id, prs.round_number, prs.player_id, prs.position, prs.status,
+                prs.all_time_elo_change, prs.tournament_elo_change,
+                prs.all_time_rating_deviation_before, prs.all_time_rating_volatility_before,
+                prs.tournament_rating_deviation_before, prs.tournament_rating_volatility_before
+         FROM player_race_scores prs
+         JOIN UNNEST($1::uuid[], $2::int[]) AS r(match_id, round_number)
+             ON r.match_id = prs.match_id AND r.round_number = prs.round_number
+         ORDER BY prs.position ASC",
+    )
+    .bind(&match_ids)
+    .bind(&round_numbers)
+    .fetch_all(&mut **tx)
+    .await?;
+
+    Ok(results)
+}
+
+/// Rewinds current ratings to the state before the first of the given races.
+///
+/// Subtracts every stored race delta, received teammate contribution and
+/// replayed decay from the current ratings, which leaves the ratings each
+/// player had going into the replay. Rating uncertainty is taken from each player's first replayed
+/// race, which stores the uncertainty going into it.
+async fn rewind_ratings(
+    tx: &mut Transaction<'_, Postgres>,
+    races: &[RecordedRace],
+    results: &[RecordedResult],
+    decays: &[models::PlayerRatingDecay],
+) -> Result<RatingState> {
+    let race_tournaments: HashMap<(Uuid, i32), Uuid> = races
+        .iter()
+        .map(|race| ((race.match_id, race.round_number), race.tournament_id))
+        .collect();
+
+    let all_time_deltas: HashMap<Uuid, i32> = results
+        .iter()
+        .map(|result| (result.player_id, result.all_time_elo_change.unwrap_or(0)))
+        .chain(decays.iter().map(|decay| (decay.player_id, decay.elo_change)))
+        .fold(HashMap::new(), |mut acc, (player_id, delta)| {
+            *acc.entry(player_id).or_insert(0) += delta;
+            acc
+        });
+
+    let race_tournament_deltas: HashMap<(Uuid, Uuid), i32> =
+        results.iter().fold(HashMap::new(), |mut acc, result| {
+            if let Some(tournament_id) =
+                race_tournaments.get(&(result.match_id, result.round_number))
+            {
+                *acc.entry((result.player_id, *tournament_id)).or_insert(0) +=
+                    result.tournament_elo_change.unwrap_or(0);
+            }
+            acc
+        });
+
+    let (match_ids, round_numbers) = race_keys(races);
+
+    let contribution_deltas = sqlx::query_as::<_, (Uuid, Uuid, i64)>(
+        "SELECT c.beneficiary_player_id, m.tournament_id, SUM(c.contribution_amount)::bigint
+         FROM player_teammate_elo_contributions c
+         JOIN matches m ON m.id = c.match_id
+         JOIN UNNEST($1::uuid[], $2::int[]) AS r(match_id, round_number)
+             ON r.match_id = c.match_id AND r.round_number = c.round_number
+         GROUP BY c.beneficiary_player_id, m.tournament_id",
+    )
+    .bind(&match_ids)
+    .bind(&round_numbers)
+    .fetch_all(&mut **tx)
+    .await?;
+
+    let tournament_deltas: HashMap<(Uuid, Uuid), i32> = contribution_deltas.into_iter().fold(
+        race_tournament_deltas,
+        |mut acc, (player_id, tournament_id, amount)| {
+            *acc.entry((player_id, tournament_id)).or_insert(0) += amount as i32;
+            acc
+        },
+    );
+
+    let player_ids: Vec<Uuid> = all_time_deltas.keys().copied().collect();
+    let current_all_time: Vec<(Uuid, i32)> =
+        sqlx::query_as("SELECT id, elo_rating FROM players WHERE id = ANY($1)")
+            .bind(&player_ids)
+            .fetch_all(&mut **tx)
+            .await?;
+
+    let tournament_ids: Vec<Uuid> = races
+        .iter()
+        .map(|race| race.tournament_id)
+        .collect::<HashSet<_>>()
+        .into_iter()
+        .collect();
+    let current_tournament: Vec<(Uuid, Uuid, i32)> = sqlx::query_as(
+        "SELECT player_id, tournament_id, elo_rating
+         FROM player_tournament_scores
+         WHERE tournament_id = ANY($1)",
+    )
+    .bind(&tournament_ids)
+    .fetch_all(&mut **tx)
+    .await?;
+    let current_tournament: HashMap<(Uuid, Uuid), i32> = current_tournament
+        .into_iter()
+        .map(|(player_id, tournament_id, elo)| ((player_id, tournament_id), elo))
+        .collect();
+
+    let all_time = current_all_time
+        .into_iter()
+        .map(|(player_id, elo)| {
+            let delta = all_time_deltas.get(&player_id).copied().unwrap_or(0);
+            (player_id, elo - delta)
+        })
+        .collect();
+
+    let tournament = current_tournament
+        .keys()
+        .chain(tournament_deltas.keys())
+        .copied()
+        .collect::<HashSet<_>>()
+        .into_iter()
+        .map(|key| {
+            let current = current_tournament
+                .get(&key)
+                .copied()
+                .unwrap_or(STARTING_ELO);
+            let delta = tournament_deltas.get(&key).copied().unwrap_or(0);
+            (key, current - delta)
+        })
+        .collect();
+
+    let race_order: HashMap<(Uuid, i32), usize> = races
+        .iter()
+        .enumerate()
+        .map(|(index, race)| ((race.match_id, race.round_number), index))
+        .collect();
+    let mut results_in_order: Vec<&RecordedResult> = results.iter().collect();
+    results_in_order.sort_by_key(|result| race_order.get(&(result.match_id, result.round_number)));
+
+    let mut all_time_uncertainty = HashMap::new();
+    let mut tournament_uncertainty = HashMap::new();
+    for result in results_in_order {
+        all_time_uncertainty
+            .entry(result.player_id)
+            .or_insert_with(|| result.all_time_uncertainty_before());
+        if let Some(tournament_id) = race_tournaments.get(&(result.match_id, result.round_number)) {
+            tournament_uncertainty
+                .entry((result.player_id, *tournament_id))
+                .or_insert_with(|| result.tournament_uncertainty_before());
+        }
+    }
+
+    Ok(RatingState {
+        all_time,
+        tournament,
+        all_time_uncertainty,
+        tournament_uncertainty,
+    })
+}
+
+/// Fetches team membership for every match touched by the replay.
+async fn fetch_match_teams(
+    tx: &mut Transaction<'_, Postgres>,
+    match_ids: &[Uuid],
+) -> Result<HashMap<Uuid, MatchTeams>> {
+    let memberships: Vec<(Uuid, Uuid, Uuid)> = sqlx::query_as(
+        "SELECT t.match_id, tp.player_id, tp.team_id
+         FROM team_players tp
+         INNER JOIN teams t ON tp.team_id = t.id
+         WHERE t.match_id = ANY($1)",
+    )
+    .bind(match_ids)
+    .fetch_all(&mut **tx)
+    .await
+    .map_err(|e| AppError::Internal(format!("Failed to fetch player teams: {e}")))?;
+
+    Ok(memberships.into_iter().fold(
+        HashMap::new(),
+        |mut acc: HashMap<Uuid, MatchTeams>, (match_id, player_id, team_id)| {
+            let teams = acc.entry(match_id).or_default();
+            teams.player_to_team.insert(player_id, team_id);
+            teams
+                .team_to_players
+                .entry(team_id)
+                .or_default()
+                .push(player_id);
+            acc
+        },
+    ))
+}
+
+/// Applies pending decay events recorded before `until` (all of them with
+/// `None`) to the all-time ratings.
+///
+/// Returns (decay_id, elo_after) for each applied event.
+fn apply_decays<'a>(
+    state: &mut RatingState,
+    decays: &mut Peekable<impl Iterator<Item = &'a models::PlayerRatingDecay>>,
+    until: Option<DateTime<Utc>>,
+) -> Vec<(Uuid, i32)> {
+    std::iter::from_fn(|| {
+        decays.next_if(|decay| until.is_none_or(|until| decay.created_at < until))
+    })
+    .map(|decay| {
+        let elo_after = state.all_time_elo(decay.player_id) + decay.elo_change;
+        state.all_time.insert(decay.player_id, elo_after);
+        (decay.id, elo_after)
+    })
+    .collect()
+}
+
+/// Replays the given races and decay events in order and persists everything
+/// they produce.
+async fn replay_races(
+    tx: &mut Transaction<'_, Postgres>,
+    group_id: Uuid,
+    races: &[RecordedRace],
+    results: &[RecordedResult],
+    decays: &[models::PlayerRatingDecay],
+    initial_state: RatingState,
+) -> Result<()> {
+    let results_by_race: HashMap<(Uuid, i32), Vec<(Uuid, i32)>> = results
+        .iter()
+        .filter(|result| result.status.is_rated())
+        .fold(HashMap::new(), |mut acc, result| {
+            acc.entry((result.match_id, result.round_number))
+                .or_default()
+                .push((result.player_id, result.position));
+            acc
+        });
+    let unrated_by_race: HashMap<(Uuid, i32), Vec<Uuid>> = results
+        .iter()
+        .filter(|result| !result.status.is_rated())
+        .fold(HashMap::new(), |mut acc, result| {
+            acc.entry((result.match_id, result.round_number))
+                .or_default()
+                .push(result.player_id);
+            acc
+        });
+
+    let match_ids: Vec<Uuid> = races
+        .iter()
+        .map(|race| race.match_id)
+        .collect::<HashSet<_>>()
+        .into_iter()
+        .collect();
+    let match_teams = fetch_match_teams(tx, &match_ids).await?;
+    let no_teams = MatchTeams::default();
+    let settings = models::GroupSettings::find_elo_settings(&mut **tx, group_id).await?;
+
+    let mut pending_decays = decays.iter().peekable();
+
+    let (mut final_state, race_score_updates, contributions, mut decay_updates) = races.iter().fold(
+        (initial_state, Vec::new(), Vec::new(), Vec::new()),
+        |(mut state, mut race_score_updates, mut contributions, mut decay_updates), race| {
+            decay_updates.extend(apply_decays(
+                &mut state,
+                &mut pending_decays,
+                Some(race.recorded_at),
+            ));
+
+            let race_results = results_by_race
+                .get(&(race.match_id, race.round_number))
+                .map(Vec::as_slice)
+                .unwrap_or_default();
+            let teams = match_teams.get(&race.match_id).unwrap_or(&no_teams);
+
+            let uncertainty_before: HashMap<Uuid, (Uncertainty, Uncertainty)> = race_results
+                .iter()
+                .map(|&(player_id, _)| {
+                    (
+                        player_id,
+                        (
+                            state.all_time_uncertainty(player_id),
+                            state.tournament_uncertainty(player_id, race.tournament_id),
+                        ),
+                    )
+                })
+                .collect();
+
+            race_score_updates.extend(
+                unrated_by_race
+                    .get(&(race.match_id, race.round_number))
+                    .into_iter()
+                    .flatten()
+                    .map(|&player_id| RaceScoreUpdate {
+                        match_id: race.match_id,
+                        round_number: race.round_number,
+                        player_id,
+                        all_time_elo_change: 0,
+                        all_time_elo_after: state.all_time_elo(player_id),
+                        tournament_elo_change: 0,
+                        tournament_elo_after: state.tournament_elo(player_id, race.tournament_id),
+                        all_time_uncertainty_before: state.all_time_uncertainty(player_id),
+                        tournament_uncertainty_before: state
+                            .tournament_uncertainty(player_id, race.tournament_id),
+                    }),
+            );
+
+            let replayed = replay_race(
+                &mut state,
+                race.tournament_id,
+                race_results,
+                &teams.player_to_team,
+                &teams.team_to_players,
+                &settings,
+            );
+
+            let tournament_changes: HashMap<Uuid, &RatingChange> = replayed
+                .tournament_elo_changes
+                .iter()
+                .map(|change| (change.player_id, change))
+                .collect();
+
+            race_score_updates.extend(replayed.all_time_elo_changes.iter().filter_map(
+                |all_time| {
+                    let tournament = tournament_changes.get(&all_time.player_id)?;
+                    let (all_time_before, tournament_before) = uncertainty_before
+                        .get(&all_time.player_id)
+                        .copied()
+                        .unwrap_or_default();
+                    Some(RaceScoreUpdate {
+                        match_id: race.match_id,
+                        round_number: race.round_number,
+                        player_id: all_time.player_id,
+                        all_time_elo_change: all_time.elo_change,
+                        all_time_elo_after: all_time.new_elo,
+                        tournament_elo_change: tournament.elo_change,
+                        tournament_elo_after: tournament.new_elo,
+                        all_time_uncertainty_before: all_time_before,
+                        tournament_uncertainty_before: tournament_before,
+                    })
+                },
+            ));
+
+            contributions.extend(replayed.teammate_contributions.iter().map(|c| {
+                (
+                    race.match_id,
+                    race.round_number,
+                    c.source_player_id,
+                    c.beneficiary_player_id,
+                    c.source_tournament_elo_change,
+                    c.contribution_amount,
+                )
+            }));
+
+            (state, race_score_updates, contributions, decay_updates)
+        },
+    );
+    decay_updates.extend(apply_decays(&mut final_state, &mut pending_decays, None));
+
+    let (race_match_ids, race_round_numbers) = race_keys(races);
+
+    sqlx::query(
+        "DELETE FROM player_teammate_elo_contributions c
+         USING UNNEST($1::uuid[], $2::int[]) AS r(match_id, round_number)
+         WHERE c.match_id = r.match_id AND c.round_number = r.round_number",
+    )
+    .bind(&race_match_ids)
+    .bind(&race_round_numbers)
+    .execute(&mut **tx)
+    .await
+    .map_err(|e| AppError::Internal(format!("Failed to clear teammate contributions: {e}")))?;
+
+    models::PlayerTeammateEloContribution::insert_contributions_batch(tx, &contributions).await?;
+
+    update_race_scores(tx, &race_score_updates).await?;
+    models::PlayerRatingDecay::update_elo_after_batch(tx, &decay_updates).await?;
+    store_final_ratings(tx, group_id, &final_state).await?;
+
+    for match_id in &match_ids {
+        score_calculation::recompute_player_match_scores(tx, *match_id).await?;
+    }
+
+    track_rating::recompute_group_track_ratings(tx, group_id).await?;
+
+    Ok(())
+}
+
+/// Writes replayed per-race ELO values back to `player_race_scores`.
+async fn update_race_scores(
+    tx: &mut Transaction<'_, Postgres>,
+    updates: &[RaceScoreUpdate],
+) -> Result<()> {
+    let match_ids: Vec<Uuid> = updates.iter().map(|u| u.match_id).collect();
+    let round_numbers: Vec<i32> = updates.iter().map(|u| u.round_number).collect();
+    let player_ids: Vec<Uuid> = updates.iter().map(|u| u.player_id).collect();
+    let all_time_changes: Vec<i32> = updates.iter().map(|u| u.all_time_elo_change).collect();
+    let all_time_afters: Vec<i32> = updates.iter().map(|u| u.all_time_elo_after).collect();
+    let tournament_changes: Vec<i32> = updates.iter().map(|u| u.tournament_elo_change).collect();
+    let tournament_afters: Vec<i32> = updates.iter().map(|u| u.tournament_elo_after).collect();
+    let all_time_deviations: Vec<f64> = updates
+        .iter()
+        .map(|u| u.all_time_uncertainty_before.deviation)
+        .collect();
+    let all_time_volatilities: Vec<f64> = updates
+        .iter()
+        .map(|u| u.all_time_uncertainty_before.volatility)
+        .collect();
+    let tournament_deviations: Vec<f64> = updates
+        .iter()
+        .map(|u| u.tournament_uncertainty_before.deviation)
+        .collect();
+    let tournament_volatilities: Vec<f64> = updates
+        .iter()
+        .map(|u| u.tournament_uncertainty_before.volatility)
+        .collect();
+
+    sqlx::query(
+        "UPDATE player_race_scores prs
+         SET all_time_elo_change = u.all_time_elo_change,
+             all_time_elo_after = u.all_time_elo_after,
+             tournament_elo_change = u.tournament_elo_change,
+             tournament_elo_after = u.tournament_elo_after,
+             all_time_rating_deviation_before = u.all_time_rating_deviation_before,
+             all_time_rating_volatility_before = u.all_time_rating_volatility_before,
+             tournament_rating_deviation_before = u.tournament_rating_deviation_before,
+             tournament_rating_volatility_before = u.tournament_rating_volatility_before
+         FROM UNNEST($1::uuid[], $2::int[], $3::uuid[], $4::int[], $5::int[], $6::int[], $7::int[],
+                     $8::float8[], $9::float8[], $10::float8[], $11::float8[])
+             AS u(match_id, round_number, player_id,
+                  all_time_elo_change, all_time_elo_after,
+                  tournament_elo_change, tournament_elo_after,
+                  all_time_rating_deviation_before, all_time_rating_volatility_before,
+                  tournament_rating_deviation_before, tournament_rating_volatility_before)
+         WHERE prs.match_id = u.match_id
+           AND prs.round_number = u.round_number
+           AND prs.player_id = u.player_id",
+    )
+    .bind(&match_ids)
+    .bind(&round_numbers)
+    .bind(&player_ids)
+    .bind(&all_time_changes)
+    .bind(&all_time_afters)
+    .bind(&tournament_changes)
+    .bind(&tournament_afters)
+    .bind(&all_time_deviations)
+    .bind(&all_time_volatilities)
+    .bind(&tournament_deviations)
+    .bind(&tournament_volatilities)
+    .execute(&mut **tx)
+    .await
+    .map_err(|e| AppError::Internal(format!("Failed to update player race scores: {e}")))?;
+
+    Ok(())
+}
+
+/// Writes the ratings at the end of the replay to players and tournament scores.
+async fn store_final_ratings(
+    tx: &mut Transaction<'_, Postgres>,
+    group_id: Uuid,
+    state: &RatingState,
+) -> Result<()> {
+    let (player_ids, elos): (Vec<Uuid>, Vec<i32>) = state.all_time.iter().unzip();
+
+    sqlx::query(
+        "UPDATE players p
+         SET elo_rating = u.elo_rating
+         FROM UNNEST($1::uuid[], $2::int[]) AS u(id, elo_rating)
+         WHERE p.id = u.id",
+    )
+    .bind(&player_ids)
+    .bind(&elos)
+    .execute(&mut **tx)
+    .await
+    .map_err(|e| AppError::Internal(format!("Failed to update player ELO rating: {e}")))?;
+
+    let (uncertain_player_ids, uncertainties): (Vec<Uuid>, Vec<Uncertainty>) =
+        state.all_time_uncertainty.iter().unzip();
+    let deviations: Vec<f64> = uncertainties.iter().map(|u| u.deviation).collect();
+    let volatilities: Vec<f64> = uncertainties.iter().map(|u| u.volatility).collect();
+
+    sqlx::query(
+        "UPDATE players p
+         SET rating_deviation = u.rating_deviation, rating_volatility = u.rating_volatility
+         FROM UNNEST($1::uuid[], $2::float8[], $3::float8[])
+             AS u(id, rating_deviation, rating_volatility)
+         WHERE p.id = u.id",
+    )
+    .bind(&uncertain_player_ids)
+    .bind(&deviations)
+    .bind(&volatilities)
+    .execute(&mut **tx)
+    .await
+    .map_err(|e| AppError::Internal(format!("Failed to update player rating uncertainty: {e}")))?;
+
+    let players_by_tournament: HashMap<Uuid, Vec<Uuid>> =
+        state
+            .tournament
+            .keys()
+            .fold(HashMap::new(), |mut acc, (player_id, tournament_id)| {
+                acc.entry(*tournament_id).or_default().push(*player_id);
+                acc
+            });
+
+    for (tournament_id, player_ids) in &players_by_tournament {
+        models::PlayerTournamentScore::get_or_create_batch(
+            tx,
+            player_ids,
+            *tournament_id,
+            group_id,
+        )
+        .await?;
+    }
+
+    let tournament_updates: Vec<(Uuid, Uuid, i32)> = state
+        .tournament
+        .iter()
+        .map(|((player_id, tournament_id), elo)| (*player_id, *tournament_id, *elo))
+        .collect();
+
+    if !tournament_updates.is_empty() {
+        models::PlayerTournamentScore::update_elo_batch(tx, &tournament_updates).await?;
+    }
+
+    let tournament_uncertainty_updates: Vec<(Uuid, Uuid, Uncertainty)> = state
+        .tournament_uncertainty
+        .iter()
+        .map(|((player_id, tournament_id), uncertainty)| (*player_id, *tournament_id, *uncertainty))
+        .collect();
+
+    if !tournament_uncertainty_updates.is_empty() {
+        models::PlayerTournamentScore::update_uncertainty_batch(
+            tx,
+            &tournament_uncertainty_updates,
+        )
+        .await?;
+    }
+
+    Ok(())
+}
+
+/// A rating that differs between before and after a recompute.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RatingDiff {
+    pub player_id: Uuid,
+    pub player_name: String,
+    /// `None` for the all-time rating
+    pub tournament_id: Option<Uuid>,
+    pub before: i32,
+    pub after: i32,
+}
+
+/// Summary of a full recompute for one group.
+#[derive(Debug, Clone)]
+pub struct RecomputeReport {
+    pub group_id: Uuid,
+    pub races_replayed: usize,
+    pub rating_changes: Vec<RatingDiff>,
+    pub race_scores_changed: usize,
+}
+
+/// Per-race ELO columns of a `player_race_scores` row, keyed by (match_id, round_number, player_id).
+type RaceScoreSnapshot =
+    HashMap<(Uuid, i32, Uuid), (Option<i32>, Option<i32>, Option<i32>, Option<i32>)>;
+
+/// Rebuilds all ELO data of a group from scratch.
+///
+/// Resets every all-time and tournament rating in the group to the starting
+/// rating, then replays every recorded race and decay event in chronological
+/// order with the current ELO rules. With `dry_run` the transaction is rolled back, so the
+/// report describes what would change without writing anything.
+///
+/// # Arguments
+///
+/// * `pool` - Database connection pool
+/// * `group_id` - UUID of the group
+/// * `dry_run` - Roll back instead of committing
+///
+/// # Returns
+///
+/// Result containing a report of the ratings and race rows that changed
+///
+/// # Errors
+///
+/// Returns an error if any database operation fails (transaction will be rolled back)
+pub async fn recompute_group(
+    pool: &DbPool,
+    group_id: Uuid,
+    dry_run: bool,
+) -> Result<RecomputeReport> {
+    let mut tx = pool
+        .begin()
+        .await
+        .map_err(|e| AppError::Internal(format!("Failed to start database transaction: {e}")))?;
+
+    let report = recompute_group_in_transaction(&mut tx, group_id).await?;
+
+    if dry_run {
+        tx.rollback()
+            .await
+            .map_err(|e| AppError::Internal(format!("Failed to roll back transaction: {e}")))?;
+    } else {
+        tx.commit()
+            .await
+            .map_err(|e| AppError::Internal(format!("Failed to commit transaction: {e}")))?;
+    }
+
+    tracing::info!(
+        %group_id,
+        dry_run,
+        races_replayed = report.races_replayed,
+        ratings_changed = report.rating_changes.len(),
+        race_scores_changed = report.race_scores_changed,
+        "Recomputed group ELO"
+    );
+
+    Ok(report)
+}
+
+/// Rebuilds all ELO data of a group inside the caller's transaction.
+///
+/// Same as `recompute_group`, for callers that need the recompute to commit
+/// together with other changes (e.g. new ELO settings).
+///
+/// # Arguments
+///
+/// * `tx` - Active database transaction
+/// * `group_id` - UUID of the group
+///
+/// # Returns
+///
+/// Result containing a report of the ratings and race rows that changed
+///
+/// # Errors
+///
+/// Returns an error if any database operation fails
+pub async fn recompute_group_in_transaction(
+    tx: &mut Transaction<'_, Postgres>,
+    group_id: Uuid,
+) -> Result<RecomputeReport> {
+    let ratings_before = snapshot_ratings(tx, group_id).await?;
+    let race_scores_before = snapshot_race_scores(tx, group_id).await?;
+
+    let races = fetch_recorded_races(tx, group_id).await?;
+    let results = fetch_recorded_results(tx, &races).await?;
+    let decays = models::PlayerRatingDecay::find_by_group_id(tx, group_id, None).await?;
+
+    let initial_state = RatingState {
+        all_time: ratings_before
+            .iter()
+            .filter(|(_, tournament_id, _, _)| tournament_id.is_none())
+            .map(|(player_id, _, _, _)| (*player_id, STARTING_ELO))
+            .collect(),
+        tournament: ratings_before
+            .iter()
+            .filter_map(|(player_id, tournament_id, _, _)| {
+                tournament_id.map(|tournament_id| ((*player_id, tournament_id), STARTING_ELO))
+            })
+            .collect(),
+        all_time_uncertainty: ratings_before
+            .iter()
+            .filter(|(_, tournament_id, _, _)| tournament_id.is_none())
+            .map(|(player_id, _, _, _)| (*player_id, Uncertainty::default()))
+            .collect(),
+        tournament_uncertainty: ratings_before
+            .iter()
+            .filter_map(|(player_id, tournament_id, _, _)| {
+                tournament_id
+                    .map(|tournament_id| ((*player_id, tournament_id), Uncertainty::default()))
+            })
+            .collect(),
+    };
+
+    replay_races(tx, group_id, &races, &results, &decays, initial_state).await?;
+
+    let ratings_after: HashMap<(Uuid, Option<Uuid>), i32> = snapshot_ratings(tx, group_id)
+        .await?
+        .into_iter()
+        .map(|(player_id, tournament_id, _, elo)| ((player_id, tournament_id), elo))
+        .collect();
+    let race_scores_after = snapshot_race_scores(tx, group_id).await?;
+
+    let rating_changes: Vec<RatingDiff> = ratings_before
+        .into_iter()
+        .filter_map(|(player_id, tournament_id, player_name, before)| {
+            let after = ratings_after
+                .get(&(player_id, tournament_id))
+                .copied()
+                .unwrap_or(before);
+            (after != before).then_some(RatingDiff {
+                player_id,
+                player_name,
+                tournament_id,
+                before,
+                after,
+            })
+        })
+        .collect();
+
+    let race_scores_changed = race_scores_after
+        .iter()
+        .filter(|(key, after)| race_scores_before.get(key) != Some(after))
+        .count();
+
+    Ok(RecomputeReport {
+        group_id,
+        races_replayed: races.len(),
+        rating_changes,
+        race_scores_changed,
+    })
+}
+
+/// Rebuilds all ELO data of every group, one transaction per group.
+///
+/// # Arguments
+///
+/// * `pool` - Database connection pool
+/// * `dry_run` - Roll back instead of committing
+///
+/// # Returns
+///
+/// Result containing a report per group
+///
+/// # Errors
+///
+/// Returns an error if any group fails to recompute; groups already
+/// processed keep their committed changes
+pub async fn recompute_all_groups(pool: &DbPool, dry_run: bool) -> Result<Vec<RecomputeReport>> {
+    let group_ids: Vec<Uuid> = sqlx::query_scalar("SELECT id FROM groups ORDER BY name")
+        .fetch_all(pool)
+        .await?;
+
+    let mut reports = Vec::with_capacity(group_ids.len());
+    for group_id in group_ids {
+        reports.push(recompute_group(pool, group_id, dry_run).await?);
+    }
+
+    Ok(reports)
+}
+
+/// Fetches every all-time and tournament rating of a group as
+/// (player_id, tournament_id, player_name, elo_rating).
+async fn snapshot_ratings(
+    tx: &mut Transaction<'_, Postgres>,
+    group_id: Uuid,
+) -> Result<Vec<(Uuid, Option<Uuid>, String, i32)>> {
+    let ratings = sqlx::query_as::<_, (Uuid, Option<Uuid>, String, i32)>(
+        "SELECT id, NULL::uuid, name, elo_rating
+         FROM players
+         WHERE group_id = $1
+         UNION ALL
+         SELECT pts.player_id, pts.tournament_id, p.name, pts.elo_rating
+         FROM player_tournament_scores pts
+         JOIN players p ON p.id = pts.player_id
+         WHERE pts.group_id = $1
+         ORDER BY 3, 2 NULLS FIRST",
+    )
+    .bind(group_id)
+    .fetch_all(&mut **tx)
+    .await?;
+
+    Ok(ratings)
+}
+
+/// Fetches the per-race ELO columns of every race score in a group.
+async fn snapshot_race_scores(
+    tx: &mut Transaction<'_, Postgres>,
+    group_id: Uuid,
+) -> Result<RaceScoreSnapshot> {
+    let rows = sqlx::query_as::<
+        _,
+        (
+            Uuid,
+            i32,
+            Uuid,
+            Option<i32>,
+            Option<i32>,
+            Option<i32>,
+            Option<i32>,
+        ),
+    >(
+        "SELECT match_id, round_number, player_id,
+                all_time_elo_change, all_time_elo_after,
+                tournament_elo_change, tournament_elo_after
+         FROM player_race_scores
+         WHERE group_id = $1",
+    )
+    .bind(group_id)
+    .fetch_all(&mut **tx)
+    .await?;
+
+    Ok(rows
+        .into_iter()
+        .map(|(match_id, round_number, player_id, atc, ata, tc, ta)| {
+            ((match_id, round_number, player_id), (atc, ata, tc, ta))
+        })
+        .collect())
+}
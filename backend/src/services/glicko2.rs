@@ -0,0 +1,223 @@
+//! Glicko-2 Rating System for Mario Kart Races
+//!
+//! This module implements Glicko-2 for multi-player races by decomposing each
+//! race into pairwise games. Unlike ELO, every rating carries a rating deviation
+//! (RD, how uncertain the rating is) and a volatility (how erratic the player's
+//! results are), so a newcomer moves quickly while an established player moves
+//! slowly.
+//!
+//! ## How It Works
+//!
+//! 1. **Create Full Field**: Fill the race with CPU opponents exactly like the
+//!    ELO system (`elo::create_full_field`). CPUs get a fixed, low RD.
+//! 2. **Pairwise Decomposition**: Each player "beats" everyone who finished
+//!    behind them, "loses" to everyone who finished ahead of them and draws
+//!    with everyone placed the same (e.g. other non-finishers), as in ELO.
+//! 3. **Glicko-2 Update**: The race is treated as one rating period and each
+//!    player's rating, RD and volatility are updated with the standard
+//!    Glicko-2 formulas (Glickman, 2012).
+//!
+//! Ratings use the same scale as ELO (players start at 1200), converted to the
+//! internal Glicko-2 scale with the usual 173.7178 factor.
+
+use crate::services::elo::{self, EloSettings, PlayerResult};
+use crate::services::elo_replay::STARTING_ELO;
+use crate::services::rating_system::{RatedPlayer, RatingChange, Uncertainty};
+use std::cmp::Ordering;
+use std::collections::HashMap;
+use std::f64::consts::PI;
+use uuid::Uuid;
+
+/// Conversion factor between the rating scale and the Glicko-2 scale
+const GLICKO2_SCALE: f64 = 173.7178;
+
+/// System constant constraining volatility changes (Glickman recommends 0.3-1.2)
+const TAU: f64 = 0.5;
+
+/// Convergence tolerance for the volatility iteration
+const CONVERGENCE_TOLERANCE: f64 = 0.000001;
+
+/// Rating deviation of CPU opponents (their strength is known)
+const CPU_RATING_DEVIATION: f64 = 50.0;
+
+/// Upper bound for rating deviation (the starting RD)
+pub const MAX_RATING_DEVIATION: f64 = 350.0;
+
+/// Calculates Glicko-2 rating changes for all players in a race.
+///
+/// # Arguments
+///
+/// * `players` - Race participants with their finishing positions and current ratings
+/// * `settings` - ELO parameters used to build the CPU field
+///
+/// # Returns
+///
+/// Vector of RatingChange structs with the new rating, RD and volatility of each player
+pub fn calculate_glicko2_changes(
+    players: &[RatedPlayer],
+    settings: &EloSettings,
+) -> Vec<RatingChange> {
+    let human_results: Vec<PlayerResult> = players
+        .iter()
+        .map(|player| PlayerResult {
+            player_id: player.player_id,
+            position: player.position,
+            current_elo: player.rating,
+        })
+        .collect();
+
+    let deviations: HashMap<Uuid, f64> = players
+        .iter()
+        .map(|player| (player.player_id, player.uncertainty.deviation))
+        .collect();
+
+    let field: Vec<(Uuid, i32, f64, f64)> = elo::create_full_field(&human_results, settings)
+        .into_iter()
+        .map(|entrant| {
+            let deviation = deviations
+                .get(&entrant.player_id)
+                .copied()
+                .unwrap_or(CPU_RATING_DEVIATION);
+            (
+                entrant.player_id,
+                entrant.position,
+                to_mu(entrant.current_elo),
+                deviation / GLICKO2_SCALE,
+            )
+        })
+        .collect();
+
+    players
+        .iter()
+        .map(|player| {
+            let mu = to_mu(player.rating);
+            let phi = player.uncertainty.deviation / GLICKO2_SCALE;
+
+            let games: Vec<(f64, f64, f64)> = field
+                .iter()
+                .filter(|(player_id, _, _, _)| *player_id != player.player_id)
+                .map(|(_, position, opponent_mu, opponent_phi)| {
+                    let score = match player.position.cmp(position) {
+                        Ordering::Less => 1.0,
+                        Ordering::Equal => 0.5,
+                        Ordering::Greater => 0.0,
+                    };
+                    let g = g(*opponent_phi);
+                    let expected = expected_score(mu, *opponent_mu, g);
+                    (g, expected, score)
+                })
+                .collect();
+
+            let (new_mu, new_phi, new_volatility) =
+                update_rating(mu, phi, player.uncertainty.volatility, &games);
+
+            let new_rating = from_mu(new_mu);
+
+            RatingChange {
+                player_id: player.player_id,
+                elo_change: new_rating - player.rating,
+                new_elo: new_rating,
+                new_uncertainty: Uncertainty {
+                    deviation: (new_phi * GLICKO2_SCALE).min(MAX_RATING_DEVIATION),
+                    volatility: new_volatility,
+                },
+            }
+        })
+        .collect()
+}
+
+/// Ratings are centred on the starting rating rather than Glicko's 1500; only
+/// differences matter, so the offset does not change any result.
+fn to_mu(rating: i32) -> f64 {
+    (rating - STARTING_ELO) as f64 / GLICKO2_SCALE
+}
+
+fn from_mu(mu: f64) -> i32 {
+    (mu * GLICKO2_SCALE).round() as i32 + STARTING_ELO
+}
+
+fn g(phi: f64) -> f64 {
+    1.0 / (1.0 + 3.0 * phi * phi / (PI * PI)).sqrt()
+}
+
+fn expected_score(mu: f64, opponent_mu: f64, g: f64) -> f64 {
+    1.0 / (1.0 + (-g * (mu - opponent_mu)).exp())
+}
+
+/// Applies one Glicko-2 rating period to a player.
+///
+/// `games` holds (g(opponent_phi), expected score, actual score) per opponent.
+/// Returns the new (mu, phi, volatility).
+fn update_rating(
+    mu: f64,
+    phi: f64,
+    volatility: f64,
+    games: &[(f64, f64, f64)],
+) -> (f64, f64, f64) {
+    if games.is_empty() {
+        let phi_star = (phi * phi + volatility * volatility).sqrt();
+        return (mu, phi_star, volatility);
+    }
+
+    let variance = 1.0
+        / games
+            .iter()
+            .map(|(g, expected, _)| g * g * expected * (1.0 - expected))
+            .sum::<f64>();
+
+    let score_sum: f64 = games
+        .iter()
+        .map(|(g, expected, score)| g * (score - expected))
+        .sum();
+    let delta = variance * score_sum;
+
+    let new_volatility = update_volatility(phi, volatility, variance, delta);
+
+    let phi_star = (phi * phi + new_volatility * new_volatility).sqrt();
+    let new_phi = 1.0 / (1.0 / (phi_star * phi_star) + 1.0 / variance).sqrt();
+    let new_mu = mu + new_phi * new_phi * score_sum;
+
+    (new_mu, new_phi, new_volatility)
+}
+
+/// Finds the new volatility with the Illinois algorithm (step 5 of Glicko-2).
+fn update_volatility(phi: f64, volatility: f64, variance: f64, delta: f64) -> f64 {
+    let a = (volatility * volatility).ln();
+    let f = |x: f64| {
+        let ex = x.exp();
+        ex * (delta * delta - phi * phi - variance - ex)
+            / (2.0 * (phi * phi + variance + ex).powi(2))
+            - (x - a) / (TAU * TAU)
+    };
+
+    let mut lower = a;
+    let mut upper = if delta * delta > phi * phi + variance {
+        (delta * delta - phi * phi - variance).ln()
+    } else {
+        let mut k = 1.0;
+        while f(a - k * TAU) < 0.0 {
+            k += 1.0;
+        }
+        a - k * TAU
+    };
+
+    let mut f_lower = f(lower);
+    let mut f_upper = f(upper);
+
+    while (upper - lower).abs() > CONVERGENCE_TOLERANCE {
+        let candidate = lower + (lower - upper) * f_lower / (f_upper - f_lower);
+        let f_candidate = f(candidate);
+
+        if f_candidate * f_upper <= 0.0 {
+            lower = upper;
+            f_lower = f_upper;
+        } else {
+            f_lower /= 2.0;
+        }
+
+        upper = candidate;
+        f_upper = f_candidate;
+    }
+
+    (lower / 2.0).exp()
+}
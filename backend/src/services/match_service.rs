@@ -9,26 +9,82 @@
 //! 1. Validate inputs (player count, race configuration)
 //! 2. Fetch players from database
 //! 3. Allocate players to balanced teams (using team_allocation service)
-//! 4. Select tracks avoiding recent usage (using track_selection service)
-//! 5. Distribute players across races (using race_allocation service)
+//! 4. Distribute players across races (using race_allocation service)
+//! 5. Select tracks or Grand Prix cups avoiding recent usage and the racers'
+//!    vetoes (using track_selection service)
 //! 6. Persist all data in a single transaction:
 //!    - Create match record
 //!    - Create teams
 //!    - Associate players with teams
 //!    - Create rounds with tracks
 //!    - Create round player assignments
+//!
+//! ## Free-for-all Matches
+//!
+//! Free-for-all matches skip team allocation: no teams are created, every
+//! player is assigned to every race, and players score individual points.
 
 use crate::db::DbPool;
 use crate::error::{AppError, Result};
 use crate::models;
+use crate::services::team_allocation::{
+    BalancingStrategy, PairConstraints, PairingHistory, RacePointsModel,
+};
+use crate::services::track_selection::{RoundTrack, TrackFormat, TrackOptions};
 use crate::services::{race_allocation, team_allocation, track_selection};
 use crate::services::notification_manager::{NotificationManager, RaceResultNotification};
-use chrono::Utc;
+use chrono::{DateTime, Utc};
 use std::collections::HashMap;
 use uuid::Uuid;
 
 /// Type alias for round player record tuple: (group_id, match_id, round_number, player_id, team_id, player_position)
-type RoundPlayerRecord = (Uuid, Uuid, i32, Uuid, Uuid, i32);
+type RoundPlayerRecord = (Uuid, Uuid, i32, Uuid, Option<Uuid>, i32);
+
+/// Loads how often players have been teammates in a tournament's matches.
+///
+/// # Arguments
+///
+/// * `pool` - Database connection pool
+/// * `tournament_id` - UUID of the tournament
+/// * `before` - Only count matches created before this time
+///
+/// # Returns
+///
+/// Result containing the tournament's pairing history
+pub async fn find_pairing_history(
+    pool: &DbPool,
+    tournament_id: Uuid,
+    before: Option<DateTime<Utc>>,
+) -> Result<PairingHistory> {
+    let counts = models::Team::find_teammate_pair_counts(pool, tournament_id, before).await?;
+
+    Ok(PairingHistory::from_counts(
+        counts
+            .into_iter()
+            .map(|(player_a, player_b, times)| (player_a, player_b, times as u32)),
+    ))
+}
+
+/// Adds a group's stored pair constraints to those given for one match.
+async fn with_group_constraints(
+    pool: &DbPool,
+    group_id: Uuid,
+    constraints: &PairConstraints,
+) -> Result<PairConstraints> {
+    let mut constraints = constraints.clone();
+    for constraint in models::PlayerPairConstraint::find_by_group_id(pool, group_id).await? {
+        match constraint.kind {
+            models::PairConstraintKind::Together => {
+                constraints.keep_together(constraint.player_a_id, constraint.player_b_id)
+            }
+            models::PairConstraintKind::Apart => {
+                constraints.keep_apart(constraint.player_a_id, constraint.player_b_id)
+            }
+        }
+    }
+
+    Ok(constraints)
+}
 
 /// Validates match creation inputs.
 ///
@@ -38,12 +94,14 @@ type RoundPlayerRecord = (Uuid, Uuid, i32, Uuid, Uuid, i32);
 /// - Players per race is positive
 /// - Players per race doesn't exceed total players
 /// - Total slots (races × players_per_race) >= number of players
+/// - Number of teams is between one and the number of players
 ///
 /// # Arguments
 ///
 /// * `player_uuids` - Slice of player UUIDs
 /// * `num_races` - Number of races to create
 /// * `players_per_race` - Maximum players per race
+/// * `num_teams` - Number of teams to split the players into
 ///
 /// # Returns
 ///
@@ -56,6 +114,7 @@ pub fn validate_create_match_inputs(
     player_uuids: &[Uuid],
     num_races: i32,
     players_per_race: i32,
+    num_teams: i32,
 ) -> Result<()> {
     let num_players = player_uuids.len() as i32;
 
@@ -90,17 +149,92 @@ pub fn validate_create_match_inputs(
         )));
     }
 
+    if num_teams <= 0 || num_teams > num_players {
+        return Err(AppError::InvalidInput(format!(
+            "Number of teams must be between 1 and the number of players ({num_players})"
+        )));
+    }
+
     Ok(())
 }
 
+/// Works out how many teams a match is split into.
+///
+/// - Both `num_teams` and `team_size`: the teams must seat every player exactly
+/// - Only `team_size`: as many teams as needed, so the last teams may be one
+///   player short
+/// - Only `num_teams`: that many teams
+/// - Neither: `players_per_race` teams, one per seat in a race
+///
+/// A team size of 1 gives every player their own team.
+///
+/// # Arguments
+///
+/// * `num_players` - Number of players in the match
+/// * `players_per_race` - Maximum players per race
+/// * `num_teams` - Requested number of teams
+/// * `team_size` - Requested players per team
+///
+/// # Returns
+///
+/// Result containing the number of teams
+///
+/// # Errors
+///
+/// Returns `AppError::InvalidInput` if the team size is not positive or the
+/// requested teams do not seat the players
+pub fn resolve_num_teams(
+    num_players: usize,
+    players_per_race: i32,
+    num_teams: Option<i32>,
+    team_size: Option<i32>,
+) -> Result<i32> {
+    match (num_teams, team_size) {
+        (_, Some(team_size)) if team_size <= 0 => Err(AppError::InvalidInput(
+            "Team size must be positive".to_string(),
+        )),
+        (Some(num_teams), Some(team_size)) if (num_teams * team_size) as usize != num_players => {
+            Err(AppError::InvalidInput(format!(
+                "{num_teams} teams of {team_size} players need {} players, but {num_players} were given",
+                num_teams * team_size
+            )))
+        }
+        (Some(num_teams), _) => Ok(num_teams),
+        (None, Some(team_size)) => Ok(num_players.div_ceil(team_size as usize) as i32),
+        (None, None) => Ok(players_per_race),
+    }
+}
+
+/// How a team match is set up; see `create_match_with_rounds`.
+#[derive(Debug, Clone, Copy)]
+pub struct TeamMatchOptions<'a> {
+    /// Number of races in the match
+    pub num_races: i32,
+    /// Maximum players per race
+    pub players_per_race: i32,
+    /// Number of teams to split the players into (see `resolve_num_teams`)
+    pub num_teams: i32,
+    /// How players are split into teams
+    pub balancing_strategy: BalancingStrategy,
+    /// Players who must or must not share a team in this match, on top of the
+    /// group's stored constraints
+    pub constraints: &'a PairConstraints,
+    /// Points table to score the match with; defaults to the tournament's
+    /// table, or the Mario Kart 8 table
+    pub points_table_id: Option<Uuid>,
+    /// Individual tracks or whole cups, and the track pool; the pool defaults
+    /// to the tournament's pool
+    pub track_options: TrackOptions,
+}
+
 /// Creates a complete match with teams, tracks, and race allocations.
 ///
 /// This is the main orchestration function that coordinates all match creation steps:
 /// 1. Validates inputs
 /// 2. Fetches players from database
-/// 3. Allocates teams using balanced ELO distribution
-/// 4. Selects tracks avoiding recent tournament usage
-/// 5. Allocates players to races fairly
+/// 3. Allocates teams with the chosen balancing strategy
+/// 4. Allocates players to races fairly
+/// 5. Selects tracks or cups avoiding recent tournament usage and vetoes
 /// 6. Persists everything in a single transaction
 /// 7. Emits notification for real-time updates
 ///
@@ -110,8 +244,7 @@ pub fn validate_create_match_inputs(
 /// * `group_id` - UUID of the group
 /// * `tournament_id` - UUID of the tournament
 /// * `player_ids` - Slice of player UUIDs participating
-/// * `num_races` - Number of races in the match
-/// * `players_per_race` - Maximum players per race
+/// * `options` - How many races and teams, and how teams and tracks are chosen
 /// * `notification_manager` - NotificationManager for emitting match creation events
 ///
 /// # Returns
@@ -130,13 +263,195 @@ pub async fn create_match_with_rounds(
     group_id: Uuid,
     tournament_id: Uuid,
     player_ids: &[Uuid],
+    options: &TeamMatchOptions<'_>,
+    notification_manager: &NotificationManager,
+) -> Result<models::Match> {
+    let TeamMatchOptions {
+        num_races,
+        players_per_race,
+        num_teams,
+        balancing_strategy,
+        constraints,
+        points_table_id,
+        track_options,
+    } = *options;
+    validate_create_match_inputs(player_ids, num_races, players_per_race, num_teams)?;
+
+    let points_table_id = resolve_points_table_id(pool, tournament_id, points_table_id).await?;
+    let players = find_match_players(pool, player_ids).await?;
+    let tournament_elos = find_tournament_elos(pool, group_id, tournament_id, player_ids).await?;
+
+    let settings = models::GroupSettings::find_elo_settings(pool, group_id).await?;
+    let history = find_pairing_history(pool, tournament_id, None).await?;
+    let constraints = with_group_constraints(pool, group_id, constraints).await?;
+
+    let teams = match balancing_strategy {
+        BalancingStrategy::Greedy => team_allocation::allocate_teams_with_constraints(
+            &players,
+            &num_teams,
+            &tournament_elos,
+            &history,
+            settings.repeat_teammate_penalty,
+            &constraints,
+        )?,
+        BalancingStrategy::Random => team_allocation::allocate_teams_randomly_with_constraints(
+            &players,
+            &num_teams,
+            &tournament_elos,
+            &constraints,
+        )?,
+        BalancingStrategy::Optimal => {
+            let points = models::PointsTable::find_by_id(pool, points_table_id)
+                .await?
+                .ok_or_else(|| AppError::NotFound("Points table not found".to_string()))?
+                .points;
+            let model = RacePointsModel {
+                num_races,
+                players_per_race,
+                points: &points,
+                settings: &settings,
+            };
+            team_allocation::allocate_teams_optimally(
+                &players,
+                &num_teams,
+                &tournament_elos,
+                &model,
+                &history,
+                settings.repeat_teammate_penalty,
+                &constraints,
+            )?
+        }
+    };
+    let race_allocations = race_allocation::check_teams_and_allocate_races(
+        &players,
+        &teams,
+        num_races,
+        players_per_race,
+        &tournament_elos,
+        &constraints,
+    )?;
+    let track_options = resolve_track_options(pool, tournament_id, track_options).await?;
+    let tracks = track_selection::select_round_tracks(
+        pool,
+        tournament_id,
+        &race_player_ids(&race_allocations),
+        track_options,
+    )
+    .await?;
+
+    let new_match = NewMatch {
+        group_id,
+        tournament_id,
+        num_races,
+        points_table_id,
+        track_pool_id: track_options.track_pool_id,
+        mode: models::MatchMode::Teams,
+        player_ids,
+        teams: &teams,
+        tracks: &tracks,
+        race_allocations: &race_allocations,
+    };
+    let match_record = create_match_in_transaction(pool, &new_match).await?;
+
+    publish_match_created(pool, &match_record, notification_manager).await;
+
+    Ok(match_record)
+}
+
+/// Creates a free-for-all match: no teams, and every player races every race.
+///
+/// # Arguments
+///
+/// * `pool` - Database connection pool
+/// * `tournament` - The tournament the match belongs to
+/// * `player_ids` - Slice of player UUIDs participating
+/// * `num_races` - Number of races in the match
+/// * `points_table_id` - Points table to score the match with; defaults to the
+///   tournament's table, or the Mario Kart 8 table
+/// * `track_options` - Individual tracks or whole cups, and the track pool;
+///   the pool defaults to the tournament's pool
+/// * `notification_manager` - NotificationManager for emitting match creation events
+///
+/// # Returns
+///
+/// Result containing the created match record
+///
+/// # Errors
+///
+/// Returns an error if:
+/// - Input validation fails
+/// - Database operations fail
+/// - Track selection fails
+pub async fn create_free_for_all_match(
+    pool: &DbPool,
+    tournament: &models::Tournament,
+    player_ids: &[Uuid],
     num_races: i32,
-    players_per_race: i32,
-    random_teams: bool,
+    points_table_id: Option<Uuid>,
+    track_options: TrackOptions,
     notification_manager: &NotificationManager,
 ) -> Result<models::Match> {
-    validate_create_match_inputs(player_ids, num_races, players_per_race)?;
+    let group_id = tournament.group_id;
+    let tournament_id = tournament.id;
+    let num_players = player_ids.len() as i32;
+    validate_create_match_inputs(player_ids, num_races, num_players, num_players)?;
+
+    let points_table_id = resolve_points_table_id(pool, tournament_id, points_table_id).await?;
+    find_match_players(pool, player_ids).await?;
+    find_tournament_elos(pool, group_id, tournament_id, player_ids).await?;
+
+    let race_allocations: Vec<race_allocation::RaceAllocation> = (1..=num_races)
+        .map(|race_number| race_allocation::RaceAllocation {
+            race_number,
+            player_ids: player_ids.to_vec(),
+        })
+        .collect();
+    let track_options = resolve_track_options(pool, tournament_id, track_options).await?;
+    let tracks = track_selection::select_round_tracks(
+        pool,
+        tournament_id,
+        &race_player_ids(&race_allocations),
+        track_options,
+    )
+    .await?;
+
+    let new_match = NewMatch {
+        group_id,
+        tournament_id,
+        num_races,
+        points_table_id,
+        track_pool_id: track_options.track_pool_id,
+        mode: models::MatchMode::FreeForAll,
+        player_ids,
+        teams: &[],
+        tracks: &tracks,
+        race_allocations: &race_allocations,
+    };
+    let match_record = create_match_in_transaction(pool, &new_match).await?;
+
+    publish_match_created(pool, &match_record, notification_manager).await;
+
+    Ok(match_record)
+}
 
+/// Picks the points table a new match is scored with: the one given, else the
+/// tournament's table, else the Mario Kart 8 table.
+async fn resolve_points_table_id(
+    pool: &DbPool,
+    tournament_id: Uuid,
+    points_table_id: Option<Uuid>,
+) -> Result<Uuid> {
+    match points_table_id {
+        Some(points_table_id) => Ok(points_table_id),
+        None => Ok(models::Tournament::find_by_id(pool, tournament_id)
+            .await?
+            .and_then(|tournament| tournament.points_table_id)
+            .unwrap_or(models::points_table::MK8_POINTS_TABLE_ID)),
+    }
+}
+
+/// Loads a match's players, failing if any of them do not exist.
+async fn find_match_players(pool: &DbPool, player_ids: &[Uuid]) -> Result<Vec<models::Player>> {
     let players = models::Player::find_by_ids(pool, player_ids).await?;
 
     if players.len() != player_ids.len() {
@@ -145,57 +460,53 @@ pub async fn create_match_with_rounds(
         ));
     }
 
-    let tournament_elos = {
-        let mut tx = pool
-            .begin()
-            .await
-            .map_err(|e| AppError::Internal(format!("Failed to start transaction: {e}")))?;
-        let elos = models::PlayerTournamentScore::get_or_create_batch(
-            &mut tx,
-            player_ids,
-            tournament_id,
-            group_id,
-        )
-        .await
-        .map_err(|e| AppError::Internal(format!("Failed to fetch tournament ELO: {e}")))?;
-        tx.commit()
-            .await
-            .map_err(|e| AppError::Internal(format!("Failed to commit transaction: {e}")))?;
-        elos
-    };
-
-    let teams = if random_teams {
-        team_allocation::allocate_teams_randomly(&players, &players_per_race, &tournament_elos)
-    } else {
-        team_allocation::allocate_teams(&players, &players_per_race, &tournament_elos)
-    };
-    let tracks = track_selection::select_tracks(pool, tournament_id, num_races).await?;
-    let race_allocations =
-        race_allocation::allocate_races(&players, &teams, num_races, players_per_race, &tournament_elos)?;
+    Ok(players)
+}
 
-    let match_record = create_match_in_transaction(
-        pool,
-        group_id,
+/// Loads the players' tournament ELO, creating scores for newcomers.
+async fn find_tournament_elos(
+    pool: &DbPool,
+    group_id: Uuid,
+    tournament_id: Uuid,
+    player_ids: &[Uuid],
+) -> Result<HashMap<Uuid, i32>> {
+    let mut tx = pool
+        .begin()
+        .await
+        .map_err(|e| AppError::Internal(format!("Failed to start transaction: {e}")))?;
+    let elos = models::PlayerTournamentScore::get_or_create_batch(
+        &mut tx,
+        player_ids,
         tournament_id,
-        num_races,
-        &teams,
-        &tracks,
-        &race_allocations,
+        group_id,
     )
-    .await?;
+    .await
+    .map_err(|e| AppError::Internal(format!("Failed to fetch tournament ELO: {e}")))?;
+    tx.commit()
+        .await
+        .map_err(|e| AppError::Internal(format!("Failed to commit transaction: {e}")))?;
+
+    Ok(elos)
+}
 
+/// Emits the match creation notification for real-time updates.
+async fn publish_match_created(
+    pool: &DbPool,
+    match_record: &models::Match,
+    notification_manager: &NotificationManager,
+) {
     // Emit notification for match creation (round_number = 0 indicates match creation)
     tracing::info!(
         "Match created: match_id={}, tournament_id={}, emitting notification",
         match_record.id,
-        tournament_id
+        match_record.tournament_id
     );
 
     let notification = RaceResultNotification {
         match_id: match_record.id,
-        tournament_id,
+        tournament_id: match_record.tournament_id,
         round_number: 0, // 0 indicates match creation, not a race result
-        group_id,
+        group_id: match_record.group_id,
     };
 
     if let Err(e) = notification_manager.publish(pool, notification).await {
@@ -204,8 +515,54 @@ pub async fn create_match_with_rounds(
             e
         );
     }
+}
 
-    Ok(match_record)
+/// The players in each race, in race order, for track vetoes.
+fn race_player_ids(race_allocations: &[race_allocation::RaceAllocation]) -> Vec<Vec<Uuid>> {
+    let mut races: Vec<&race_allocation::RaceAllocation> = race_allocations.iter().collect();
+    races.sort_by_key(|race| race.race_number);
+    races.into_iter().map(|race| race.player_ids.clone()).collect()
+}
+
+/// Fills in the tournament's track pool when none is given. Grand Prix
+/// matches race whole cups, so they never use a pool.
+async fn resolve_track_options(
+    pool: &DbPool,
+    tournament_id: Uuid,
+    options: TrackOptions,
+) -> Result<TrackOptions> {
+    let track_pool_id = match (options.format, options.track_pool_id) {
+        (TrackFormat::GrandPrix, _) => None,
+        (TrackFormat::Shuffled, Some(track_pool_id)) => Some(track_pool_id),
+        (TrackFormat::Shuffled, None) => models::Tournament::find_by_id(pool, tournament_id)
+            .await?
+            .and_then(|tournament| tournament.track_pool_id),
+    };
+
+    Ok(TrackOptions {
+        track_pool_id,
+        ..options
+    })
+}
+
+/// A match ready to be persisted by `create_match_in_transaction`.
+#[derive(Clone, Copy)]
+struct NewMatch<'a> {
+    group_id: Uuid,
+    tournament_id: Uuid,
+    num_races: i32,
+    /// Points table to score the match with
+    points_table_id: Uuid,
+    /// Track pool the tracks were drawn from, if any
+    track_pool_id: Option<Uuid>,
+    /// Whether the match is raced in teams or free-for-all
+    mode: models::MatchMode,
+    player_ids: &'a [Uuid],
+    /// Allocated teams (empty for free-for-all matches)
+    teams: &'a [team_allocation::Team],
+    /// Selected tracks, in race order
+    tracks: &'a [RoundTrack],
+    race_allocations: &'a [race_allocation::RaceAllocation],
 }
 
 /// Internal function: Persists match data in a single database transaction.
@@ -220,12 +577,7 @@ pub async fn create_match_with_rounds(
 /// # Arguments
 ///
 /// * `pool` - Database connection pool
-/// * `group_id` - UUID of the group
-/// * `tournament_id` - UUID of the tournament
-/// * `num_races` - Number of races
-/// * `teams` - Slice of allocated teams
-/// * `tracks` - Slice of selected tracks
-/// * `race_allocations` - Slice of race allocations
+/// * `new_match` - The match, its teams, tracks and race allocations
 ///
 /// # Returns
 ///
@@ -236,28 +588,39 @@ pub async fn create_match_with_rounds(
 /// Returns an error if any database operation fails (transaction will be rolled back)
 async fn create_match_in_transaction(
     pool: &DbPool,
-    group_id: Uuid,
-    tournament_id: Uuid,
-    num_races: i32,
-    teams: &[team_allocation::Team],
-    tracks: &[models::Track],
-    race_allocations: &[race_allocation::RaceAllocation],
+    new_match: &NewMatch<'_>,
 ) -> Result<models::Match> {
+    let NewMatch {
+        group_id,
+        tournament_id,
+        num_races,
+        points_table_id,
+        track_pool_id,
+        mode,
+        player_ids,
+        teams,
+        tracks,
+        race_allocations,
+    } = *new_match;
+
     let mut tx = pool
         .begin()
         .await
         .map_err(|e| AppError::Internal(format!("Failed to start database transaction: {e}")))?;
 
     let match_record = sqlx::query_as::<_, models::Match>(
-        "INSERT INTO matches (group_id, tournament_id, time, rounds, completed)
-         VALUES ($1, $2, $3, $4, $5)
-         RETURNING id, group_id, tournament_id, time, rounds, completed",
+        "INSERT INTO matches (group_id, tournament_id, time, rounds, completed, points_table_id, mode, track_pool_id)
+         VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
+         RETURNING id, group_id, tournament_id, time, rounds, completed, points_table_id, mode, track_pool_id",
     )
     .bind(group_id)
     .bind(tournament_id)
     .bind(Utc::now())
     .bind(num_races)
     .bind(false)
+    .bind(points_table_id)
+    .bind(mode)
+    .bind(track_pool_id)
     .fetch_one(tx.as_mut())
     .await
     .map_err(|e| AppError::Internal(format!("Failed to create match record: {e}")))?;
@@ -321,15 +684,10 @@ async fn create_match_in_transaction(
         .await?;
     }
 
-    let all_player_ids: Vec<Uuid> = teams
-        .iter()
-        .flat_map(|team| team.players.iter().map(|p| p.id))
-        .collect();
-
-    if !all_player_ids.is_empty() {
-        let group_ids_for_scores: Vec<Uuid> = vec![group_id; all_player_ids.len()];
-        let match_ids_for_scores: Vec<Uuid> = vec![match_record.id; all_player_ids.len()];
-        let zeros: Vec<i32> = vec![0; all_player_ids.len()];
+    if !player_ids.is_empty() {
+        let group_ids_for_scores: Vec<Uuid> = vec![group_id; player_ids.len()];
+        let match_ids_for_scores: Vec<Uuid> = vec![match_record.id; player_ids.len()];
+        let zeros: Vec<i32> = vec![0; player_ids.len()];
 
         sqlx::query(
             "INSERT INTO player_match_scores (
@@ -344,7 +702,7 @@ async fn create_match_in_transaction(
         )
         .bind(&group_ids_for_scores)
         .bind(&match_ids_for_scores)
-        .bind(&all_player_ids)
+        .bind(player_ids)
         .bind(&zeros)
         .bind(&zeros)
         .bind(&zeros)
@@ -369,14 +727,16 @@ async fn create_match_in_transaction(
         .filter_map(|team| team_id_map.get(&team.team_num).map(|&team_id| (team_id, team.team_num)))
         .collect();
 
-    for (idx, track) in tracks.iter().enumerate() {
+    for (idx, round_track) in tracks.iter().enumerate() {
         sqlx::query(
-            "INSERT INTO rounds (match_id, round_number, track_id)
-             VALUES ($1, $2, $3)",
+            "INSERT INTO rounds (match_id, round_number, track_id, cup_id, track_selection_notes)
+             VALUES ($1, $2, $3, $4, $5)",
         )
         .bind(match_record.id)
         .bind((idx + 1) as i32)
-        .bind(track.id)
+        .bind(round_track.track.id)
+        .bind(round_track.cup_id)
+        .bind(&round_track.selection_notes)
         .execute(tx.as_mut())
         .await?;
     }
@@ -387,7 +747,19 @@ async fn create_match_in_transaction(
             allocation
                 .player_ids
                 .iter()
-                .map(|player_id| {
+                .enumerate()
+                .map(|(seat, player_id)| {
+                    if mode == models::MatchMode::FreeForAll {
+                        return Ok((
+                            group_id,
+                            match_record.id,
+                            allocation.race_number,
+                            *player_id,
+                            None,
+                            (seat + 1) as i32,
+                        ));
+                    }
+
                     player_team_map
                         .get(player_id)
                         .ok_or_else(|| {
@@ -405,7 +777,7 @@ async fn create_match_in_transaction(
                                         match_record.id,
                                         allocation.race_number,
                                         *player_id,
-                                        team_id,
+                                        Some(team_id),
                                         team_num,
                                     )
                                 })
@@ -8,26 +8,44 @@
 //! ## Architecture
 //!
 //! - **elo**: ELO rating system for Mario Kart races
+//! - **glicko2**: Glicko-2 rating system with rating deviation and volatility
+//! - **rating_system**: Pluggable rating system interface (ELO or Glicko-2 per group)
 //! - **scoring**: Position-to-points conversion utilities
 //! - **validation**: Input validation for names, passwords, etc.
 //! - **team_allocation**: Pure functions for balanced team creation
 //! - **teammate_elo**: Pure functions for teammate ELO contribution calculations
 //! - **race_allocation**: Algorithm for fair race distribution across players
 //! - **track_selection**: Track selection with history avoidance
+//! - **track_rating**: Per-track player ELO ratings
 //! - **match_service**: High-level match creation orchestration
 //! - **score_calculation**: Aggregate score calculations for players and teams
 //! - **result_recording**: Race result recording and ELO update orchestration
+//! - **elo_replay**: Chronological replay of recorded races after results change
+//! - **prediction**: Race outcome and match win probability predictions
+//! - **rating_decay**: Inactivity decay of all-time ratings
+//! - **player_merge**: Merging duplicate player records
+//! - **avatar**: Avatar upload validation, resizing and replacement
+//! - **avatar_storage**: Pluggable storage for avatar images (local disk)
 //! - **notification_manager**: PostgreSQL LISTEN/NOTIFY for GraphQL subscriptions
 
+pub mod avatar;
+pub mod avatar_storage;
 pub mod elo;
+pub mod elo_replay;
+pub mod glicko2;
 pub mod match_service;
 pub mod notification_manager;
+pub mod player_merge;
+pub mod prediction;
 pub mod race_allocation;
+pub mod rating_decay;
+pub mod rating_system;
 pub mod result_recording;
 pub mod score_calculation;
 pub mod scoring;
 pub mod team_allocation;
 pub mod teammate_elo;
 pub mod tournament_completion;
+pub mod track_rating;
 pub mod track_selection;
 pub mod validation;
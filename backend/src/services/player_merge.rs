@@ -0,0 +1,280 @@
+//! Player Merge Service
+//!
+//! Folds a duplicate player record into the record that is kept. Everything
+//! the source player took part in is reassigned to the target player in one
+//! transaction, the source player is deleted, and the group's ratings are
+//! rebuilt with `elo_replay` so the target's history is rated as one player.
+//! Completed tournaments the source raced in get their winner and stats
+//! recalculated.
+//!
+//! ## Conflicts
+//!
+//! A player cannot race against themselves, so in rounds both players raced,
+//! the target's result is kept and the source's is discarded. Rounds only the
+//! source raced are moved to the target, even within a match both played.
+//! Other rows that would collide are resolved the same way, in favour of the
+//! target:
+//! - Match players, match scores and team membership: folded into the
+//!   target's, with match aggregates rebuilt by the recompute
+//! - Tournament ratings: both are replaced by the recompute anyway
+//! - Lobby entries and track vetoes: kept once
+//! - Pair constraints: moved to the target, except those between the two
+//!   players or already set for the target
+//!
+//! The source's inactivity decay events are discarded, since they describe a
+//! record that is no longer raced under, and per-track ratings are rebuilt by
+//! the recompute.
+
+use crate::db::DbPool;
+use crate::error::{AppError, Result};
+use crate::models;
+use crate::services::{elo_replay, score_calculation, tournament_completion};
+use sqlx::{Postgres, Transaction};
+use uuid::Uuid;
+
+/// Merges the source player into the target player.
+///
+/// # Arguments
+///
+/// * `pool` - Database connection pool
+/// * `group_id` - UUID of the group both players belong to
+/// * `source_id` - UUID of the player to merge and delete
+/// * `target_id` - UUID of the player to keep
+///
+/// # Returns
+///
+/// Result containing the target player with their recomputed ratings
+///
+/// # Errors
+///
+/// Returns an error if:
+/// - The players are the same, or either is not in the group
+/// - Any database operation fails (transaction will be rolled back)
+pub async fn merge_players(
+    pool: &DbPool,
+    group_id: Uuid,
+    source_id: Uuid,
+    target_id: Uuid,
+) -> Result<models::Player> {
+    if source_id == target_id {
+        return Err(AppError::InvalidInput(
+            "A player cannot be merged into themselves".to_string(),
+        ));
+    }
+
+    let players = models::Player::find_by_ids(pool, &[source_id, target_id]).await?;
+    if players.len() != 2 || players.iter().any(|p| p.group_id != group_id) {
+        return Err(AppError::NotFound("Player not found".to_string()));
+    }
+
+    let mut tx = pool
+        .begin()
+        .await
+        .map_err(|e| AppError::Internal(format!("Failed to start database transaction: {e}")))?;
+
+    let shared_matches = find_shared_matches(&mut tx, source_id, target_id).await?;
+    let completed_tournament_ids =
+        tournament_completion::find_completed_tournaments_with_player(&mut tx, source_id).await?;
+
+    discard_shared_rounds(&mut tx, source_id, target_id).await?;
+    reassign_history(&mut tx, source_id, target_id).await?;
+    reassign_preferences(&mut tx, source_id, target_id).await?;
+
+    sqlx::query("DELETE FROM players WHERE id = $1")
+        .bind(source_id)
+        .execute(&mut *tx)
+        .await?;
+
+    let report = elo_replay::recompute_group_in_transaction(&mut tx, group_id).await?;
+
+    for match_record in shared_matches.iter().filter(|m| m.completed) {
+        score_calculation::calculate_and_store_match_scores(&mut tx, match_record).await?;
+    }
+
+    for tournament_id in &completed_tournament_ids {
+        tournament_completion::recalculate_completion(&mut tx, *tournament_id).await?;
+    }
+
+    let target = sqlx::query_as::<_, models::Player>(
+        "SELECT id, group_id, name, elo_rating, rating_deviation, rating_volatility,
+                avatar_filename, disabled, guest
+         FROM players
+         WHERE id = $1",
+    )
+    .bind(target_id)
+    .fetch_one(&mut *tx)
+    .await?;
+
+    tx.commit()
+        .await
+        .map_err(|e| AppError::Internal(format!("Failed to commit transaction: {e}")))?;
+
+    tracing::info!(
+        %group_id,
+        %source_id,
+        %target_id,
+        shared_matches = shared_matches.len(),
+        completed_tournaments = completed_tournament_ids.len(),
+        races_replayed = report.races_replayed,
+        "Merged players"
+    );
+
+    Ok(target)
+}
+
+/// Finds the matches both players took part in, in the same round or not,
+/// whose scores need storing again after the merge.
+async fn find_shared_matches(
+    tx: &mut Transaction<'_, Postgres>,
+    source_id: Uuid,
+    target_id: Uuid,
+) -> Result<Vec<models::Match>> {
+    let shared_matches = sqlx::query_as::<_, models::Match>(
+        "SELECT m.id, m.group_id, m.tournament_id, m.time, m.rounds, m.completed,
+                m.points_table_id, m.mode, m.track_pool_id
+         FROM matches m
+         WHERE EXISTS (SELECT 1 FROM round_players rp WHERE rp.match_id = m.id AND rp.player_id = $1)
+           AND EXISTS (SELECT 1 FROM round_players rp WHERE rp.match_id = m.id AND rp.player_id = $2)",
+    )
+    .bind(source_id)
+    .bind(target_id)
+    .fetch_all(&mut **tx)
+    .await?;
+
+    Ok(shared_matches)
+}
+
+/// Removes the source's rows from rounds both players raced, and folds the
+/// source's match-level rows into the target's in matches both played.
+///
+/// Everything left over belongs to rounds only the source raced, and is
+/// moved to the target by `reassign_history`.
+async fn discard_shared_rounds(
+    tx: &mut Transaction<'_, Postgres>,
+    source_id: Uuid,
+    target_id: Uuid,
+) -> Result<()> {
+    let statements = [
+        "DELETE FROM player_teammate_elo_contributions c
+         USING round_players kept
+         WHERE $1 IN (c.source_player_id, c.beneficiary_player_id)
+           AND kept.player_id = $2
+           AND kept.match_id = c.match_id
+           AND kept.round_number = c.round_number",
+        "DELETE FROM player_race_scores merged
+         USING round_players kept
+         WHERE merged.player_id = $1
+           AND kept.player_id = $2
+           AND kept.match_id = merged.match_id
+           AND kept.round_number = merged.round_number",
+        "DELETE FROM round_players merged
+         USING round_players kept
+         WHERE merged.player_id = $1
+           AND kept.player_id = $2
+           AND kept.match_id = merged.match_id
+           AND kept.round_number = merged.round_number",
+        "DELETE FROM team_players merged
+         USING teams merged_team, teams kept_team, team_players kept
+         WHERE merged.player_id = $1
+           AND merged_team.id = merged.team_id
+           AND kept.player_id = $2
+           AND kept_team.id = kept.team_id
+           AND kept_team.match_id = merged_team.match_id",
+        "DELETE FROM player_match_scores merged
+         USING player_match_scores kept
+         WHERE merged.player_id = $1
+           AND kept.player_id = $2
+           AND kept.match_id = merged.match_id",
+        "DELETE FROM match_players merged
+         USING match_players kept
+         WHERE merged.player_id = $1
+           AND kept.player_id = $2
+           AND kept.match_id = merged.match_id",
+    ];
+
+    for statement in statements {
+        sqlx::query(statement)
+            .bind(source_id)
+            .bind(target_id)
+            .execute(&mut **tx)
+            .await?;
+    }
+
+    Ok(())
+}
+
+/// Moves the source's races, matches, tournament results and name history to
+/// the target.
+async fn reassign_history(
+    tx: &mut Transaction<'_, Postgres>,
+    source_id: Uuid,
+    target_id: Uuid,
+) -> Result<()> {
+    let statements = [
+        "UPDATE match_players SET player_id = $2 WHERE player_id = $1",
+        "UPDATE round_players SET player_id = $2 WHERE player_id = $1",
+        "UPDATE team_players SET player_id = $2 WHERE player_id = $1",
+        "UPDATE player_match_scores SET player_id = $2 WHERE player_id = $1",
+        "UPDATE player_race_scores SET player_id = $2 WHERE player_id = $1",
+        "UPDATE player_teammate_elo_contributions SET source_player_id = $2
+         WHERE source_player_id = $1",
+        "UPDATE player_teammate_elo_contributions SET beneficiary_player_id = $2
+         WHERE beneficiary_player_id = $1",
+        "DELETE FROM player_tournament_scores merged
+         USING player_tournament_scores kept
+         WHERE merged.player_id = $1
+           AND kept.player_id = $2
+           AND kept.tournament_id = merged.tournament_id",
+        "UPDATE player_tournament_scores SET player_id = $2 WHERE player_id = $1",
+        "UPDATE tournament_stats SET player_id = $2 WHERE player_id = $1",
+        "UPDATE tournaments SET winner = $2 WHERE winner = $1",
+        "UPDATE player_name_changes SET player_id = $2 WHERE player_id = $1",
+    ];
+
+    for statement in statements {
+        sqlx::query(statement)
+            .bind(source_id)
+            .bind(target_id)
+            .execute(&mut **tx)
+            .await?;
+    }
+
+    Ok(())
+}
+
+/// Copies the source's lobby entries, track vetoes and pair constraints to the
+/// target. The source's own rows are removed with the source player.
+async fn reassign_preferences(
+    tx: &mut Transaction<'_, Postgres>,
+    source_id: Uuid,
+    target_id: Uuid,
+) -> Result<()> {
+    let statements = [
+        "INSERT INTO lobby_entries (group_id, player_id, checked_in_at)
+         SELECT group_id, $2, checked_in_at FROM lobby_entries WHERE player_id = $1
+         ON CONFLICT (group_id, player_id) DO NOTHING",
+        "INSERT INTO track_vetoes (player_id, tournament_id, track_id, created_at)
+         SELECT $2, tournament_id, track_id, created_at FROM track_vetoes WHERE player_id = $1
+         ON CONFLICT (player_id, tournament_id, track_id) DO NOTHING",
+        "INSERT INTO player_pair_constraints (group_id, player_a_id, player_b_id, kind)
+         SELECT group_id, LEAST(other_id, $2), GREATEST(other_id, $2), kind
+         FROM (
+             SELECT group_id, kind,
+                    CASE WHEN player_a_id = $1 THEN player_b_id ELSE player_a_id END AS other_id
+             FROM player_pair_constraints
+             WHERE $1 IN (player_a_id, player_b_id)
+         ) merged
+         WHERE other_id <> $2
+         ON CONFLICT (group_id, player_a_id, player_b_id) DO NOTHING",
+    ];
+
+    for statement in statements {
+        sqlx::query(statement)
+            .bind(source_id)
+            .bind(target_id)
+            .execute(&mut **tx)
+            .await?;
+    }
+
+    Ok(())
+}
@@ -0,0 +1,501 @@
+//! Race Prediction Service
+//!
+//! This module predicts the outcome of an upcoming race and of the rest of a
+//! match from the players' current all-time ratings, using the same model the
+//! ELO system rates races with.
+//!
+//! ## Model
+//!
+//! - **Field**: Human players are placed at evenly spread positions in rating
+//!   order and the rest of the field is filled with CPUs by
+//!   `elo::create_full_field`, as if the race finished as expected
+//! - **Expected position**: Derived from the ELO expected score against the
+//!   field (`elo::calculate_expected_score`): a player expected to beat a share
+//!   `s` of the other racers finishes `1 + (racers - 1) * (1 - s)` on average
+//! - **Simulated races**: Each racer's performance is their rating plus Gumbel
+//!   noise, scaled so that any two racers' odds of finishing ahead of each
+//!   other equal the ELO expected score. Expected points are averaged over the
+//!   simulated races.
+//! - **Expected ELO changes**: The group's rating system is run with the player
+//!   in first, median and last place and the other players at evenly spread
+//!   positions
+//! - **Team win probability**: Every remaining round of the match is simulated
+//!   and the simulated points are added to the points each team already has.
+//!   The team with the most points wins; ties split the win.
+
+use crate::db::DbPool;
+use crate::error::{AppError, Result};
+use crate::models;
+use crate::services::elo::{self, EloSettings, PlayerResult};
+use crate::services::rating_system::{self, RatedPlayer, Uncertainty};
+use crate::services::scoring;
+use rand::Rng;
+use std::collections::HashMap;
+use uuid::Uuid;
+
+/// Number of simulated races or matches behind each prediction
+pub const DEFAULT_SIMULATIONS: usize = 2000;
+
+/// Scale of the Gumbel noise that reproduces ELO head-to-head odds (400 / ln 10)
+const PERFORMANCE_SCALE: f64 = 400.0 / std::f64::consts::LN_10;
+
+/// A human player taking part in a predicted race.
+#[derive(Debug, Clone)]
+pub struct PredictionPlayer {
+    pub player_id: Uuid,
+    pub rating: i32,
+    pub uncertainty: Uncertainty,
+}
+
+/// The predicted outcome of a race for one player.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PlayerPrediction {
+    pub player_id: Uuid,
+    pub expected_position: f64,
+    pub expected_points: f64,
+    pub elo_change_if_first: i32,
+    pub elo_change_if_median: i32,
+    pub elo_change_if_last: i32,
+}
+
+/// The predicted outcome of a match for one team.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TeamPrediction {
+    pub team_id: Uuid,
+    /// Points already scored in recorded rounds
+    pub current_points: i32,
+    /// Points expected at the end of the match
+    pub expected_points: f64,
+    pub win_probability: f64,
+}
+
+/// Predictions for one round of a match.
+#[derive(Debug, Clone)]
+pub struct RoundPrediction {
+    pub match_id: Uuid,
+    pub round_number: i32,
+    pub players: Vec<PlayerPrediction>,
+    pub teams: Vec<TeamPrediction>,
+}
+
+/// A racer in a round still to be raced: (player_id, team_id, rating).
+pub type RemainingRacer = (Uuid, Uuid, i32);
+
+/// Picks `count` positions spread evenly over `available`, best first.
+fn spread_positions(count: usize, available: &[i32]) -> Vec<i32> {
+    if count > available.len() {
+        return (1..=count as i32).collect();
+    }
+    (0..count)
+        .map(|index| available[index * available.len() / count])
+        .collect()
+}
+
+/// Builds the full field for a race that finishes in rating order.
+///
+/// Humans take evenly spread positions in rating order and CPUs fill the
+/// remaining positions.
+fn prediction_field(ratings: &[(Uuid, i32)], settings: &EloSettings) -> Vec<PlayerResult> {
+    let mut by_rating = ratings.to_vec();
+    by_rating.sort_by_key(|r| std::cmp::Reverse(r.1));
+
+    let all_positions: Vec<i32> = (1..=settings.total_race_size).collect();
+    let humans: Vec<PlayerResult> = by_rating
+        .iter()
+        .zip(spread_positions(by_rating.len(), &all_positions))
+        .map(|(&(player_id, current_elo), position)| PlayerResult {
+            player_id,
+            position,
+            current_elo,
+        })
+        .collect();
+
+    elo::create_full_field(&humans, settings)
+}
+
+/// Average finishing position of a racer, derived from their ELO expected
+/// score against the rest of the field.
+fn expected_position(field: &[PlayerResult], player_id: Uuid) -> f64 {
+    let opponent_count = field.len().saturating_sub(1) as f64;
+    let expected_score = field
+        .iter()
+        .find(|racer| racer.player_id == player_id)
+        .map(|racer| elo::calculate_expected_score(racer, field))
+        .unwrap_or(0.5);
+
+    1.0 + opponent_count * (1.0 - expected_score)
+}
+
+/// Calculates each player's average finishing position in a race, including
+/// the CPUs filling the rest of the field.
+///
+/// # Arguments
+///
+/// * `ratings` - (player_id, rating) of each human in the race
+/// * `settings` - The group's ELO settings
+///
+/// # Returns
+///
+/// HashMap mapping player IDs to their expected position
+pub fn expected_positions(ratings: &[(Uuid, i32)], settings: &EloSettings) -> HashMap<Uuid, f64> {
+    let field = prediction_field(ratings, settings);
+
+    ratings
+        .iter()
+        .map(|&(player_id, _)| (player_id, expected_position(&field, player_id)))
+        .collect()
+}
+
+/// Simulates one race and returns each human's finishing position.
+fn simulate_race(field: &[PlayerResult], rng: &mut impl Rng) -> HashMap<Uuid, i32> {
+    let mut performances: Vec<(Uuid, f64)> = field
+        .iter()
+        .map(|racer| {
+            let uniform: f64 = rng.random_range(f64::EPSILON..1.0);
+            let noise = -(-uniform.ln()).ln();
+            (racer.player_id, racer.current_elo as f64 + PERFORMANCE_SCALE * noise)
+        })
+        .collect();
+    performances.sort_by(|a, b| b.1.total_cmp(&a.1));
+
+    performances
+        .into_iter()
+        .zip(1..)
+        .filter(|((player_id, _), _)| !player_id.is_nil())
+        .map(|((player_id, _), position)| (player_id, position))
+        .collect()
+}
+
+/// Calculates a player's rating change if they finish at `position`, with the
+/// other players at evenly spread positions in rating order.
+fn elo_change_at_position(
+    player: &PredictionPlayer,
+    others: &[&PredictionPlayer],
+    position: i32,
+    settings: &EloSettings,
+) -> i32 {
+    let available: Vec<i32> = (1..=settings.total_race_size)
+        .filter(|&p| p != position)
+        .collect();
+
+    let rated_players: Vec<RatedPlayer> = std::iter::once(RatedPlayer {
+        player_id: player.player_id,
+        position,
+        rating: player.rating,
+        uncertainty: player.uncertainty,
+    })
+    .chain(
+        others
+            .iter()
+            .zip(spread_positions(others.len(), &available))
+            .map(|(other, position)| RatedPlayer {
+                player_id: other.player_id,
+                position,
+                rating: other.rating,
+                uncertainty: other.uncertainty,
+            }),
+    )
+    .collect();
+
+    rating_system::calculate_rating_changes(&rated_players, settings)
+        .into_iter()
+        .find(|change| change.player_id == player.player_id)
+        .map(|change| change.elo_change)
+        .unwrap_or_default()
+}
+
+/// Predicts the outcome of a single race.
+///
+/// # Arguments
+///
+/// * `players` - The human players in the race
+/// * `settings` - The group's ELO settings
+/// * `points` - Points per position of the match's points table
+/// * `simulations` - Number of races to simulate for expected points
+/// * `rng` - Random number generator for the simulations
+///
+/// # Returns
+///
+/// A prediction per player, in the order given
+pub fn predict_race(
+    players: &[PredictionPlayer],
+    settings: &EloSettings,
+    points: &[i32],
+    simulations: usize,
+    rng: &mut impl Rng,
+) -> Vec<PlayerPrediction> {
+    let ratings: Vec<(Uuid, i32)> = players.iter().map(|p| (p.player_id, p.rating)).collect();
+    let field = prediction_field(&ratings, settings);
+
+    let total_points: HashMap<Uuid, i32> =
+        (0..simulations).fold(HashMap::new(), |mut acc, _| {
+            simulate_race(&field, rng)
+                .into_iter()
+                .for_each(|(player_id, position)| {
+                    *acc.entry(player_id).or_insert(0) +=
+                        scoring::points_for_position(points, position);
+                });
+            acc
+        });
+
+    let median_position = (settings.total_race_size + 1) / 2;
+
+    players
+        .iter()
+        .map(|player| {
+            let others: Vec<&PredictionPlayer> = players
+                .iter()
+                .filter(|other| other.player_id != player.player_id)
+                .collect();
+
+            PlayerPrediction {
+                player_id: player.player_id,
+                expected_position: expected_position(&field, player.player_id),
+                expected_points: total_points.get(&player.player_id).copied().unwrap_or(0)
+                    as f64
+                    / simulations.max(1) as f64,
+                elo_change_if_first: elo_change_at_position(player, &others, 1, settings),
+                elo_change_if_median: elo_change_at_position(
+                    player,
+                    &others,
+                    median_position,
+                    settings,
+                ),
+                elo_change_if_last: elo_change_at_position(
+                    player,
+                    &others,
+                    settings.total_race_size,
+                    settings,
+                ),
+            }
+        })
+        .collect()
+}
+
+/// Simulates the remaining rounds of a match to estimate who wins it.
+///
+/// # Arguments
+///
+/// * `current_points` - Points each team has already scored (every team of the match)
+/// * `remaining_rounds` - The racers of each round still to be raced
+/// * `settings` - The group's ELO settings
+/// * `points` - Points per position of the match's points table
+/// * `simulations` - Number of matches to simulate
+/// * `rng` - Random number generator for the simulations
+///
+/// # Returns
+///
+/// A prediction per team, sorted by team ID
+pub fn predict_match_winner(
+    current_points: &HashMap<Uuid, i32>,
+    remaining_rounds: &[Vec<RemainingRacer>],
+    settings: &EloSettings,
+    points: &[i32],
+    simulations: usize,
+    rng: &mut impl Rng,
+) -> Vec<TeamPrediction> {
+    let rounds: Vec<(Vec<PlayerResult>, HashMap<Uuid, Uuid>)> = remaining_rounds
+        .iter()
+        .map(|racers| {
+            let ratings: Vec<(Uuid, i32)> = racers
+                .iter()
+                .map(|&(player_id, _, rating)| (player_id, rating))
+                .collect();
+            let teams = racers
+                .iter()
+                .map(|&(player_id, team_id, _)| (player_id, team_id))
+                .collect();
+            (prediction_field(&ratings, settings), teams)
+        })
+        .collect();
+
+    let mut total_points: HashMap<Uuid, f64> = HashMap::new();
+    let mut wins: HashMap<Uuid, f64> = HashMap::new();
+
+    for _ in 0..simulations {
+        let final_points: HashMap<Uuid, i32> =
+            rounds
+                .iter()
+                .fold(current_points.clone(), |mut acc, (field, teams)| {
+                    simulate_race(field, rng)
+                        .into_iter()
+                        .for_each(|(player_id, position)| {
+                            if let Some(team_id) = teams.get(&player_id) {
+                                *acc.entry(*team_id).or_insert(0) +=
+                                    scoring::points_for_position(points, position);
+                            }
+                        });
+                    acc
+                });
+
+        let best = final_points.values().copied().max().unwrap_or(0);
+        let winners: Vec<Uuid> = final_points
+            .iter()
+            .filter(|(_, points)| **points == best)
+            .map(|(team_id, _)| *team_id)
+            .collect();
+
+        winners.iter().for_each(|team_id| {
+            *wins.entry(*team_id).or_insert(0.0) += 1.0 / winners.len() as f64;
+        });
+        final_points.iter().for_each(|(team_id, points)| {
+            *total_points.entry(*team_id).or_insert(0.0) += *points as f64;
+        });
+    }
+
+    let runs = simulations.max(1) as f64;
+    let mut predictions: Vec<TeamPrediction> = current_points
+        .iter()
+        .map(|(team_id, points)| TeamPrediction {
+            team_id: *team_id,
+            current_points: *points,
+            expected_points: total_points.get(team_id).copied().unwrap_or(0.0) / runs,
+            win_probability: wins.get(team_id).copied().unwrap_or(0.0) / runs,
+        })
+        .collect();
+    predictions.sort_by_key(|prediction| prediction.team_id);
+    predictions
+}
+
+/// Predicts a round of a match and the match winner.
+///
+/// Uses the players' current all-time ratings, the group's ELO settings and
+/// the match's points table.
+///
+/// # Arguments
+///
+/// * `pool` - Database connection pool
+/// * `match_record` - The match containing the round
+/// * `round_number` - Round number (1-indexed)
+///
+/// # Returns
+///
+/// Result containing the predictions for the round's players and the match's teams
+/// (none for free-for-all matches)
+///
+/// # Errors
+///
+/// Returns an error if:
+/// - The round does not exist or has no players assigned
+/// - Any database operation fails
+pub async fn predict_round(
+    pool: &DbPool,
+    match_record: &models::Match,
+    round_number: i32,
+) -> Result<RoundPrediction> {
+    let match_id = match_record.id;
+    let settings = models::GroupSettings::find_elo_settings(pool, match_record.group_id).await?;
+    let points = models::PointsTable::find_points_by_match_id(pool, match_id).await?;
+
+    let rounds = models::Round::find_by_match_id(pool, match_id).await?;
+    if !rounds.iter().any(|round| round.round_number == round_number) {
+        return Err(AppError::NotFound("Round not found".to_string()));
+    }
+
+    let round_players = sqlx::query_as::<_, (i32, Uuid, Option<Uuid>)>(
+        "SELECT round_number, player_id, team_id
+         FROM round_players
+         WHERE match_id = $1
+         ORDER BY round_number ASC, player_position ASC",
+    )
+    .bind(match_id)
+    .fetch_all(pool)
+    .await?;
+
+    let player_ids: Vec<Uuid> = round_players
+        .iter()
+        .filter(|(round, _, _)| *round == round_number)
+        .map(|(_, player_id, _)| *player_id)
+        .collect();
+    if player_ids.is_empty() {
+        return Err(AppError::InvalidInput(
+            "No players are assigned to this round".to_string(),
+        ));
+    }
+
+    let all_player_ids: Vec<Uuid> = round_players.iter().map(|(_, id, _)| *id).collect();
+    let players: HashMap<Uuid, models::Player> =
+        models::Player::find_by_ids(pool, &all_player_ids)
+            .await?
+            .into_iter()
+            .map(|player| (player.id, player))
+            .collect();
+
+    let recorded_positions = sqlx::query_as::<_, (Uuid, i32)>(
+        "SELECT rp.team_id,
+                CASE prs.status WHEN 'finished' THEN prs.position WHEN 'dnf' THEN $2 ELSE 0 END
+         FROM player_race_scores prs
+         JOIN round_players rp ON rp.match_id = prs.match_id
+             AND rp.round_number = prs.round_number
+             AND rp.player_id = prs.player_id
+         WHERE prs.match_id = $1 AND rp.team_id IS NOT NULL",
+    )
+    .bind(match_id)
+    .bind(points.len() as i32)
+    .fetch_all(pool)
+    .await?;
+
+    let teams = models::Team::find_by_match_id(pool, match_id).await?;
+    let current_points: HashMap<Uuid, i32> = recorded_positions.into_iter().fold(
+        teams.iter().map(|team| (team.id, 0)).collect(),
+        |mut acc, (team_id, position)| {
+            *acc.entry(team_id).or_insert(0) += scoring::points_for_position(&points, position);
+            acc
+        },
+    );
+
+    let race_players: Vec<PredictionPlayer> = player_ids
+        .iter()
+        .filter_map(|id| players.get(id))
+        .map(|player| PredictionPlayer {
+            player_id: player.id,
+            rating: player.elo_rating,
+            uncertainty: Uncertainty {
+                deviation: player.rating_deviation,
+                volatility: player.rating_volatility,
+            },
+        })
+        .collect();
+
+    let remaining_rounds: Vec<Vec<RemainingRacer>> = rounds
+        .iter()
+        .filter(|round| !round.completed)
+        .map(|round| {
+            round_players
+                .iter()
+                .filter(|(number, _, _)| *number == round.round_number)
+                .filter_map(|&(_, player_id, team_id)| {
+                    let player = players.get(&player_id)?;
+                    Some((player_id, team_id?, player.elo_rating))
+                })
+                .collect()
+        })
+        .collect();
+
+    let mut rng = rand::rng();
+    let player_predictions = predict_race(
+        &race_players,
+        &settings,
+        &points,
+        DEFAULT_SIMULATIONS,
+        &mut rng,
+    );
+    let mut team_predictions = predict_match_winner(
+        &current_points,
+        &remaining_rounds,
+        &settings,
+        &points,
+        DEFAULT_SIMULATIONS,
+        &mut rng,
+    );
+    let team_numbers: HashMap<Uuid, i32> =
+        teams.iter().map(|team| (team.id, team.team_num)).collect();
+    team_predictions.sort_by_key(|prediction| team_numbers.get(&prediction.team_id).copied());
+
+    Ok(RoundPrediction {
+        match_id,
+        round_number,
+        players: player_predictions,
+        teams: team_predictions,
+    })
+}
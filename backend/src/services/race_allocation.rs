@@ -4,7 +4,7 @@
 //! This module provides algorithms for distributing players fairly across multiple races.
 //! The algorithm ensures:
 //! - Each player races an appropriate number of times based on team size
-//! - Each race has exactly one player from each team
+//! - Each race has the same number of players from each team, give or take one
 //! - Players are matched by similar ELO ratings to create balanced races
 //! - Position indices are distributed evenly to prevent exhaustion
 //!
@@ -24,10 +24,17 @@
 //!
 //! This approach prevents position index exhaustion while maintaining good ELO balance,
 //! especially in the final races of a match.
+//!
+//! ## Several Seats per Team
+//!
+//! When the number of teams differs from the players per race (e.g. 2v2v2v2
+//! with eight players per race, or 3v3 with four), teams share each race's
+//! seats evenly, remainder seats rotate between teams from race to race, and
+//! each team fills its seats by rotating through its ELO-sorted players.
 
 use crate::error::{AppError, Result};
 use crate::models;
-use crate::services::team_allocation::Team;
+use crate::services::team_allocation::{PairConstraints, Team};
 use std::collections::HashMap;
 use uuid::Uuid;
 
@@ -49,17 +56,23 @@ struct PlayerWithElo {
 /// Allocates players to races using ELO-based matching with positional rotation.
 ///
 /// This function distributes players across multiple races ensuring:
-/// - Each race has one player from each team
+/// - Every team fills the same number of seats in each race, give or take
+///   one seat that rotates between teams from race to race
 /// - Players from smaller teams race more frequently (to balance total races)
 /// - Players are matched by closest ELO rating across teams
-/// - Primary team players are selected in rotating positions (1→2→3→1→2→3...)
+/// - Players within a team are selected in rotating positions (1→2→3→1→2→3...)
+///
+/// When a race has exactly one seat per team, positions are scheduled against
+/// the largest team as described in the module documentation. Otherwise each
+/// team hands out its seats by rotating through its ELO-sorted players, so
+/// several teammates can share a race.
 ///
 /// # Arguments
 ///
 /// * `players` - Slice of all players participating in the match
 /// * `teams` - Slice of teams with their assigned players
 /// * `num_races` - Total number of races in the match
-/// * `_players_per_race` - Maximum players per race (currently unused, kept for compatibility)
+/// * `players_per_race` - Number of players in each race
 /// * `elo_ratings` - Map of player IDs to ELO ratings (typically tournament ELO)
 ///
 /// # Returns
@@ -98,11 +111,25 @@ pub fn allocate_races(
     _players: &[models::Player],
     teams: &[Team],
     num_races: i32,
-    _players_per_race: i32,
+    players_per_race: i32,
     elo_ratings: &HashMap<Uuid, i32>,
 ) -> Result<Vec<RaceAllocation>> {
-    // Build team state with sorted players
-    let team_state: Vec<(i32, Vec<PlayerWithElo>)> = teams
+    let team_state = sorted_team_state(teams, elo_ratings);
+
+    if team_state.iter().any(|(_, players)| players.is_empty()) {
+        return Err(AppError::InvalidInput("Teams cannot be empty".to_string()));
+    }
+
+    if players_per_race as usize == team_state.len() {
+        allocate_one_per_team(&team_state, num_races)
+    } else {
+        allocate_by_rotation(&team_state, num_races, players_per_race)
+    }
+}
+
+/// Players of each team, sorted by ELO (highest first)
+fn sorted_team_state(teams: &[Team], elo_ratings: &HashMap<Uuid, i32>) -> Vec<(i32, Vec<PlayerWithElo>)> {
+    teams
         .iter()
         .map(|team| {
             let mut sorted_players: Vec<PlayerWithElo> = team
@@ -118,8 +145,14 @@ pub fn allocate_races(
 
             (team.team_num, sorted_players)
         })
-        .collect();
+        .collect()
+}
 
+/// Schedules one player from each team into every race.
+fn allocate_one_per_team(
+    team_state: &[(i32, Vec<PlayerWithElo>)],
+    num_races: i32,
+) -> Result<Vec<RaceAllocation>> {
     let primary_team_index = team_state
         .iter()
         .enumerate()
@@ -232,3 +265,92 @@ pub fn allocate_races(
 
     Ok(allocations)
 }
+
+/// Schedules teams into races whose seats do not match the number of teams,
+/// so a team can fill several seats of one race or sit a race out.
+///
+/// Every race gives each team `players_per_race / teams` seats and hands the
+/// remaining seats to the next teams in turn, so over a match the teams fill
+/// the same number of seats give or take one. A team never fills more seats
+/// than it has players; its spare seats go to the next team with players to
+/// spare. Within a team, seats go to players in rotating ELO order.
+fn allocate_by_rotation(
+    team_state: &[(i32, Vec<PlayerWithElo>)],
+    num_races: i32,
+    players_per_race: i32,
+) -> Result<Vec<RaceAllocation>> {
+    if team_state.is_empty() {
+        return Err(AppError::Internal("No teams available".to_string()));
+    }
+
+    let num_teams = team_state.len();
+    let seats = players_per_race.max(0) as usize;
+    let mut next_extra_team = 0;
+    let mut cursors = vec![0usize; num_teams];
+    let mut allocations = Vec::with_capacity(num_races.max(0) as usize);
+
+    for race_num in 0..num_races {
+        let mut team_seats: Vec<usize> = team_state
+            .iter()
+            .map(|(_, team_players)| (seats / num_teams).min(team_players.len()))
+            .collect();
+        let mut remaining = seats - team_seats.iter().sum::<usize>();
+
+        // Hand out the remaining seats one per team, continuing where the
+        // previous race stopped
+        let mut team_idx = next_extra_team;
+        let mut teams_full = 0;
+        while remaining > 0 && teams_full < num_teams {
+            if team_seats[team_idx] < team_state[team_idx].1.len() {
+                team_seats[team_idx] += 1;
+                remaining -= 1;
+                teams_full = 0;
+                next_extra_team = (team_idx + 1) % num_teams;
+            } else {
+                teams_full += 1;
+            }
+            team_idx = (team_idx + 1) % num_teams;
+        }
+
+        let mut race_players = Vec::with_capacity(seats);
+        for (team_idx, (_, team_players)) in team_state.iter().enumerate() {
+            for _ in 0..team_seats[team_idx] {
+                race_players.push(team_players[cursors[team_idx]].id);
+                cursors[team_idx] = (cursors[team_idx] + 1) % team_players.len();
+            }
+        }
+
+        allocations.push(RaceAllocation {
+            race_number: race_num + 1,
+            player_ids: race_players,
+        });
+    }
+
+    Ok(allocations)
+}
+
+/// Checks teams against pair constraints, then allocates their races.
+///
+/// Pair constraints only decide who shares a team; races are allocated
+/// without looking at them. Keep-apart players are on different teams, so
+/// they can meet in a race as opponents in any mode. When each race takes one
+/// player from each team, keep-together players never race at the same time
+/// (they can share a console), but when teams fill several seats of a race
+/// they can.
+///
+/// # Errors
+///
+/// Returns `AppError::InvalidInput` naming the first constraint the teams
+/// break, or any error from `allocate_races`
+pub fn check_teams_and_allocate_races(
+    players: &[models::Player],
+    teams: &[Team],
+    num_races: i32,
+    players_per_race: i32,
+    elo_ratings: &HashMap<Uuid, i32>,
+    constraints: &PairConstraints,
+) -> Result<Vec<RaceAllocation>> {
+    constraints.check_teams(teams)?;
+
+    allocate_races(players, teams, num_races, players_per_race, elo_ratings)
+}
@@ -0,0 +1,275 @@
+//! Inactivity Rating Decay Service
+//!
+//! Players who have not raced for `inactivity_weeks` count as inactive. Groups
+//! with a non-zero `inactivity_decay_per_week` also lose all-time ELO for every
+//! further inactive week, down to the starting rating; tournament ratings are
+//! never decayed.
+//!
+//! The decay job is idempotent: each decay event in `player_rating_decays`
+//! records how many inactive weeks it covered, and weeks already covered since
+//! the player's last race are not decayed again. Running the job daily or
+//! weekly therefore gives the same result. Decay events are replayed by
+//! `elo_replay` as fixed rating changes, so amending or recomputing races keeps
+//! them.
+
+use crate::db::DbPool;
+use crate::error::{AppError, Result};
+use crate::models;
+use crate::services::elo::EloSettings;
+use crate::services::elo_replay::STARTING_ELO;
+use chrono::{DateTime, Utc};
+use sqlx::{FromRow, Postgres, Transaction};
+use uuid::Uuid;
+
+/// A rating reduction due for one player.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RatingDecay {
+    pub weeks: i32,
+    pub elo_change: i32,
+    pub elo_after: i32,
+}
+
+/// Calculates the decay due for a player, if any.
+///
+/// # Arguments
+///
+/// * `elo_rating` - The player's current all-time rating
+/// * `weeks_inactive` - Whole weeks since the player's last race
+/// * `weeks_decayed` - Inactive weeks already covered by earlier decay events
+/// * `settings` - The group's ELO settings
+///
+/// # Returns
+///
+/// The decay to apply, or `None` if decay is disabled, the player is not yet
+/// inactive, every inactive week has already been decayed, or the rating is
+/// already at or below the starting rating
+pub fn calculate_decay(
+    elo_rating: i32,
+    weeks_inactive: i32,
+    weeks_decayed: i32,
+    settings: &EloSettings,
+) -> Option<RatingDecay> {
+    if settings.inactivity_decay_per_week <= 0 || weeks_inactive < settings.inactivity_weeks {
+        return None;
+    }
+
+    let weeks = weeks_inactive - settings.inactivity_weeks + 1 - weeks_decayed;
+    if weeks <= 0 {
+        return None;
+    }
+
+    let elo_change = (-weeks * settings.inactivity_decay_per_week).max(STARTING_ELO - elo_rating);
+    if elo_change >= 0 {
+        return None;
+    }
+
+    Some(RatingDecay {
+        weeks,
+        elo_change,
+        elo_after: elo_rating + elo_change,
+    })
+}
+
+/// A decay applied (or, in a dry run, due) for one player.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AppliedDecay {
+    pub player_id: Uuid,
+    pub player_name: String,
+    pub weeks: i32,
+    pub before: i32,
+    pub after: i32,
+}
+
+/// Summary of a decay run for one group.
+#[derive(Debug, Clone)]
+pub struct DecayReport {
+    pub group_id: Uuid,
+    pub inactive_players: usize,
+    pub decays: Vec<AppliedDecay>,
+}
+
+/// A player who has raced in the group, with their inactivity so far.
+#[derive(Debug, Clone, FromRow)]
+struct RacedPlayer {
+    player_id: Uuid,
+    player_name: String,
+    elo_rating: i32,
+    last_raced_at: DateTime<Utc>,
+    weeks_decayed: i32,
+}
+
+/// Applies inactivity decay to every player of a group.
+///
+/// With `dry_run` the transaction is rolled back, so the report describes the
+/// decay that is due without writing anything.
+///
+/// # Arguments
+///
+/// * `pool` - Database connection pool
+/// * `group_id` - UUID of the group
+/// * `now` - Time the decay is applied at (recorded on each decay event)
+/// * `dry_run` - Roll back instead of committing
+///
+/// # Returns
+///
+/// Result containing a report of the inactive players and the decays applied
+///
+/// # Errors
+///
+/// Returns an error if any database operation fails (transaction will be rolled back)
+pub async fn apply_inactivity_decay(
+    pool: &DbPool,
+    group_id: Uuid,
+    now: DateTime<Utc>,
+    dry_run: bool,
+) -> Result<DecayReport> {
+    let mut tx = pool
+        .begin()
+        .await
+        .map_err(|e| AppError::Internal(format!("Failed to start database transaction: {e}")))?;
+
+    let report = apply_inactivity_decay_in_transaction(&mut tx, group_id, now).await?;
+
+    if dry_run {
+        tx.rollback()
+            .await
+            .map_err(|e| AppError::Internal(format!("Failed to roll back transaction: {e}")))?;
+    } else {
+        tx.commit()
+            .await
+            .map_err(|e| AppError::Internal(format!("Failed to commit transaction: {e}")))?;
+    }
+
+    tracing::info!(
+        %group_id,
+        dry_run,
+        inactive_players = report.inactive_players,
+        decays = report.decays.len(),
+        "Applied inactivity decay"
+    );
+
+    Ok(report)
+}
+
+async fn apply_inactivity_decay_in_transaction(
+    tx: &mut Transaction<'_, Postgres>,
+    group_id: Uuid,
+    now: DateTime<Utc>,
+) -> Result<DecayReport> {
+    let settings = models::GroupSettings::find_elo_settings(&mut **tx, group_id).await?;
+
+    let players = sqlx::query_as::<_, RacedPlayer>(
+        "SELECT p.id AS player_id, p.name AS player_name, p.elo_rating, last.last_raced_at,
+                COALESCE((
+                    SELECT SUM(d.weeks)
+                    FROM player_rating_decays d
+                    WHERE d.player_id = p.id AND d.created_at > last.last_raced_at
+                ), 0)::int AS weeks_decayed
+         FROM players p
+         JOIN (
+             SELECT player_id, MAX(created_at) AS last_raced_at
+             FROM player_race_scores
+             WHERE group_id = $1
+             GROUP BY player_id
+         ) last ON last.player_id = p.id
+         WHERE p.group_id = $1
+         FOR UPDATE OF p",
+    )
+    .bind(group_id)
+    .fetch_all(&mut **tx)
+    .await?;
+
+    let inactive: Vec<(&RacedPlayer, i32)> = players
+        .iter()
+        .map(|player| (player, (now - player.last_raced_at).num_weeks() as i32))
+        .filter(|(_, weeks_inactive)| *weeks_inactive >= settings.inactivity_weeks)
+        .collect();
+
+    let decays: Vec<AppliedDecay> = inactive
+        .iter()
+        .filter_map(|(player, weeks_inactive)| {
+            let decay = calculate_decay(
+                player.elo_rating,
+                *weeks_inactive,
+                player.weeks_decayed,
+                &settings,
+            )?;
+            Some(AppliedDecay {
+                player_id: player.player_id,
+                player_name: player.player_name.clone(),
+                weeks: decay.weeks,
+                before: player.elo_rating,
+                after: decay.elo_after,
+            })
+        })
+        .collect();
+
+    let decay_rows: Vec<(Uuid, i32, i32, i32)> = decays
+        .iter()
+        .map(|decay| {
+            (
+                decay.player_id,
+                decay.weeks,
+                decay.after - decay.before,
+                decay.after,
+            )
+        })
+        .collect();
+    models::PlayerRatingDecay::insert_batch(tx, group_id, now, &decay_rows).await?;
+
+    let (player_ids, elos): (Vec<Uuid>, Vec<i32>) = decays
+        .iter()
+        .map(|decay| (decay.player_id, decay.after))
+        .unzip();
+
+    sqlx::query(
+        "UPDATE players p
+         SET elo_rating = u.elo_rating
+         FROM UNNEST($1::uuid[], $2::int[]) AS u(id, elo_rating)
+         WHERE p.id = u.id",
+    )
+    .bind(&player_ids)
+    .bind(&elos)
+    .execute(&mut **tx)
+    .await
+    .map_err(|e| AppError::Internal(format!("Failed to update player ELO rating: {e}")))?;
+
+    Ok(DecayReport {
+        group_id,
+        inactive_players: inactive.len(),
+        decays,
+    })
+}
+
+/// Applies inactivity decay to every group, one transaction per group.
+///
+/// # Arguments
+///
+/// * `pool` - Database connection pool
+/// * `now` - Time the decay is applied at
+/// * `dry_run` - Roll back instead of committing
+///
+/// # Returns
+///
+/// Result containing a report per group
+///
+/// # Errors
+///
+/// Returns an error if any group fails; groups already processed keep their
+/// committed changes
+pub async fn apply_inactivity_decay_all_groups(
+    pool: &DbPool,
+    now: DateTime<Utc>,
+    dry_run: bool,
+) -> Result<Vec<DecayReport>> {
+    let group_ids: Vec<Uuid> = sqlx::query_scalar("SELECT id FROM groups ORDER BY name")
+        .fetch_all(pool)
+        .await?;
+
+    let mut reports = Vec::with_capacity(group_ids.len());
+    for group_id in group_ids {
+        reports.push(apply_inactivity_decay(pool, group_id, now, dry_run).await?);
+    }
+
+    Ok(reports)
+}
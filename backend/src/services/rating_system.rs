@@ -0,0 +1,185 @@
+//! Rating Systems
+//!
+//! This module defines the interface shared by the rating systems a group can
+//! choose from. Every system takes the human players of one race with their
+//! positions, current ratings and rating uncertainty, and returns their new
+//! ratings. The chosen system drives both the all-time and the tournament
+//! leaderboard.
+//!
+//! ## Implementations
+//!
+//! - **Elo**: The multi-player ELO system (`elo::calculate_elo_changes`). It has
+//!   no notion of uncertainty, so deviation and volatility pass through unchanged.
+//! - **Glicko2**: Glicko-2 with pairwise decomposition (`glicko2::calculate_glicko2_changes`).
+//!   New and inactive players move faster than established ones.
+
+use crate::services::elo::{self, EloSettings, PlayerResult};
+use crate::services::glicko2::{self, MAX_RATING_DEVIATION};
+use serde::{Deserialize, Serialize};
+use sqlx::Type;
+use std::collections::HashSet;
+use uuid::Uuid;
+
+/// Volatility every player starts with (Glickman's recommended default)
+pub const DEFAULT_VOLATILITY: f64 = 0.06;
+
+/// Which rating system a group uses.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Type, Serialize, Deserialize)]
+#[sqlx(type_name = "rating_system_kind", rename_all = "snake_case")]
+pub enum RatingSystemKind {
+    #[default]
+    Elo,
+    Glicko2,
+}
+
+/// How certain a rating is.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Uncertainty {
+    /// Rating deviation, on the same scale as the rating
+    pub deviation: f64,
+    /// Expected fluctuation of the rating
+    pub volatility: f64,
+}
+
+impl Default for Uncertainty {
+    fn default() -> Self {
+        Self {
+            deviation: MAX_RATING_DEVIATION,
+            volatility: DEFAULT_VOLATILITY,
+        }
+    }
+}
+
+/// A human player's result in a single race, with their current rating.
+#[derive(Debug, Clone)]
+pub struct RatedPlayer {
+    pub player_id: Uuid,
+    pub position: i32,
+    pub rating: i32,
+    pub uncertainty: Uncertainty,
+}
+
+/// The rating change for a player after a race.
+#[derive(Debug, Clone)]
+pub struct RatingChange {
+    pub player_id: Uuid,
+    pub elo_change: i32,
+    pub new_elo: i32,
+    pub new_uncertainty: Uncertainty,
+}
+
+/// A rating system that can rate a single race.
+pub trait RatingSystem {
+    /// Calculates the rating change of every player in a race.
+    fn calculate_changes(&self, players: &[RatedPlayer], settings: &EloSettings)
+    -> Vec<RatingChange>;
+}
+
+/// The multi-player ELO system.
+pub struct Elo;
+
+impl RatingSystem for Elo {
+    fn calculate_changes(
+        &self,
+        players: &[RatedPlayer],
+        settings: &EloSettings,
+    ) -> Vec<RatingChange> {
+        let results: Vec<PlayerResult> = players
+            .iter()
+            .map(|player| PlayerResult {
+                player_id: player.player_id,
+                position: player.position,
+                current_elo: player.rating,
+            })
+            .collect();
+
+        elo::calculate_elo_changes(&results, settings)
+            .into_iter()
+            .zip(players)
+            .map(|(change, player)| RatingChange {
+                player_id: change.player_id,
+                elo_change: change.elo_change,
+                new_elo: change.new_elo,
+                new_uncertainty: player.uncertainty,
+            })
+            .collect()
+    }
+}
+
+/// The Glicko-2 system.
+pub struct Glicko2;
+
+impl RatingSystem for Glicko2 {
+    fn calculate_changes(
+        &self,
+        players: &[RatedPlayer],
+        settings: &EloSettings,
+    ) -> Vec<RatingChange> {
+        glicko2::calculate_glicko2_changes(players, settings)
+    }
+}
+
+impl RatingSystemKind {
+    /// The implementation of this rating system.
+    pub fn system(self) -> &'static dyn RatingSystem {
+        match self {
+            Self::Elo => &Elo,
+            Self::Glicko2 => &Glicko2,
+        }
+    }
+}
+
+/// Calculates rating changes for a race with the system selected in `settings`.
+///
+/// # Arguments
+///
+/// * `players` - Human players with their positions, ratings and uncertainty
+/// * `settings` - The group's settings, including its rating system
+///
+/// # Returns
+///
+/// Vector of RatingChange structs, one per player
+pub fn calculate_rating_changes(
+    players: &[RatedPlayer],
+    settings: &EloSettings,
+) -> Vec<RatingChange> {
+    settings
+        .rating_system
+        .system()
+        .calculate_changes(players, settings)
+}
+
+/// Calculates rating changes for a race in which some players are not rated.
+///
+/// Unrated players are left out of the calculation, as if they had not been in
+/// the race, and keep their rating and uncertainty.
+///
+/// # Arguments
+///
+/// * `players` - Human players with their positions, ratings and uncertainty
+/// * `unrated` - IDs of players whose rating the race must not change
+/// * `settings` - The group's settings, including its rating system
+///
+/// # Returns
+///
+/// Vector of RatingChange structs, one per player (unrated players last)
+pub fn calculate_rating_changes_excluding(
+    players: &[RatedPlayer],
+    unrated: &HashSet<Uuid>,
+    settings: &EloSettings,
+) -> Vec<RatingChange> {
+    let (unrated_players, rated_players): (Vec<RatedPlayer>, Vec<RatedPlayer>) = players
+        .iter()
+        .cloned()
+        .partition(|player| unrated.contains(&player.player_id));
+
+    calculate_rating_changes(&rated_players, settings)
+        .into_iter()
+        .chain(unrated_players.into_iter().map(|player| RatingChange {
+            player_id: player.player_id,
+            elo_change: 0,
+            new_elo: player.rating,
+            new_uncertainty: player.uncertainty,
+        }))
+        .collect()
+}
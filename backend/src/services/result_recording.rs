@@ -15,33 +15,51 @@
 //! 1. Validate result inputs (positions, duplicates)
 //! 2. Fetch players and round participants from database
 //! 3. Validate that submitted players match round participants
-//! 4. Calculate ELO changes using the ELO service
+//! 4. Calculate rating changes with the group's rating system (ELO or Glicko-2)
 //! 5. Persist results in a transaction:
 //!    - Insert player race scores
-//!    - Update player ELO ratings
+//!    - Update player ratings and rating uncertainty
+//!    - Update the players' ratings on the round's track
 //!    - Update/insert player match aggregates
 //!    - Mark round as completed
 //!    - If all rounds complete: calculate and store team scores, mark match complete
+//!
+//! Players who did not finish a race are passed separately from the finishers'
+//! positions, with their `ResultStatus`. They are placed at the back of the
+//! field (the group's race size): DNF and disqualified players are rated as
+//! last place, disconnected players are left out of every rating calculation,
+//! and only finished and DNF players score points.
+//!
+//! Already recorded rounds can be corrected with `amend_race_results`, which
+//! replays ELO for the round and every later race in the group, or the latest
+//! round of a match can be removed with `undo_last_round`.
+//!
+//! `simulate_race_results` runs the same pipeline in a transaction that is
+//! always rolled back, to preview a round's outcome before recording it.
 
 use crate::db::DbPool;
 use crate::error::{AppError, Result};
 use crate::models;
 use crate::services::elo::{self, PlayerResult};
+use crate::services::elo_replay;
+use crate::services::rating_system::{self, RatedPlayer, RatingChange, Uncertainty};
 use crate::services::score_calculation;
 use crate::services::teammate_elo;
-use std::collections::HashMap;
+use crate::services::track_rating;
+use std::collections::{HashMap, HashSet};
 use uuid::Uuid;
 
 /// Validates race result inputs.
 ///
 /// Ensures that:
 /// - At least one result is provided
-/// - All positions are between 1 and 24
+/// - All positions fall within the group's race size
 /// - No duplicate positions exist
 ///
 /// # Arguments
 ///
 /// * `results` - Slice of player result inputs with position data
+/// * `settings` - The group's ELO settings
 ///
 /// # Returns
 ///
@@ -50,7 +68,7 @@ use uuid::Uuid;
 /// # Errors
 ///
 /// Returns an error if any validation rule fails
-pub fn validate_results<T>(results: &[T]) -> Result<()>
+pub fn validate_results<T>(results: &[T], settings: &elo::EloSettings) -> Result<()>
 where
     T: AsRef<PlayerResultData>,
 {
@@ -62,11 +80,7 @@ where
 
     let positions: Vec<i32> = results.iter().map(|r| r.as_ref().position).collect();
 
-    if positions.iter().any(|&p| !(1..=24).contains(&p)) {
-        return Err(AppError::InvalidInput(
-            "Positions must be between 1 and 24".to_string(),
-        ));
-    }
+    validate_positions(positions.iter().copied(), settings)?;
 
     let unique_positions: std::collections::HashSet<i32> = positions.iter().copied().collect();
     if unique_positions.len() != positions.len() {
@@ -78,6 +92,28 @@ where
     Ok(())
 }
 
+/// Ensures every position falls within the race size in `settings`.
+///
+/// # Errors
+///
+/// Returns an error naming the allowed range if any position falls outside it
+pub fn validate_positions(
+    positions: impl IntoIterator<Item = i32>,
+    settings: &elo::EloSettings,
+) -> Result<()> {
+    if positions
+        .into_iter()
+        .any(|p| !(1..=settings.total_race_size).contains(&p))
+    {
+        return Err(AppError::InvalidInput(format!(
+            "Positions must be between 1 and {}",
+            settings.total_race_size
+        )));
+    }
+
+    Ok(())
+}
+
 /// Trait for accessing player result data (to support multiple input types)
 pub trait AsRef<T> {
     fn as_ref(&self) -> &T;
@@ -162,6 +198,30 @@ pub fn create_player_elo_map(players: &[models::Player]) -> HashMap<Uuid, i32> {
     players.iter().map(|p| (p.id, p.elo_rating)).collect()
 }
 
+/// Creates a mapping of player IDs to the uncertainty of their all-time ratings.
+///
+/// # Arguments
+///
+/// * `players` - Slice of player models
+///
+/// # Returns
+///
+/// HashMap mapping player UUIDs to rating uncertainty
+pub fn create_player_uncertainty_map(players: &[models::Player]) -> HashMap<Uuid, Uncertainty> {
+    players
+        .iter()
+        .map(|p| {
+            (
+                p.id,
+                Uncertainty {
+                    deviation: p.rating_deviation,
+                    volatility: p.rating_volatility,
+                },
+            )
+        })
+        .collect()
+}
+
 /// Converts GraphQL input results to ELO PlayerResult structs.
 ///
 /// # Arguments
@@ -198,22 +258,103 @@ pub fn create_player_results(
         .collect()
 }
 
+/// Converts results to RatedPlayer structs for the group's rating system.
+///
+/// Players without a stored uncertainty get the starting uncertainty.
+///
+/// # Arguments
+///
+/// * `results` - Slice of result inputs with player IDs and positions
+/// * `player_elos` - HashMap of current ratings by player ID
+/// * `uncertainties` - HashMap of current rating uncertainty by player ID
+///
+/// # Returns
+///
+/// Result containing a vector of RatedPlayer structs
+///
+/// # Errors
+///
+/// Returns an error if a player is not found in the rating map
+pub fn create_rated_players(
+    results: &[(Uuid, i32)],
+    player_elos: &HashMap<Uuid, i32>,
+    uncertainties: &HashMap<Uuid, Uncertainty>,
+) -> Result<Vec<RatedPlayer>> {
+    Ok(create_player_results(results, player_elos)?
+        .into_iter()
+        .map(|result| RatedPlayer {
+            player_id: result.player_id,
+            position: result.position,
+            rating: result.current_elo,
+            uncertainty: uncertainties
+                .get(&result.player_id)
+                .copied()
+                .unwrap_or_default(),
+        })
+        .collect())
+}
+
+/// Adds players who did not finish a race to its results.
+///
+/// Non-finishers are placed at the back of the field (the race size in
+/// `settings`), tied with each other.
+///
+/// # Arguments
+///
+/// * `results` - Slice of (player_id, position) tuples for players who finished
+/// * `non_finishers` - Slice of (player_id, status) tuples for players who did not
+/// * `settings` - The group's ELO settings
+///
+/// # Returns
+///
+/// Tuple of every player's (player_id, position) and each player's result status
+pub fn place_non_finishers(
+    results: &[(Uuid, i32)],
+    non_finishers: &[(Uuid, models::ResultStatus)],
+    settings: &elo::EloSettings,
+) -> (Vec<(Uuid, i32)>, HashMap<Uuid, models::ResultStatus>) {
+    let placed = results
+        .iter()
+        .copied()
+        .chain(
+            non_finishers
+                .iter()
+                .map(|(player_id, _)| (*player_id, settings.total_race_size)),
+        )
+        .collect();
+
+    let statuses = results
+        .iter()
+        .map(|(player_id, _)| (*player_id, models::ResultStatus::Finished))
+        .chain(non_finishers.iter().copied())
+        .collect();
+
+    (placed, statuses)
+}
+
+/// IDs of players whose race does not change their ratings.
+fn unrated_players(statuses: &HashMap<Uuid, models::ResultStatus>) -> HashSet<Uuid> {
+    statuses
+        .iter()
+        .filter(|(_, status)| !status.is_rated())
+        .map(|(player_id, _)| *player_id)
+        .collect()
+}
+
 /// High-level orchestration function for recording race results with dual ELO tracking.
 ///
 /// This is the main entry point that:
-/// 1. Fetches players from database (for all-time ELO)
+/// 1. Fetches players from database (for all-time ELO) and the group's ELO settings
 /// 2. Gets or creates tournament ELO records (lazy initialization at 1200)
-/// 3. Calculates all-time ELO changes using current all-time ELO
-/// 4. Calculates tournament ELO changes using current tournament ELO (independent)
+/// 3. Calculates all-time rating changes using current all-time ratings
+/// 4. Calculates tournament rating changes using current tournament ratings (independent)
 /// 5. Calls record_results_in_transaction to persist everything
 ///
 /// # Arguments
 ///
 /// * `pool` - Database connection pool
 /// * `group_id` - UUID of the group
-/// * `match_id` - UUID of the match
-/// * `round_number` - Round number (1-indexed)
-/// * `results` - Slice of tuples containing (player_id, position)
+/// * `round` - The round's results as entered
 /// * `match_record` - Current match record
 ///
 /// # Returns
@@ -226,26 +367,27 @@ pub fn create_player_results(
 pub async fn record_race_results(
     pool: &DbPool,
     group_id: Uuid,
-    match_id: Uuid,
-    round_number: i32,
-    results: &[(Uuid, i32)],
+    round: &RoundResults<'_>,
     match_record: &models::Match,
     notification_manager: &crate::services::notification_manager::NotificationManager,
 ) -> Result<models::Match> {
-    let player_ids: Vec<Uuid> = results.iter().map(|(id, _)| *id).collect();
-
-    let players = models::Player::find_by_ids(pool, &player_ids).await?;
-    let all_time_player_elos = create_player_elo_map(&players);
+    let elo_settings = models::GroupSettings::find_elo_settings(pool, group_id).await?;
+    validate_positions(round.results.iter().map(|(_, p)| *p), &elo_settings)?;
+    let (results, statuses) =
+        place_non_finishers(round.results, round.non_finishers, &elo_settings);
 
     let mut tx = pool.begin().await.map_err(|e| {
         AppError::Internal(format!("Failed to start transaction for ELO fetch: {e}"))
     })?;
 
-    let tournament_player_elos = models::PlayerTournamentScore::get_or_create_batch(
+    let (all_time_elo_changes, tournament_elo_changes) = calculate_round_rating_changes(
+        pool,
         &mut tx,
-        &player_ids,
-        match_record.tournament_id,
         group_id,
+        &results,
+        &statuses,
+        &elo_settings,
+        match_record,
     )
     .await?;
 
@@ -253,31 +395,112 @@ pub async fn record_race_results(
         AppError::Internal(format!("Failed to commit tournament ELO fetch transaction: {e}"))
     })?;
 
-    let all_time_player_results = create_player_results(results, &all_time_player_elos)?;
-    let all_time_elo_changes = elo::calculate_elo_changes(&all_time_player_results);
+    let outcome = RaceOutcome {
+        round_number: round.round_number,
+        results: &results,
+        statuses: &statuses,
+        builds: round.builds,
+        all_time_elo_changes: &all_time_elo_changes,
+        tournament_elo_changes: &tournament_elo_changes,
+        elo_settings: &elo_settings,
+    };
+    record_results_in_transaction(pool, group_id, &outcome, match_record, notification_manager)
+        .await
+}
 
-    let tournament_player_results = create_player_results(results, &tournament_player_elos)?;
-    let tournament_elo_changes = elo::calculate_elo_changes(&tournament_player_results);
+/// Calculates the all-time and tournament rating changes of a race.
+///
+/// Reads current all-time ratings from the pool, and gets or creates the
+/// tournament ratings in `tx`. Players whose status is not rated keep their
+/// ratings.
+async fn calculate_round_rating_changes(
+    pool: &DbPool,
+    tx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+    group_id: Uuid,
+    results: &[(Uuid, i32)],
+    statuses: &HashMap<Uuid, models::ResultStatus>,
+    elo_settings: &elo::EloSettings,
+    match_record: &models::Match,
+) -> Result<(Vec<RatingChange>, Vec<RatingChange>)> {
+    let player_ids: Vec<Uuid> = results.iter().map(|(id, _)| *id).collect();
+    let unrated = unrated_players(statuses);
 
-    record_results_in_transaction(
-        pool,
+    let players = models::Player::find_by_ids(pool, &player_ids).await?;
+    let all_time_player_elos = create_player_elo_map(&players);
+    let all_time_uncertainties = create_player_uncertainty_map(&players);
+
+    let tournament_player_elos = models::PlayerTournamentScore::get_or_create_batch(
+        tx,
+        &player_ids,
+        match_record.tournament_id,
         group_id,
-        match_id,
-        round_number,
-        results,
-        &all_time_elo_changes,
-        &tournament_elo_changes,
-        match_record,
-        notification_manager,
     )
-    .await
+    .await?;
+
+    let tournament_uncertainties = models::PlayerTournamentScore::get_uncertainty_batch(
+        tx,
+        &player_ids,
+        match_record.tournament_id,
+    )
+    .await?;
+
+    let all_time_players =
+        create_rated_players(results, &all_time_player_elos, &all_time_uncertainties)?;
+    let all_time_elo_changes = rating_system::calculate_rating_changes_excluding(
+        &all_time_players,
+        &unrated,
+        elo_settings,
+    );
+
+    let tournament_players =
+        create_rated_players(results, &tournament_player_elos, &tournament_uncertainties)?;
+    let tournament_elo_changes = rating_system::calculate_rating_changes_excluding(
+        &tournament_players,
+        &unrated,
+        elo_settings,
+    );
+
+    Ok((all_time_elo_changes, tournament_elo_changes))
+}
+
+/// A round's results as entered, before non-finishers are placed and ratings
+/// calculated.
+#[derive(Debug, Clone, Copy)]
+pub struct RoundResults<'a> {
+    /// Round number (1-indexed)
+    pub round_number: i32,
+    /// (player_id, position) pairs for players who finished
+    pub results: &'a [(Uuid, i32)],
+    /// (player_id, status) pairs for players who did not
+    pub non_finishers: &'a [(Uuid, models::ResultStatus)],
+    /// (player_id, build) pairs for players whose build was given
+    pub builds: &'a [(Uuid, models::RaceBuild)],
+}
+
+/// A race's results and the rating changes they cause, ready to be written.
+#[derive(Debug, Clone, Copy)]
+pub struct RaceOutcome<'a> {
+    /// Round number (1-indexed)
+    pub round_number: i32,
+    /// (player_id, position) pairs, non-finishers included
+    pub results: &'a [(Uuid, i32)],
+    /// Each player's result status
+    pub statuses: &'a HashMap<Uuid, models::ResultStatus>,
+    /// (player_id, build) pairs for players whose build was given
+    pub builds: &'a [(Uuid, models::RaceBuild)],
+    /// All-time rating changes calculated by the rating system
+    pub all_time_elo_changes: &'a [RatingChange],
+    /// Tournament rating changes calculated by the rating system
+    pub tournament_elo_changes: &'a [RatingChange],
+    /// The group's ELO settings (for teammate contributions)
+    pub elo_settings: &'a elo::EloSettings,
 }
 
 /// Records race results and updates all related data in a single transaction.
 ///
 /// This is the main orchestration function that:
-/// 1. Inserts player race scores
-/// 2. Updates player ELO ratings
+/// 1. Inserts player race scores and the builds raced with
+/// 2. Updates player ELO ratings and track ratings
 /// 3. Updates player match aggregates (avg position, total ELO change)
 /// 4. Marks round as completed
 /// 5. If all rounds complete:
@@ -288,10 +511,7 @@ pub async fn record_race_results(
 ///
 /// * `pool` - Database connection pool
 /// * `group_id` - UUID of the group
-/// * `match_id` - UUID of the match
-/// * `round_number` - Round number (1-indexed)
-/// * `results` - Slice of tuples containing (player_id, position)
-/// * `elo_changes` - Slice of ELO changes calculated by ELO service
+/// * `outcome` - The race's results and rating changes
 /// * `match_record` - Current match record
 ///
 /// # Returns
@@ -304,14 +524,12 @@ pub async fn record_race_results(
 pub async fn record_results_in_transaction(
     pool: &DbPool,
     group_id: Uuid,
-    match_id: Uuid,
-    round_number: i32,
-    results: &[(Uuid, i32)],
-    all_time_elo_changes: &[elo::EloChange],
-    tournament_elo_changes: &[elo::EloChange],
+    outcome: &RaceOutcome<'_>,
     match_record: &models::Match,
     notification_manager: &crate::services::notification_manager::NotificationManager,
 ) -> Result<models::Match> {
+    let match_id = match_record.id;
+    let round_number = outcome.round_number;
     tracing::info!("NOTIFY STEP 1: Starting transaction for match={}, round={}", match_id, round_number);
 
     let mut tx = pool
@@ -319,9 +537,233 @@ pub async fn record_results_in_transaction(
         .await
         .map_err(|e| AppError::Internal(format!("Failed to start database transaction: {e}")))?;
 
-    let all_time_elo_map: HashMap<Uuid, &elo::EloChange> =
+    let updated_match = write_race_results(&mut tx, group_id, outcome, match_record).await?;
+
+    // Commit transaction first
+    tx.commit()
+        .await
+        .map_err(|e| AppError::Internal(format!("Failed to commit transaction: {e}")))?;
+
+    tracing::info!("NOTIFY STEP 1: Transaction committed successfully");
+
+    // Publish via pg_notify; each instance's LISTEN task fans out to local
+    // subscribers, so the publishing instance also receives its own event.
+    let notification = crate::services::notification_manager::RaceResultNotification {
+        match_id,
+        tournament_id: updated_match.tournament_id,
+        round_number,
+        group_id,
+    };
+
+    tracing::info!(
+        "NOTIFY STEP 1: Publishing pg_notify: match_id={}, tournament_id={}, round={}, group_id={}",
+        match_id,
+        updated_match.tournament_id,
+        round_number,
+        group_id
+    );
+
+    if let Err(e) = notification_manager.publish(pool, notification).await {
+        tracing::error!(
+            "NOTIFY STEP 1: pg_notify failed (data is already committed; live update will be missed): {}",
+            e
+        );
+    } else {
+        tracing::info!("NOTIFY STEP 1: Successfully published pg_notify");
+    }
+
+    Ok(updated_match)
+}
+
+/// Would-be outcome of a race for one player.
+#[derive(Debug, Clone)]
+pub struct SimulatedPlayerResult {
+    pub player_id: Uuid,
+    pub position: i32,
+    pub status: models::ResultStatus,
+    pub all_time_elo_change: i32,
+    pub all_time_elo_after: i32,
+    /// Tournament ELO change from the race itself, before teammate contributions
+    pub tournament_elo_change: i32,
+    /// Tournament ELO received from teammates' results in this race
+    pub teammate_contribution: i32,
+    pub tournament_elo_after: i32,
+}
+
+/// Would-be score of a team after a race.
+#[derive(Debug, Clone)]
+pub struct SimulatedTeamScore {
+    pub team_id: Uuid,
+    /// Average points per round so far (stored rounded once the match completes)
+    pub score: f64,
+}
+
+/// Would-be outcome of recording a round's results.
+#[derive(Debug, Clone)]
+pub struct SimulatedRound {
+    pub players: Vec<SimulatedPlayerResult>,
+    pub leaderboard: Vec<models::TournamentLeaderboardRow>,
+    pub teams: Vec<SimulatedTeamScore>,
+    pub match_completed: bool,
+}
+
+/// Simulates recording race results without persisting anything.
+///
+/// Runs the same calculations and writes as `record_race_results` in a
+/// transaction that is always rolled back, then reports the rating changes,
+/// tournament leaderboard and team scores as they would have been. No
+/// notification is published.
+///
+/// # Arguments
+///
+/// * `pool` - Database connection pool
+/// * `group_id` - UUID of the group
+/// * `round_number` - Round number (1-indexed)
+/// * `results` - Slice of tuples containing (player_id, position) for players who finished
+/// * `non_finishers` - Slice of tuples containing (player_id, status) for players who did not
+/// * `match_record` - Current match record
+///
+/// # Returns
+///
+/// Result containing the simulated outcome of the round
+///
+/// # Errors
+///
+/// Returns an error if any database operation fails
+pub async fn simulate_race_results(
+    pool: &DbPool,
+    group_id: Uuid,
+    round_number: i32,
+    results: &[(Uuid, i32)],
+    non_finishers: &[(Uuid, models::ResultStatus)],
+    match_record: &models::Match,
+) -> Result<SimulatedRound> {
+    let match_id = match_record.id;
+    let elo_settings = models::GroupSettings::find_elo_settings(pool, group_id).await?;
+    validate_positions(results.iter().map(|(_, p)| *p), &elo_settings)?;
+    let (results, statuses) = place_non_finishers(results, non_finishers, &elo_settings);
+
+    let mut tx = pool
+        .begin()
+        .await
+        .map_err(|e| AppError::Internal(format!("Failed to start database transaction: {e}")))?;
+
+    let (all_time_elo_changes, tournament_elo_changes) = calculate_round_rating_changes(
+        pool,
+        &mut tx,
+        group_id,
+        &results,
+        &statuses,
+        &elo_settings,
+        match_record,
+    )
+    .await?;
+
+    let outcome = RaceOutcome {
+        round_number,
+        results: &results,
+        statuses: &statuses,
+        builds: &[],
+        all_time_elo_changes: &all_time_elo_changes,
+        tournament_elo_changes: &tournament_elo_changes,
+        elo_settings: &elo_settings,
+    };
+    let updated_match = write_race_results(&mut tx, group_id, &outcome, match_record).await?;
+
+    let player_ids: Vec<Uuid> = results.iter().map(|(id, _)| *id).collect();
+    let contributions = models::PlayerTeammateEloContribution::get_round_total_for_players(
+        &mut tx,
+        match_id,
+        round_number,
+        &player_ids,
+    )
+    .await?;
+    let leaderboard = models::PlayerTournamentScore::get_tournament_leaderboard(
+        &mut *tx,
+        match_record.tournament_id,
+    )
+    .await?;
+    let team_scores = score_calculation::calculate_team_scores(&mut tx, match_id).await?;
+
+    tx.rollback()
+        .await
+        .map_err(|e| AppError::Internal(format!("Failed to roll back transaction: {e}")))?;
+
+    let all_time_elo_map: HashMap<Uuid, &RatingChange> =
+        all_time_elo_changes.iter().map(|c| (c.player_id, c)).collect();
+    let tournament_elo_map: HashMap<Uuid, &RatingChange> =
+        tournament_elo_changes.iter().map(|c| (c.player_id, c)).collect();
+    let tournament_elo_after: HashMap<Uuid, i32> = leaderboard
+        .iter()
+        .map(|row| (row.player_id, row.elo_rating))
+        .collect();
+
+    let players = results
+        .iter()
+        .map(|(player_id, position)| {
+            let all_time_change = all_time_elo_map
+                .get(player_id)
+                .ok_or_else(|| AppError::Internal("Missing all-time ELO change".to_string()))?;
+            let tournament_change = tournament_elo_map
+                .get(player_id)
+                .ok_or_else(|| AppError::Internal("Missing tournament ELO change".to_string()))?;
+
+            Ok(SimulatedPlayerResult {
+                player_id: *player_id,
+                position: *position,
+                status: statuses.get(player_id).copied().unwrap_or_default(),
+                all_time_elo_change: all_time_change.elo_change,
+                all_time_elo_after: all_time_change.new_elo,
+                tournament_elo_change: tournament_change.elo_change,
+                teammate_contribution: contributions.get(player_id).copied().unwrap_or(0),
+                tournament_elo_after: tournament_elo_after
+                    .get(player_id)
+                    .copied()
+                    .unwrap_or(tournament_change.new_elo),
+            })
+        })
+        .collect::<Result<Vec<_>>>()?;
+
+    let teams = models::Team::find_by_match_id(pool, match_id)
+        .await?
+        .into_iter()
+        .map(|team| SimulatedTeamScore {
+            team_id: team.id,
+            score: team_scores.get(&team.id).copied().unwrap_or(0.0),
+        })
+        .collect();
+
+    Ok(SimulatedRound {
+        players,
+        leaderboard,
+        teams,
+        match_completed: updated_match.completed,
+    })
+}
+
+/// Writes a race's results and everything derived from them in `tx`.
+///
+/// Shared by `record_results_in_transaction` and `simulate_race_results`; the
+/// caller decides whether to commit.
+async fn write_race_results(
+    tx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+    group_id: Uuid,
+    outcome: &RaceOutcome<'_>,
+    match_record: &models::Match,
+) -> Result<models::Match> {
+    let match_id = match_record.id;
+    let RaceOutcome {
+        round_number,
+        results,
+        statuses,
+        builds,
+        all_time_elo_changes,
+        tournament_elo_changes,
+        elo_settings,
+    } = *outcome;
+    let all_time_elo_map: HashMap<Uuid, &RatingChange> =
         all_time_elo_changes.iter().map(|c| (c.player_id, c)).collect();
-    let tournament_elo_map: HashMap<Uuid, &elo::EloChange> =
+    let tournament_elo_map: HashMap<Uuid, &RatingChange> =
         tournament_elo_changes.iter().map(|c| (c.player_id, c)).collect();
 
     for (player_id, position) in results {
@@ -332,12 +774,23 @@ pub async fn record_results_in_transaction(
             .get(player_id)
             .ok_or_else(|| AppError::Internal("Missing tournament ELO change".to_string()))?;
 
+        // Ratings are updated further down, so the stored uncertainty is still
+        // the uncertainty going into this race.
         sqlx::query(
             "INSERT INTO player_race_scores (
-                group_id, match_id, round_number, player_id, position,
+                group_id, match_id, round_number, player_id, position, status,
                 all_time_elo_change, all_time_elo_after,
-                tournament_elo_change, tournament_elo_after
-             ) VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)",
+                tournament_elo_change, tournament_elo_after,
+                all_time_rating_deviation_before, all_time_rating_volatility_before,
+                tournament_rating_deviation_before, tournament_rating_volatility_before
+             )
+             SELECT $1, $2, $3, $4, $5, $11, $6, $7, $8, $9,
+                    p.rating_deviation, p.rating_volatility,
+                    pts.rating_deviation, pts.rating_volatility
+             FROM players p
+             LEFT JOIN player_tournament_scores pts
+                 ON pts.player_id = p.id AND pts.tournament_id = $10
+             WHERE p.id = $4",
         )
         .bind(group_id)
         .bind(match_id)
@@ -348,11 +801,29 @@ pub async fn record_results_in_transaction(
         .bind(all_time_change.new_elo)
         .bind(tournament_change.elo_change)
         .bind(tournament_change.new_elo)
+        .bind(match_record.tournament_id)
+        .bind(statuses.get(player_id).copied().unwrap_or_default())
         .execute(tx.as_mut())
         .await
         .map_err(|e| AppError::Internal(format!("Failed to insert player race score: {e}")))?;
     }
 
+    if !builds.is_empty() {
+        models::PlayerRaceScore::set_builds(tx.as_mut(), match_id, round_number, builds)
+            .await
+            .map_err(|e| AppError::Internal(format!("Failed to store race builds: {e}")))?;
+    }
+
+    // Unrated players neither pass tournament ELO on to teammates nor change
+    // their track ratings
+    let unrated = unrated_players(statuses);
+    let rated_results: Vec<(Uuid, i32)> = results
+        .iter()
+        .filter(|(player_id, _)| !unrated.contains(player_id))
+        .copied()
+        .collect();
+
+    // Free-for-all matches have no team players, so no teammate contributions
     let player_teams: Vec<(Uuid, Uuid)> = sqlx::query_as(
         "SELECT tp.player_id, tp.team_id
          FROM team_players tp
@@ -381,10 +852,11 @@ pub async fn record_results_in_transaction(
 
     let (teammate_contributions, tournament_elo_adjustments) =
         teammate_elo::calculate_teammate_contributions(
-            results,
+            &rated_results,
             &player_to_team,
             &team_to_players,
             &tournament_elo_change_map,
+            elo_settings,
         );
 
     let contributions: Vec<(Uuid, i32, Uuid, Uuid, i32, i32)> = teammate_contributions
@@ -405,13 +877,13 @@ pub async fn record_results_in_transaction(
         tournament_elo_adjustments.keys().copied().collect();
 
     if !contributions.is_empty() {
-        models::PlayerTeammateEloContribution::insert_contributions_batch(&mut tx, &contributions)
+        models::PlayerTeammateEloContribution::insert_contributions_batch(tx, &contributions)
             .await?;
 
         let beneficiary_ids: Vec<Uuid> = tournament_elo_adjustments.keys().copied().collect();
 
         let beneficiary_current_elos = models::PlayerTournamentScore::get_or_create_batch(
-            &mut tx,
+            tx,
             &beneficiary_ids,
             match_record.tournament_id,
             group_id,
@@ -434,7 +906,7 @@ pub async fn record_results_in_transaction(
 
         if !tournament_elo_updates_with_contributions.is_empty() {
             models::PlayerTournamentScore::update_elo_batch(
-                &mut tx,
+                tx,
                 &tournament_elo_updates_with_contributions,
             )
             .await?;
@@ -444,10 +916,12 @@ pub async fn record_results_in_transaction(
     for change in all_time_elo_changes {
         sqlx::query(
             "UPDATE players
-             SET elo_rating = $1
-             WHERE id = $2",
+             SET elo_rating = $1, rating_deviation = $2, rating_volatility = $3
+             WHERE id = $4",
         )
         .bind(change.new_elo)
+        .bind(change.new_uncertainty.deviation)
+        .bind(change.new_uncertainty.volatility)
         .bind(change.player_id)
         .execute(tx.as_mut())
         .await
@@ -461,11 +935,27 @@ pub async fn record_results_in_transaction(
         .collect();
 
     if !tournament_elo_updates.is_empty() {
-        models::PlayerTournamentScore::update_elo_batch(&mut tx, &tournament_elo_updates).await?;
+        models::PlayerTournamentScore::update_elo_batch(tx, &tournament_elo_updates).await?;
+    }
+
+    let tournament_uncertainty_updates: Vec<(Uuid, Uuid, Uncertainty)> = tournament_elo_changes
+        .iter()
+        .map(|c| (c.player_id, match_record.tournament_id, c.new_uncertainty))
+        .collect();
+
+    if !tournament_uncertainty_updates.is_empty() {
+        models::PlayerTournamentScore::update_uncertainty_batch(
+            tx,
+            &tournament_uncertainty_updates,
+        )
+        .await?;
     }
 
+    track_rating::record_track_ratings(tx, match_id, round_number, &rated_results, elo_settings)
+        .await?;
+
     let player_match_updates = score_calculation::calculate_player_match_aggregates(
-        &mut tx,
+        tx,
         match_id,
         round_number,
         all_time_elo_changes,
@@ -515,16 +1005,16 @@ pub async fn record_results_in_transaction(
     .await?;
 
     let all_rounds_completed =
-        score_calculation::check_all_rounds_completed(&mut tx, match_id).await?;
+        score_calculation::check_all_rounds_completed(tx, match_id).await?;
 
     let updated_match = if all_rounds_completed {
-        score_calculation::calculate_and_store_team_scores(&mut tx, group_id, match_id).await?;
+        score_calculation::calculate_and_store_match_scores(tx, match_record).await?;
 
         sqlx::query_as::<_, models::Match>(
             "UPDATE matches
              SET completed = true
              WHERE id = $1
-             RETURNING id, group_id, tournament_id, time, rounds, completed",
+             RETURNING id, group_id, tournament_id, time, rounds, completed, points_table_id, mode, track_pool_id",
         )
         .bind(match_id)
         .fetch_one(tx.as_mut())
@@ -533,37 +1023,360 @@ pub async fn record_results_in_transaction(
         match_record.clone()
     };
 
-    // Commit transaction first
+    Ok(updated_match)
+}
+
+/// Replaces the positions of an already recorded round and replays ELO.
+///
+/// This function:
+/// 1. Updates positions, result statuses and any given builds in
+///    `player_race_scores` for the round
+/// 2. Replays the round and every later race in the group (see `elo_replay`)
+/// 3. Recalculates team scores or free-for-all points if the match is completed
+/// 4. Publishes a race result notification after commit
+///
+/// All changes happen in a single transaction.
+///
+/// # Arguments
+///
+/// * `pool` - Database connection pool
+/// * `group_id` - UUID of the group
+/// * `round` - The corrected results; given builds replace the recorded ones
+/// * `match_record` - Current match record
+///
+/// # Returns
+///
+/// Result containing the match record
+///
+/// # Errors
+///
+/// Returns an error if any database operation fails (transaction will be rolled back)
+pub async fn amend_race_results(
+    pool: &DbPool,
+    group_id: Uuid,
+    round: &RoundResults<'_>,
+    match_record: &models::Match,
+    notification_manager: &crate::services::notification_manager::NotificationManager,
+) -> Result<models::Match> {
+    let match_id = match_record.id;
+    let round_number = round.round_number;
+    let elo_settings = models::GroupSettings::find_elo_settings(pool, group_id).await?;
+    validate_positions(round.results.iter().map(|(_, p)| *p), &elo_settings)?;
+    let (results, statuses) =
+        place_non_finishers(round.results, round.non_finishers, &elo_settings);
+
+    let mut tx = pool
+        .begin()
+        .await
+        .map_err(|e| AppError::Internal(format!("Failed to start database transaction: {e}")))?;
+
+    let (player_ids, positions): (Vec<Uuid>, Vec<i32>) = results.into_iter().unzip();
+    let player_statuses: Vec<models::ResultStatus> = player_ids
+        .iter()
+        .map(|player_id| statuses.get(player_id).copied().unwrap_or_default())
+        .collect();
+
+    sqlx::query(
+        "UPDATE player_race_scores prs
+         SET position = u.position, status = u.status
+         FROM UNNEST($3::uuid[], $4::int[], $5::result_status[]) AS u(player_id, position, status)
+         WHERE prs.match_id = $1 AND prs.round_number = $2 AND prs.player_id = u.player_id",
+    )
+    .bind(match_id)
+    .bind(round_number)
+    .bind(&player_ids)
+    .bind(&positions)
+    .bind(&player_statuses)
+    .execute(tx.as_mut())
+    .await
+    .map_err(|e| AppError::Internal(format!("Failed to update race positions: {e}")))?;
+
+    if !round.builds.is_empty() {
+        models::PlayerRaceScore::set_builds(tx.as_mut(), match_id, round_number, round.builds)
+            .await
+            .map_err(|e| AppError::Internal(format!("Failed to store race builds: {e}")))?;
+    }
+
+    let races_replayed =
+        elo_replay::replay_from_round(&mut tx, group_id, match_id, round_number).await?;
+
+    if match_record.completed {
+        score_calculation::calculate_and_store_match_scores(&mut tx, match_record).await?;
+    }
+
     tx.commit()
         .await
         .map_err(|e| AppError::Internal(format!("Failed to commit transaction: {e}")))?;
 
-    tracing::info!("NOTIFY STEP 1: Transaction committed successfully");
+    tracing::info!(
+        %match_id,
+        round_number,
+        races_replayed,
+        "Amended round results"
+    );
 
-    // Publish via pg_notify; each instance's LISTEN task fans out to local
-    // subscribers, so the publishing instance also receives its own event.
     let notification = crate::services::notification_manager::RaceResultNotification {
         match_id,
-        tournament_id: updated_match.tournament_id,
+        tournament_id: match_record.tournament_id,
         round_number,
         group_id,
     };
 
-    tracing::info!(
-        "NOTIFY STEP 1: Publishing pg_notify: match_id={}, tournament_id={}, round={}, group_id={}",
+    if let Err(e) = notification_manager.publish(pool, notification).await {
+        tracing::error!(
+            "pg_notify failed after amending results (data is already committed; live update will be missed): {}",
+            e
+        );
+    }
+
+    Ok(match_record.clone())
+}
+
+/// Undoes the most recently recorded round of a match.
+///
+/// This function:
+/// 1. Finds the round whose results were recorded last
+/// 2. Reverts all-time and tournament ELO by the stored per-race deltas and
+///    teammate contributions, and restores the rating uncertainty of players
+///    whose latest race this round was
+/// 3. Deletes the round's race scores and teammate contributions
+/// 4. Recomputes player match aggregates from the remaining rounds and the
+///    group's track ratings
+/// 5. Reopens the round, and the match if it was completed
+/// 6. Publishes a race result notification after commit
+///
+/// Ratings are reverted by delta rather than replayed, so races recorded in
+/// other matches since this round keep their stored values.
+///
+/// # Arguments
+///
+/// * `pool` - Database connection pool
+/// * `group_id` - UUID of the group
+/// * `match_record` - Current match record
+///
+/// # Returns
+///
+/// Result containing the updated match record
+///
+/// # Errors
+///
+/// Returns an error if:
+/// - The match has no recorded rounds
+/// - Any database operation fails (transaction will be rolled back)
+pub async fn undo_last_round(
+    pool: &DbPool,
+    group_id: Uuid,
+    match_record: &models::Match,
+    notification_manager: &crate::services::notification_manager::NotificationManager,
+) -> Result<models::Match> {
+    let match_id = match_record.id;
+
+    let mut tx = pool
+        .begin()
+        .await
+        .map_err(|e| AppError::Internal(format!("Failed to start database transaction: {e}")))?;
+
+    let round_number: i32 = sqlx::query_scalar(
+        "SELECT round_number
+         FROM player_race_scores
+         WHERE match_id = $1
+         GROUP BY round_number
+         ORDER BY MAX(created_at) DESC, round_number DESC
+         LIMIT 1",
+    )
+    .bind(match_id)
+    .fetch_optional(tx.as_mut())
+    .await?
+    .ok_or_else(|| AppError::NotFound("No recorded rounds to undo".to_string()))?;
+
+    let race_deltas: Vec<(Uuid, i32, i32)> = sqlx::query_as(
+        "SELECT player_id, COALESCE(all_time_elo_change, 0), COALESCE(tournament_elo_change, 0)
+         FROM player_race_scores
+         WHERE match_id = $1 AND round_number = $2",
+    )
+    .bind(match_id)
+    .bind(round_number)
+    .fetch_all(tx.as_mut())
+    .await?;
+
+    let contributions =
+        models::PlayerTeammateEloContribution::get_all_beneficiaries_for_round(
+            &mut tx,
+            match_id,
+            round_number,
+        )
+        .await?;
+
+    let (player_ids, all_time_deltas): (Vec<Uuid>, Vec<i32>) = race_deltas
+        .iter()
+        .map(|(player_id, all_time_change, _)| (*player_id, *all_time_change))
+        .unzip();
+
+    sqlx::query(
+        "UPDATE players p
+         SET elo_rating = p.elo_rating - u.delta
+         FROM UNNEST($1::uuid[], $2::int[]) AS u(id, delta)
+         WHERE p.id = u.id",
+    )
+    .bind(&player_ids)
+    .bind(&all_time_deltas)
+    .execute(tx.as_mut())
+    .await
+    .map_err(|e| AppError::Internal(format!("Failed to revert player ELO rating: {e}")))?;
+
+    let tournament_deltas: HashMap<Uuid, i32> = race_deltas
+        .iter()
+        .map(|(player_id, _, tournament_change)| (*player_id, *tournament_change))
+        .chain(contributions)
+        .fold(HashMap::new(), |mut acc, (player_id, delta)| {
+            *acc.entry(player_id).or_insert(0) += delta;
+            acc
+        });
+    let (tournament_player_ids, tournament_elo_deltas): (Vec<Uuid>, Vec<i32>) =
+        tournament_deltas.into_iter().unzip();
+
+    sqlx::query(
+        "UPDATE player_tournament_scores pts
+         SET elo_rating = pts.elo_rating - u.delta, updated_at = NOW()
+         FROM UNNEST($2::uuid[], $3::int[]) AS u(player_id, delta)
+         WHERE pts.player_id = u.player_id AND pts.tournament_id = $1",
+    )
+    .bind(match_record.tournament_id)
+    .bind(&tournament_player_ids)
+    .bind(&tournament_elo_deltas)
+    .execute(tx.as_mut())
+    .await
+    .map_err(|e| AppError::Internal(format!("Failed to revert tournament ELO rating: {e}")))?;
+
+    let starting_uncertainty = Uncertainty::default();
+
+    sqlx::query(
+        "UPDATE players p
+         SET rating_deviation = COALESCE(prs.all_time_rating_deviation_before, $3),
+             rating_volatility = COALESCE(prs.all_time_rating_volatility_before, $4)
+         FROM player_race_scores prs
+         WHERE prs.match_id = $1 AND prs.round_number = $2 AND prs.player_id = p.id
+           AND NOT EXISTS (
+               SELECT 1 FROM player_race_scores later
+               WHERE later.player_id = prs.player_id AND later.created_at > prs.created_at
+           )",
+    )
+    .bind(match_id)
+    .bind(round_number)
+    .bind(starting_uncertainty.deviation)
+    .bind(starting_uncertainty.volatility)
+    .execute(tx.as_mut())
+    .await
+    .map_err(|e| AppError::Internal(format!("Failed to revert player rating uncertainty: {e}")))?;
+
+    sqlx::query(
+        "UPDATE player_tournament_scores pts
+         SET rating_deviation = COALESCE(prs.tournament_rating_deviation_before, $4),
+             rating_volatility = COALESCE(prs.tournament_rating_volatility_before, $5),
+             updated_at = NOW()
+         FROM player_race_scores prs
+         WHERE prs.match_id = $1 AND prs.round_number = $2
+           AND pts.player_id = prs.player_id AND pts.tournament_id = $3
+           AND NOT EXISTS (
+               SELECT 1 FROM player_race_scores later
+               JOIN matches m ON m.id = later.match_id
+               WHERE later.player_id = prs.player_id
+                 AND m.tournament_id = $3
+                 AND later.created_at > prs.created_at
+           )",
+    )
+    .bind(match_id)
+    .bind(round_number)
+    .bind(match_record.tournament_id)
+    .bind(starting_uncertainty.deviation)
+    .bind(starting_uncertainty.volatility)
+    .execute(tx.as_mut())
+    .await
+    .map_err(|e| {
+        AppError::Internal(format!("Failed to revert tournament rating uncertainty: {e}"))
+    })?;
+
+    sqlx::query(
+        "DELETE FROM player_teammate_elo_contributions
+         WHERE match_id = $1 AND round_number = $2",
+    )
+    .bind(match_id)
+    .bind(round_number)
+    .execute(tx.as_mut())
+    .await?;
+
+    sqlx::query(
+        "DELETE FROM player_race_scores
+         WHERE match_id = $1 AND round_number = $2",
+    )
+    .bind(match_id)
+    .bind(round_number)
+    .execute(tx.as_mut())
+    .await?;
+
+    score_calculation::recompute_player_match_scores(&mut tx, match_id).await?;
+    track_rating::recompute_group_track_ratings(&mut tx, group_id).await?;
+
+    sqlx::query(
+        "UPDATE rounds
+         SET completed = false
+         WHERE match_id = $1 AND round_number = $2",
+    )
+    .bind(match_id)
+    .bind(round_number)
+    .execute(tx.as_mut())
+    .await?;
+
+    let updated_match = if match_record.completed {
+        sqlx::query(
+            "UPDATE teams
+             SET score = NULL
+             WHERE match_id = $1",
+        )
+        .bind(match_id)
+        .execute(tx.as_mut())
+        .await?;
+
+        sqlx::query(
+            "UPDATE player_match_scores
+             SET points = NULL
+             WHERE match_id = $1",
+        )
+        .bind(match_id)
+        .execute(tx.as_mut())
+        .await?;
+
+        sqlx::query_as::<_, models::Match>(
+            "UPDATE matches
+             SET completed = false
+             WHERE id = $1
+             RETURNING id, group_id, tournament_id, time, rounds, completed, points_table_id, mode, track_pool_id",
+        )
+        .bind(match_id)
+        .fetch_one(tx.as_mut())
+        .await?
+    } else {
+        match_record.clone()
+    };
+
+    tx.commit()
+        .await
+        .map_err(|e| AppError::Internal(format!("Failed to commit transaction: {e}")))?;
+
+    tracing::info!(%match_id, round_number, "Undid last recorded round");
+
+    let notification = crate::services::notification_manager::RaceResultNotification {
         match_id,
-        updated_match.tournament_id,
+        tournament_id: updated_match.tournament_id,
         round_number,
-        group_id
-    );
+        group_id,
+    };
 
     if let Err(e) = notification_manager.publish(pool, notification).await {
         tracing::error!(
-            "NOTIFY STEP 1: pg_notify failed (data is already committed; live update will be missed): {}",
+            "pg_notify failed after undoing round (data is already committed; live update will be missed): {}",
             e
         );
-    } else {
-        tracing::info!("NOTIFY STEP 1: Successfully published pg_notify");
     }
 
     Ok(updated_match)
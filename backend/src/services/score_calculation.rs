@@ -5,18 +5,24 @@
 //! The service handles:
 //! - Player match aggregates (average position, ELO changes)
 //! - Team score calculations from race positions
+//! - Individual points for free-for-all matches
+//! - Cup standings for Grand Prix matches
 //! - Match completion status checking
 //! - Database updates for team scores
 //!
 //! ## Score Calculation
 //!
 //! - Player scores: Average position across all races in a match
-//! - Team scores: Average points across all rounds (using position-to-points conversion)
+//! - Team scores: Average points across all rounds, using the match's points table
+//! - Disqualified and disconnected players score no points for the race (their
+//!   position is read as 0, which no points table rewards)
+//! - Free-for-all points: Each player's total points across all rounds
 //! - ELO changes: Aggregated from individual race results
 
+use crate::db::DbPool;
 use crate::error::Result;
 use crate::models;
-use crate::services::elo;
+use crate::services::rating_system::RatingChange;
 use crate::services::scoring;
 use std::collections::HashMap;
 use uuid::Uuid;
@@ -48,11 +54,11 @@ pub async fn calculate_player_match_aggregates(
     tx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
     match_id: Uuid,
     round_number: i32,
-    current_round_all_time_elo_changes: &[elo::EloChange],
-    current_round_tournament_elo_changes: &[elo::EloChange],
+    current_round_all_time_elo_changes: &[RatingChange],
+    current_round_tournament_elo_changes: &[RatingChange],
 ) -> Result<Vec<(Uuid, i32, i32, i32, i32)>> {
     let all_race_scores: Vec<models::PlayerRaceScore> = sqlx::query_as(
-        "SELECT group_id, match_id, round_number, player_id, position,
+        "SELECT group_id, match_id, round_number, player_id, position, status,
                 all_time_elo_change, all_time_elo_after,
                 tournament_elo_change, tournament_elo_after, created_at
          FROM player_race_scores
@@ -123,6 +129,68 @@ pub async fn calculate_player_match_aggregates(
     Ok(aggregates)
 }
 
+/// Recomputes every player match aggregate for a match from source data.
+///
+/// Unlike `calculate_player_match_aggregates`, which adds the current round's
+/// changes to the stored totals, this rebuilds each row from all recorded race
+/// scores and teammate contributions. Used when recorded results are corrected.
+///
+/// # Arguments
+///
+/// * `tx` - Active database transaction
+/// * `match_id` - UUID of the match
+///
+/// # Returns
+///
+/// Result indicating success or failure
+///
+/// # Errors
+///
+/// Returns an error if database update fails
+pub async fn recompute_player_match_scores(
+    tx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+    match_id: Uuid,
+) -> Result<()> {
+    sqlx::query(
+        "UPDATE player_match_scores pms
+         SET position = s.position,
+             elo_change = s.elo_change,
+             tournament_elo_from_races = s.tournament_elo_from_races,
+             tournament_elo_from_contributions = s.tournament_elo_from_contributions,
+             tournament_elo_change = s.tournament_elo_from_races + s.tournament_elo_from_contributions
+         FROM (
+             SELECT base.player_id,
+                    COALESCE(r.position, 0) AS position,
+                    COALESCE(r.elo_change, 0) AS elo_change,
+                    COALESCE(r.tournament_elo_change, 0) AS tournament_elo_from_races,
+                    COALESCE(c.contribution_amount, 0) AS tournament_elo_from_contributions
+             FROM player_match_scores base
+             LEFT JOIN (
+                 SELECT player_id,
+                        ROUND(AVG(position))::int AS position,
+                        SUM(all_time_elo_change)::int AS elo_change,
+                        SUM(tournament_elo_change)::int AS tournament_elo_change
+                 FROM player_race_scores
+                 WHERE match_id = $1
+                 GROUP BY player_id
+             ) r ON r.player_id = base.player_id
+             LEFT JOIN (
+                 SELECT beneficiary_player_id, SUM(contribution_amount)::int AS contribution_amount
+                 FROM player_teammate_elo_contributions
+                 WHERE match_id = $1
+                 GROUP BY beneficiary_player_id
+             ) c ON c.beneficiary_player_id = base.player_id
+             WHERE base.match_id = $1
+         ) s
+         WHERE pms.match_id = $1 AND pms.player_id = s.player_id",
+    )
+    .bind(match_id)
+    .execute(&mut **tx)
+    .await?;
+
+    Ok(())
+}
+
 /// Checks if all rounds in a match have been completed.
 ///
 /// # Arguments
@@ -153,6 +221,219 @@ pub async fn check_all_rounds_completed(
     Ok(incomplete_count == 0)
 }
 
+/// Calculates and stores the final scores of a completed match.
+///
+/// Team matches store a score per team; free-for-all matches store each
+/// player's points on their match score.
+///
+/// # Arguments
+///
+/// * `tx` - Active database transaction
+/// * `match_record` - The match to score
+///
+/// # Returns
+///
+/// Result indicating success or failure
+///
+/// # Errors
+///
+/// Returns an error if database queries fail
+pub async fn calculate_and_store_match_scores(
+    tx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+    match_record: &models::Match,
+) -> Result<()> {
+    match match_record.mode {
+        models::MatchMode::Teams => {
+            calculate_and_store_team_scores(tx, match_record.group_id, match_record.id).await
+        }
+        models::MatchMode::FreeForAll => {
+            calculate_and_store_player_points(tx, match_record.id).await
+        }
+    }
+}
+
+/// Calculates and stores each player's points for a free-for-all match.
+///
+/// # Arguments
+///
+/// * `tx` - Active database transaction
+/// * `match_id` - UUID of the match
+///
+/// # Returns
+///
+/// Result indicating success or failure
+///
+/// # Errors
+///
+/// Returns an error if database queries fail
+pub async fn calculate_and_store_player_points(
+    tx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+    match_id: Uuid,
+) -> Result<()> {
+    let points = models::PointsTable::find_points_by_match_id(&mut **tx, match_id).await?;
+
+    let race_scores = sqlx::query_as::<_, (Uuid, i32)>(
+        "SELECT player_id, CASE status WHEN 'finished' THEN position WHEN 'dnf' THEN $2 ELSE 0 END
+         FROM player_race_scores
+         WHERE match_id = $1",
+    )
+    .bind(match_id)
+    .bind(points.len() as i32)
+    .fetch_all(&mut **tx)
+    .await?;
+
+    let (player_ids, totals): (Vec<Uuid>, Vec<i32>) =
+        calculate_player_points_with_points(&race_scores, &points)
+            .into_iter()
+            .unzip();
+
+    sqlx::query(
+        "UPDATE player_match_scores pms
+         SET points = u.points
+         FROM UNNEST($2::uuid[], $3::int[]) AS u(player_id, points)
+         WHERE pms.match_id = $1 AND pms.player_id = u.player_id",
+    )
+    .bind(match_id)
+    .bind(&player_ids)
+    .bind(&totals)
+    .execute(&mut **tx)
+    .await?;
+
+    Ok(())
+}
+
+/// Totals each player's points from race positions under a points table.
+///
+/// # Arguments
+///
+/// * `race_scores` - Slice of tuples containing (player_id, position)
+/// * `points` - Points per position, starting with first place
+///
+/// # Returns
+///
+/// HashMap mapping player IDs to their total points
+pub fn calculate_player_points_with_points(
+    race_scores: &[(Uuid, i32)],
+    points: &[i32],
+) -> HashMap<Uuid, i32> {
+    race_scores
+        .iter()
+        .fold(HashMap::new(), |mut acc, &(player_id, position)| {
+            *acc.entry(player_id).or_insert(0) += scoring::points_for_position(points, position);
+            acc
+        })
+}
+
+/// A player's standing in one cup of a Grand Prix match
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CupPlayerStanding {
+    pub player_id: Uuid,
+    /// Rank in the cup; players on equal points share a position
+    pub position: i32,
+    pub points: i32,
+}
+
+/// Standings of one cup of a Grand Prix match
+#[derive(Debug, Clone)]
+pub struct CupStandings {
+    pub cup_id: Uuid,
+    /// Ranked best first
+    pub players: Vec<CupPlayerStanding>,
+}
+
+/// Calculates the standings of each cup in a Grand Prix match from the races
+/// recorded so far, using the points table the match is scored with.
+///
+/// # Arguments
+///
+/// * `pool` - Database connection pool
+/// * `match_id` - UUID of the match
+///
+/// # Returns
+///
+/// Result containing the standings of each cup in race order (empty for
+/// matches that are not a Grand Prix)
+///
+/// # Errors
+///
+/// Returns an error if database queries fail
+pub async fn calculate_cup_standings(pool: &DbPool, match_id: Uuid) -> Result<Vec<CupStandings>> {
+    let cup_ids: Vec<Uuid> = sqlx::query_scalar(
+        "SELECT cup_id
+         FROM rounds
+         WHERE match_id = $1 AND cup_id IS NOT NULL
+         GROUP BY cup_id
+         ORDER BY MIN(round_number)",
+    )
+    .bind(match_id)
+    .fetch_all(pool)
+    .await?;
+
+    if cup_ids.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let points = models::PointsTable::find_points_by_match_id(pool, match_id).await?;
+
+    let race_scores = sqlx::query_as::<_, (Uuid, Uuid, i32)>(
+        "SELECT r.cup_id, prs.player_id,
+                CASE prs.status WHEN 'finished' THEN prs.position WHEN 'dnf' THEN $2 ELSE 0 END
+         FROM player_race_scores prs
+         JOIN rounds r ON r.match_id = prs.match_id AND r.round_number = prs.round_number
+         WHERE prs.match_id = $1 AND r.cup_id IS NOT NULL",
+    )
+    .bind(match_id)
+    .bind(points.len() as i32)
+    .fetch_all(pool)
+    .await?;
+
+    let scores_by_cup: HashMap<Uuid, Vec<(Uuid, i32)>> = race_scores.into_iter().fold(
+        HashMap::new(),
+        |mut acc, (cup_id, player_id, position)| {
+            acc.entry(cup_id).or_default().push((player_id, position));
+            acc
+        },
+    );
+
+    Ok(cup_ids
+        .into_iter()
+        .map(|cup_id| {
+            let totals = scores_by_cup
+                .get(&cup_id)
+                .map(|scores| calculate_player_points_with_points(scores, &points))
+                .unwrap_or_default();
+            CupStandings {
+                cup_id,
+                players: rank_by_points(totals),
+            }
+        })
+        .collect())
+}
+
+/// Ranks players by points, best first; players on equal points share a
+/// position.
+///
+/// # Arguments
+///
+/// * `totals` - Points per player ID
+///
+/// # Returns
+///
+/// Standings sorted by points descending, then player ID
+pub fn rank_by_points(totals: HashMap<Uuid, i32>) -> Vec<CupPlayerStanding> {
+    let mut sorted: Vec<(Uuid, i32)> = totals.into_iter().collect();
+    sorted.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(&b.0)));
+
+    sorted
+        .iter()
+        .map(|&(player_id, points)| CupPlayerStanding {
+            player_id,
+            position: 1 + sorted.iter().filter(|(_, other)| *other > points).count() as i32,
+            points,
+        })
+        .collect()
+}
+
 /// Calculates and stores team scores for a match.
 ///
 /// This function:
@@ -180,15 +461,51 @@ pub async fn calculate_and_store_team_scores(
     _group_id: Uuid,
     match_id: Uuid,
 ) -> Result<()> {
+    let team_scores = calculate_team_scores(tx, match_id).await?;
+
+    for (team_id, score) in team_scores {
+        sqlx::query(
+            "UPDATE teams
+             SET score = $1
+             WHERE id = $2",
+        )
+        .bind(score.round() as i32)
+        .bind(team_id)
+        .execute(&mut **tx)
+        .await?;
+    }
+
+    Ok(())
+}
+
+/// Calculates a match's team scores from the race results recorded so far,
+/// using the points table the match is scored with.
+///
+/// # Arguments
+///
+/// * `tx` - Database transaction
+/// * `match_id` - UUID of the match
+///
+/// # Returns
+///
+/// HashMap mapping team IDs to their average scores (unrounded)
+pub async fn calculate_team_scores(
+    tx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+    match_id: Uuid,
+) -> Result<HashMap<Uuid, f64>> {
+    let points = models::PointsTable::find_points_by_match_id(&mut **tx, match_id).await?;
+
     let race_scores = sqlx::query_as::<_, (Uuid, i32)>(
-        "SELECT rp.team_id, prs.position
+        "SELECT rp.team_id,
+                CASE prs.status WHEN 'finished' THEN prs.position WHEN 'dnf' THEN $2 ELSE 0 END
          FROM player_race_scores prs
          JOIN round_players rp ON rp.match_id = prs.match_id
              AND rp.round_number = prs.round_number
              AND rp.player_id = prs.player_id
-         WHERE prs.match_id = $1",
+         WHERE prs.match_id = $1 AND rp.team_id IS NOT NULL",
     )
     .bind(match_id)
+    .bind(points.len() as i32)
     .fetch_all(&mut **tx)
     .await?;
 
@@ -197,27 +514,17 @@ pub async fn calculate_and_store_team_scores(
         .fetch_one(&mut **tx)
         .await?;
 
-    let team_scores = calculate_team_scores_from_positions(&race_scores, num_rounds as i32);
-
-    for (team_id, score) in team_scores {
-        sqlx::query(
-            "UPDATE teams
-             SET score = $1
-             WHERE id = $2",
-        )
-        .bind(score.round() as i32)
-        .bind(team_id)
-        .execute(&mut **tx)
-        .await?;
-    }
-
-    Ok(())
+    Ok(calculate_team_scores_with_points(
+        &race_scores,
+        num_rounds as i32,
+        &points,
+    ))
 }
 
 /// Calculates team scores from race positions.
 ///
-/// Converts positions to points using the scoring module, then calculates
-/// the average score per team across all rounds.
+/// Converts positions to points with the Mario Kart 8 points table, then
+/// calculates the average score per team across all rounds.
 ///
 /// # Arguments
 ///
@@ -242,13 +549,33 @@ pub async fn calculate_and_store_team_scores(
 pub fn calculate_team_scores_from_positions(
     race_scores: &[(Uuid, i32)],
     num_rounds: i32,
+) -> HashMap<Uuid, f64> {
+    calculate_team_scores_with_points(race_scores, num_rounds, &scoring::MK8_POINTS)
+}
+
+/// Calculates team scores from race positions under a points table.
+///
+/// # Arguments
+///
+/// * `race_scores` - Slice of tuples containing (team_id, position)
+/// * `num_rounds` - Total number of rounds in the match
+/// * `points` - Points per position, starting with first place
+///
+/// # Returns
+///
+/// HashMap mapping team IDs to their average scores
+pub fn calculate_team_scores_with_points(
+    race_scores: &[(Uuid, i32)],
+    num_rounds: i32,
+    points: &[i32],
 ) -> HashMap<Uuid, f64> {
     let team_points: HashMap<Uuid, Vec<i32>> =
         race_scores
             .iter()
             .fold(HashMap::new(), |mut acc, &(team_id, position)| {
-                let points = scoring::position_to_points(position);
-                acc.entry(team_id).or_default().push(points);
+                acc.entry(team_id)
+                    .or_default()
+                    .push(scoring::points_for_position(points, position));
                 acc
             });
 
@@ -1,18 +1,32 @@
+/// Points per position in Mario Kart 8 12-player races
+pub const MK8_POINTS: [i32; 12] = [15, 12, 10, 9, 8, 7, 6, 5, 4, 3, 2, 1];
+
+/// Points per position in Mario Kart World 24-player races
+pub const MK_WORLD_POINTS: [i32; 24] = [
+    15, 12, 10, 9, 9, 8, 8, 7, 7, 6, 6, 6, 5, 5, 5, 4, 4, 4, 3, 3, 3, 2, 2, 1,
+];
 
 pub const fn position_to_points(position: i32) -> i32 {
-    match position {
-        1 => 15,
-        2 => 12,
-        3 => 10,
-        4 => 9,
-        5 => 8,
-        6 => 7,
-        7 => 6,
-        8 => 5,
-        9 => 4,
-        10 => 3,
-        11 => 2,
-        12 => 1,
-        _ => 0,
+    points_for_position(&MK8_POINTS, position)
+}
+
+/// Points a position scores under a points table; positions past the end of
+/// the table score nothing.
+pub const fn points_for_position(points: &[i32], position: i32) -> i32 {
+    if position >= 1 && position as usize <= points.len() {
+        points[position as usize - 1]
+    } else {
+        0
     }
 }
+
+/// Points for a fractional (expected) position, interpolated between the two
+/// neighbouring positions of a points table.
+pub fn interpolated_points(points: &[i32], position: f64) -> f64 {
+    let lower = position.floor();
+    let fraction = position - lower;
+    let lower_points = points_for_position(points, lower as i32) as f64;
+    let upper_points = points_for_position(points, lower as i32 + 1) as f64;
+
+    lower_points + (upper_points - lower_points) * fraction
+}
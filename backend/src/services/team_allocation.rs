@@ -13,13 +13,233 @@
 //! 2. Calculate team sizes (distributing remainder players evenly)
 //! 3. Iteratively assign each player to the team with the lowest total ELO
 //!    that still has capacity
+//!
+//! ## Optimal Balancing
+//!
+//! `allocate_teams_optimally` searches for the teams that minimise a balance
+//! cost: the spread (highest minus lowest) of team average ELO, plus the
+//! spread of each team's expected points per race weighted by
+//! `POINTS_SPREAD_WEIGHT`. Expected points follow the races `race_allocation`
+//! would schedule for the teams, with each racer's expected position from the
+//! prediction model.
+//!
+//! - Up to `EXACT_BALANCING_MAX_PLAYERS` players: branch and bound over every
+//!   partition into teams of the calculated sizes, starting from the greedy
+//!   teams and pruning on a lower bound of the average-ELO spread
+//! - Larger matches: the greedy teams are improved by repeatedly applying the
+//!   player swap between two teams that lowers the cost the most
+//!
+//! ## Teammate Variety
+//!
+//! Ratings barely move between matches of a tournament, so balancing alone
+//! keeps pairing the same people. Given a `PairingHistory` of the tournament,
+//! greedy and optimal balancing charge `repeat_penalty` ELO for every earlier
+//! match two teammates already shared a team in: greedy adds it to a team's
+//! total when choosing where a player goes, optimal adds it to the balance
+//! cost. A penalty of 0 balances on ELO alone.
+//!
+//! ## Pair Constraints
+//!
+//! `PairConstraints` lists players who must (keep together) or must not (keep
+//! apart) share a team. Keep-together players are placed as one unit; when the
+//! greedy or random placement gets stuck, a backtracking search looks for any
+//! placement that honours every constraint. If none exists, allocation fails
+//! with `AppError::InvalidInput` naming the constraint that makes it
+//! impossible.
 
+use crate::error::{AppError, Result};
 use crate::models;
+use crate::services::elo::EloSettings;
+use crate::services::{prediction, race_allocation, scoring};
 use rand::seq::SliceRandom;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use tracing::instrument;
 use uuid::Uuid;
 
+/// Largest match whose teams are found by exhaustive search
+pub const EXACT_BALANCING_MAX_PLAYERS: usize = 12;
+
+/// ELO of average-rating spread that weighs as much as one point of expected
+/// points spread per race
+const POINTS_SPREAD_WEIGHT: f64 = 25.0;
+
+/// Upper bound on swaps applied by local search
+const MAX_LOCAL_SEARCH_SWAPS: usize = 200;
+
+/// Upper bound on placements tried while searching for teams that honour the
+/// pair constraints
+const MAX_CONSTRAINED_PLACEMENT_STEPS: usize = 100_000;
+
+/// How players are split into teams when a match is created
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum BalancingStrategy {
+    /// Highest ELO first onto the team with the lowest total ELO
+    #[default]
+    Greedy,
+    /// Smallest spread of team average ELO and expected race points
+    Optimal,
+    /// Random teams of balanced sizes
+    Random,
+}
+
+/// Match settings that teams' expected race points depend on
+#[derive(Debug, Clone, Copy)]
+pub struct RacePointsModel<'a> {
+    pub num_races: i32,
+    pub players_per_race: i32,
+    /// Points per position of the match's points table
+    pub points: &'a [i32],
+    pub settings: &'a EloSettings,
+}
+
+/// How often pairs of players have been teammates, typically in the matches of
+/// one tournament
+#[derive(Debug, Clone, Default)]
+pub struct PairingHistory {
+    counts: HashMap<(Uuid, Uuid), u32>,
+}
+
+impl PairingHistory {
+    /// Builds a history from (player, player, times teammates) counts.
+    pub fn from_counts(counts: impl IntoIterator<Item = (Uuid, Uuid, u32)>) -> Self {
+        let mut history = Self::default();
+        for (player_a, player_b, times) in counts {
+            *history.counts.entry(Self::key(player_a, player_b)).or_default() += times;
+        }
+        history
+    }
+
+    fn key(player_a: Uuid, player_b: Uuid) -> (Uuid, Uuid) {
+        (player_a.min(player_b), player_a.max(player_b))
+    }
+
+    /// Number of times two players have been teammates.
+    pub fn times_paired(&self, player_a: Uuid, player_b: Uuid) -> u32 {
+        self.counts
+            .get(&Self::key(player_a, player_b))
+            .copied()
+            .unwrap_or(0)
+    }
+
+    /// Earlier pairings of a player with each of a team's players.
+    pub fn times_paired_with(&self, player_id: Uuid, teammates: &[Uuid]) -> u32 {
+        teammates
+            .iter()
+            .map(|&teammate| self.times_paired(player_id, teammate))
+            .sum()
+    }
+
+    /// Earlier pairings between every two players of a team.
+    pub fn repeat_pairings(&self, team: &[Uuid]) -> u32 {
+        team.iter()
+            .enumerate()
+            .map(|(idx, &player_id)| self.times_paired_with(player_id, &team[idx + 1..]))
+            .sum()
+    }
+
+    /// Share of teammate pairs who have not been teammates before, from 0.0
+    /// (every pair repeats) to 1.0 (all pairings are new).
+    ///
+    /// Teams without any pair of players count as fully fresh.
+    pub fn freshness(&self, teams: &[Vec<Uuid>]) -> f64 {
+        let (pairs, fresh) = teams
+            .iter()
+            .flat_map(|team| {
+                team.iter().enumerate().flat_map(move |(idx, &player_a)| {
+                    team[idx + 1..]
+                        .iter()
+                        .map(move |&player_b| (player_a, player_b))
+                })
+            })
+            .fold((0usize, 0usize), |(pairs, fresh), (player_a, player_b)| {
+                let is_fresh = self.times_paired(player_a, player_b) == 0;
+                (pairs + 1, fresh + usize::from(is_fresh))
+            });
+
+        if pairs == 0 {
+            1.0
+        } else {
+            fresh as f64 / pairs as f64
+        }
+    }
+}
+
+/// Players who must or must not share a team.
+///
+/// Constraints on players who are not in a match are ignored.
+#[derive(Debug, Clone, Default)]
+pub struct PairConstraints {
+    together: Vec<(Uuid, Uuid)>,
+    apart: Vec<(Uuid, Uuid)>,
+}
+
+impl PairConstraints {
+    pub fn keep_together(&mut self, player_a: Uuid, player_b: Uuid) {
+        self.together.push((player_a, player_b));
+    }
+
+    pub fn keep_apart(&mut self, player_a: Uuid, player_b: Uuid) {
+        self.apart.push((player_a, player_b));
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.together.is_empty() && self.apart.is_empty()
+    }
+
+    /// Checks that teams honour every constraint between their players.
+    ///
+    /// # Errors
+    ///
+    /// Returns `AppError::InvalidInput` naming the first constraint broken
+    pub fn check_teams(&self, teams: &[Team]) -> Result<()> {
+        let team_of: HashMap<Uuid, (i32, &str)> = teams
+            .iter()
+            .flat_map(|team| {
+                team.players
+                    .iter()
+                    .map(move |player| (player.id, (team.team_num, player.name.as_str())))
+            })
+            .collect();
+        let pair = |(player_a, player_b): &(Uuid, Uuid)| {
+            Some((*team_of.get(player_a)?, *team_of.get(player_b)?))
+        };
+
+        for ((team_a, name_a), (team_b, name_b)) in self.together.iter().filter_map(pair) {
+            if team_a != team_b {
+                return Err(AppError::InvalidInput(format!(
+                    "{name_a} and {name_b} must be kept together, but are on teams {team_a} and {team_b}"
+                )));
+            }
+        }
+
+        for ((team_a, name_a), (team_b, name_b)) in self.apart.iter().filter_map(pair) {
+            if team_a == team_b {
+                return Err(AppError::InvalidInput(format!(
+                    "{name_a} and {name_b} must be kept apart, but are both on team {team_a}"
+                )));
+            }
+        }
+
+        Ok(())
+    }
+
+    /// The constraints without the `index`-th one, counting keep-together
+    /// constraints first.
+    fn without(&self, index: usize) -> Self {
+        let mut constraints = self.clone();
+        if index < constraints.together.len() {
+            constraints.together.remove(index);
+        } else {
+            constraints.apart.remove(index - self.together.len());
+        }
+        constraints
+    }
+
+    fn len(&self) -> usize {
+        self.together.len() + self.apart.len()
+    }
+}
+
 /// Represents a team with its players and total ELO rating
 #[derive(Debug, Clone)]
 pub struct Team {
@@ -80,7 +300,7 @@ pub fn calculate_team_sizes(num_players: usize, num_teams: usize) -> Vec<usize>
 /// # Arguments
 ///
 /// * `players` - Slice of players to allocate to teams
-/// * `players_per_race` - Maximum number of players per race (determines number of teams)
+/// * `num_teams` - Number of teams to create (at most one per player)
 /// * `elo_ratings` - Map of player IDs to ELO ratings (typically tournament ELO)
 ///
 /// # Returns
@@ -107,11 +327,45 @@ pub fn calculate_team_sizes(num_players: usize, num_teams: usize) -> Vec<usize>
 /// // Team 1: Alice (1400), Team 2: Bob (1200), Team 1: Charlie (1000)
 /// // Results in balanced teams: Team 1 (2400), Team 2 (1200)
 /// ```
-#[instrument(level = "info", skip(players, elo_ratings), fields(num_players = players.len(), players_per_race = *players_per_race))]
+#[instrument(level = "info", skip(players, elo_ratings), fields(num_players = players.len(), num_teams = *num_teams))]
 pub fn allocate_teams(
     players: &[models::Player],
-    players_per_race: &i32,
+    num_teams: &i32,
+    elo_ratings: &HashMap<Uuid, i32>,
+) -> Vec<Team> {
+    allocate_teams_with_history(
+        players,
+        num_teams,
+        elo_ratings,
+        &PairingHistory::default(),
+        0,
+    )
+}
+
+/// Allocates players to teams greedily, steering away from repeat teammates.
+///
+/// Works like `allocate_teams`, except that each player goes to the team with
+/// the lowest total ELO plus `repeat_penalty` for every earlier pairing with
+/// the team's players.
+///
+/// # Arguments
+///
+/// * `players` - Slice of players to allocate to teams
+/// * `num_teams` - Number of teams to create (at most one per player)
+/// * `elo_ratings` - Map of player IDs to ELO ratings (typically tournament ELO)
+/// * `history` - Earlier teammate pairings
+/// * `repeat_penalty` - ELO charged per earlier pairing
+///
+/// # Returns
+///
+/// Vector of teams with players distributed across them
+#[instrument(level = "info", skip(players, elo_ratings, history), fields(num_players = players.len(), num_teams = *num_teams))]
+pub fn allocate_teams_with_history(
+    players: &[models::Player],
+    num_teams: &i32,
     elo_ratings: &HashMap<Uuid, i32>,
+    history: &PairingHistory,
+    repeat_penalty: i32,
 ) -> Vec<Team> {
     let get_elo = |player: &models::Player| -> i32 {
         elo_ratings
@@ -124,7 +378,7 @@ pub fn allocate_teams(
     sorted_players.sort_by(|a, b| get_elo(b).cmp(&get_elo(a)));
 
     let num_players = players.len();
-    let num_teams = std::cmp::min(*players_per_race as usize, num_players);
+    let num_teams = std::cmp::min(*num_teams as usize, num_players);
     let team_sizes = calculate_team_sizes(num_players, num_teams);
 
     let initial_teams: Vec<Team> = (0..num_teams)
@@ -143,7 +397,12 @@ pub fn allocate_teams(
                 .iter()
                 .enumerate()
                 .filter(|(idx, team)| team.players.len() < team_sizes[*idx])
-                .min_by_key(|(_, team)| team.total_elo)
+                .min_by_key(|(_, team)| {
+                    let teammates: Vec<Uuid> = team.players.iter().map(|p| p.id).collect();
+                    team.total_elo as i64
+                        + repeat_penalty as i64
+                            * history.times_paired_with(player.id, &teammates) as i64
+                })
                 .map(|(idx, _)| idx)
                 .unwrap_or(0);
 
@@ -175,10 +434,10 @@ pub fn allocate_teams(
 /// Players are shuffled randomly and then assigned sequentially to teams.
 /// Team sizes are still balanced using `calculate_team_sizes`.
 /// Total ELO is computed per team for display purposes.
-#[instrument(level = "info", skip(players, elo_ratings), fields(num_players = players.len(), players_per_race = *players_per_race))]
+#[instrument(level = "info", skip(players, elo_ratings), fields(num_players = players.len(), num_teams = *num_teams))]
 pub fn allocate_teams_randomly(
     players: &[models::Player],
-    players_per_race: &i32,
+    num_teams: &i32,
     elo_ratings: &HashMap<Uuid, i32>,
 ) -> Vec<Team> {
     let get_elo = |player: &models::Player| -> i32 {
@@ -192,7 +451,7 @@ pub fn allocate_teams_randomly(
     shuffled_players.shuffle(&mut rand::rng());
 
     let num_players = players.len();
-    let num_teams = std::cmp::min(*players_per_race as usize, num_players);
+    let num_teams = std::cmp::min(*num_teams as usize, num_players);
     let team_sizes = calculate_team_sizes(num_players, num_teams);
 
     // Build prefix sums of team sizes so we can slice the shuffled players
@@ -222,3 +481,695 @@ pub fn allocate_teams_randomly(
         })
         .collect()
 }
+
+/// Allocates players to teams greedily while honouring pair constraints.
+///
+/// Works like `allocate_teams_with_history`, placing keep-together players as
+/// one unit (largest units first) and never putting keep-apart players on the
+/// same team. If the greedy placement gets stuck, the next best team choices
+/// are backtracked into until the constraints are met.
+///
+/// # Arguments
+///
+/// * `players` - Slice of players to allocate to teams
+/// * `num_teams` - Number of teams to create (at most one per player)
+/// * `elo_ratings` - Map of player IDs to ELO ratings (typically tournament ELO)
+/// * `history` - Earlier teammate pairings
+/// * `repeat_penalty` - ELO charged per earlier pairing
+/// * `constraints` - Players who must or must not share a team
+///
+/// # Returns
+///
+/// Result containing the teams with players distributed across them
+///
+/// # Errors
+///
+/// Returns `AppError::InvalidInput` naming the constraint that makes allocation
+/// impossible
+#[instrument(level = "info", skip(players, elo_ratings, history, constraints), fields(num_players = players.len(), num_teams = *num_teams))]
+pub fn allocate_teams_with_constraints(
+    players: &[models::Player],
+    num_teams: &i32,
+    elo_ratings: &HashMap<Uuid, i32>,
+    history: &PairingHistory,
+    repeat_penalty: i32,
+    constraints: &PairConstraints,
+) -> Result<Vec<Team>> {
+    if constraints.is_empty() {
+        return Ok(allocate_teams_with_history(
+            players,
+            num_teams,
+            elo_ratings,
+            history,
+            repeat_penalty,
+        ));
+    }
+
+    let (sorted_players, elos) = sort_by_elo(players, elo_ratings);
+    let sizes = calculate_team_sizes(players.len(), team_count(players, num_teams));
+
+    let team_order = |partition: &Partition, unit: &[usize]| -> Vec<usize> {
+        let unit_ids: Vec<Uuid> = unit.iter().map(|&i| sorted_players[i].id).collect();
+        let mut order: Vec<(i64, usize)> = partition
+            .iter()
+            .enumerate()
+            .map(|(team, members)| {
+                let total: i64 = members.iter().map(|&i| elos[i] as i64).sum();
+                let repeats: u32 = members
+                    .iter()
+                    .map(|&i| history.times_paired_with(sorted_players[i].id, &unit_ids))
+                    .sum();
+                (total + repeat_penalty as i64 * repeats as i64, team)
+            })
+            .collect();
+        order.sort_by_key(|&(key, _)| key);
+        order.into_iter().map(|(_, team)| team).collect()
+    };
+
+    let mut partition =
+        place_with_constraints(&sorted_players, &sizes, constraints, &team_order)?;
+    partition.iter_mut().for_each(|members| members.sort_unstable());
+
+    Ok(build_teams(&sorted_players, &elos, &partition))
+}
+
+/// Allocates players to random teams while honouring pair constraints.
+///
+/// Players are shuffled and teams filled in order, keeping keep-together
+/// players in one unit and moving on to a later team when a keep-apart
+/// partner is already on the current one.
+///
+/// # Errors
+///
+/// Returns `AppError::InvalidInput` naming the constraint that makes allocation
+/// impossible
+#[instrument(level = "info", skip(players, elo_ratings, constraints), fields(num_players = players.len(), num_teams = *num_teams))]
+pub fn allocate_teams_randomly_with_constraints(
+    players: &[models::Player],
+    num_teams: &i32,
+    elo_ratings: &HashMap<Uuid, i32>,
+    constraints: &PairConstraints,
+) -> Result<Vec<Team>> {
+    if constraints.is_empty() {
+        return Ok(allocate_teams_randomly(players, num_teams, elo_ratings));
+    }
+
+    let mut shuffled_players = players.to_vec();
+    shuffled_players.shuffle(&mut rand::rng());
+    let elos: Vec<i32> = shuffled_players
+        .iter()
+        .map(|player| player_elo(player, elo_ratings))
+        .collect();
+    let sizes = calculate_team_sizes(players.len(), team_count(players, num_teams));
+
+    let team_order = |partition: &Partition, _: &[usize]| -> Vec<usize> { (0..partition.len()).collect() };
+    let partition = place_with_constraints(&shuffled_players, &sizes, constraints, &team_order)?;
+
+    Ok(build_teams(&shuffled_players, &elos, &partition))
+}
+
+/// Teams as indices into the ELO-sorted player list
+type Partition = Vec<Vec<usize>>;
+
+fn player_elo(player: &models::Player, elo_ratings: &HashMap<Uuid, i32>) -> i32 {
+    elo_ratings
+        .get(&player.id)
+        .copied()
+        .unwrap_or(player.elo_rating)
+}
+
+/// Players sorted by ELO (highest first) with their ELO ratings.
+fn sort_by_elo(
+    players: &[models::Player],
+    elo_ratings: &HashMap<Uuid, i32>,
+) -> (Vec<models::Player>, Vec<i32>) {
+    let mut sorted_players = players.to_vec();
+    sorted_players.sort_by_key(|player| std::cmp::Reverse(player_elo(player, elo_ratings)));
+    let elos = sorted_players
+        .iter()
+        .map(|player| player_elo(player, elo_ratings))
+        .collect();
+    (sorted_players, elos)
+}
+
+fn team_count(players: &[models::Player], num_teams: &i32) -> usize {
+    std::cmp::min(*num_teams as usize, players.len())
+}
+
+fn build_teams(players: &[models::Player], elos: &[i32], partition: &Partition) -> Vec<Team> {
+    partition
+        .iter()
+        .enumerate()
+        .map(|(team_idx, members)| Team {
+            team_num: (team_idx + 1) as i32,
+            players: members.iter().map(|&i| players[i].clone()).collect(),
+            total_elo: members.iter().map(|&i| elos[i]).sum(),
+        })
+        .collect()
+}
+
+/// Pair constraints resolved to indices into a match's player list.
+#[derive(Debug, Clone, Default)]
+struct ResolvedConstraints {
+    /// Players each player must share a team with, directly or through others
+    together_with: Vec<Vec<usize>>,
+    /// Players each player must not share a team with
+    apart_from: Vec<Vec<usize>>,
+    /// Players grouped by keep-together constraints, largest group first
+    units: Vec<Vec<usize>>,
+}
+
+impl ResolvedConstraints {
+    fn new(constraints: &PairConstraints, players: &[models::Player]) -> Self {
+        let n = players.len();
+        let index_of: HashMap<Uuid, usize> = players
+            .iter()
+            .enumerate()
+            .map(|(idx, player)| (player.id, idx))
+            .collect();
+        let resolve = |pairs: &[(Uuid, Uuid)]| -> Vec<(usize, usize)> {
+            pairs
+                .iter()
+                .filter_map(|(a, b)| Some((*index_of.get(a)?, *index_of.get(b)?)))
+                .filter(|(a, b)| a != b)
+                .collect()
+        };
+
+        // Label every player with the lowest index of their keep-together unit
+        let mut unit_of: Vec<usize> = (0..n).collect();
+        let together = resolve(&constraints.together);
+        let mut changed = true;
+        while changed {
+            changed = false;
+            for &(a, b) in &together {
+                let label = unit_of[a].min(unit_of[b]);
+                for player in [a, b] {
+                    if unit_of[player] != label {
+                        unit_of[player] = label;
+                        changed = true;
+                    }
+                }
+            }
+        }
+
+        let mut units: Vec<Vec<usize>> = (0..n)
+            .filter(|&player| unit_of[player] == player)
+            .map(|label| (0..n).filter(|&player| unit_of[player] == label).collect())
+            .collect();
+        units.sort_by_key(|unit: &Vec<usize>| std::cmp::Reverse(unit.len()));
+
+        let mut together_with = vec![Vec::new(); n];
+        for unit in &units {
+            for &player in unit {
+                together_with[player] = unit.iter().copied().filter(|&p| p != player).collect();
+            }
+        }
+
+        let mut apart_from = vec![Vec::new(); n];
+        for (a, b) in resolve(&constraints.apart) {
+            apart_from[a].push(b);
+            apart_from[b].push(a);
+        }
+
+        Self {
+            together_with,
+            apart_from,
+            units,
+        }
+    }
+
+    /// Rejects constraints that contradict each other or the team sizes.
+    fn check(&self, players: &[models::Player], sizes: &[usize]) -> Result<()> {
+        for (a, partners) in self.apart_from.iter().enumerate() {
+            if let Some(&b) = partners.iter().find(|b| self.together_with[a].contains(b)) {
+                return Err(AppError::InvalidInput(format!(
+                    "{} and {} must be kept apart, but keep-together constraints put them on the same team",
+                    players[a].name, players[b].name
+                )));
+            }
+        }
+
+        let largest_team = sizes.iter().copied().max().unwrap_or(0);
+        if let Some(unit) = self.units.iter().find(|unit| unit.len() > largest_team) {
+            let names: Vec<&str> = unit.iter().map(|&i| players[i].name.as_str()).collect();
+            return Err(AppError::InvalidInput(format!(
+                "{} must be kept together, but teams hold at most {largest_team} players",
+                names.join(", ")
+            )));
+        }
+
+        Ok(())
+    }
+
+    fn conflicts_with(&self, members: &[usize], unit: &[usize]) -> bool {
+        unit.iter()
+            .any(|player| self.apart_from[*player].iter().any(|other| members.contains(other)))
+    }
+
+    /// Places every unit into a team, trying teams in the order `team_order`
+    /// gives and backtracking when a unit fits nowhere.
+    fn place(
+        &self,
+        sizes: &[usize],
+        team_order: &dyn Fn(&Partition, &[usize]) -> Vec<usize>,
+    ) -> Option<Partition> {
+        let mut partition: Partition = sizes.iter().map(|&size| Vec::with_capacity(size)).collect();
+        let mut steps = 0;
+        self.place_from(0, sizes, team_order, &mut partition, &mut steps)
+            .then_some(partition)
+    }
+
+    fn place_from(
+        &self,
+        next: usize,
+        sizes: &[usize],
+        team_order: &dyn Fn(&Partition, &[usize]) -> Vec<usize>,
+        partition: &mut Partition,
+        steps: &mut usize,
+    ) -> bool {
+        let Some(unit) = self.units.get(next) else {
+            return true;
+        };
+
+        let mut tried_empty_sizes = HashSet::new();
+        for team in team_order(partition, unit) {
+            *steps += 1;
+            if *steps > MAX_CONSTRAINED_PLACEMENT_STEPS {
+                return false;
+            }
+            if partition[team].len() + unit.len() > sizes[team]
+                || self.conflicts_with(&partition[team], unit)
+            {
+                continue;
+            }
+            // Empty teams of the same size are interchangeable; only try one
+            if partition[team].is_empty() && !tried_empty_sizes.insert(sizes[team]) {
+                continue;
+            }
+
+            partition[team].extend_from_slice(unit);
+            if self.place_from(next + 1, sizes, team_order, partition, steps) {
+                return true;
+            }
+            let placed = partition[team].len() - unit.len();
+            partition[team].truncate(placed);
+        }
+
+        false
+    }
+}
+
+/// Places players into teams of the given sizes under pair constraints.
+fn place_with_constraints(
+    players: &[models::Player],
+    sizes: &[usize],
+    constraints: &PairConstraints,
+    team_order: &dyn Fn(&Partition, &[usize]) -> Vec<usize>,
+) -> Result<Partition> {
+    let resolved = ResolvedConstraints::new(constraints, players);
+    resolved.check(players, sizes)?;
+
+    if let Some(partition) = resolved.place(sizes, team_order) {
+        return Ok(partition);
+    }
+
+    // Name the first constraint without which the players could be placed
+    let in_order = |partition: &Partition, _: &[usize]| -> Vec<usize> { (0..partition.len()).collect() };
+    let blocking = (0..constraints.len()).find(|&index| {
+        let relaxed = ResolvedConstraints::new(&constraints.without(index), players);
+        relaxed.check(players, sizes).is_ok() && relaxed.place(sizes, &in_order).is_some()
+    });
+
+    let message = match blocking {
+        Some(index) => {
+            let (kind, (player_a, player_b)) = if index < constraints.together.len() {
+                ("together", constraints.together[index])
+            } else {
+                ("apart", constraints.apart[index - constraints.together.len()])
+            };
+            let name = |id: Uuid| {
+                players
+                    .iter()
+                    .find(|player| player.id == id)
+                    .map_or_else(|| id.to_string(), |player| player.name.clone())
+            };
+            format!(
+                "Cannot split players into {} teams while keeping {} and {} {kind}",
+                sizes.len(),
+                name(player_a),
+                name(player_b)
+            )
+        }
+        None => format!(
+            "Cannot split players into {} teams that meet every keep-together and keep-apart constraint",
+            sizes.len()
+        ),
+    };
+
+    Err(AppError::InvalidInput(message))
+}
+
+/// Spread (highest minus lowest) of a set of values
+fn spread(values: impl IntoIterator<Item = f64>) -> f64 {
+    let (min, max) = values
+        .into_iter()
+        .fold((f64::INFINITY, f64::NEG_INFINITY), |(min, max), value| {
+            (min.min(value), max.max(value))
+        });
+    if min.is_finite() { max - min } else { 0.0 }
+}
+
+/// Exchange of two players between teams, as (team, slot) positions in a
+/// partition, with the cost of the partition after the exchange.
+#[derive(Debug, Clone, Copy)]
+struct Swap {
+    cost: f64,
+    a: (usize, usize),
+    b: (usize, usize),
+}
+
+/// Evaluates and searches team partitions of one match.
+struct Balancer<'a> {
+    /// Players sorted by ELO, highest first
+    players: Vec<models::Player>,
+    elos: Vec<i32>,
+    elo_ratings: &'a HashMap<Uuid, i32>,
+    model: &'a RacePointsModel<'a>,
+    history: &'a PairingHistory,
+    repeat_penalty: i32,
+    constraints: ResolvedConstraints,
+}
+
+impl Balancer<'_> {
+    fn to_teams(&self, partition: &Partition) -> Vec<Team> {
+        build_teams(&self.players, &self.elos, partition)
+    }
+
+    /// Whether a player may join a team given the players placed so far
+    /// (those with a lower index).
+    fn may_join(&self, partition: &Partition, team: usize, player: usize) -> bool {
+        let members = &partition[team];
+        self.constraints.together_with[player]
+            .iter()
+            .all(|mate| *mate > player || members.contains(mate))
+            && !self.constraints.conflicts_with(members, &[player])
+    }
+
+    /// Whether two players on different teams may swap places.
+    fn may_swap(&self, partition: &Partition, (team_a, a): (usize, usize), (team_b, b): (usize, usize)) -> bool {
+        let conflicts = |player: usize, team: usize, leaving: usize| {
+            self.constraints.apart_from[player]
+                .iter()
+                .any(|other| *other != leaving && partition[team].contains(other))
+        };
+        self.constraints.together_with[a].is_empty()
+            && self.constraints.together_with[b].is_empty()
+            && !conflicts(a, team_b, b)
+            && !conflicts(b, team_a, a)
+    }
+
+    fn average_elo_spread(&self, partition: &Partition) -> f64 {
+        spread(partition.iter().map(|members| {
+            members.iter().map(|&i| self.elos[i] as f64).sum::<f64>() / members.len() as f64
+        }))
+    }
+
+    /// Spread of the teams' expected points per race.
+    fn points_spread(&self, partition: &Partition) -> f64 {
+        let teams = self.to_teams(partition);
+        let Ok(races) = race_allocation::allocate_races(
+            &self.players,
+            &teams,
+            self.model.num_races,
+            self.model.players_per_race,
+            self.elo_ratings,
+        ) else {
+            return 0.0;
+        };
+
+        let player_teams: HashMap<Uuid, (usize, i32)> = partition
+            .iter()
+            .enumerate()
+            .flat_map(|(team_idx, members)| {
+                members
+                    .iter()
+                    .map(move |&i| (self.players[i].id, (team_idx, self.elos[i])))
+            })
+            .collect();
+
+        let mut team_points = vec![0.0; partition.len()];
+        for race in &races {
+            let ratings: Vec<(Uuid, i32)> = race
+                .player_ids
+                .iter()
+                .filter_map(|id| player_teams.get(id).map(|&(_, elo)| (*id, elo)))
+                .collect();
+            for (player_id, position) in prediction::expected_positions(&ratings, self.model.settings)
+            {
+                if let Some(&(team_idx, _)) = player_teams.get(&player_id) {
+                    team_points[team_idx] +=
+                        scoring::interpolated_points(self.model.points, position);
+                }
+            }
+        }
+
+        spread(team_points) / self.model.num_races.max(1) as f64
+    }
+
+    fn repeat_pairings(&self, partition: &Partition) -> u32 {
+        partition
+            .iter()
+            .map(|members| {
+                let ids: Vec<Uuid> = members.iter().map(|&i| self.players[i].id).collect();
+                self.history.repeat_pairings(&ids)
+            })
+            .sum()
+    }
+
+    fn cost(&self, partition: &Partition) -> f64 {
+        self.average_elo_spread(partition)
+            + POINTS_SPREAD_WEIGHT * self.points_spread(partition)
+            + self.repeat_penalty as f64 * self.repeat_pairings(partition) as f64
+    }
+
+    /// Lower bound on the average-ELO spread of any completion of a partial
+    /// partition, from the range each team's average can still reach.
+    fn spread_lower_bound(
+        &self,
+        next: usize,
+        sizes: &[usize],
+        sums: &[i64],
+        partition: &Partition,
+        prefix: &[i64],
+    ) -> f64 {
+        let n = self.elos.len();
+        let (highest_low, lowest_high) = sizes.iter().enumerate().fold(
+            (f64::NEG_INFINITY, f64::INFINITY),
+            |(highest_low, lowest_high), (team, &size)| {
+                let missing = size - partition[team].len();
+                let max_add = prefix[next + missing] - prefix[next];
+                let min_add = prefix[n] - prefix[n - missing];
+                let low = (sums[team] + min_add) as f64 / size as f64;
+                let high = (sums[team] + max_add) as f64 / size as f64;
+                (highest_low.max(low), lowest_high.min(high))
+            },
+        );
+
+        (highest_low - lowest_high).max(0.0)
+    }
+
+    fn branch(
+        &self,
+        next: usize,
+        sizes: &[usize],
+        prefix: &[i64],
+        partition: &mut Partition,
+        sums: &mut [i64],
+        best: &mut (f64, Partition),
+    ) {
+        if next == self.elos.len() {
+            let cost = self.cost(partition);
+            if cost < best.0 {
+                *best = (cost, partition.clone());
+            }
+            return;
+        }
+
+        if self.spread_lower_bound(next, sizes, sums, partition, prefix) >= best.0 {
+            return;
+        }
+
+        for team in 0..sizes.len() {
+            if partition[team].len() == sizes[team] {
+                continue;
+            }
+            // Empty teams of the same size are interchangeable; only try the first
+            if partition[team].is_empty()
+                && (0..team).any(|other| partition[other].is_empty() && sizes[other] == sizes[team])
+            {
+                continue;
+            }
+            if !self.may_join(partition, team, next) {
+                continue;
+            }
+
+            partition[team].push(next);
+            sums[team] += self.elos[next] as i64;
+            self.branch(next + 1, sizes, prefix, partition, sums, best);
+            sums[team] -= self.elos[next] as i64;
+            partition[team].pop();
+        }
+    }
+
+    fn search_exact(&self, sizes: &[usize], initial: Partition) -> Partition {
+        let prefix: Vec<i64> = std::iter::once(0)
+            .chain(self.elos.iter().scan(0i64, |acc, &elo| {
+                *acc += elo as i64;
+                Some(*acc)
+            }))
+            .collect();
+
+        let mut best = (self.cost(&initial), initial);
+        let mut partition: Partition = sizes.iter().map(|&size| Vec::with_capacity(size)).collect();
+        let mut sums = vec![0i64; sizes.len()];
+        self.branch(0, sizes, &prefix, &mut partition, &mut sums, &mut best);
+
+        best.1
+    }
+
+    fn improve_by_swaps(&self, mut partition: Partition) -> Partition {
+        let mut cost = self.cost(&partition);
+
+        for _ in 0..MAX_LOCAL_SEARCH_SWAPS {
+            let mut best_swap: Option<Swap> = None;
+
+            for team_a in 0..partition.len() {
+                for team_b in team_a + 1..partition.len() {
+                    for slot_a in 0..partition[team_a].len() {
+                        for slot_b in 0..partition[team_b].len() {
+                            let (a, b) = (partition[team_a][slot_a], partition[team_b][slot_b]);
+                            if self.elos[a] == self.elos[b]
+                                || !self.may_swap(&partition, (team_a, a), (team_b, b))
+                            {
+                                continue;
+                            }
+
+                            partition[team_a][slot_a] = b;
+                            partition[team_b][slot_b] = a;
+                            let swapped_cost = self.cost(&partition);
+                            partition[team_a][slot_a] = a;
+                            partition[team_b][slot_b] = b;
+
+                            if best_swap.is_none_or(|best| swapped_cost < best.cost) {
+                                best_swap = Some(Swap {
+                                    cost: swapped_cost,
+                                    a: (team_a, slot_a),
+                                    b: (team_b, slot_b),
+                                });
+                            }
+                        }
+                    }
+                }
+            }
+
+            match best_swap {
+                Some(Swap {
+                    cost: swapped_cost,
+                    a: (team_a, slot_a),
+                    b: (team_b, slot_b),
+                }) if swapped_cost < cost - 1e-9 => {
+                    let a = partition[team_a][slot_a];
+                    partition[team_a][slot_a] = partition[team_b][slot_b];
+                    partition[team_b][slot_b] = a;
+                    partition.iter_mut().for_each(|members| members.sort_unstable());
+                    cost = swapped_cost;
+                }
+                _ => break,
+            }
+        }
+
+        partition
+    }
+}
+
+/// Allocates players to teams with the smallest spread of team average ELO
+/// and expected race points, plus `repeat_penalty` per earlier pairing of
+/// teammates.
+///
+/// Team sizes follow `calculate_team_sizes`. Matches of up to
+/// `EXACT_BALANCING_MAX_PLAYERS` players are searched exhaustively with branch
+/// and bound; larger matches improve the greedy teams by swapping players.
+///
+/// # Arguments
+///
+/// * `players` - Slice of players to allocate to teams
+/// * `num_teams` - Number of teams to create (at most one per player)
+/// * `elo_ratings` - Map of player IDs to ELO ratings (typically tournament ELO)
+/// * `model` - Races, points table and ELO settings to estimate expected points with
+/// * `history` - Earlier teammate pairings
+/// * `repeat_penalty` - ELO of balance cost charged per earlier pairing
+/// * `constraints` - Players who must or must not share a team
+///
+/// # Returns
+///
+/// Result containing the teams with players distributed across them
+///
+/// # Errors
+///
+/// Returns `AppError::InvalidInput` naming the constraint that makes allocation
+/// impossible
+#[instrument(level = "info", skip(players, elo_ratings, model, history, constraints), fields(num_players = players.len(), num_teams = *num_teams))]
+pub fn allocate_teams_optimally(
+    players: &[models::Player],
+    num_teams: &i32,
+    elo_ratings: &HashMap<Uuid, i32>,
+    model: &RacePointsModel,
+    history: &PairingHistory,
+    repeat_penalty: i32,
+    constraints: &PairConstraints,
+) -> Result<Vec<Team>> {
+    let greedy = allocate_teams_with_constraints(
+        players,
+        num_teams,
+        elo_ratings,
+        history,
+        repeat_penalty,
+        constraints,
+    )?;
+    if greedy.len() <= 1 || greedy.len() == players.len() {
+        return Ok(greedy);
+    }
+
+    let (sorted_players, elos) = sort_by_elo(players, elo_ratings);
+    let index_of: HashMap<Uuid, usize> = sorted_players
+        .iter()
+        .enumerate()
+        .map(|(idx, player)| (player.id, idx))
+        .collect();
+
+    let mut initial: Partition = greedy
+        .iter()
+        .map(|team| team.players.iter().map(|player| index_of[&player.id]).collect())
+        .collect();
+    initial.iter_mut().for_each(|members: &mut Vec<usize>| members.sort_unstable());
+
+    let constraints = ResolvedConstraints::new(constraints, &sorted_players);
+    let balancer = Balancer {
+        players: sorted_players,
+        elos,
+        elo_ratings,
+        model,
+        history,
+        repeat_penalty,
+        constraints,
+    };
+
+    let partition = if players.len() <= EXACT_BALANCING_MAX_PLAYERS {
+        let sizes = calculate_team_sizes(players.len(), greedy.len());
+        balancer.search_exact(&sizes, initial)
+    } else {
+        balancer.improve_by_swaps(initial)
+    };
+
+    Ok(balancer.to_teams(&partition))
+}
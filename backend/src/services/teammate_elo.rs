@@ -2,9 +2,11 @@
 //! Teammate ELO Service
 //!
 //! This module provides pure functions for calculating teammate ELO contributions.
-//! When players on a team compete in races, their teammates receive 10% of the
+//! When players on a team compete in races, their teammates receive a share
+//! (20% by default, see `EloSettings::teammate_contribution_ratio`) of the
 //! tournament ELO changes as a bonus contribution.
 
+use crate::services::elo::EloSettings;
 use std::collections::HashMap;
 use uuid::Uuid;
 
@@ -19,9 +21,9 @@ pub struct TeammateContribution {
 
 /// Calculates teammate ELO contributions for a race.
 ///
-/// For each player who participates in a race, their teammates receive 20% of
-/// that player's tournament ELO change. This function performs the pure calculation
-/// logic without any database operations.
+/// For each player who participates in a race, their teammates receive
+/// `settings.teammate_contribution_ratio` of that player's tournament ELO change.
+/// This function performs the pure calculation logic without any database operations.
 ///
 /// # Arguments
 ///
@@ -29,6 +31,7 @@ pub struct TeammateContribution {
 /// * `player_to_team` - Map of player IDs to their team IDs
 /// * `team_to_players` - Map of team IDs to vectors of player IDs on that team
 /// * `tournament_elo_changes` - Map of player IDs to their tournament ELO changes
+/// * `settings` - ELO parameters, including the teammate contribution ratio
 ///
 /// # Returns
 ///
@@ -39,6 +42,7 @@ pub struct TeammateContribution {
 /// # Examples
 ///
 /// ```
+/// use mario_kart_leaderboard_backend::services::elo::EloSettings;
 /// use mario_kart_leaderboard_backend::services::teammate_elo::calculate_teammate_contributions;
 /// use std::collections::HashMap;
 /// use uuid::Uuid;
@@ -64,6 +68,7 @@ pub struct TeammateContribution {
 ///     &player_to_team,
 ///     &team_to_players,
 ///     &elo_changes,
+///     &EloSettings::default(),
 /// );
 ///
 /// assert_eq!(contributions.len(), 1);
@@ -77,6 +82,7 @@ pub fn calculate_teammate_contributions(
     player_to_team: &HashMap<Uuid, Uuid>,
     team_to_players: &HashMap<Uuid, Vec<Uuid>>,
     tournament_elo_changes: &HashMap<Uuid, i32>,
+    settings: &EloSettings,
 ) -> (Vec<TeammateContribution>, HashMap<Uuid, i32>) {
     let mut contributions = Vec::new();
     let mut tournament_elo_adjustments: HashMap<Uuid, i32> = HashMap::new();
@@ -86,7 +92,8 @@ pub fn calculate_teammate_contributions(
             if let Some(teammates) = team_to_players.get(team_id) {
                 if let Some(&tournament_elo_change) = tournament_elo_changes.get(source_player_id) {
                     let contribution_amount =
-                        (tournament_elo_change as f64 * 0.2).round() as i32;
+                        (tournament_elo_change as f64 * settings.teammate_contribution_ratio).round()
+                            as i32;
 
                     for teammate_id in teammates {
                         if teammate_id != source_player_id {
@@ -37,10 +37,59 @@ pub async fn complete_tournament(
         .await
         .map_err(|e| AppError::Internal(format!("Failed to start transaction: {e}")))?;
 
-    let winner_id = calculate_winner(&mut tx, tournament_id).await?;
-    let stats = calculate_all_stats(&mut tx, tournament_id).await?;
+    let updated_tournament = store_winner_and_stats(&mut tx, tournament_id).await?;
 
-    let updated_tournament = Tournament::set_winner(&mut tx, tournament_id, winner_id).await?;
+    tx.commit()
+        .await
+        .map_err(|e| AppError::Internal(format!("Failed to commit transaction: {e}")))?;
+
+    Ok(updated_tournament)
+}
+
+/// Recalculates the winner and stats of a completed tournament inside the
+/// caller's transaction, for when its results have changed since completion
+/// (e.g. after a player merge).
+pub async fn recalculate_completion(
+    tx: &mut Transaction<'_, Postgres>,
+    tournament_id: Uuid,
+) -> Result<Tournament> {
+    TournamentStat::delete_by_tournament_id(tx, tournament_id).await?;
+
+    store_winner_and_stats(tx, tournament_id).await
+}
+
+/// Finds the completed tournaments a player raced in, whose winner and stats
+/// need recalculating when the player's results or standing change.
+pub async fn find_completed_tournaments_with_player(
+    tx: &mut Transaction<'_, Postgres>,
+    player_id: Uuid,
+) -> Result<Vec<Uuid>> {
+    let tournament_ids = sqlx::query_scalar(
+        "SELECT t.id
+         FROM tournaments t
+         WHERE t.winner IS NOT NULL
+           AND EXISTS (
+               SELECT 1
+               FROM matches m
+               JOIN round_players rp ON rp.match_id = m.id
+               WHERE m.tournament_id = t.id AND rp.player_id = $1
+           )",
+    )
+    .bind(player_id)
+    .fetch_all(&mut **tx)
+    .await?;
+
+    Ok(tournament_ids)
+}
+
+async fn store_winner_and_stats(
+    tx: &mut Transaction<'_, Postgres>,
+    tournament_id: Uuid,
+) -> Result<Tournament> {
+    let winner_id = calculate_winner(tx, tournament_id).await?;
+    let stats = calculate_all_stats(tx, tournament_id).await?;
+
+    let updated_tournament = Tournament::set_winner(tx, tournament_id, winner_id).await?;
 
     let stat_records: Vec<_> = stats
         .into_iter()
@@ -55,11 +104,7 @@ pub async fn complete_tournament(
         })
         .collect();
 
-    TournamentStat::insert_batch(&mut tx, &stat_records).await?;
-
-    tx.commit()
-        .await
-        .map_err(|e| AppError::Internal(format!("Failed to commit transaction: {e}")))?;
+    TournamentStat::insert_batch(tx, &stat_records).await?;
 
     Ok(updated_tournament)
 }
@@ -69,10 +114,11 @@ async fn calculate_winner(
     tournament_id: Uuid,
 ) -> Result<Uuid> {
     let result: Option<(Uuid,)> = sqlx::query_as(
-        "SELECT player_id
-         FROM player_tournament_scores
-         WHERE tournament_id = $1
-         ORDER BY elo_rating DESC
+        "SELECT pts.player_id
+         FROM player_tournament_scores pts
+         JOIN players p ON p.id = pts.player_id
+         WHERE pts.tournament_id = $1 AND NOT p.guest
+         ORDER BY pts.elo_rating DESC
          LIMIT 1",
     )
     .bind(tournament_id)
@@ -84,6 +130,7 @@ async fn calculate_winner(
         .ok_or_else(|| AppError::InvalidInput("No players in tournament".to_string()))
 }
 
+/// Calculates the tournament's stats, leaving guests out as the winner does.
 async fn calculate_all_stats(
     tx: &mut Transaction<'_, Postgres>,
     tournament_id: Uuid,
@@ -122,7 +169,8 @@ async fn calculate_race_stats(
                 prs.tournament_elo_after
             FROM player_race_scores prs
             INNER JOIN tournament_matches tm ON prs.match_id = tm.match_id
-            WHERE prs.tournament_elo_change IS NOT NULL
+            INNER JOIN players p ON p.id = prs.player_id
+            WHERE prs.tournament_elo_change IS NOT NULL AND NOT p.guest
         ),
         best_race AS (
             SELECT
@@ -229,12 +277,24 @@ async fn calculate_contribution_stats(
         value_primary: i64,
     }
 
+    // Free-for-all matches have no teammates, so only team matches count
+    let has_team_matches: bool = sqlx::query_scalar(
+        "SELECT EXISTS(SELECT 1 FROM matches WHERE tournament_id = $1 AND mode = 'teams')",
+    )
+    .bind(tournament_id)
+    .fetch_one(&mut **tx)
+    .await?;
+
+    if !has_team_matches {
+        return Ok(Vec::new());
+    }
+
     let rows: Vec<ContributionStatRow> = sqlx::query_as(
         r#"
         WITH tournament_matches AS (
             SELECT id AS match_id
             FROM matches
-            WHERE tournament_id = $1
+            WHERE tournament_id = $1 AND mode = 'teams'
         ),
         contributions AS (
             SELECT
@@ -249,6 +309,8 @@ async fn calculate_contribution_stats(
                 source_player_id AS player_id,
                 SUM(contribution_amount) AS total_contribution
             FROM contributions
+            INNER JOIN players p ON p.id = source_player_id
+            WHERE NOT p.guest
             GROUP BY source_player_id
         ),
         best_teammate AS (
@@ -268,6 +330,8 @@ async fn calculate_contribution_stats(
                 beneficiary_player_id AS player_id,
                 SUM(contribution_amount) AS total_received
             FROM contributions
+            INNER JOIN players p ON p.id = beneficiary_player_id
+            WHERE NOT p.guest
             GROUP BY beneficiary_player_id
         ),
         most_helped AS (
@@ -353,7 +417,8 @@ async fn calculate_match_stats(
                 pms.tournament_elo_change
             FROM player_match_scores pms
             INNER JOIN matches m ON pms.match_id = m.id
-            WHERE m.tournament_id = $1
+            INNER JOIN players p ON p.id = pms.player_id
+            WHERE m.tournament_id = $1 AND NOT p.guest
         ),
         best_match AS (
             SELECT player_id, tournament_elo_change AS value_primary
@@ -0,0 +1,210 @@
+//! Track Rating Service
+//!
+//! This module keeps a separate ELO rating per player and track, to show who
+//! races a track better than their overall rating suggests. Track ratings are
+//! calculated with the plain ELO formula and the group's `EloSettings`
+//! (regardless of the group's rating system), using only earlier races on the
+//! same track. They do not affect the leaderboards.
+//!
+//! - `record_track_ratings` updates the ratings of a race's players on its
+//!   track when results are recorded
+//! - `recompute_group_track_ratings` rebuilds a group's track ratings from
+//!   every recorded race in replay order, after results are amended or undone
+//!   and whenever the group's ELO is recomputed
+
+use crate::error::{AppError, Result};
+use crate::models;
+use crate::services::elo::{self, EloSettings, PlayerResult};
+use sqlx::{FromRow, Postgres, Transaction};
+use std::collections::HashMap;
+use uuid::Uuid;
+
+/// Rating used for a player's first race on a track.
+pub const STARTING_TRACK_ELO: i32 = 1200;
+
+/// Calculates the track ratings of a race's players after the race.
+///
+/// # Arguments
+///
+/// * `results` - Slice of (player_id, position) tuples for race participants
+/// * `track_elos` - Current track ratings; players missing from the map start
+///   at `STARTING_TRACK_ELO`
+/// * `settings` - ELO parameters to calculate with
+///
+/// # Returns
+///
+/// Vector of (player_id, new track ELO) tuples
+pub fn calculate_track_elos(
+    results: &[(Uuid, i32)],
+    track_elos: &HashMap<Uuid, i32>,
+    settings: &EloSettings,
+) -> Vec<(Uuid, i32)> {
+    let player_results: Vec<PlayerResult> = results
+        .iter()
+        .map(|&(player_id, position)| PlayerResult {
+            player_id,
+            position,
+            current_elo: track_elos
+                .get(&player_id)
+                .copied()
+                .unwrap_or(STARTING_TRACK_ELO),
+        })
+        .collect();
+
+    elo::calculate_elo_changes(&player_results, settings)
+        .into_iter()
+        .map(|change| (change.player_id, change.new_elo))
+        .collect()
+}
+
+/// Updates track ratings for a newly recorded race.
+///
+/// # Arguments
+///
+/// * `tx` - Active database transaction
+/// * `match_id` - UUID of the match
+/// * `round_number` - Round number (1-indexed)
+/// * `results` - Slice of (player_id, position) tuples for race participants
+/// * `settings` - The group's ELO settings
+///
+/// # Errors
+///
+/// Returns an error if any database operation fails
+pub async fn record_track_ratings(
+    tx: &mut Transaction<'_, Postgres>,
+    match_id: Uuid,
+    round_number: i32,
+    results: &[(Uuid, i32)],
+    settings: &EloSettings,
+) -> Result<()> {
+    let track_id: Option<Uuid> = sqlx::query_scalar(
+        "SELECT track_id FROM rounds WHERE match_id = $1 AND round_number = $2",
+    )
+    .bind(match_id)
+    .bind(round_number)
+    .fetch_optional(&mut **tx)
+    .await?
+    .flatten();
+
+    let Some(track_id) = track_id else {
+        return Ok(());
+    };
+
+    let player_ids: Vec<Uuid> = results.iter().map(|(id, _)| *id).collect();
+    let track_elos = models::PlayerTrackRating::get_batch(tx, &player_ids, track_id).await?;
+
+    let new_elos = calculate_track_elos(results, &track_elos, settings);
+
+    models::PlayerTrackRating::record_race_batch(tx, track_id, &new_elos)
+        .await
+        .map_err(|e| AppError::Internal(format!("Failed to update track ratings: {e}")))?;
+
+    Ok(())
+}
+
+#[derive(Debug, FromRow)]
+struct TrackRaceResult {
+    track_id: Uuid,
+    match_id: Uuid,
+    round_number: i32,
+    player_id: Uuid,
+    position: i32,
+}
+
+/// Rebuilds every track rating of a group from its recorded races.
+///
+/// Disconnected players are left out of the races they dropped from, as when
+/// results are recorded.
+///
+/// # Arguments
+///
+/// * `tx` - Active database transaction
+/// * `group_id` - UUID of the group
+///
+/// # Returns
+///
+/// Result containing the number of races replayed
+///
+/// # Errors
+///
+/// Returns an error if any database operation fails
+pub async fn recompute_group_track_ratings(
+    tx: &mut Transaction<'_, Postgres>,
+    group_id: Uuid,
+) -> Result<usize> {
+    let settings = models::GroupSettings::find_elo_settings(&mut **tx, group_id).await?;
+
+    let rows = sqlx::query_as::<_, TrackRaceResult>(
+        "SELECT r.track_id, prs.match_id, prs.round_number, prs.player_id, prs.position
+         FROM player_race_scores prs
+         JOIN matches m ON m.id = prs.match_id
+         JOIN rounds r ON r.match_id = prs.match_id AND r.round_number = prs.round_number
+         WHERE prs.group_id = $1 AND r.track_id IS NOT NULL AND prs.status <> 'disconnected'
+         ORDER BY m.time ASC, prs.round_number ASC, prs.match_id ASC, prs.position ASC",
+    )
+    .bind(group_id)
+    .fetch_all(&mut **tx)
+    .await
+    .map_err(|e| AppError::Internal(format!("Failed to load races for track ratings: {e}")))?;
+
+    let races: Vec<(Uuid, Vec<(Uuid, i32)>)> = rows
+        .chunk_by(|a, b| (a.match_id, a.round_number) == (b.match_id, b.round_number))
+        .map(|race| {
+            let results = race.iter().map(|row| (row.player_id, row.position)).collect();
+            (race[0].track_id, results)
+        })
+        .collect();
+
+    let ratings = replay_track_ratings(&races, &settings);
+
+    let rows: Vec<(Uuid, Uuid, i32, i32)> = ratings
+        .into_iter()
+        .map(|((player_id, track_id), (elo, races_played))| {
+            (player_id, track_id, elo, races_played)
+        })
+        .collect();
+
+    models::PlayerTrackRating::replace_for_group(tx, group_id, &rows)
+        .await
+        .map_err(|e| AppError::Internal(format!("Failed to store track ratings: {e}")))?;
+
+    Ok(races.len())
+}
+
+/// Replays races in order and returns the resulting track ratings.
+///
+/// # Arguments
+///
+/// * `races` - (track_id, results) per race in replay order, where results are
+///   (player_id, position) tuples
+/// * `settings` - ELO parameters to calculate with
+///
+/// # Returns
+///
+/// Map of (player_id, track_id) to (track ELO, races played on the track)
+pub fn replay_track_ratings(
+    races: &[(Uuid, Vec<(Uuid, i32)>)],
+    settings: &EloSettings,
+) -> HashMap<(Uuid, Uuid), (i32, i32)> {
+    races
+        .iter()
+        .fold(HashMap::new(), |mut ratings, (track_id, results)| {
+            let track_elos: HashMap<Uuid, i32> = results
+                .iter()
+                .filter_map(|(player_id, _)| {
+                    ratings
+                        .get(&(*player_id, *track_id))
+                        .map(|&(elo, _)| (*player_id, elo))
+                })
+                .collect();
+
+            for (player_id, new_elo) in calculate_track_elos(results, &track_elos, settings) {
+                let entry = ratings
+                    .entry((player_id, *track_id))
+                    .or_insert((STARTING_TRACK_ELO, 0));
+                *entry = (new_elo, entry.1 + 1);
+            }
+
+            ratings
+        })
+}
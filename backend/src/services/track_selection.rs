@@ -7,75 +7,481 @@
 //!
 //! Uses cycle-position-based selection to avoid the sliding window bug where
 //! tracks could repeat within a cycle at boundary crossings.
+//!
+//! The bag holds the built-in tracks and the group's own tracks; retired
+//! tracks, including built-in tracks the group has retired for itself, are
+//! left out so they are never selected again.
+//!
+//! ## Track Pools
+//!
+//! A match can draw from one of the group's named track pools instead. The
+//! cycle is tracked per bag, so every track in a pool is raced once before
+//! any of them repeats. Pool tracks may be weighted: within a cycle, heavier
+//! tracks tend to be drawn earlier, so they come up more often in
+//! tournaments too short to finish a cycle.
+//!
+//! ## Vetoes
+//!
+//! Each player may veto a few tracks per tournament. A race skips tracks
+//! vetoed by any of its players and takes the next one in the bag, only
+//! racing a vetoed track when nothing else is left. Grand Prix cups are raced
+//! whole, so vetoes do not apply to them.
+//!
+//! ## Grand Prix
+//!
+//! Grand Prix matches race whole cups of four tracks in cup order. The same
+//! shuffle bag works at the cup level: every cup is raced once before any cup
+//! repeats in the tournament. Cups with a retired track are left out.
 
 use crate::db::DbPool;
 use crate::error::{AppError, Result};
 use crate::models;
+use rand::Rng;
 use rand::seq::SliceRandom;
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
 use uuid::Uuid;
 
+/// Number of races in a Grand Prix cup
+pub const RACES_PER_CUP: i32 = 4;
+
+/// Number of tracks each player may veto per tournament
+pub const MAX_TRACK_VETOES: i64 = 3;
+
+/// How a match's tracks are picked
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum TrackFormat {
+    /// Individual tracks from the track shuffle bag
+    #[default]
+    Shuffled,
+    /// Whole cups from the cup shuffle bag
+    GrandPrix,
+}
+
+/// Where a match's tracks come from
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct TrackOptions {
+    pub format: TrackFormat,
+    /// Pool to draw individual tracks from; `None` for every active track.
+    /// Grand Prix matches race whole cups and ignore it.
+    pub track_pool_id: Option<Uuid>,
+}
+
+/// A track picked for a round, with its cup in Grand Prix matches
+#[derive(Debug, Clone)]
+pub struct RoundTrack {
+    pub track: models::Track,
+    pub cup_id: Option<Uuid>,
+    /// Why tracks were skipped for this round, for debugging
+    pub selection_notes: Vec<String>,
+}
+
+/// Picks the tracks of a match's rounds, in race order.
+///
+/// # Arguments
+///
+/// * `pool` - Database connection pool
+/// * `tournament_id` - UUID of the tournament whose history is avoided
+/// * `race_player_ids` - The players in each race, in race order
+/// * `options` - Individual tracks or whole cups, and the track pool
+///
+/// # Returns
+///
+/// Result containing a track per race
+///
+/// # Errors
+///
+/// Returns `AppError::InvalidInput` if a Grand Prix match is not a whole
+/// number of cups, or an error if database queries fail
+pub async fn select_round_tracks(
+    pool: &DbPool,
+    tournament_id: Uuid,
+    race_player_ids: &[Vec<Uuid>],
+    options: TrackOptions,
+) -> Result<Vec<RoundTrack>> {
+    let num_races = race_player_ids.len() as i32;
+
+    match options.format {
+        TrackFormat::Shuffled => {
+            select_tracks_avoiding_vetoes(pool, tournament_id, options.track_pool_id, race_player_ids)
+                .await
+        }
+        TrackFormat::GrandPrix => {
+            if num_races % RACES_PER_CUP != 0 {
+                return Err(AppError::InvalidInput(format!(
+                    "Grand Prix matches race whole cups, so the number of races must be a multiple of {RACES_PER_CUP}"
+                )));
+            }
+
+            Ok(select_cups(pool, tournament_id, num_races / RACES_PER_CUP)
+                .await?
+                .into_iter()
+                .flat_map(|(cup, tracks)| {
+                    tracks.into_iter().map(move |track| RoundTrack {
+                        track,
+                        cup_id: Some(cup.id),
+                        selection_notes: Vec::new(),
+                    })
+                })
+                .collect())
+        }
+    }
+}
+
+/// Picks cups for a Grand Prix match from the tournament's cup shuffle bag.
+///
+/// Only cups with all four tracks are drawn; a cup with a track that is
+/// retired, or that the tournament's group has retired, is left out.
+///
+/// # Arguments
+///
+/// * `pool` - Database connection pool
+/// * `tournament_id` - UUID of the tournament whose history is avoided
+/// * `num_cups` - Number of cups to pick
+///
+/// # Returns
+///
+/// Result containing each cup with its tracks in race order
+///
+/// # Errors
+///
+/// Returns `AppError::InvalidInput` if there are fewer complete cups than
+/// requested, or an error if database queries fail
+pub async fn select_cups(
+    pool: &DbPool,
+    tournament_id: Uuid,
+    num_cups: i32,
+) -> Result<Vec<(models::Cup, Vec<models::Track>)>> {
+    let group_id = models::Tournament::find_by_id(pool, tournament_id)
+        .await?
+        .map(|tournament| tournament.group_id);
+    let all_cups = models::Cup::find_all(pool).await?;
+    let cup_ids: Vec<Uuid> = all_cups.iter().map(|cup| cup.id).collect();
+    let mut cup_tracks: HashMap<Uuid, Vec<models::Track>> =
+        models::Cup::find_tracks_by_cup_ids(pool, &cup_ids, group_id)
+            .await?
+            .into_iter()
+            .filter(|(_, track)| !track.retired)
+            .fold(HashMap::new(), |mut acc, (cup_id, track)| {
+                acc.entry(cup_id).or_default().push(track);
+                acc
+            });
+
+    let complete_cups: Vec<models::Cup> = all_cups
+        .into_iter()
+        .filter(|cup| {
+            cup_tracks
+                .get(&cup.id)
+                .is_some_and(|tracks| tracks.len() == RACES_PER_CUP as usize)
+        })
+        .collect();
+
+    if complete_cups.is_empty() || num_cups as usize > complete_cups.len() {
+        return Err(AppError::InvalidInput(format!(
+            "{num_cups} cups were requested, but only {} cups with four active tracks are available",
+            complete_cups.len()
+        )));
+    }
+
+    // Most recent cup plays first; a cup play is a cup raced in one match
+    let cups_played: Vec<Uuid> = sqlx::query_scalar(
+        "SELECT r.cup_id
+         FROM rounds r
+         JOIN matches m ON m.id = r.match_id
+         WHERE m.tournament_id = $1 AND r.cup_id IS NOT NULL
+         GROUP BY m.id, m.time, r.cup_id
+         ORDER BY m.time DESC, MIN(r.round_number) DESC",
+    )
+    .bind(tournament_id)
+    .fetch_all(pool)
+    .await
+    .map_err(|e| AppError::Internal(format!("Failed to count cups played: {e}")))?;
+
+    let cycle_position = cups_played.len() % complete_cups.len();
+    let current_cycle_cup_ids: HashSet<Uuid> =
+        cups_played.into_iter().take(cycle_position).collect();
+
+    let selected = draw_from_shuffle_bag(
+        complete_cups,
+        |cup| current_cycle_cup_ids.contains(&cup.id),
+        num_cups as usize,
+    );
+
+    Ok(selected
+        .into_iter()
+        .map(|cup| {
+            let tracks = cup_tracks.remove(&cup.id).unwrap_or_default();
+            (cup, tracks)
+        })
+        .collect())
+}
+
+/// Picks individual tracks from the track shuffle bag.
+///
+/// # Arguments
+///
+/// * `pool` - Database connection pool
+/// * `tournament_id` - UUID of the tournament whose history is avoided
+/// * `track_pool_id` - Pool to draw from, or `None` for every active track
+/// * `num_races` - Number of tracks to pick
+///
+/// # Returns
+///
+/// Result containing a distinct track per race
+///
+/// # Errors
+///
+/// Returns `AppError::InvalidInput` if fewer active tracks are available than
+/// races, or an error if database queries fail
 pub async fn select_tracks(
     pool: &DbPool,
     tournament_id: Uuid,
+    track_pool_id: Option<Uuid>,
     num_races: i32,
 ) -> Result<Vec<models::Track>> {
-    let all_tracks = models::Track::find_all(pool).await?;
-    let total_track_count = all_tracks.len();
+    let candidates = find_track_candidates(pool, tournament_id, track_pool_id, num_races).await?;
+
+    Ok(candidates.into_iter().take(num_races as usize).collect())
+}
+
+/// Picks individual tracks from the track shuffle bag, avoiding tracks vetoed
+/// by a player in the race.
+///
+/// Races take the next track in shuffle bag order that nobody in the race has
+/// vetoed. When every remaining track is vetoed by someone in a race, the race
+/// falls back to the next track regardless. Each round's
+/// `selection_notes` record the tracks skipped and any fallback.
+///
+/// # Arguments
+///
+/// * `pool` - Database connection pool
+/// * `tournament_id` - UUID of the tournament whose history and vetoes apply
+/// * `track_pool_id` - Pool to draw from, or `None` for every active track
+/// * `race_player_ids` - The players in each race, in race order
+///
+/// # Returns
+///
+/// Result containing a distinct track per race
+///
+/// # Errors
+///
+/// Returns `AppError::InvalidInput` if fewer active tracks are available than
+/// races, or an error if database queries fail
+pub async fn select_tracks_avoiding_vetoes(
+    pool: &DbPool,
+    tournament_id: Uuid,
+    track_pool_id: Option<Uuid>,
+    race_player_ids: &[Vec<Uuid>],
+) -> Result<Vec<RoundTrack>> {
+    let num_races = race_player_ids.len() as i32;
+    let candidates = find_track_candidates(pool, tournament_id, track_pool_id, num_races).await?;
+
+    let vetoes = models::TrackVeto::find_by_tournament_id(pool, tournament_id).await?;
+    let vetoed_by: HashMap<Uuid, Vec<Uuid>> =
+        vetoes.iter().fold(HashMap::new(), |mut acc, veto| {
+            acc.entry(veto.track_id).or_default().push(veto.player_id);
+            acc
+        });
+
+    let veto_player_ids: Vec<Uuid> = vetoes.iter().map(|veto| veto.player_id).collect();
+    let player_names: HashMap<Uuid, String> =
+        models::Player::find_by_ids(pool, &veto_player_ids)
+            .await?
+            .into_iter()
+            .map(|player| (player.id, player.name))
+            .collect();
+
+    Ok(assign_tracks_to_races(
+        candidates,
+        race_player_ids,
+        &vetoed_by,
+        &player_names,
+    ))
+}
+
+/// Gives each race the first remaining candidate that nobody in the race has
+/// vetoed, or the first remaining candidate if all are vetoed.
+fn assign_tracks_to_races(
+    mut candidates: Vec<models::Track>,
+    race_player_ids: &[Vec<Uuid>],
+    vetoed_by: &HashMap<Uuid, Vec<Uuid>>,
+    player_names: &HashMap<Uuid, String>,
+) -> Vec<RoundTrack> {
+    let vetoers_in_race = |track: &models::Track, players: &[Uuid]| -> Vec<String> {
+        vetoed_by
+            .get(&track.id)
+            .into_iter()
+            .flatten()
+            .filter(|player_id| players.contains(player_id))
+            .map(|player_id| {
+                player_names
+                    .get(player_id)
+                    .cloned()
+                    .unwrap_or_else(|| player_id.to_string())
+            })
+            .collect()
+    };
+
+    race_player_ids
+        .iter()
+        .map(|players| {
+            let unvetoed = candidates
+                .iter()
+                .position(|track| vetoers_in_race(track, players).is_empty());
+            let index = unvetoed.unwrap_or(0);
+
+            let mut selection_notes: Vec<String> = candidates[..index]
+                .iter()
+                .map(|track| {
+                    format!(
+                        "Skipped {}: vetoed by {}",
+                        track.name,
+                        vetoers_in_race(track, players).join(", ")
+                    )
+                })
+                .collect();
+
+            let track = candidates.remove(index);
+            if unvetoed.is_none() {
+                selection_notes.push(format!(
+                    "Every remaining track was vetoed; raced {} despite vetoes by {}",
+                    track.name,
+                    vetoers_in_race(&track, players).join(", ")
+                ));
+            }
+
+            RoundTrack {
+                track,
+                cup_id: None,
+                selection_notes,
+            }
+        })
+        .collect()
+}
+
+/// Orders the active tracks a tournament's match can draw from: tracks not
+/// yet raced in the current cycle first, then the next cycle's.
+async fn find_track_candidates(
+    pool: &DbPool,
+    tournament_id: Uuid,
+    track_pool_id: Option<Uuid>,
+    num_races: i32,
+) -> Result<Vec<models::Track>> {
+    let bag = load_track_bag(pool, tournament_id, track_pool_id).await?;
     let num_races = num_races as usize;
 
-    let total_rounds_played: i64 = sqlx::query_scalar(
-        "SELECT COUNT(*)
+    if bag.len() < num_races {
+        return Err(AppError::InvalidInput(format!(
+            "Only {} active tracks are available to select from, but the match has {num_races} races",
+            bag.len()
+        )));
+    }
+
+    let bag_track_ids: HashSet<Uuid> = bag.iter().map(|(track, _)| track.id).collect();
+
+    // Only rounds raced on tracks in this bag count towards its cycle, so a
+    // pool keeps its own no-repeat guarantee when matches switch pools.
+    let played_track_ids: Vec<Uuid> = sqlx::query_scalar::<_, Uuid>(
+        "SELECT r.track_id
          FROM rounds r
          JOIN matches m ON m.id = r.match_id
-         WHERE m.tournament_id = $1 AND r.track_id IS NOT NULL",
+         WHERE m.tournament_id = $1 AND r.track_id IS NOT NULL
+         ORDER BY m.time DESC, r.round_number DESC",
     )
     .bind(tournament_id)
-    .fetch_one(pool)
+    .fetch_all(pool)
     .await
-    .map_err(|e| AppError::Internal(format!("Failed to count rounds: {e}")))?;
+    .map_err(|e| AppError::Internal(format!("Failed to load played tracks: {e}")))?
+    .into_iter()
+    .filter(|track_id| bag_track_ids.contains(track_id))
+    .collect();
 
-    let cycle_position = (total_rounds_played as usize) % total_track_count;
+    let cycle_position = played_track_ids.len() % bag.len();
+    let current_cycle_track_ids: HashSet<Uuid> =
+        played_track_ids.into_iter().take(cycle_position).collect();
 
-    let current_cycle_track_ids: HashSet<Uuid> = if cycle_position == 0 {
-        HashSet::new()
-    } else {
-        sqlx::query_scalar(
-            "SELECT r.track_id
-             FROM rounds r
-             JOIN matches m ON m.id = r.match_id
-             WHERE m.tournament_id = $1 AND r.track_id IS NOT NULL
-             ORDER BY m.time DESC, r.round_number DESC
-             LIMIT $2",
-        )
-        .bind(tournament_id)
-        .bind(cycle_position as i32)
-        .fetch_all(pool)
-        .await?
-        .into_iter()
-        .collect()
-    };
+    Ok(shuffle_bag_order(
+        bag,
+        |(track, _)| current_cycle_track_ids.contains(&track.id),
+        |(_, weight)| f64::from(*weight),
+    )
+    .into_iter()
+    .map(|(track, _)| track)
+    .collect())
+}
 
-    let (available_tracks, used_tracks): (Vec<models::Track>, Vec<models::Track>) = all_tracks
-        .into_iter()
-        .partition(|track| !current_cycle_track_ids.contains(&track.id));
+/// Loads the active tracks a tournament's match can draw from, with their
+/// weights.
+async fn load_track_bag(
+    pool: &DbPool,
+    tournament_id: Uuid,
+    track_pool_id: Option<Uuid>,
+) -> Result<Vec<(models::Track, i32)>> {
+    match track_pool_id {
+        Some(track_pool_id) => Ok(models::TrackPool::find_tracks_by_pool_ids(pool, &[track_pool_id])
+            .await?
+            .into_iter()
+            .filter(|entry| !entry.track.retired)
+            .map(|entry| (entry.track, entry.weight))
+            .collect()),
+        None => Ok(models::Track::find_active_for_tournament(pool, tournament_id)
+            .await?
+            .into_iter()
+            .map(|track| (track, 1))
+            .collect()),
+    }
+}
+
+/// Draws `count` items at random, preferring those not yet used in the
+/// current cycle.
+fn draw_from_shuffle_bag<T>(items: Vec<T>, is_used: impl Fn(&T) -> bool, count: usize) -> Vec<T> {
+    let (available, used): (Vec<T>, Vec<T>) = items.into_iter().partition(|item| !is_used(item));
 
     let mut rng = rand::rng();
 
-    if available_tracks.len() >= num_races {
-        let mut track_pool = available_tracks;
-        track_pool.shuffle(&mut rng);
-        Ok(track_pool.into_iter().take(num_races).collect())
+    if available.len() >= count {
+        let mut pool = available;
+        pool.shuffle(&mut rng);
+        pool.into_iter().take(count).collect()
     } else {
         // Cycle boundary: take all remaining, then draw from new cycle
-        let mut selected = available_tracks;
-        let remaining_needed = num_races - selected.len();
+        let mut selected = available;
+        let remaining_needed = count - selected.len();
 
-        let mut new_cycle_pool = used_tracks;
+        let mut new_cycle_pool = used;
         new_cycle_pool.shuffle(&mut rng);
         selected.extend(new_cycle_pool.into_iter().take(remaining_needed));
 
         selected.shuffle(&mut rng);
-        Ok(selected)
+        selected
     }
 }
+
+/// Orders every item for drawing: items not yet used in the current cycle
+/// come first, then the new cycle's. Heavier items tend to come earlier
+/// within each part.
+fn shuffle_bag_order<T>(
+    items: Vec<T>,
+    is_used: impl Fn(&T) -> bool,
+    weight: impl Fn(&T) -> f64,
+) -> Vec<T> {
+    let (available, used): (Vec<T>, Vec<T>) = items.into_iter().partition(|item| !is_used(item));
+
+    let mut rng = rand::rng();
+
+    let mut ordered = weighted_shuffle(available, &weight, &mut rng);
+    ordered.extend(weighted_shuffle(used, &weight, &mut rng));
+    ordered
+}
+
+/// Orders items randomly so that an item's chance of coming first is
+/// proportional to its weight (Efraimidis-Spirakis sampling).
+fn weighted_shuffle<T>(items: Vec<T>, weight: impl Fn(&T) -> f64, rng: &mut impl Rng) -> Vec<T> {
+    let mut keyed: Vec<(f64, T)> = items
+        .into_iter()
+        .map(|item| (rng.random::<f64>().powf(1.0 / weight(&item)), item))
+        .collect();
+
+    keyed.sort_by(|(a, _), (b, _)| b.total_cmp(a));
+
+    keyed.into_iter().map(|(_, item)| item).collect()
+}
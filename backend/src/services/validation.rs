@@ -1,10 +1,17 @@
 
 use crate::error::{AppError, Result};
+use crate::services::elo::EloSettings;
+use std::collections::HashSet;
+use uuid::Uuid;
 
 const MIN_NAME_LENGTH: usize = 1;
 const MAX_NAME_LENGTH: usize = 100;
 const MIN_PASSWORD_LENGTH: usize = 8;
 const MAX_PASSWORD_LENGTH: usize = 128;
+const MAX_RACE_SIZE: i32 = 24;
+const MAX_TRACK_TAGS: usize = 20;
+const MAX_TAG_LENGTH: usize = 50;
+const MAX_TRACK_WEIGHT: i32 = 100;
 
 /// Validates a name (for players, groups, etc.)
 pub fn validate_name(name: &str, field_name: &str) -> Result<()> {
@@ -62,3 +69,161 @@ pub fn validate_password(password: &str) -> Result<()> {
 
     Ok(())
 }
+
+/// Validates ELO settings before they are stored for a group
+pub fn validate_elo_settings(settings: &EloSettings) -> Result<()> {
+    if !settings.k_factor.is_finite() || settings.k_factor <= 0.0 {
+        return Err(AppError::InvalidInput(
+            "K-factor must be greater than 0".to_string(),
+        ));
+    }
+
+    if !(2..=MAX_RACE_SIZE).contains(&settings.total_race_size) {
+        return Err(AppError::InvalidInput(format!(
+            "Total race size must be between 2 and {MAX_RACE_SIZE}"
+        )));
+    }
+
+    if !settings.position_score_exponent.is_finite() || settings.position_score_exponent <= 0.0 {
+        return Err(AppError::InvalidInput(
+            "Position score exponent must be greater than 0".to_string(),
+        ));
+    }
+
+    if settings.min_cpu_elo < 0 || settings.cpu_elo_spread < 0 || settings.cpu_elo_decrease < 0 {
+        return Err(AppError::InvalidInput(
+            "CPU ELO settings cannot be negative".to_string(),
+        ));
+    }
+
+    if settings.min_average_elo_for_cpu > settings.max_average_elo_for_cpu {
+        return Err(AppError::InvalidInput(
+            "Minimum average ELO for CPUs cannot exceed the maximum".to_string(),
+        ));
+    }
+
+    if !(0.0..=1.0).contains(&settings.teammate_contribution_ratio) {
+        return Err(AppError::InvalidInput(
+            "Teammate contribution ratio must be between 0 and 1".to_string(),
+        ));
+    }
+
+    if settings.provisional_race_count < 0 {
+        return Err(AppError::InvalidInput(
+            "Provisional race count cannot be negative".to_string(),
+        ));
+    }
+
+    if settings.inactivity_weeks < 1 {
+        return Err(AppError::InvalidInput(
+            "Inactivity weeks must be at least 1".to_string(),
+        ));
+    }
+
+    if settings.inactivity_decay_per_week < 0 {
+        return Err(AppError::InvalidInput(
+            "Inactivity decay cannot be negative".to_string(),
+        ));
+    }
+
+    if settings.repeat_teammate_penalty < 0 {
+        return Err(AppError::InvalidInput(
+            "Repeat teammate penalty cannot be negative".to_string(),
+        ));
+    }
+
+    Ok(())
+}
+
+/// Validates the points of a custom points table, first place first
+pub fn validate_points_table(points: &[i32]) -> Result<()> {
+    if points.is_empty() || points.len() > MAX_RACE_SIZE as usize {
+        return Err(AppError::InvalidInput(format!(
+            "Points tables must cover between 1 and {MAX_RACE_SIZE} positions"
+        )));
+    }
+
+    if points.iter().any(|&p| p < 0) {
+        return Err(AppError::InvalidInput(
+            "Points cannot be negative".to_string(),
+        ));
+    }
+
+    if points.windows(2).any(|pair| pair[1] > pair[0]) {
+        return Err(AppError::InvalidInput(
+            "A position cannot score more points than the position before it".to_string(),
+        ));
+    }
+
+    Ok(())
+}
+
+/// Validates a track name. Track names may also contain `?`, `!`, `&` and
+/// `:`, as in "Great ? Block Ruins".
+pub fn validate_track_name(name: &str) -> Result<()> {
+    let trimmed = name.trim();
+
+    if trimmed.is_empty() || trimmed.len() > MAX_NAME_LENGTH {
+        return Err(AppError::InvalidInput(format!(
+            "Track name must be between {MIN_NAME_LENGTH} and {MAX_NAME_LENGTH} characters long"
+        )));
+    }
+
+    if !trimmed
+        .chars()
+        .all(|c| c.is_alphanumeric() || c.is_whitespace() || "-_'.?!&:".contains(c))
+    {
+        return Err(AppError::InvalidInput(
+            "Track name contains invalid characters".to_string(),
+        ));
+    }
+
+    Ok(())
+}
+
+/// Validates a track's tags
+pub fn validate_track_tags(tags: &[String]) -> Result<()> {
+    if tags.len() > MAX_TRACK_TAGS {
+        return Err(AppError::InvalidInput(format!(
+            "A track can have at most {MAX_TRACK_TAGS} tags"
+        )));
+    }
+
+    if tags
+        .iter()
+        .any(|tag| tag.trim().is_empty() || tag.trim().len() > MAX_TAG_LENGTH)
+    {
+        return Err(AppError::InvalidInput(format!(
+            "Tags must be between 1 and {MAX_TAG_LENGTH} characters long"
+        )));
+    }
+
+    Ok(())
+}
+
+/// Validates a track pool's `(track_id, weight)` entries
+pub fn validate_track_pool(tracks: &[(Uuid, i32)]) -> Result<()> {
+    if tracks.is_empty() {
+        return Err(AppError::InvalidInput(
+            "A track pool must contain at least one track".to_string(),
+        ));
+    }
+
+    let unique_tracks: HashSet<Uuid> = tracks.iter().map(|(track_id, _)| *track_id).collect();
+    if unique_tracks.len() != tracks.len() {
+        return Err(AppError::InvalidInput(
+            "A track can only appear once in a track pool".to_string(),
+        ));
+    }
+
+    if tracks
+        .iter()
+        .any(|(_, weight)| !(1..=MAX_TRACK_WEIGHT).contains(weight))
+    {
+        return Err(AppError::InvalidInput(format!(
+            "Track weights must be between 1 and {MAX_TRACK_WEIGHT}"
+        )));
+    }
+
+    Ok(())
+}
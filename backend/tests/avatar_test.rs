@@ -0,0 +1,213 @@
+mod common;
+
+use async_graphql::{Request, UploadValue, Variables};
+use common::{fixtures, setup};
+use image::{DynamicImage, ImageFormat, RgbImage};
+use mario_kart_leaderboard_backend::graphql::context::GraphQLContext;
+use mario_kart_leaderboard_backend::services::avatar::{
+    AVATAR_SIZES, MAX_AVATAR_BYTES, is_uploaded_avatar, new_avatar_filename, process_avatar,
+    storage_key,
+};
+use mario_kart_leaderboard_backend::services::avatar_storage::{
+    self, AvatarStorage, LocalDiskStorage,
+};
+use mario_kart_leaderboard_backend::services::notification_manager::NotificationManager;
+use std::io::Cursor;
+use uuid::Uuid;
+
+fn encode_test_image(width: u32, height: u32, format: ImageFormat) -> Vec<u8> {
+    let image = DynamicImage::ImageRgb8(RgbImage::from_fn(width, height, |x, y| {
+        image::Rgb([(x % 256) as u8, (y % 256) as u8, 128])
+    }));
+    let mut bytes = Cursor::new(Vec::new());
+    image
+        .write_to(&mut bytes, format)
+        .expect("Failed to encode test image");
+    bytes.into_inner()
+}
+
+#[test]
+fn test_process_avatar_resizes_to_every_size() {
+    let images = process_avatar(&encode_test_image(300, 200, ImageFormat::Png))
+        .expect("Failed to process avatar");
+
+    assert_eq!(
+        images.iter().map(|image| image.size).collect::<Vec<_>>(),
+        AVATAR_SIZES.to_vec()
+    );
+    for processed in images {
+        let decoded = image::load_from_memory_with_format(&processed.bytes, ImageFormat::Jpeg)
+            .expect("Processed avatar should be a JPEG");
+        assert_eq!(
+            (decoded.width(), decoded.height()),
+            (processed.size, processed.size),
+            "Avatars should be cropped to a square"
+        );
+    }
+}
+
+#[test]
+fn test_process_avatar_rejects_invalid_uploads() {
+    let cases = [
+        (b"definitely not an image".to_vec(), "PNG, JPEG or WebP"),
+        (b"BM\0\0\0\0 a bitmap header".to_vec(), "PNG, JPEG or WebP"),
+        (vec![0; MAX_AVATAR_BYTES + 1], "at most 5 MB"),
+        (encode_test_image(5000, 1, ImageFormat::Png), "could not be read"),
+    ];
+
+    for (bytes, expected) in cases {
+        let error = process_avatar(&bytes).expect_err("Upload should be rejected");
+        assert!(
+            error.to_string().contains(expected),
+            "Expected {expected:?}, got: {error}"
+        );
+    }
+}
+
+#[test]
+fn test_only_uploaded_avatar_filenames_are_recognised() {
+    assert!(is_uploaded_avatar(&new_avatar_filename(Uuid::new_v4())));
+    assert!(!is_uploaded_avatar("luigi.jpg"));
+    assert!(!is_uploaded_avatar("../../etc/passwd"));
+    assert!(!is_uploaded_avatar(&format!("{}-../x.jpg", Uuid::new_v4())));
+}
+
+#[tokio::test]
+async fn test_local_disk_storage_round_trip() {
+    let storage = LocalDiskStorage::new(std::env::temp_dir().join(Uuid::new_v4().to_string()));
+
+    storage
+        .put("64/avatar.jpg", vec![1, 2, 3])
+        .await
+        .expect("Failed to store avatar");
+    assert_eq!(
+        storage.get("64/avatar.jpg").await.expect("Failed to read avatar"),
+        Some(vec![1, 2, 3])
+    );
+
+    storage
+        .delete("64/avatar.jpg")
+        .await
+        .expect("Failed to delete avatar");
+    storage
+        .delete("64/avatar.jpg")
+        .await
+        .expect("Deleting a missing avatar should succeed");
+    assert_eq!(
+        storage.get("64/avatar.jpg").await.expect("Failed to read avatar"),
+        None
+    );
+}
+
+#[tokio::test]
+async fn test_upload_replace_and_remove_player_avatar() {
+    let ctx = setup::setup_test_db().await;
+    let storage = avatar_storage::from_config(&ctx.config);
+
+    let group = fixtures::create_test_group(&ctx.pool, "Test Group", "password")
+        .await
+        .expect("Failed to create test group");
+    let player = fixtures::create_test_player(&ctx.pool, group.id, "Luigi")
+        .await
+        .expect("Failed to create test player");
+
+    let upload = r#"
+        mutation UploadPlayerAvatar($playerId: ID!, $file: Upload!) {
+            uploadPlayerAvatar(playerId: $playerId, file: $file) {
+                avatarFilename
+            }
+        }
+    "#;
+
+    let mut filenames = Vec::new();
+    for _ in 0..2 {
+        let path = std::env::temp_dir().join(format!("{}.png", Uuid::new_v4()));
+        std::fs::write(&path, encode_test_image(40, 40, ImageFormat::Png))
+            .expect("Failed to write upload");
+
+        let mut request = Request::new(upload)
+            .variables(Variables::from_json(serde_json::json!({
+                "playerId": player.id.to_string(),
+                "file": null
+            })))
+            .data(ctx.config.clone());
+        request.set_upload(
+            "variables.file",
+            UploadValue {
+                filename: "avatar.png".to_string(),
+                content_type: Some("image/png".to_string()),
+                content: std::fs::File::open(&path).expect("Failed to open upload"),
+            },
+        );
+
+        let gql_ctx =
+            GraphQLContext::new(ctx.pool.clone(), Some(group.id), NotificationManager::new());
+        let response = ctx.schema.execute(request.data(gql_ctx)).await;
+
+        assert!(
+            response.errors.is_empty(),
+            "Expected no errors: {:?}",
+            response.errors
+        );
+        let data = response.data.into_json().expect("Failed to parse response");
+        filenames.push(
+            data["uploadPlayerAvatar"]["avatarFilename"]
+                .as_str()
+                .expect("avatarFilename should be set")
+                .to_string(),
+        );
+    }
+
+    let (replaced, current) = (&filenames[0], &filenames[1]);
+    assert_ne!(replaced, current, "Every upload should get a new filename");
+    for size in AVATAR_SIZES {
+        assert!(
+            storage
+                .get(&storage_key(size, current))
+                .await
+                .expect("Failed to read avatar")
+                .is_some(),
+            "The {size}px avatar should be stored"
+        );
+        assert!(
+            storage
+                .get(&storage_key(size, replaced))
+                .await
+                .expect("Failed to read avatar")
+                .is_none(),
+            "The replaced {size}px avatar should be deleted"
+        );
+    }
+
+    let remove = r#"
+        mutation RemovePlayerAvatar($playerId: ID!) {
+            removePlayerAvatar(playerId: $playerId) {
+                avatarFilename
+            }
+        }
+    "#;
+
+    let request = Request::new(remove)
+        .variables(Variables::from_json(serde_json::json!({
+            "playerId": player.id.to_string()
+        })))
+        .data(ctx.config.clone());
+    let gql_ctx = GraphQLContext::new(ctx.pool.clone(), Some(group.id), NotificationManager::new());
+    let response = ctx.schema.execute(request.data(gql_ctx)).await;
+
+    assert!(
+        response.errors.is_empty(),
+        "Expected no errors: {:?}",
+        response.errors
+    );
+    let data = response.data.into_json().expect("Failed to parse response");
+    assert!(data["removePlayerAvatar"]["avatarFilename"].is_null());
+    assert!(
+        storage
+            .get(&storage_key(AVATAR_SIZES[0], current))
+            .await
+            .expect("Failed to read avatar")
+            .is_none(),
+        "The removed avatar should be deleted"
+    );
+}
@@ -59,7 +59,7 @@ pub async fn create_test_tournament(
     start_date: Option<NaiveDate>,
     end_date: Option<NaiveDate>,
 ) -> Result<Tournament, sqlx::Error> {
-    Tournament::create(pool, group_id, start_date, end_date).await
+    Tournament::create(pool, group_id, start_date, end_date, None, None).await
 }
 
 /// Create multiple test tournaments for a group
@@ -86,7 +86,7 @@ pub async fn create_test_match(
     sqlx::query_as::<_, Match>(
         "INSERT INTO matches (group_id, tournament_id, time, rounds, completed)
          VALUES ($1, $2, $3, $4, $5)
-         RETURNING id, group_id, tournament_id, time, rounds, completed",
+         RETURNING id, group_id, tournament_id, time, rounds, completed, points_table_id, mode, track_pool_id",
     )
     .bind(group_id)
     .bind(tournament_id)
@@ -139,7 +139,7 @@ pub async fn create_test_round(
     sqlx::query_as::<_, Round>(
         "INSERT INTO rounds (match_id, round_number, track_id, completed)
          VALUES ($1, $2, $3, $4)
-         RETURNING match_id, round_number, track_id, completed",
+         RETURNING match_id, round_number, track_id, completed, cup_id, track_selection_notes",
     )
     .bind(match_id)
     .bind(round_number)
@@ -198,3 +198,25 @@ pub async fn add_players_to_round(
     ).await?;
     Ok(())
 }
+
+/// Add players to a team
+pub async fn add_players_to_team(
+    pool: &PgPool,
+    group_id: Uuid,
+    team_id: Uuid,
+    player_ids: &[Uuid],
+) -> Result<(), sqlx::Error> {
+    futures::future::try_join_all(player_ids.iter().enumerate().map(|(i, player_id)| {
+        sqlx::query(
+            "INSERT INTO team_players (group_id, team_id, player_id, rank)
+             VALUES ($1, $2, $3, $4)",
+        )
+        .bind(group_id)
+        .bind(team_id)
+        .bind(player_id)
+        .bind(i as i32 + 1)
+        .execute(pool)
+    }))
+    .await?;
+    Ok(())
+}
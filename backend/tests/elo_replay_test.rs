@@ -0,0 +1,403 @@
+mod common;
+
+use common::{fixtures, setup};
+use mario_kart_leaderboard_backend::models;
+use mario_kart_leaderboard_backend::services::elo::{EloSettings, PlayerResult, calculate_elo_changes};
+use mario_kart_leaderboard_backend::services::elo_replay::{
+    self, RatingState, STARTING_ELO, replay_race,
+};
+use mario_kart_leaderboard_backend::services::glicko2::MAX_RATING_DEVIATION;
+use mario_kart_leaderboard_backend::services::notification_manager::NotificationManager;
+use mario_kart_leaderboard_backend::services::rating_system::RatingSystemKind;
+use mario_kart_leaderboard_backend::services::result_recording;
+use sqlx::PgPool;
+use std::collections::HashMap;
+use uuid::Uuid;
+
+fn single_team(players: &[Uuid]) -> (HashMap<Uuid, Uuid>, HashMap<Uuid, Vec<Uuid>>) {
+    let team = Uuid::new_v4();
+    let player_to_team = players.iter().map(|p| (*p, team)).collect();
+    let team_to_players = HashMap::from([(team, players.to_vec())]);
+    (player_to_team, team_to_players)
+}
+
+#[test]
+fn test_replay_race_uses_state_ratings() {
+    let player1 = Uuid::new_v4();
+    let player2 = Uuid::new_v4();
+    let tournament = Uuid::new_v4();
+
+    let mut state = RatingState::default();
+    state.all_time.insert(player1, 1400);
+    state.all_time.insert(player2, 1000);
+
+    let results = vec![(player1, 2), (player2, 1)];
+    let replayed = replay_race(
+        &mut state,
+        tournament,
+        &results,
+        &HashMap::new(),
+        &HashMap::new(),
+        &EloSettings::default(),
+    );
+
+    let expected = calculate_elo_changes(
+        &[
+            PlayerResult {
+                player_id: player1,
+                position: 2,
+                current_elo: 1400,
+            },
+            PlayerResult {
+                player_id: player2,
+                position: 1,
+                current_elo: 1000,
+            },
+        ],
+        &EloSettings::default(),
+    );
+
+    assert_eq!(replayed.all_time_elo_changes.len(), expected.len());
+    for change in &expected {
+        assert_eq!(state.all_time.get(&change.player_id), Some(&change.new_elo));
+    }
+}
+
+#[test]
+fn test_replay_race_defaults_unknown_players_to_starting_elo() {
+    let player = Uuid::new_v4();
+    let tournament = Uuid::new_v4();
+
+    let mut state = RatingState::default();
+    let replayed = replay_race(
+        &mut state,
+        tournament,
+        &[(player, 1)],
+        &HashMap::new(),
+        &HashMap::new(),
+        &EloSettings::default(),
+    );
+
+    let all_time = &replayed.all_time_elo_changes[0];
+    let tournament_change = &replayed.tournament_elo_changes[0];
+    assert_eq!(all_time.new_elo - all_time.elo_change, STARTING_ELO);
+    assert_eq!(
+        tournament_change.new_elo - tournament_change.elo_change,
+        STARTING_ELO
+    );
+}
+
+#[test]
+fn test_replay_race_applies_teammate_contributions_to_tournament_only() {
+    let racer = Uuid::new_v4();
+    let teammate = Uuid::new_v4();
+    let tournament = Uuid::new_v4();
+    let (player_to_team, team_to_players) = single_team(&[racer, teammate]);
+
+    let mut state = RatingState::default();
+    state.tournament.insert((teammate, tournament), 1250);
+
+    let replayed = replay_race(
+        &mut state,
+        tournament,
+        &[(racer, 1)],
+        &player_to_team,
+        &team_to_players,
+        &EloSettings::default(),
+    );
+
+    assert_eq!(replayed.teammate_contributions.len(), 1);
+    let contribution = &replayed.teammate_contributions[0];
+    assert_eq!(contribution.beneficiary_player_id, teammate);
+    assert_eq!(
+        state.tournament_elo(teammate, tournament),
+        1250 + contribution.contribution_amount
+    );
+    assert!(!state.all_time.contains_key(&teammate));
+}
+
+#[test]
+fn test_replay_race_keeps_tournaments_separate() {
+    let player = Uuid::new_v4();
+    let tournament_a = Uuid::new_v4();
+    let tournament_b = Uuid::new_v4();
+
+    let mut state = RatingState::default();
+    state.tournament.insert((player, tournament_b), 1500);
+
+    replay_race(
+        &mut state,
+        tournament_a,
+        &[(player, 1)],
+        &HashMap::new(),
+        &HashMap::new(),
+        &EloSettings::default(),
+    );
+
+    assert_eq!(state.tournament_elo(player, tournament_b), 1500);
+    assert_ne!(state.tournament_elo(player, tournament_a), STARTING_ELO);
+}
+
+/// Creates a two-team match with two rounds and records both rounds.
+async fn setup_recorded_match(pool: &PgPool) -> (models::Group, Vec<models::Player>) {
+    let group = fixtures::create_test_group(pool, "Test Group", "password")
+        .await
+        .expect("Failed to create test group");
+    let tournaments = fixtures::create_test_tournaments(pool, group.id, 1)
+        .await
+        .expect("Failed to create test tournaments");
+    let players = fixtures::create_test_players(pool, group.id, 4)
+        .await
+        .expect("Failed to create test players");
+    let match_record = fixtures::create_test_match(pool, group.id, tournaments[0].id, 2)
+        .await
+        .expect("Failed to create test match");
+    let teams = fixtures::create_test_teams(pool, group.id, match_record.id, 2)
+        .await
+        .expect("Failed to create test teams");
+    fixtures::create_test_rounds(pool, match_record.id, 2)
+        .await
+        .expect("Failed to create test rounds");
+
+    for (team, team_players) in teams.iter().zip(players.chunks(2)) {
+        let team_player_ids: Vec<Uuid> = team_players.iter().map(|p| p.id).collect();
+        fixtures::add_players_to_team(pool, group.id, team.id, &team_player_ids)
+            .await
+            .expect("Failed to add players to team");
+        for round_number in 1..=2 {
+            fixtures::add_players_to_round(
+                pool,
+                group.id,
+                match_record.id,
+                round_number,
+                team.id,
+                &team_player_ids,
+            )
+            .await
+            .expect("Failed to add players to round");
+        }
+    }
+
+    for (round_number, positions) in [(1, [1, 5, 2, 9]), (2, [3, 1, 8, 2])] {
+        let results: Vec<(Uuid, i32)> = players.iter().map(|p| p.id).zip(positions).collect();
+        let current_match = models::Match::find_by_id(pool, match_record.id)
+            .await
+            .expect("Failed to fetch match")
+            .expect("Match not found");
+        result_recording::record_race_results(
+            pool,
+            group.id,
+            &result_recording::RoundResults {
+                round_number,
+                results: &results,
+                non_finishers: &[],
+                builds: &[],
+            },
+            &current_match,
+            &NotificationManager::new(),
+        )
+        .await
+        .expect("Failed to record results");
+    }
+
+    (group, players)
+}
+
+async fn fetch_elo_state(pool: &PgPool, group_id: Uuid) -> Vec<(Uuid, i32, i32, i32, i32)> {
+    sqlx::query_as::<_, (Uuid, i32, i32, i32, i32)>(
+        "SELECT p.id, p.elo_rating, pts.elo_rating,
+                (SELECT SUM(all_time_elo_after)::int FROM player_race_scores WHERE player_id = p.id),
+                (SELECT COALESCE(SUM(contribution_amount), 0)::int
+                 FROM player_teammate_elo_contributions WHERE beneficiary_player_id = p.id)
+         FROM players p
+         JOIN player_tournament_scores pts ON pts.player_id = p.id
+         WHERE p.group_id = $1
+         ORDER BY p.id",
+    )
+    .bind(group_id)
+    .fetch_all(pool)
+    .await
+    .expect("Failed to fetch ELO state")
+}
+
+async fn corrupt_elo_state(pool: &PgPool, group_id: Uuid) {
+    sqlx::query("UPDATE players SET elo_rating = elo_rating + 500 WHERE group_id = $1")
+        .bind(group_id)
+        .execute(pool)
+        .await
+        .expect("Failed to corrupt players");
+    sqlx::query(
+        "UPDATE player_race_scores SET all_time_elo_after = 0 WHERE group_id = $1 AND round_number = 2",
+    )
+    .bind(group_id)
+    .execute(pool)
+    .await
+    .expect("Failed to corrupt race scores");
+    sqlx::query("DELETE FROM player_teammate_elo_contributions")
+        .execute(pool)
+        .await
+        .expect("Failed to delete contributions");
+}
+
+#[tokio::test]
+async fn test_recompute_group_restores_recorded_elo() {
+    let ctx = setup::setup_test_db().await;
+    let (group, _players) = setup_recorded_match(&ctx.pool).await;
+
+    let recorded = fetch_elo_state(&ctx.pool, group.id).await;
+    corrupt_elo_state(&ctx.pool, group.id).await;
+
+    let report = elo_replay::recompute_group(&ctx.pool, group.id, false)
+        .await
+        .expect("Failed to recompute group");
+
+    assert_eq!(report.races_replayed, 2);
+    assert_eq!(report.race_scores_changed, 4);
+    assert_eq!(
+        report
+            .rating_changes
+            .iter()
+            .filter(|c| c.tournament_id.is_none())
+            .count(),
+        4
+    );
+    assert_eq!(fetch_elo_state(&ctx.pool, group.id).await, recorded);
+}
+
+#[tokio::test]
+async fn test_recompute_group_dry_run_writes_nothing() {
+    let ctx = setup::setup_test_db().await;
+    let (group, _players) = setup_recorded_match(&ctx.pool).await;
+
+    corrupt_elo_state(&ctx.pool, group.id).await;
+    let corrupted = fetch_elo_state(&ctx.pool, group.id).await;
+
+    let report = elo_replay::recompute_group(&ctx.pool, group.id, true)
+        .await
+        .expect("Failed to recompute group");
+
+    let all_time_deltas: Vec<i32> = report
+        .rating_changes
+        .iter()
+        .filter(|c| c.tournament_id.is_none())
+        .map(|c| c.after - c.before)
+        .collect();
+    assert_eq!(all_time_deltas, vec![-500; 4]);
+    assert_eq!(fetch_elo_state(&ctx.pool, group.id).await, corrupted);
+}
+
+#[tokio::test]
+async fn test_recompute_group_is_idempotent() {
+    let ctx = setup::setup_test_db().await;
+    let (group, _players) = setup_recorded_match(&ctx.pool).await;
+
+    let report = elo_replay::recompute_group(&ctx.pool, group.id, false)
+        .await
+        .expect("Failed to recompute group");
+
+    assert!(
+        report.rating_changes.is_empty(),
+        "Recomputing freshly recorded results should change nothing: {:?}",
+        report.rating_changes
+    );
+    assert_eq!(report.race_scores_changed, 0);
+}
+
+#[tokio::test]
+async fn test_recompute_group_uses_group_settings() {
+    let ctx = setup::setup_test_db().await;
+    let (group, players) = setup_recorded_match(&ctx.pool).await;
+
+    let settings = EloSettings {
+        k_factor: 50.0,
+        teammate_contribution_ratio: 0.0,
+        ..EloSettings::default()
+    };
+    models::GroupSettings::upsert(&ctx.pool, group.id, &settings)
+        .await
+        .expect("Failed to store group settings");
+
+    elo_replay::recompute_group(&ctx.pool, group.id, false)
+        .await
+        .expect("Failed to recompute group");
+
+    let first_race: Vec<PlayerResult> = players
+        .iter()
+        .zip([1, 5, 2, 9])
+        .map(|(player, position)| PlayerResult {
+            player_id: player.id,
+            position,
+            current_elo: STARTING_ELO,
+        })
+        .collect();
+    let expected = calculate_elo_changes(&first_race, &settings);
+
+    let stored: HashMap<Uuid, i32> = sqlx::query_as::<_, (Uuid, i32)>(
+        "SELECT player_id, all_time_elo_change FROM player_race_scores
+         WHERE group_id = $1 AND round_number = 1",
+    )
+    .bind(group.id)
+    .fetch_all(&ctx.pool)
+    .await
+    .expect("Failed to fetch race scores")
+    .into_iter()
+    .collect();
+
+    for change in &expected {
+        assert_eq!(stored.get(&change.player_id), Some(&change.elo_change));
+    }
+
+    let contribution_total: i64 = sqlx::query_scalar(
+        "SELECT COALESCE(SUM(ABS(contribution_amount)), 0)::bigint
+         FROM player_teammate_elo_contributions",
+    )
+    .fetch_one(&ctx.pool)
+    .await
+    .expect("Failed to fetch contributions");
+    assert_eq!(contribution_total, 0);
+}
+
+#[tokio::test]
+async fn test_recompute_group_with_glicko2_tracks_uncertainty() {
+    let ctx = setup::setup_test_db().await;
+    let (group, _players) = setup_recorded_match(&ctx.pool).await;
+
+    let settings = EloSettings {
+        rating_system: RatingSystemKind::Glicko2,
+        ..EloSettings::default()
+    };
+    models::GroupSettings::upsert(&ctx.pool, group.id, &settings)
+        .await
+        .expect("Failed to store group settings");
+
+    let report = elo_replay::recompute_group(&ctx.pool, group.id, false)
+        .await
+        .expect("Failed to recompute group");
+    assert!(!report.rating_changes.is_empty());
+
+    let deviations: Vec<(f64, f64)> = sqlx::query_as(
+        "SELECT p.rating_deviation, pts.rating_deviation
+         FROM players p
+         JOIN player_tournament_scores pts ON pts.player_id = p.id
+         WHERE p.group_id = $1",
+    )
+    .bind(group.id)
+    .fetch_all(&ctx.pool)
+    .await
+    .expect("Failed to fetch rating deviations");
+    assert_eq!(deviations.len(), 4);
+    for (all_time, tournament) in deviations {
+        assert!(all_time < MAX_RATING_DEVIATION);
+        assert!(tournament < MAX_RATING_DEVIATION);
+    }
+
+    let report = elo_replay::recompute_group(&ctx.pool, group.id, false)
+        .await
+        .expect("Failed to recompute group");
+    assert!(
+        report.rating_changes.is_empty(),
+        "Recomputing again should change nothing: {:?}",
+        report.rating_changes
+    );
+    assert_eq!(report.race_scores_changed, 0);
+}
@@ -18,7 +18,7 @@ fn test_scenario_four_players_positions_1_to_4() {
         create_player(4, 4, 1200),
     ];
 
-    let changes = calculate_elo_changes(&results);
+    let changes = calculate_elo_changes(&results, &EloSettings::default());
 
     println!("\n=== Scenario: 4 players at 1200 ELO, positions 1-4 ===");
     for change in &changes {
@@ -54,7 +54,7 @@ fn test_scenario_four_players_positions_1_2_4_11() {
         create_player(4, 11, 1200),
     ];
 
-    let changes = calculate_elo_changes(&results);
+    let changes = calculate_elo_changes(&results, &EloSettings::default());
 
     println!("\n=== Scenario: 4 players at 1200 ELO, positions 1, 2, 4, 11 ===");
     for change in &changes {
@@ -80,7 +80,7 @@ fn test_scenario_four_players_positions_1_2_4_11() {
 fn test_elo_two_equal_players_head_to_head() {
     let results = vec![create_player(1, 1, 1200), create_player(2, 2, 1200)];
 
-    let changes = calculate_elo_changes(&results);
+    let changes = calculate_elo_changes(&results, &EloSettings::default());
 
     assert_eq!(changes.len(), 2);
 
@@ -108,7 +108,7 @@ fn test_elo_two_equal_players_head_to_head() {
 fn test_elo_underdog_wins() {
     let results = vec![create_player(1, 1, 1000), create_player(2, 2, 1400)];
 
-    let changes = calculate_elo_changes(&results);
+    let changes = calculate_elo_changes(&results, &EloSettings::default());
 
     let underdog = changes
         .iter()
@@ -121,8 +121,10 @@ fn test_elo_underdog_wins() {
 
     assert!(underdog.elo_change > 0, "Underdog should gain ELO");
 
-    let equal_match_changes =
-        calculate_elo_changes(&[create_player(1, 1, 1200), create_player(2, 2, 1200)]);
+    let equal_match_changes = calculate_elo_changes(
+        &[create_player(1, 1, 1200), create_player(2, 2, 1200)],
+        &EloSettings::default(),
+    );
     let equal_winner = equal_match_changes
         .iter()
         .find(|c| c.player_id == Uuid::from_u128(1))
@@ -138,7 +140,7 @@ fn test_elo_underdog_wins() {
 fn test_elo_with_cpu_opponents() {
     let results = vec![create_player(1, 1, 1200), create_player(2, 2, 1200)];
 
-    let changes = calculate_elo_changes(&results);
+    let changes = calculate_elo_changes(&results, &EloSettings::default());
 
     assert_eq!(changes.len(), 2);
 
@@ -176,7 +178,7 @@ fn test_elo_many_human_players_ranking() {
         create_player(6, 20, 1200),
     ];
 
-    let changes = calculate_elo_changes(&results);
+    let changes = calculate_elo_changes(&results, &EloSettings::default());
 
     let elo_gains: Vec<(u128, i32)> = changes
         .iter()
@@ -221,7 +223,7 @@ fn test_elo_many_human_players_ranking() {
 fn test_elo_edge_case_single_player() {
     let results = vec![create_player(1, 1, 1200)];
 
-    let changes = calculate_elo_changes(&results);
+    let changes = calculate_elo_changes(&results, &EloSettings::default());
 
     assert_eq!(changes.len(), 1);
     let change = &changes[0];
@@ -237,7 +239,7 @@ fn test_elo_edge_case_single_player() {
 fn test_elo_extreme_rating_differences() {
     let results = vec![create_player(1, 1, 500), create_player(2, 2, 2000)];
 
-    let changes = calculate_elo_changes(&results);
+    let changes = calculate_elo_changes(&results, &EloSettings::default());
 
     let underdog = changes
         .iter()
@@ -264,8 +266,8 @@ fn test_elo_extreme_rating_differences() {
 fn test_position_to_score_boundary() {
     use mario_kart_leaderboard_backend::services::elo::position_to_score;
 
-    let score_1st = position_to_score(1);
-    let score_24th = position_to_score(24);
+    let score_1st = position_to_score(1, &EloSettings::default());
+    let score_24th = position_to_score(24, &EloSettings::default());
 
     assert_eq!(score_1st, 1.0, "1st place should have score of 1.0");
     assert_eq!(score_24th, 0.0, "24th place should have score of 0.0");
@@ -277,7 +279,7 @@ fn test_full_field_creation() {
 
     let results = vec![create_player(1, 1, 1200), create_player(2, 5, 1200)];
 
-    let full_field = create_full_field(&results);
+    let full_field = create_full_field(&results, &EloSettings::default());
 
     assert_eq!(full_field.len(), 24, "Full field should have 24 players");
 
@@ -305,7 +307,7 @@ fn test_full_field_creation() {
 fn test_low_elo_player_12th_place_penalty() {
     let results = vec![create_player(1, 12, 1000)];
 
-    let changes = calculate_elo_changes(&results);
+    let changes = calculate_elo_changes(&results, &EloSettings::default());
     let player = &changes[0];
 
     assert!(
@@ -328,7 +330,7 @@ fn test_top_player_3rd_place_protected() {
         create_player(3, 3, 1420),
     ];
 
-    let changes = calculate_elo_changes(&results);
+    let changes = calculate_elo_changes(&results, &EloSettings::default());
     let top_player = changes
         .iter()
         .find(|c| c.player_id == Uuid::from_u128(3))
@@ -350,7 +352,7 @@ fn test_underdog_first_place_increased_gain() {
         create_player(4, 4, 1400),
     ];
 
-    let changes = calculate_elo_changes(&results);
+    let changes = calculate_elo_changes(&results, &EloSettings::default());
     let underdog = changes
         .iter()
         .find(|c| c.player_id == Uuid::from_u128(1))
@@ -372,7 +374,7 @@ fn test_top_4_finishers_rarely_lose_elo() {
         create_player(4, 4, 1200),
     ];
 
-    let changes = calculate_elo_changes(&results);
+    let changes = calculate_elo_changes(&results, &EloSettings::default());
 
     for (i, change) in changes.iter().enumerate() {
         let position = i + 1;
@@ -392,8 +394,8 @@ fn test_cpu_elo_scales_with_race_average() {
     let low_elo_race = vec![create_player(1, 1, 900), create_player(2, 2, 950)];
     let high_elo_race = vec![create_player(1, 1, 1400), create_player(2, 2, 1450)];
 
-    let low_field = create_full_field(&low_elo_race);
-    let high_field = create_full_field(&high_elo_race);
+    let low_field = create_full_field(&low_elo_race, &EloSettings::default());
+    let high_field = create_full_field(&high_elo_race, &EloSettings::default());
 
     let low_cpu_avg: i32 = low_field
         .iter()
@@ -421,9 +423,9 @@ fn test_cpu_elo_scales_with_race_average() {
 fn test_position_scoring_is_nonlinear() {
     use mario_kart_leaderboard_backend::services::elo::position_to_score;
 
-    let score_1st = position_to_score(1);
-    let score_4th = position_to_score(4);
-    let score_12th = position_to_score(12);
+    let score_1st = position_to_score(1, &EloSettings::default());
+    let score_4th = position_to_score(4, &EloSettings::default());
+    let score_12th = position_to_score(12, &EloSettings::default());
 
     let linear_4th = (24.0 - 4.0) / 23.0;
     let linear_12th = (24.0 - 12.0) / 23.0;
@@ -450,3 +452,46 @@ fn test_position_scoring_is_nonlinear() {
         score_4th
     );
 }
+
+#[test]
+fn test_k_factor_scales_elo_changes() {
+    let results = vec![create_player(1, 1, 1200), create_player(2, 8, 1200)];
+
+    let default_changes = calculate_elo_changes(&results, &EloSettings::default());
+    let gentle_changes = calculate_elo_changes(
+        &results,
+        &EloSettings {
+            k_factor: 50.0,
+            ..EloSettings::default()
+        },
+    );
+
+    for (default_change, gentle_change) in default_changes.iter().zip(&gentle_changes) {
+        assert!(
+            (default_change.elo_change - 2 * gentle_change.elo_change).abs() <= 1,
+            "Halving K should halve the change (got {} vs {})",
+            default_change.elo_change,
+            gentle_change.elo_change
+        );
+    }
+}
+
+#[test]
+fn test_smaller_race_size() {
+    let settings = EloSettings {
+        total_race_size: 12,
+        ..EloSettings::default()
+    };
+
+    assert_eq!(position_to_score(1, &settings), 1.0);
+    assert_eq!(position_to_score(12, &settings), 0.0);
+    assert_eq!(
+        position_to_score(20, &settings),
+        0.0,
+        "Positions beyond the race size should score like last place"
+    );
+
+    let results = vec![create_player(1, 1, 1200), create_player(2, 12, 1200)];
+    let full_field = create_full_field(&results, &settings);
+    assert_eq!(full_field.len(), 12);
+}
@@ -0,0 +1,129 @@
+use mario_kart_leaderboard_backend::services::elo::EloSettings;
+use mario_kart_leaderboard_backend::services::glicko2::{
+    MAX_RATING_DEVIATION, calculate_glicko2_changes,
+};
+use mario_kart_leaderboard_backend::services::rating_system::{
+    RatedPlayer, RatingSystemKind, Uncertainty, calculate_rating_changes,
+};
+use uuid::Uuid;
+
+fn create_player(id: u128, position: i32, rating: i32, deviation: f64) -> RatedPlayer {
+    RatedPlayer {
+        player_id: Uuid::from_u128(id),
+        position,
+        rating,
+        uncertainty: Uncertainty {
+            deviation,
+            volatility: 0.06,
+        },
+    }
+}
+
+fn glicko2_settings() -> EloSettings {
+    EloSettings {
+        rating_system: RatingSystemKind::Glicko2,
+        ..EloSettings::default()
+    }
+}
+
+#[test]
+fn test_winner_gains_and_loser_drops() {
+    let players = vec![
+        create_player(1, 1, 1200, 200.0),
+        create_player(2, 12, 1200, 200.0),
+        create_player(3, 24, 1200, 200.0),
+    ];
+
+    let changes = calculate_glicko2_changes(&players, &EloSettings::default());
+
+    assert_eq!(changes.len(), 3);
+    assert!(changes[0].elo_change > 0);
+    assert!(changes[2].elo_change < 0);
+    assert!(changes[0].elo_change > changes[1].elo_change);
+    assert!(changes[1].elo_change > changes[2].elo_change);
+    for (player, change) in players.iter().zip(&changes) {
+        assert_eq!(change.new_elo, player.rating + change.elo_change);
+    }
+}
+
+#[test]
+fn test_uncertain_player_moves_more_than_established_player() {
+    let players = vec![
+        create_player(1, 1, 1200, MAX_RATING_DEVIATION),
+        create_player(2, 2, 1200, 50.0),
+    ];
+
+    let changes = calculate_glicko2_changes(&players, &EloSettings::default());
+
+    assert!(changes[0].elo_change > 0);
+    assert!(changes[1].elo_change > 0);
+    assert!(changes[0].elo_change > changes[1].elo_change);
+}
+
+#[test]
+fn test_racing_reduces_rating_deviation() {
+    let players = vec![
+        create_player(1, 3, 1200, MAX_RATING_DEVIATION),
+        create_player(2, 8, 1300, 150.0),
+    ];
+
+    let changes = calculate_glicko2_changes(&players, &EloSettings::default());
+
+    assert!(changes[0].new_uncertainty.deviation < MAX_RATING_DEVIATION);
+    assert!(changes[1].new_uncertainty.deviation < 150.0);
+    assert!(changes[0].new_uncertainty.volatility > 0.0);
+}
+
+#[test]
+fn test_rating_deviation_never_exceeds_maximum() {
+    let players = vec![create_player(1, 1, 1200, MAX_RATING_DEVIATION)];
+
+    let changes = calculate_glicko2_changes(
+        &players,
+        &EloSettings {
+            total_race_size: 2,
+            ..EloSettings::default()
+        },
+    );
+
+    assert!(changes[0].new_uncertainty.deviation <= MAX_RATING_DEVIATION);
+}
+
+#[test]
+fn test_tied_players_draw() {
+    let players = vec![
+        create_player(1, 2, 1400, 200.0),
+        create_player(2, 2, 1000, 200.0),
+    ];
+
+    let changes = calculate_glicko2_changes(
+        &players,
+        &EloSettings {
+            total_race_size: 2,
+            ..EloSettings::default()
+        },
+    );
+
+    assert!(changes[0].elo_change < 0, "A draw costs the favourite");
+    assert!(changes[1].elo_change > 0, "A draw rewards the underdog");
+    assert!(changes.iter().all(|change| change.new_uncertainty.deviation < 200.0));
+}
+
+#[test]
+fn test_rating_system_dispatches_on_settings() {
+    let players = vec![
+        create_player(1, 1, 1200, MAX_RATING_DEVIATION),
+        create_player(2, 2, 1200, MAX_RATING_DEVIATION),
+    ];
+
+    let elo_changes = calculate_rating_changes(&players, &EloSettings::default());
+    let glicko2_changes = calculate_rating_changes(&players, &glicko2_settings());
+
+    assert_eq!(
+        elo_changes[0].new_uncertainty,
+        players[0].uncertainty,
+        "ELO leaves uncertainty untouched"
+    );
+    assert!(glicko2_changes[0].new_uncertainty.deviation < MAX_RATING_DEVIATION);
+    assert_ne!(elo_changes[0].elo_change, glicko2_changes[0].elo_change);
+}
@@ -4,7 +4,8 @@ use async_graphql::Request;
 use common::{fixtures, setup};
 use mario_kart_leaderboard_backend::{
     graphql::context::GraphQLContext,
-    services::notification_manager::NotificationManager,
+    models,
+    services::{notification_manager::NotificationManager, rating_system::RatingSystemKind},
 };
 
 #[tokio::test]
@@ -74,3 +75,152 @@ async fn test_current_group_without_auth() {
         response.errors[0].message
     );
 }
+
+#[tokio::test]
+async fn test_group_settings_defaults() {
+    let ctx = setup::setup_test_db().await;
+
+    let group = fixtures::create_test_group(&ctx.pool, "Test Group", "password")
+        .await
+        .expect("Failed to create test group");
+
+    let query = r#"
+        query {
+            groupSettings {
+                kFactor
+                totalRaceSize
+                positionScoreExponent
+                teammateContributionRatio
+            }
+        }
+    "#;
+
+    let request = Request::new(query).data(ctx.config.clone());
+
+    let gql_ctx = GraphQLContext::new(ctx.pool.clone(), Some(group.id), NotificationManager::new());
+    let response = ctx.schema.execute(request.data(gql_ctx)).await;
+
+    assert!(
+        response.errors.is_empty(),
+        "Expected no errors: {:?}",
+        response.errors
+    );
+
+    let data = response.data.into_json().expect("Failed to parse response");
+    let settings = &data["groupSettings"];
+
+    assert_eq!(settings["kFactor"].as_f64(), Some(100.0));
+    assert_eq!(settings["totalRaceSize"].as_i64(), Some(24));
+    assert_eq!(settings["positionScoreExponent"].as_f64(), Some(1.5));
+    assert_eq!(settings["teammateContributionRatio"].as_f64(), Some(0.2));
+}
+
+#[tokio::test]
+async fn test_update_group_settings_keeps_omitted_fields() {
+    let ctx = setup::setup_test_db().await;
+
+    let group = fixtures::create_test_group(&ctx.pool, "Test Group", "password")
+        .await
+        .expect("Failed to create test group");
+
+    for (k_factor, race_size) in [("50", "12"), ("40", "null")] {
+        let mutation = format!(
+            r#"
+            mutation {{
+                updateGroupSettings(input: {{ kFactor: {k_factor}, totalRaceSize: {race_size} }}) {{
+                    kFactor
+                }}
+            }}
+        "#
+        );
+
+        let request = Request::new(mutation).data(ctx.config.clone());
+        let gql_ctx =
+            GraphQLContext::new(ctx.pool.clone(), Some(group.id), NotificationManager::new());
+        let response = ctx.schema.execute(request.data(gql_ctx)).await;
+
+        assert!(
+            response.errors.is_empty(),
+            "Expected no errors: {:?}",
+            response.errors
+        );
+    }
+
+    let settings = models::GroupSettings::find_elo_settings(&ctx.pool, group.id)
+        .await
+        .expect("Failed to fetch group settings");
+
+    assert_eq!(settings.k_factor, 40.0);
+    assert_eq!(settings.total_race_size, 12);
+    assert_eq!(settings.position_score_exponent, 1.5);
+}
+
+#[tokio::test]
+async fn test_update_group_settings_rejects_invalid_values() {
+    let ctx = setup::setup_test_db().await;
+
+    let group = fixtures::create_test_group(&ctx.pool, "Test Group", "password")
+        .await
+        .expect("Failed to create test group");
+
+    let mutation = r#"
+        mutation {
+            updateGroupSettings(input: { totalRaceSize: 30 }) {
+                totalRaceSize
+            }
+        }
+    "#;
+
+    let request = Request::new(mutation).data(ctx.config.clone());
+    let gql_ctx = GraphQLContext::new(ctx.pool.clone(), Some(group.id), NotificationManager::new());
+    let response = ctx.schema.execute(request.data(gql_ctx)).await;
+
+    assert!(!response.errors.is_empty(), "Expected validation error");
+    assert!(
+        response.errors[0].message.contains("Total race size"),
+        "Unexpected error: {}",
+        response.errors[0].message
+    );
+
+    let stored = models::GroupSettings::find_by_group_id(&ctx.pool, group.id)
+        .await
+        .expect("Failed to fetch group settings");
+    assert!(stored.is_none(), "Invalid settings should not be stored");
+}
+
+#[tokio::test]
+async fn test_update_group_settings_switches_rating_system() {
+    let ctx = setup::setup_test_db().await;
+
+    let group = fixtures::create_test_group(&ctx.pool, "Test Group", "password")
+        .await
+        .expect("Failed to create test group");
+
+    let mutation = r#"
+        mutation {
+            updateGroupSettings(input: { ratingSystem: GLICKO2 }, recompute: true) {
+                ratingSystem
+                kFactor
+            }
+        }
+    "#;
+
+    let request = Request::new(mutation).data(ctx.config.clone());
+    let gql_ctx = GraphQLContext::new(ctx.pool.clone(), Some(group.id), NotificationManager::new());
+    let response = ctx.schema.execute(request.data(gql_ctx)).await;
+
+    assert!(
+        response.errors.is_empty(),
+        "Expected no errors: {:?}",
+        response.errors
+    );
+
+    let data = response.data.into_json().unwrap();
+    assert_eq!(data["updateGroupSettings"]["ratingSystem"], "GLICKO2");
+    assert_eq!(data["updateGroupSettings"]["kFactor"].as_f64(), Some(100.0));
+
+    let settings = models::GroupSettings::find_elo_settings(&ctx.pool, group.id)
+        .await
+        .expect("Failed to fetch group settings");
+    assert_eq!(settings.rating_system, RatingSystemKind::Glicko2);
+}
@@ -3,8 +3,8 @@ mod common;
 use async_graphql::{Request, Variables, value};
 use common::{fixtures, setup};
 use mario_kart_leaderboard_backend::{
-    graphql::context::GraphQLContext,
-    services::notification_manager::NotificationManager,
+    graphql::context::GraphQLContext, models,
+    services::{notification_manager::NotificationManager, result_recording},
 };
 
 // ============================================================================
@@ -1392,3 +1392,793 @@ async fn test_cancel_match_no_auth() {
         response.errors[0].message
     );
 }
+
+#[tokio::test]
+async fn test_create_match_with_rounds_uses_tournament_points_table() {
+    let ctx = setup::setup_test_db().await;
+
+    let group = fixtures::create_test_group(&ctx.pool, "Test Group", "password")
+        .await
+        .expect("Failed to create test group");
+
+    let tournament = fixtures::create_test_tournaments(&ctx.pool, group.id, 1)
+        .await
+        .expect("Failed to create test tournaments")
+        .remove(0);
+
+    models::Tournament::set_points_table(
+        &ctx.pool,
+        tournament.id,
+        Some(models::points_table::MK_WORLD_POINTS_TABLE_ID),
+    )
+    .await
+    .expect("Failed to set points table");
+
+    let players = fixtures::create_test_players(&ctx.pool, group.id, 4)
+        .await
+        .expect("Failed to create test players");
+
+    let player_ids: Vec<String> = players.iter().map(|p| p.id.to_string()).collect();
+
+    let query = r#"
+        mutation CreateMatch($tournamentId: ID!, $playerIds: [ID!]!) {
+            createMatchWithRounds(tournamentId: $tournamentId, playerIds: $playerIds, numRaces: 2) {
+                pointsTable {
+                    name
+                    points
+                    builtIn
+                }
+            }
+        }
+    "#;
+
+    let request = Request::new(query)
+        .variables(Variables::from_value(value!({
+            "tournamentId": tournament.id.to_string(),
+            "playerIds": player_ids,
+        })))
+        .data(ctx.config.clone());
+
+    let gql_ctx = GraphQLContext::new(ctx.pool.clone(), Some(group.id), NotificationManager::new());
+    let response = ctx.schema.execute(request.data(gql_ctx)).await;
+
+    assert!(
+        response.errors.is_empty(),
+        "Expected no errors: {:?}",
+        response.errors
+    );
+
+    let data = response.data.into_json().expect("Failed to parse response");
+    let points_table = &data["createMatchWithRounds"]["pointsTable"];
+
+    assert_eq!(points_table["name"], "Mario Kart World (24 players)");
+    assert_eq!(points_table["points"].as_array().map(|p| p.len()), Some(24));
+    assert_eq!(points_table["builtIn"], true);
+}
+
+#[tokio::test]
+async fn test_create_match_with_rounds_rejects_other_groups_points_table() {
+    let ctx = setup::setup_test_db().await;
+
+    let group = fixtures::create_test_group(&ctx.pool, "Test Group", "password")
+        .await
+        .expect("Failed to create test group");
+
+    let other_group = fixtures::create_test_group(&ctx.pool, "Other Group", "password")
+        .await
+        .expect("Failed to create other group");
+
+    let other_table = models::PointsTable::create(&ctx.pool, other_group.id, "Custom", &[10, 5])
+        .await
+        .expect("Failed to create points table");
+
+    let tournament = fixtures::create_test_tournaments(&ctx.pool, group.id, 1)
+        .await
+        .expect("Failed to create test tournaments")
+        .remove(0);
+
+    let players = fixtures::create_test_players(&ctx.pool, group.id, 4)
+        .await
+        .expect("Failed to create test players");
+
+    let player_ids: Vec<String> = players.iter().map(|p| p.id.to_string()).collect();
+
+    let query = r#"
+        mutation CreateMatch($tournamentId: ID!, $playerIds: [ID!]!, $pointsTableId: ID) {
+            createMatchWithRounds(
+                tournamentId: $tournamentId
+                playerIds: $playerIds
+                numRaces: 2
+                pointsTableId: $pointsTableId
+            ) {
+                id
+            }
+        }
+    "#;
+
+    let request = Request::new(query)
+        .variables(Variables::from_value(value!({
+            "tournamentId": tournament.id.to_string(),
+            "playerIds": player_ids,
+            "pointsTableId": other_table.id.to_string(),
+        })))
+        .data(ctx.config.clone());
+
+    let gql_ctx = GraphQLContext::new(ctx.pool.clone(), Some(group.id), NotificationManager::new());
+    let response = ctx.schema.execute(request.data(gql_ctx)).await;
+
+    assert!(!response.errors.is_empty(), "Expected not found error");
+    assert!(
+        response.errors[0].message.contains("Points table not found"),
+        "Expected 'Points table not found' error, got: {}",
+        response.errors[0].message
+    );
+}
+
+#[tokio::test]
+async fn test_create_match_with_rounds_accepts_balancing_strategy() {
+    let ctx = setup::setup_test_db().await;
+
+    let group = fixtures::create_test_group(&ctx.pool, "Test Group", "password")
+        .await
+        .expect("Failed to create test group");
+
+    let tournament = fixtures::create_test_tournaments(&ctx.pool, group.id, 1)
+        .await
+        .expect("Failed to create test tournaments")
+        .remove(0);
+
+    let players = fixtures::create_test_players(&ctx.pool, group.id, 6)
+        .await
+        .expect("Failed to create test players");
+
+    let player_ids: Vec<String> = players.iter().map(|p| p.id.to_string()).collect();
+
+    let query = r#"
+        mutation CreateMatch($tournamentId: ID!, $playerIds: [ID!]!, $strategy: BalancingStrategy!) {
+            createMatchWithRounds(
+                tournamentId: $tournamentId
+                playerIds: $playerIds
+                numRaces: 3
+                playersPerRace: 2
+                balancingStrategy: $strategy
+            ) {
+                id
+            }
+        }
+    "#;
+
+    for strategy in ["GREEDY", "OPTIMAL", "RANDOM"] {
+        let request = Request::new(query)
+            .variables(Variables::from_value(value!({
+                "tournamentId": tournament.id.to_string(),
+                "playerIds": player_ids.clone(),
+                "strategy": strategy,
+            })))
+            .data(ctx.config.clone());
+
+        let gql_ctx =
+            GraphQLContext::new(ctx.pool.clone(), Some(group.id), NotificationManager::new());
+        let response = ctx.schema.execute(request.data(gql_ctx)).await;
+
+        assert!(
+            response.errors.is_empty(),
+            "Expected no errors for {strategy}: {:?}",
+            response.errors
+        );
+
+        let data = response.data.into_json().expect("Failed to parse response");
+        let match_id = uuid::Uuid::parse_str(
+            data["createMatchWithRounds"]["id"]
+                .as_str()
+                .expect("id not found"),
+        )
+        .expect("invalid uuid");
+
+        let team_sizes: Vec<i64> = sqlx::query_scalar(
+            "SELECT COUNT(*) FROM team_players tp
+             JOIN teams t ON t.id = tp.team_id
+             WHERE t.match_id = $1
+             GROUP BY t.team_num
+             ORDER BY t.team_num",
+        )
+        .bind(match_id)
+        .fetch_all(&ctx.pool)
+        .await
+        .expect("Failed to fetch team sizes");
+
+        assert_eq!(team_sizes, vec![3, 3], "Unexpected teams for {strategy}");
+    }
+}
+
+#[tokio::test]
+async fn test_create_match_with_rounds_reports_pairing_freshness() {
+    let ctx = setup::setup_test_db().await;
+
+    let group = fixtures::create_test_group(&ctx.pool, "Test Group", "password")
+        .await
+        .expect("Failed to create test group");
+
+    let tournament = fixtures::create_test_tournaments(&ctx.pool, group.id, 1)
+        .await
+        .expect("Failed to create test tournaments")
+        .remove(0);
+
+    let players = fixtures::create_test_players(&ctx.pool, group.id, 4)
+        .await
+        .expect("Failed to create test players");
+
+    let player_ids: Vec<String> = players.iter().map(|p| p.id.to_string()).collect();
+
+    let query = r#"
+        mutation CreateMatch($tournamentId: ID!, $playerIds: [ID!]!) {
+            createMatchWithRounds(
+                tournamentId: $tournamentId
+                playerIds: $playerIds
+                numRaces: 2
+                playersPerRace: 2
+            ) {
+                pairingFreshness
+            }
+        }
+    "#;
+
+    // Equal ratings leave the repeat penalty to pick new teammates each time
+    for _ in 0..2 {
+        let request = Request::new(query)
+            .variables(Variables::from_value(value!({
+                "tournamentId": tournament.id.to_string(),
+                "playerIds": player_ids.clone(),
+            })))
+            .data(ctx.config.clone());
+
+        let gql_ctx =
+            GraphQLContext::new(ctx.pool.clone(), Some(group.id), NotificationManager::new());
+        let response = ctx.schema.execute(request.data(gql_ctx)).await;
+
+        assert!(
+            response.errors.is_empty(),
+            "Expected no errors: {:?}",
+            response.errors
+        );
+
+        let data = response.data.into_json().expect("Failed to parse response");
+        assert_eq!(data["createMatchWithRounds"]["pairingFreshness"], 1.0);
+    }
+
+    let repeat_pairs: i64 = sqlx::query_scalar(
+        "SELECT COUNT(*) FROM (
+             SELECT a.player_id, b.player_id
+             FROM team_players a
+             JOIN team_players b ON b.team_id = a.team_id AND b.player_id > a.player_id
+             GROUP BY a.player_id, b.player_id
+             HAVING COUNT(*) > 1
+         ) repeats",
+    )
+    .fetch_one(&ctx.pool)
+    .await
+    .expect("Failed to count repeat pairs");
+
+    assert_eq!(repeat_pairs, 0);
+}
+
+#[tokio::test]
+async fn test_create_match_with_rounds_honours_pair_constraints() {
+    let ctx = setup::setup_test_db().await;
+
+    let group = fixtures::create_test_group(&ctx.pool, "Test Group", "password")
+        .await
+        .expect("Failed to create test group");
+
+    let tournament = fixtures::create_test_tournaments(&ctx.pool, group.id, 1)
+        .await
+        .expect("Failed to create test tournaments")
+        .remove(0);
+
+    let players = fixtures::create_test_players(&ctx.pool, group.id, 6)
+        .await
+        .expect("Failed to create test players");
+
+    let player_ids: Vec<String> = players.iter().map(|p| p.id.to_string()).collect();
+
+    models::PlayerPairConstraint::upsert(
+        &ctx.pool,
+        group.id,
+        players[2].id,
+        players[3].id,
+        models::PairConstraintKind::Apart,
+    )
+    .await
+    .expect("Failed to store pair constraint");
+
+    let query = r#"
+        mutation CreateMatch(
+            $tournamentId: ID!
+            $playerIds: [ID!]!
+            $keepTogether: [PlayerPairInput!]
+        ) {
+            createMatchWithRounds(
+                tournamentId: $tournamentId
+                playerIds: $playerIds
+                numRaces: 3
+                playersPerRace: 2
+                keepTogether: $keepTogether
+            ) {
+                id
+            }
+        }
+    "#;
+
+    let request = Request::new(query)
+        .variables(Variables::from_value(value!({
+            "tournamentId": tournament.id.to_string(),
+            "playerIds": player_ids.clone(),
+            "keepTogether": [{
+                "firstPlayerId": player_ids[0].clone(),
+                "secondPlayerId": player_ids[5].clone(),
+            }],
+        })))
+        .data(ctx.config.clone());
+
+    let gql_ctx = GraphQLContext::new(ctx.pool.clone(), Some(group.id), NotificationManager::new());
+    let response = ctx.schema.execute(request.data(gql_ctx)).await;
+
+    assert!(
+        response.errors.is_empty(),
+        "Expected no errors: {:?}",
+        response.errors
+    );
+
+    let team_of = |player_id: uuid::Uuid| {
+        sqlx::query_scalar::<_, uuid::Uuid>("SELECT team_id FROM team_players WHERE player_id = $1")
+            .bind(player_id)
+            .fetch_one(&ctx.pool)
+    };
+
+    assert_eq!(
+        team_of(players[0].id).await.expect("Failed to find team"),
+        team_of(players[5].id).await.expect("Failed to find team")
+    );
+    assert_ne!(
+        team_of(players[2].id).await.expect("Failed to find team"),
+        team_of(players[3].id).await.expect("Failed to find team")
+    );
+}
+
+#[tokio::test]
+async fn test_create_match_with_rounds_rejects_impossible_pair_constraints() {
+    let ctx = setup::setup_test_db().await;
+
+    let group = fixtures::create_test_group(&ctx.pool, "Test Group", "password")
+        .await
+        .expect("Failed to create test group");
+
+    let tournament = fixtures::create_test_tournaments(&ctx.pool, group.id, 1)
+        .await
+        .expect("Failed to create test tournaments")
+        .remove(0);
+
+    let players = fixtures::create_test_players(&ctx.pool, group.id, 4)
+        .await
+        .expect("Failed to create test players");
+
+    let player_ids: Vec<String> = players.iter().map(|p| p.id.to_string()).collect();
+
+    let query = r#"
+        mutation CreateMatch(
+            $tournamentId: ID!
+            $playerIds: [ID!]!
+            $keepTogether: [PlayerPairInput!]
+        ) {
+            createMatchWithRounds(
+                tournamentId: $tournamentId
+                playerIds: $playerIds
+                numRaces: 2
+                playersPerRace: 2
+                keepTogether: $keepTogether
+            ) {
+                id
+            }
+        }
+    "#;
+
+    // Three players kept together cannot fit in a team of two
+    let request = Request::new(query)
+        .variables(Variables::from_value(value!({
+            "tournamentId": tournament.id.to_string(),
+            "playerIds": player_ids.clone(),
+            "keepTogether": [
+                { "firstPlayerId": player_ids[0].clone(), "secondPlayerId": player_ids[1].clone() },
+                { "firstPlayerId": player_ids[1].clone(), "secondPlayerId": player_ids[2].clone() },
+            ],
+        })))
+        .data(ctx.config.clone());
+
+    let gql_ctx = GraphQLContext::new(ctx.pool.clone(), Some(group.id), NotificationManager::new());
+    let response = ctx.schema.execute(request.data(gql_ctx)).await;
+
+    assert_eq!(response.errors.len(), 1);
+    assert!(
+        response.errors[0].message.contains("must be kept together"),
+        "Unexpected error: {}",
+        response.errors[0].message
+    );
+
+    let matches: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM matches")
+        .fetch_one(&ctx.pool)
+        .await
+        .expect("Failed to count matches");
+    assert_eq!(matches, 0);
+}
+
+#[tokio::test]
+async fn test_create_match_with_rounds_chooses_team_count_and_size() {
+    let ctx = setup::setup_test_db().await;
+
+    let group = fixtures::create_test_group(&ctx.pool, "Test Group", "password")
+        .await
+        .expect("Failed to create test group");
+
+    let tournament = fixtures::create_test_tournaments(&ctx.pool, group.id, 1)
+        .await
+        .expect("Failed to create test tournaments")
+        .remove(0);
+
+    let players = fixtures::create_test_players(&ctx.pool, group.id, 6)
+        .await
+        .expect("Failed to create test players");
+
+    let player_ids: Vec<String> = players.iter().map(|p| p.id.to_string()).collect();
+
+    let query = r#"
+        mutation CreateMatch(
+            $tournamentId: ID!
+            $playerIds: [ID!]!
+            $numTeams: Int
+            $teamSize: Int
+        ) {
+            createMatchWithRounds(
+                tournamentId: $tournamentId
+                playerIds: $playerIds
+                numRaces: 3
+                playersPerRace: 4
+                numTeams: $numTeams
+                teamSize: $teamSize
+            ) {
+                id
+                teams {
+                    players { id }
+                }
+            }
+        }
+    "#;
+
+    // 3v3 with four seats per race, and free-for-all
+    for (num_teams, team_size, expected_teams) in [
+        (Some(2), None, vec![3, 3]),
+        (None, Some(1), vec![1; 6]),
+    ] {
+        let request = Request::new(query)
+            .variables(Variables::from_value(value!({
+                "tournamentId": tournament.id.to_string(),
+                "playerIds": player_ids.clone(),
+                "numTeams": num_teams,
+                "teamSize": team_size,
+            })))
+            .data(ctx.config.clone());
+
+        let gql_ctx =
+            GraphQLContext::new(ctx.pool.clone(), Some(group.id), NotificationManager::new());
+        let response = ctx.schema.execute(request.data(gql_ctx)).await;
+
+        assert!(
+            response.errors.is_empty(),
+            "Expected no errors: {:?}",
+            response.errors
+        );
+
+        let data = response.data.into_json().expect("Failed to parse response");
+        let team_sizes: Vec<usize> = data["createMatchWithRounds"]["teams"]
+            .as_array()
+            .expect("teams should be an array")
+            .iter()
+            .map(|team| team["players"].as_array().map_or(0, |p| p.len()))
+            .collect();
+        assert_eq!(team_sizes, expected_teams);
+
+        let match_id = uuid::Uuid::parse_str(
+            data["createMatchWithRounds"]["id"]
+                .as_str()
+                .expect("id should be a string"),
+        )
+        .expect("Invalid match ID");
+        let race_sizes: Vec<i64> = sqlx::query_scalar(
+            "SELECT COUNT(*) FROM round_players WHERE match_id = $1 GROUP BY round_number",
+        )
+        .bind(match_id)
+        .fetch_all(&ctx.pool)
+        .await
+        .expect("Failed to count round players");
+        assert_eq!(race_sizes, vec![4, 4, 4]);
+    }
+
+    let request = Request::new(query)
+        .variables(Variables::from_value(value!({
+            "tournamentId": tournament.id.to_string(),
+            "playerIds": player_ids.clone(),
+            "numTeams": 4,
+            "teamSize": 2,
+        })))
+        .data(ctx.config.clone());
+
+    let gql_ctx = GraphQLContext::new(ctx.pool.clone(), Some(group.id), NotificationManager::new());
+    let response = ctx.schema.execute(request.data(gql_ctx)).await;
+
+    assert_eq!(response.errors.len(), 1);
+    assert!(
+        response.errors[0].message.contains("4 teams of 2 players need 8 players"),
+        "Unexpected error: {}",
+        response.errors[0].message
+    );
+}
+
+#[tokio::test]
+async fn test_create_match_with_rounds_free_for_all() {
+    let ctx = setup::setup_test_db().await;
+
+    let group = fixtures::create_test_group(&ctx.pool, "Test Group", "password")
+        .await
+        .expect("Failed to create test group");
+
+    let tournament = fixtures::create_test_tournaments(&ctx.pool, group.id, 1)
+        .await
+        .expect("Failed to create test tournaments")
+        .remove(0);
+
+    let players = fixtures::create_test_players(&ctx.pool, group.id, 3)
+        .await
+        .expect("Failed to create test players");
+
+    let player_ids: Vec<String> = players.iter().map(|p| p.id.to_string()).collect();
+
+    let query = r#"
+        mutation CreateMatch($tournamentId: ID!, $playerIds: [ID!]!, $teamSize: Int) {
+            createMatchWithRounds(
+                tournamentId: $tournamentId
+                playerIds: $playerIds
+                numRaces: 2
+                mode: FREE_FOR_ALL
+                teamSize: $teamSize
+            ) {
+                mode
+                teams { id }
+                rounds {
+                    players { id teamId }
+                }
+            }
+        }
+    "#;
+
+    let request = Request::new(query)
+        .variables(Variables::from_value(value!({
+            "tournamentId": tournament.id.to_string(),
+            "playerIds": player_ids.clone(),
+        })))
+        .data(ctx.config.clone());
+
+    let gql_ctx = GraphQLContext::new(ctx.pool.clone(), Some(group.id), NotificationManager::new());
+    let response = ctx.schema.execute(request.data(gql_ctx)).await;
+
+    assert!(
+        response.errors.is_empty(),
+        "Expected no errors: {:?}",
+        response.errors
+    );
+
+    let data = response.data.into_json().expect("Failed to parse response");
+    let created = &data["createMatchWithRounds"];
+    assert_eq!(created["mode"], "FREE_FOR_ALL");
+    assert_eq!(created["teams"].as_array().map(|t| t.len()), Some(0));
+
+    let rounds = created["rounds"].as_array().expect("rounds should be an array");
+    assert_eq!(rounds.len(), 2);
+    for round in rounds {
+        let round_players = round["players"].as_array().expect("players should be an array");
+        let mut ids: Vec<&str> = round_players
+            .iter()
+            .map(|p| p["id"].as_str().expect("id should be a string"))
+            .collect();
+        ids.sort();
+        let mut expected: Vec<&str> = player_ids.iter().map(String::as_str).collect();
+        expected.sort();
+        assert_eq!(ids, expected, "Every player should race every race");
+        assert!(round_players.iter().all(|p| p["teamId"].is_null()));
+    }
+
+    let request = Request::new(query)
+        .variables(Variables::from_value(value!({
+            "tournamentId": tournament.id.to_string(),
+            "playerIds": player_ids,
+            "teamSize": 1,
+        })))
+        .data(ctx.config.clone());
+
+    let gql_ctx = GraphQLContext::new(ctx.pool.clone(), Some(group.id), NotificationManager::new());
+    let response = ctx.schema.execute(request.data(gql_ctx)).await;
+
+    assert_eq!(response.errors.len(), 1);
+    assert_eq!(response.errors[0].message, "Free-for-all matches have no teams");
+}
+
+#[tokio::test]
+async fn test_create_match_with_rounds_grand_prix_cup_standings() {
+    let ctx = setup::setup_test_db().await;
+
+    let group = fixtures::create_test_group(&ctx.pool, "Test Group", "password")
+        .await
+        .expect("Failed to create test group");
+
+    let tournament = fixtures::create_test_tournaments(&ctx.pool, group.id, 1)
+        .await
+        .expect("Failed to create test tournaments")
+        .remove(0);
+
+    let players = fixtures::create_test_players(&ctx.pool, group.id, 3)
+        .await
+        .expect("Failed to create test players");
+
+    let player_ids: Vec<String> = players.iter().map(|p| p.id.to_string()).collect();
+
+    let query = r#"
+        mutation CreateMatch($tournamentId: ID!, $playerIds: [ID!]!, $numRaces: Int!) {
+            createMatchWithRounds(
+                tournamentId: $tournamentId
+                playerIds: $playerIds
+                numRaces: $numRaces
+                mode: FREE_FOR_ALL
+                grandPrix: true
+            ) {
+                id
+                rounds {
+                    cup { id }
+                }
+            }
+        }
+    "#;
+
+    let request = Request::new(query)
+        .variables(Variables::from_value(value!({
+            "tournamentId": tournament.id.to_string(),
+            "playerIds": player_ids.clone(),
+            "numRaces": 6,
+        })))
+        .data(ctx.config.clone());
+
+    let gql_ctx = GraphQLContext::new(ctx.pool.clone(), Some(group.id), NotificationManager::new());
+    let response = ctx.schema.execute(request.data(gql_ctx)).await;
+
+    assert_eq!(response.errors.len(), 1);
+    assert!(
+        response.errors[0].message.contains("multiple of 4"),
+        "Unexpected error: {}",
+        response.errors[0].message
+    );
+
+    let request = Request::new(query)
+        .variables(Variables::from_value(value!({
+            "tournamentId": tournament.id.to_string(),
+            "playerIds": player_ids,
+            "numRaces": 8,
+        })))
+        .data(ctx.config.clone());
+
+    let gql_ctx = GraphQLContext::new(ctx.pool.clone(), Some(group.id), NotificationManager::new());
+    let response = ctx.schema.execute(request.data(gql_ctx)).await;
+
+    assert!(
+        response.errors.is_empty(),
+        "Expected no errors: {:?}",
+        response.errors
+    );
+
+    let data = response.data.into_json().expect("Failed to parse response");
+    let round_cups: Vec<String> = data["createMatchWithRounds"]["rounds"]
+        .as_array()
+        .expect("rounds should be an array")
+        .iter()
+        .map(|round| {
+            round["cup"]["id"]
+                .as_str()
+                .expect("Grand Prix rounds should have a cup")
+                .to_string()
+        })
+        .collect();
+    assert_eq!(round_cups.len(), 8);
+    assert!(round_cups[..4].iter().all(|cup| *cup == round_cups[0]));
+    assert!(round_cups[4..].iter().all(|cup| *cup == round_cups[4]));
+    assert_ne!(round_cups[0], round_cups[4]);
+
+    let match_id = uuid::Uuid::parse_str(
+        data["createMatchWithRounds"]["id"]
+            .as_str()
+            .expect("id should be a string"),
+    )
+    .expect("Invalid match ID");
+    let match_record = models::Match::find_by_id(&ctx.pool, match_id)
+        .await
+        .expect("Failed to fetch match")
+        .expect("Match should exist");
+
+    // Second and third tie on 12 points across the first two races
+    for (round_number, positions) in [(1, [1, 2, 3]), (2, [5, 3, 2])] {
+        let results: Vec<(uuid::Uuid, i32)> =
+            players.iter().map(|p| p.id).zip(positions).collect();
+        result_recording::record_race_results(
+            &ctx.pool,
+            group.id,
+            &result_recording::RoundResults {
+                round_number,
+                results: &results,
+                non_finishers: &[],
+                builds: &[],
+            },
+            &match_record,
+            &NotificationManager::new(),
+        )
+        .await
+        .expect("Failed to record results");
+    }
+
+    let query = r#"
+        query MatchById($matchId: ID!) {
+            matchById(matchId: $matchId) {
+                cups {
+                    cup { id name }
+                    standings {
+                        player { id }
+                        position
+                        points
+                    }
+                }
+            }
+        }
+    "#;
+
+    let request = Request::new(query)
+        .variables(Variables::from_value(value!({ "matchId": match_id.to_string() })))
+        .data(ctx.config.clone());
+
+    let gql_ctx = GraphQLContext::new(ctx.pool.clone(), Some(group.id), NotificationManager::new());
+    let response = ctx.schema.execute(request.data(gql_ctx)).await;
+
+    assert!(
+        response.errors.is_empty(),
+        "Expected no errors: {:?}",
+        response.errors
+    );
+
+    let data = response.data.into_json().expect("Failed to parse response");
+    let cups = data["matchById"]["cups"].as_array().expect("cups should be an array");
+    assert_eq!(cups.len(), 2);
+    assert_eq!(cups[0]["cup"]["id"], round_cups[0].as_str());
+    assert!(cups[1]["standings"].as_array().is_some_and(|s| s.is_empty()));
+
+    // Mario Kart 8 points: 15 + 8, 12 + 10 and 10 + 12
+    let standings: Vec<(String, i64, i64)> = cups[0]["standings"]
+        .as_array()
+        .expect("standings should be an array")
+        .iter()
+        .map(|s| {
+            (
+                s["player"]["id"].as_str().unwrap_or_default().to_string(),
+                s["position"].as_i64().unwrap_or_default(),
+                s["points"].as_i64().unwrap_or_default(),
+            )
+        })
+        .collect();
+    assert_eq!(standings[0], (players[0].id.to_string(), 1, 23));
+    assert_eq!(
+        standings[1..].iter().map(|(_, position, points)| (*position, *points)).collect::<Vec<_>>(),
+        vec![(2, 22), (2, 22)]
+    );
+}
@@ -0,0 +1,383 @@
+mod common;
+
+use common::{fixtures, setup};
+use mario_kart_leaderboard_backend::models;
+use mario_kart_leaderboard_backend::services::elo_replay;
+use mario_kart_leaderboard_backend::services::notification_manager::NotificationManager;
+use mario_kart_leaderboard_backend::services::player_merge::merge_players;
+use mario_kart_leaderboard_backend::services::{result_recording, tournament_completion};
+use sqlx::PgPool;
+use uuid::Uuid;
+
+/// Record a one-race team match for four players, split into two teams of two
+async fn record_match(
+    pool: &PgPool,
+    group_id: Uuid,
+    tournament_id: Uuid,
+    player_ids: &[Uuid],
+    positions: [i32; 4],
+) -> models::Match {
+    let match_record = fixtures::create_test_match(pool, group_id, tournament_id, 1)
+        .await
+        .expect("Failed to create test match");
+    let teams = fixtures::create_test_teams(pool, group_id, match_record.id, 2)
+        .await
+        .expect("Failed to create test teams");
+    fixtures::create_test_rounds(pool, match_record.id, 1)
+        .await
+        .expect("Failed to create test rounds");
+
+    for (team, team_player_ids) in teams.iter().zip(player_ids.chunks(2)) {
+        fixtures::add_players_to_team(pool, group_id, team.id, team_player_ids)
+            .await
+            .expect("Failed to add players to team");
+        fixtures::add_players_to_round(pool, group_id, match_record.id, 1, team.id, team_player_ids)
+            .await
+            .expect("Failed to add players to round");
+    }
+
+    let results: Vec<(Uuid, i32)> = player_ids.iter().copied().zip(positions).collect();
+    result_recording::record_race_results(
+        pool,
+        group_id,
+        &result_recording::RoundResults {
+            round_number: 1,
+            results: &results,
+            non_finishers: &[],
+            builds: &[],
+        },
+        &match_record,
+        &NotificationManager::new(),
+    )
+    .await
+    .expect("Failed to record results");
+
+    match_record
+}
+
+async fn count_race_scores(pool: &PgPool, player_id: Uuid) -> i64 {
+    sqlx::query_scalar("SELECT COUNT(*) FROM player_race_scores WHERE player_id = $1")
+        .bind(player_id)
+        .fetch_one(pool)
+        .await
+        .expect("Failed to count race scores")
+}
+
+#[tokio::test]
+async fn test_merge_players_moves_history_and_recomputes() {
+    let ctx = setup::setup_test_db().await;
+
+    let group = fixtures::create_test_group(&ctx.pool, "Test Group", "password")
+        .await
+        .expect("Failed to create test group");
+    let players = fixtures::create_test_players(&ctx.pool, group.id, 5)
+        .await
+        .expect("Failed to create test players");
+    let (target, source) = (&players[0], &players[4]);
+    let tournament = fixtures::create_test_tournament(&ctx.pool, group.id, None, None)
+        .await
+        .expect("Failed to create test tournament");
+
+    let others = [players[1].id, players[2].id, players[3].id];
+    record_match(
+        &ctx.pool,
+        group.id,
+        tournament.id,
+        &[target.id, others[0], others[1], others[2]],
+        [1, 4, 7, 10],
+    )
+    .await;
+    record_match(
+        &ctx.pool,
+        group.id,
+        tournament.id,
+        &[source.id, others[0], others[1], others[2]],
+        [2, 5, 8, 11],
+    )
+    .await;
+
+    let merged = merge_players(&ctx.pool, group.id, source.id, target.id)
+        .await
+        .expect("Failed to merge players");
+
+    assert_eq!(merged.id, target.id);
+    assert!(
+        models::Player::find_by_id(&ctx.pool, source.id)
+            .await
+            .expect("Failed to fetch player")
+            .is_none(),
+        "The source player should be deleted"
+    );
+    assert_eq!(count_race_scores(&ctx.pool, target.id).await, 2);
+
+    let tournament_scores: i64 = sqlx::query_scalar(
+        "SELECT COUNT(*) FROM player_tournament_scores WHERE player_id = $1",
+    )
+    .bind(target.id)
+    .fetch_one(&ctx.pool)
+    .await
+    .expect("Failed to count tournament scores");
+    assert_eq!(tournament_scores, 1, "Tournament ratings should be combined");
+
+    let report = elo_replay::recompute_group(&ctx.pool, group.id, true)
+        .await
+        .expect("Failed to recompute group");
+    assert!(
+        report.rating_changes.is_empty(),
+        "The merge should leave the group fully recomputed: {:?}",
+        report.rating_changes
+    );
+    assert_ne!(
+        merged.elo_rating, target.elo_rating,
+        "The target should be rated on both players' races"
+    );
+}
+
+#[tokio::test]
+async fn test_merge_players_keeps_target_results_in_shared_matches() {
+    let ctx = setup::setup_test_db().await;
+
+    let group = fixtures::create_test_group(&ctx.pool, "Test Group", "password")
+        .await
+        .expect("Failed to create test group");
+    let players = fixtures::create_test_players(&ctx.pool, group.id, 4)
+        .await
+        .expect("Failed to create test players");
+    let (target, source) = (&players[0], &players[1]);
+    let tournament = fixtures::create_test_tournament(&ctx.pool, group.id, None, None)
+        .await
+        .expect("Failed to create test tournament");
+
+    let match_record = record_match(
+        &ctx.pool,
+        group.id,
+        tournament.id,
+        &[target.id, source.id, players[2].id, players[3].id],
+        [3, 1, 6, 9],
+    )
+    .await;
+
+    merge_players(&ctx.pool, group.id, source.id, target.id)
+        .await
+        .expect("Failed to merge players");
+
+    let race_results: Vec<(Uuid, i32)> = sqlx::query_as(
+        "SELECT player_id, position FROM player_race_scores WHERE match_id = $1 ORDER BY position",
+    )
+    .bind(match_record.id)
+    .fetch_all(&ctx.pool)
+    .await
+    .expect("Failed to fetch race results");
+    assert_eq!(
+        race_results,
+        vec![(target.id, 3), (players[2].id, 6), (players[3].id, 9)],
+        "Only the target's result should be kept"
+    );
+
+    let team_members: i64 = sqlx::query_scalar(
+        "SELECT COUNT(*) FROM team_players tp
+         JOIN teams t ON t.id = tp.team_id
+         WHERE t.match_id = $1",
+    )
+    .bind(match_record.id)
+    .fetch_one(&ctx.pool)
+    .await
+    .expect("Failed to count team members");
+    assert_eq!(team_members, 3);
+
+    let error = merge_players(&ctx.pool, group.id, target.id, target.id)
+        .await
+        .expect_err("Merging a player into themselves should fail");
+    assert!(error.to_string().contains("themselves"));
+}
+
+#[tokio::test]
+async fn test_merge_players_moves_rounds_only_the_source_raced() {
+    let ctx = setup::setup_test_db().await;
+
+    let group = fixtures::create_test_group(&ctx.pool, "Test Group", "password")
+        .await
+        .expect("Failed to create test group");
+    let players = fixtures::create_test_players(&ctx.pool, group.id, 5)
+        .await
+        .expect("Failed to create test players");
+    let (target, source) = (players[0].id, players[1].id);
+    let [p2, p3, p4] = [players[2].id, players[3].id, players[4].id];
+    let tournament = fixtures::create_test_tournament(&ctx.pool, group.id, None, None)
+        .await
+        .expect("Failed to create test tournament");
+
+    let mut match_record = fixtures::create_test_match(&ctx.pool, group.id, tournament.id, 2)
+        .await
+        .expect("Failed to create test match");
+    let teams = fixtures::create_test_teams(&ctx.pool, group.id, match_record.id, 2)
+        .await
+        .expect("Failed to create test teams");
+    fixtures::create_test_rounds(&ctx.pool, match_record.id, 2)
+        .await
+        .expect("Failed to create test rounds");
+
+    // The target and source are on different teams; both race round 1, and
+    // only the source races round 2
+    let rosters = [(teams[0].id, vec![target, p2, p4]), (teams[1].id, vec![source, p3])];
+    let round_players = [
+        [(teams[0].id, vec![target, p2]), (teams[1].id, vec![source, p3])],
+        [(teams[0].id, vec![p2, p4]), (teams[1].id, vec![source, p3])],
+    ];
+    for (team_id, player_ids) in &rosters {
+        fixtures::add_players_to_team(&ctx.pool, group.id, *team_id, player_ids)
+            .await
+            .expect("Failed to add players to team");
+    }
+    for (round_number, teams) in (1..).zip(&round_players) {
+        for (team_id, player_ids) in teams {
+            fixtures::add_players_to_round(
+                &ctx.pool,
+                group.id,
+                match_record.id,
+                round_number,
+                *team_id,
+                player_ids,
+            )
+            .await
+            .expect("Failed to add players to round");
+        }
+    }
+    for player_id in [target, source, p2, p3, p4] {
+        sqlx::query(
+            "INSERT INTO match_players (group_id, match_id, player_id) VALUES ($1, $2, $3)",
+        )
+        .bind(group.id)
+        .bind(match_record.id)
+        .bind(player_id)
+        .execute(&ctx.pool)
+        .await
+        .expect("Failed to add match player");
+        sqlx::query(
+            "INSERT INTO player_match_scores
+             (group_id, match_id, player_id, position, elo_change, tournament_elo_change,
+              tournament_elo_from_races, tournament_elo_from_contributions)
+             VALUES ($1, $2, $3, 0, 0, 0, 0, 0)",
+        )
+        .bind(group.id)
+        .bind(match_record.id)
+        .bind(player_id)
+        .execute(&ctx.pool)
+        .await
+        .expect("Failed to add match score");
+    }
+
+    let results = [
+        vec![(source, 1), (target, 3), (p2, 6), (p3, 9)],
+        vec![(source, 2), (p2, 5), (p3, 8), (p4, 11)],
+    ];
+    for (round_number, results) in (1..).zip(&results) {
+        match_record = result_recording::record_race_results(
+            &ctx.pool,
+            group.id,
+            &result_recording::RoundResults {
+                round_number,
+                results,
+                non_finishers: &[],
+                builds: &[],
+            },
+            &match_record,
+            &NotificationManager::new(),
+        )
+        .await
+        .expect("Failed to record results");
+    }
+    assert!(match_record.completed);
+
+    let completed = tournament_completion::complete_tournament(&ctx.pool, tournament.id, group.id)
+        .await
+        .expect("Failed to complete tournament");
+    assert_eq!(completed.winner, Some(source), "The source wins before the merge");
+
+    merge_players(&ctx.pool, group.id, source, target)
+        .await
+        .expect("Failed to merge players");
+
+    let race_results: Vec<(i32, Uuid, i32)> = sqlx::query_as(
+        "SELECT round_number, player_id, position FROM player_race_scores
+         WHERE match_id = $1
+         ORDER BY round_number, position",
+    )
+    .bind(match_record.id)
+    .fetch_all(&ctx.pool)
+    .await
+    .expect("Failed to fetch race results");
+    assert_eq!(
+        race_results,
+        vec![
+            (1, target, 3),
+            (1, p2, 6),
+            (1, p3, 9),
+            (2, target, 2),
+            (2, p2, 5),
+            (2, p3, 8),
+            (2, p4, 11),
+        ],
+        "The source's round 1 result is discarded and round 2 moves to the target"
+    );
+
+    let target_rows: (i64, i64, i64) = sqlx::query_as(
+        "SELECT
+             (SELECT COUNT(*) FROM match_players WHERE match_id = $1 AND player_id = $2),
+             (SELECT COUNT(*) FROM player_match_scores WHERE match_id = $1 AND player_id = $2),
+             (SELECT COUNT(*) FROM team_players tp
+              JOIN teams t ON t.id = tp.team_id
+              WHERE t.match_id = $1 AND tp.player_id = $2)",
+    )
+    .bind(match_record.id)
+    .bind(target)
+    .fetch_one(&ctx.pool)
+    .await
+    .expect("Failed to count target rows");
+    assert_eq!(target_rows, (1, 1, 1), "Match rows should be folded into the target's");
+
+    let position: i32 = sqlx::query_scalar(
+        "SELECT position FROM player_match_scores WHERE match_id = $1 AND player_id = $2",
+    )
+    .bind(match_record.id)
+    .bind(target)
+    .fetch_one(&ctx.pool)
+    .await
+    .expect("Failed to fetch match score");
+    assert_eq!(position, 3, "The target's match average covers both rounds");
+
+    let winner: Option<Uuid> = sqlx::query_scalar("SELECT winner FROM tournaments WHERE id = $1")
+        .bind(tournament.id)
+        .fetch_one(&ctx.pool)
+        .await
+        .expect("Failed to fetch tournament");
+    let leader: Uuid = sqlx::query_scalar(
+        "SELECT player_id FROM player_tournament_scores
+         WHERE tournament_id = $1
+         ORDER BY elo_rating DESC
+         LIMIT 1",
+    )
+    .bind(tournament.id)
+    .fetch_one(&ctx.pool)
+    .await
+    .expect("Failed to fetch tournament leader");
+    assert_eq!(winner, Some(leader), "The winner should be recalculated");
+
+    let stat_players: Vec<Uuid> =
+        sqlx::query_scalar("SELECT player_id FROM tournament_stats WHERE tournament_id = $1")
+            .bind(tournament.id)
+            .fetch_all(&ctx.pool)
+            .await
+            .expect("Failed to fetch stats");
+    assert!(!stat_players.is_empty(), "Stats should be recalculated");
+    assert!(stat_players.iter().all(|player_id| *player_id != source));
+
+    let report = elo_replay::recompute_group(&ctx.pool, group.id, true)
+        .await
+        .expect("Failed to recompute group");
+    assert!(
+        report.rating_changes.is_empty(),
+        "The merge should leave the group fully recomputed: {:?}",
+        report.rating_changes
+    );
+}
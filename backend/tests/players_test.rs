@@ -4,6 +4,7 @@ use async_graphql::{Request, Variables, value};
 use common::{fixtures, setup};
 use mario_kart_leaderboard_backend::{
     graphql::context::GraphQLContext,
+    models,
     services::notification_manager::NotificationManager,
 };
 
@@ -327,3 +328,526 @@ async fn test_past_tournament_placings_ranks_among_all_players() {
         Some(tournament.id.to_string().as_str())
     );
 }
+
+/// Fetch a player's first past placing and the number of players ranked with them
+async fn first_past_placing(
+    ctx: &setup::TestContext,
+    group_id: uuid::Uuid,
+    player_id: uuid::Uuid,
+) -> (i64, i64) {
+    let query = r#"
+        query PlayerById($playerId: ID!) {
+            playerById(playerId: $playerId) {
+                pastTournamentPlacings {
+                    placing
+                    totalPlayers
+                }
+            }
+        }
+    "#;
+
+    let request = Request::new(query)
+        .variables(Variables::from_value(value!({
+            "playerId": player_id.to_string()
+        })))
+        .data(ctx.config.clone());
+
+    let gql_ctx = GraphQLContext::new(ctx.pool.clone(), Some(group_id), NotificationManager::new());
+    let response = ctx.schema.execute(request.data(gql_ctx)).await;
+
+    assert!(
+        response.errors.is_empty(),
+        "Expected no errors: {:?}",
+        response.errors
+    );
+
+    let data = response.data.into_json().expect("Failed to parse response");
+    let placing = &data["playerById"]["pastTournamentPlacings"][0];
+
+    (
+        placing["placing"].as_i64().expect("placing should be a number"),
+        placing["totalPlayers"].as_i64().expect("totalPlayers should be a number"),
+    )
+}
+
+#[tokio::test]
+async fn test_guest_players_are_not_ranked_until_promoted() {
+    let ctx = setup::setup_test_db().await;
+
+    let group = fixtures::create_test_group(&ctx.pool, "Test Group", "password")
+        .await
+        .expect("Failed to create test group");
+
+    let players = fixtures::create_test_players(&ctx.pool, group.id, 2)
+        .await
+        .expect("Failed to create test players");
+
+    let create_guest = r#"
+        mutation CreatePlayer($name: String!) {
+            createPlayer(name: $name, guest: true) {
+                id
+                isGuest
+            }
+        }
+    "#;
+
+    let request = Request::new(create_guest)
+        .variables(Variables::from_value(value!({ "name": "Visitor" })))
+        .data(ctx.config.clone());
+    let gql_ctx = GraphQLContext::new(ctx.pool.clone(), Some(group.id), NotificationManager::new());
+    let response = ctx.schema.execute(request.data(gql_ctx)).await;
+
+    assert!(
+        response.errors.is_empty(),
+        "Expected no errors: {:?}",
+        response.errors
+    );
+
+    let data = response.data.into_json().expect("Failed to parse response");
+    assert_eq!(data["createPlayer"]["isGuest"].as_bool(), Some(true));
+    let guest_id = uuid::Uuid::parse_str(
+        data["createPlayer"]["id"].as_str().expect("id should be a string"),
+    )
+    .expect("id should be a UUID");
+
+    let player_lists = r#"
+        query Players($includeGuests: Boolean) {
+            players(includeGuests: $includeGuests) {
+                id
+            }
+            currentGroup {
+                players {
+                    id
+                }
+            }
+        }
+    "#;
+    for include_guests in [false, true] {
+        let request = Request::new(player_lists)
+            .variables(Variables::from_value(value!({ "includeGuests": include_guests })))
+            .data(ctx.config.clone());
+        let gql_ctx =
+            GraphQLContext::new(ctx.pool.clone(), Some(group.id), NotificationManager::new());
+        let response = ctx.schema.execute(request.data(gql_ctx)).await;
+
+        assert!(
+            response.errors.is_empty(),
+            "Expected no errors: {:?}",
+            response.errors
+        );
+
+        let data = response.data.into_json().expect("Failed to parse response");
+        let contains_guest = |players: &serde_json::Value| {
+            players
+                .as_array()
+                .expect("players should be an array")
+                .iter()
+                .any(|player| player["id"] == guest_id.to_string())
+        };
+        assert_eq!(
+            contains_guest(&data["players"]),
+            include_guests,
+            "Guests should only be listed when asked for"
+        );
+        assert!(
+            !contains_guest(&data["currentGroup"]["players"]),
+            "The group's ELO board should leave out guests"
+        );
+    }
+
+    let tournament = fixtures::create_test_tournament(
+        &ctx.pool,
+        group.id,
+        chrono::NaiveDate::from_ymd_opt(2024, 1, 1),
+        chrono::NaiveDate::from_ymd_opt(2024, 1, 7),
+    )
+    .await
+    .expect("Failed to create test tournament");
+
+    let scores = [(guest_id, 1400), (players[0].id, 1300), (players[1].id, 1200)];
+    for (player_id, elo) in scores {
+        sqlx::query(
+            "INSERT INTO player_tournament_scores
+             (tournament_id, player_id, group_id, elo_rating)
+             VALUES ($1, $2, $3, $4)",
+        )
+        .bind(tournament.id)
+        .bind(player_id)
+        .bind(group.id)
+        .bind(elo)
+        .execute(&ctx.pool)
+        .await
+        .expect("Failed to insert tournament score");
+    }
+
+    sqlx::query("UPDATE tournaments SET winner = $1 WHERE id = $2")
+        .bind(players[0].id)
+        .bind(tournament.id)
+        .execute(&ctx.pool)
+        .await
+        .expect("Failed to mark tournament completed");
+
+    let leaderboard =
+        models::PlayerTournamentScore::get_tournament_leaderboard(&ctx.pool, tournament.id)
+            .await
+            .expect("Failed to fetch leaderboard");
+    assert_eq!(
+        leaderboard.iter().map(|row| row.player_id).collect::<Vec<_>>(),
+        vec![players[0].id, players[1].id],
+        "The guest should be left off the tournament leaderboard"
+    );
+    assert_eq!(
+        first_past_placing(&ctx, group.id, players[0].id).await,
+        (1, 2),
+        "The guest should not be ranked"
+    );
+
+    let promote = r#"
+        mutation PromoteGuestPlayer($playerId: ID!) {
+            promoteGuestPlayer(playerId: $playerId) {
+                isGuest
+                eloRating
+            }
+        }
+    "#;
+
+    let request = Request::new(promote)
+        .variables(Variables::from_value(value!({ "playerId": guest_id.to_string() })))
+        .data(ctx.config.clone());
+    let gql_ctx = GraphQLContext::new(ctx.pool.clone(), Some(group.id), NotificationManager::new());
+    let response = ctx.schema.execute(request.data(gql_ctx)).await;
+
+    assert!(
+        response.errors.is_empty(),
+        "Expected no errors: {:?}",
+        response.errors
+    );
+
+    let data = response.data.into_json().expect("Failed to parse response");
+    assert_eq!(data["promoteGuestPlayer"]["isGuest"].as_bool(), Some(false));
+
+    assert_eq!(
+        first_past_placing(&ctx, group.id, players[0].id).await,
+        (2, 3),
+        "The promoted player should be ranked with their history"
+    );
+
+    let request = Request::new(promote)
+        .variables(Variables::from_value(value!({ "playerId": guest_id.to_string() })))
+        .data(ctx.config.clone());
+    let gql_ctx = GraphQLContext::new(ctx.pool.clone(), Some(group.id), NotificationManager::new());
+    let response = ctx.schema.execute(request.data(gql_ctx)).await;
+
+    assert!(
+        response.errors.iter().any(|e| e.message.contains("Player is not a guest")),
+        "Expected a not-a-guest error, got: {:?}",
+        response.errors
+    );
+}
+
+#[tokio::test]
+async fn test_player_pair_constraint_lifecycle() {
+    let ctx = setup::setup_test_db().await;
+
+    let group = fixtures::create_test_group(&ctx.pool, "Test Group", "password")
+        .await
+        .expect("Failed to create test group");
+
+    let players = fixtures::create_test_players(&ctx.pool, group.id, 2)
+        .await
+        .expect("Failed to create test players");
+
+    let execute = |query: &'static str, variables: async_graphql::Value| {
+        let request = Request::new(query)
+            .variables(Variables::from_value(variables))
+            .data(ctx.config.clone());
+        let gql_ctx =
+            GraphQLContext::new(ctx.pool.clone(), Some(group.id), NotificationManager::new());
+        ctx.schema.execute(request.data(gql_ctx))
+    };
+
+    let set_query = r#"
+        mutation SetConstraint($pair: PlayerPairInput!, $kind: PairConstraintKind!) {
+            setPlayerPairConstraint(pair: $pair, kind: $kind) {
+                id
+                kind
+                players { id }
+            }
+        }
+    "#;
+    let pair = value!({
+        "firstPlayerId": players[1].id.to_string(),
+        "secondPlayerId": players[0].id.to_string(),
+    });
+
+    let response = execute(set_query, value!({ "pair": pair.clone(), "kind": "TOGETHER" })).await;
+    assert!(
+        response.errors.is_empty(),
+        "Expected no errors: {:?}",
+        response.errors
+    );
+
+    // Setting the same pair again replaces the constraint
+    let response = execute(set_query, value!({ "pair": pair, "kind": "APART" })).await;
+    assert!(
+        response.errors.is_empty(),
+        "Expected no errors: {:?}",
+        response.errors
+    );
+    let data = response.data.into_json().expect("Failed to parse response");
+    let constraint_id = data["setPlayerPairConstraint"]["id"]
+        .as_str()
+        .expect("id should be a string")
+        .to_string();
+    assert_eq!(data["setPlayerPairConstraint"]["kind"], "APART");
+    let mut constraint_players: Vec<&str> = data["setPlayerPairConstraint"]["players"]
+        .as_array()
+        .expect("players should be an array")
+        .iter()
+        .filter_map(|p| p["id"].as_str())
+        .collect();
+    constraint_players.sort_unstable();
+    let mut expected = [players[0].id.to_string(), players[1].id.to_string()];
+    expected.sort_unstable();
+    assert_eq!(constraint_players, expected);
+
+    let list_query = r#"
+        query {
+            playerPairConstraints {
+                id
+                kind
+            }
+        }
+    "#;
+    let response = execute(list_query, value!({})).await;
+    let data = response.data.into_json().expect("Failed to parse response");
+    let constraints = data["playerPairConstraints"]
+        .as_array()
+        .expect("playerPairConstraints should be an array");
+    assert_eq!(constraints.len(), 1);
+    assert_eq!(constraints[0]["kind"], "APART");
+
+    let remove_query = r#"
+        mutation RemoveConstraint($constraintId: ID!) {
+            removePlayerPairConstraint(constraintId: $constraintId)
+        }
+    "#;
+    let response = execute(remove_query, value!({ "constraintId": constraint_id })).await;
+    assert!(
+        response.errors.is_empty(),
+        "Expected no errors: {:?}",
+        response.errors
+    );
+
+    let response = execute(list_query, value!({})).await;
+    let data = response.data.into_json().expect("Failed to parse response");
+    assert_eq!(
+        data["playerPairConstraints"]
+            .as_array()
+            .expect("playerPairConstraints should be an array")
+            .len(),
+        0
+    );
+}
+
+/// Run a query authenticated as the given group
+async fn execute_as_group(
+    ctx: &setup::TestContext,
+    group_id: uuid::Uuid,
+    query: &str,
+    variables: serde_json::Value,
+) -> async_graphql::Response {
+    let request = Request::new(query)
+        .variables(Variables::from_json(variables))
+        .data(ctx.config.clone());
+
+    let gql_ctx = GraphQLContext::new(ctx.pool.clone(), Some(group_id), NotificationManager::new());
+    ctx.schema.execute(request.data(gql_ctx)).await
+}
+
+#[tokio::test]
+async fn test_player_lifecycle() {
+    let ctx = setup::setup_test_db().await;
+
+    let group = fixtures::create_test_group(&ctx.pool, "Test Group", "password")
+        .await
+        .expect("Failed to create test group");
+
+    let players = fixtures::create_test_players(&ctx.pool, group.id, 2)
+        .await
+        .expect("Failed to create test players");
+    let (veteran, newcomer) = (&players[0], &players[1]);
+
+    let tournament = fixtures::create_test_tournament(&ctx.pool, group.id, None, None)
+        .await
+        .expect("Failed to create test tournament");
+    fixtures::create_test_match(&ctx.pool, group.id, tournament.id, 1)
+        .await
+        .expect("Failed to create test match");
+    sqlx::query(
+        "INSERT INTO player_tournament_scores (tournament_id, player_id, group_id, elo_rating)
+         VALUES ($1, $2, $3, 1250)",
+    )
+    .bind(tournament.id)
+    .bind(veteran.id)
+    .bind(group.id)
+    .execute(&ctx.pool)
+    .await
+    .expect("Failed to insert tournament score");
+    sqlx::query("UPDATE tournaments SET winner = $1 WHERE id = $2")
+        .bind(veteran.id)
+        .bind(tournament.id)
+        .execute(&ctx.pool)
+        .await
+        .expect("Failed to mark tournament completed");
+
+    let rename = r#"
+        mutation RenamePlayer($playerId: ID!, $name: String!) {
+            renamePlayer(playerId: $playerId, name: $name) {
+                name
+                nameHistory { previousName }
+            }
+        }
+    "#;
+
+    let response = execute_as_group(
+        &ctx,
+        group.id,
+        rename,
+        serde_json::json!({ "playerId": veteran.id.to_string(), "name": "player 2" }),
+    )
+    .await;
+    assert!(
+        response.errors.iter().any(|e| e.message.contains("already exists")),
+        "Expected a duplicate name error, got: {:?}",
+        response.errors
+    );
+
+    let response = execute_as_group(
+        &ctx,
+        group.id,
+        rename,
+        serde_json::json!({ "playerId": veteran.id.to_string(), "name": "Luigi" }),
+    )
+    .await;
+    assert!(
+        response.errors.is_empty(),
+        "Expected no errors: {:?}",
+        response.errors
+    );
+    let data = response.data.into_json().expect("Failed to parse response");
+    assert_eq!(data["renamePlayer"]["name"].as_str(), Some("Luigi"));
+    assert_eq!(
+        data["renamePlayer"]["nameHistory"][0]["previousName"].as_str(),
+        Some("Player 1")
+    );
+
+    let leaderboard =
+        models::PlayerTournamentScore::get_tournament_leaderboard(&ctx.pool, tournament.id)
+            .await
+            .expect("Failed to fetch leaderboard");
+    assert_eq!(leaderboard[0].player_name, "Luigi");
+    assert_eq!(
+        leaderboard[0].formerly_known_as.as_deref(),
+        Some("Player 1"),
+        "Completed tournaments should show the name the player raced under"
+    );
+
+    let set_disabled = r#"
+        mutation SetPlayerDisabled($playerId: ID!, $disabled: Boolean!) {
+            setPlayerDisabled(playerId: $playerId, disabled: $disabled) {
+                disabled
+            }
+        }
+    "#;
+    let players_query = r#"
+        query Players($includeDisabled: Boolean!) {
+            players(includeDisabled: $includeDisabled) { id }
+        }
+    "#;
+
+    let response = execute_as_group(
+        &ctx,
+        group.id,
+        set_disabled,
+        serde_json::json!({ "playerId": veteran.id.to_string(), "disabled": true }),
+    )
+    .await;
+    assert!(
+        response.errors.is_empty(),
+        "Expected no errors: {:?}",
+        response.errors
+    );
+
+    for (include_disabled, expected) in [(false, 1), (true, 2)] {
+        let response = execute_as_group(
+            &ctx,
+            group.id,
+            players_query,
+            serde_json::json!({ "includeDisabled": include_disabled }),
+        )
+        .await;
+        let data = response.data.into_json().expect("Failed to parse response");
+        assert_eq!(
+            data["players"].as_array().map(Vec::len),
+            Some(expected),
+            "includeDisabled: {include_disabled}"
+        );
+    }
+
+    let response = execute_as_group(
+        &ctx,
+        group.id,
+        set_disabled,
+        serde_json::json!({ "playerId": veteran.id.to_string(), "disabled": false }),
+    )
+    .await;
+    let data = response.data.into_json().expect("Failed to parse response");
+    assert_eq!(data["setPlayerDisabled"]["disabled"].as_bool(), Some(false));
+
+    let delete = r#"
+        mutation DeletePlayer($playerId: ID!) {
+            deletePlayer(playerId: $playerId)
+        }
+    "#;
+
+    let response = execute_as_group(
+        &ctx,
+        group.id,
+        delete,
+        serde_json::json!({ "playerId": veteran.id.to_string() }),
+    )
+    .await;
+    assert!(
+        response.errors.iter().any(|e| e.message.contains("Cannot delete player")),
+        "Expected a history error, got: {:?}",
+        response.errors
+    );
+
+    let response = execute_as_group(
+        &ctx,
+        group.id,
+        delete,
+        serde_json::json!({ "playerId": newcomer.id.to_string() }),
+    )
+    .await;
+    assert!(
+        response.errors.is_empty(),
+        "Expected no errors: {:?}",
+        response.errors
+    );
+    assert!(
+        models::Player::find_by_id(&ctx.pool, newcomer.id)
+            .await
+            .expect("Failed to fetch player")
+            .is_none()
+    );
+
+    let winner: Option<uuid::Uuid> =
+        sqlx::query_scalar("SELECT winner FROM tournaments WHERE id = $1")
+            .bind(tournament.id)
+            .fetch_one(&ctx.pool)
+            .await
+            .expect("Failed to fetch tournament");
+    assert_eq!(winner, Some(veteran.id));
+}
@@ -0,0 +1,177 @@
+mod common;
+
+use common::{fixtures, setup};
+use mario_kart_leaderboard_backend::services::elo::EloSettings;
+use mario_kart_leaderboard_backend::services::prediction::{
+    self, PredictionPlayer, predict_match_winner, predict_race,
+};
+use mario_kart_leaderboard_backend::services::rating_system::Uncertainty;
+use mario_kart_leaderboard_backend::services::scoring::MK8_POINTS;
+use rand::SeedableRng;
+use rand::rngs::StdRng;
+use std::collections::HashMap;
+use uuid::Uuid;
+
+fn create_player(id: u128, rating: i32) -> PredictionPlayer {
+    PredictionPlayer {
+        player_id: Uuid::from_u128(id),
+        rating,
+        uncertainty: Uncertainty::default(),
+    }
+}
+
+#[test]
+fn test_stronger_player_is_expected_to_finish_ahead() {
+    let players = vec![create_player(1, 1600), create_player(2, 1200), create_player(3, 900)];
+
+    let predictions = predict_race(
+        &players,
+        &EloSettings::default(),
+        &MK8_POINTS,
+        2000,
+        &mut StdRng::seed_from_u64(7),
+    );
+
+    assert_eq!(predictions.len(), 3);
+    assert!(predictions[0].expected_position < predictions[1].expected_position);
+    assert!(predictions[1].expected_position < predictions[2].expected_position);
+    assert!(predictions[0].expected_points > predictions[1].expected_points);
+    assert!(predictions[1].expected_points > predictions[2].expected_points);
+    for prediction in &predictions {
+        assert!((1.0..=24.0).contains(&prediction.expected_position));
+        assert!((0.0..=15.0).contains(&prediction.expected_points));
+    }
+}
+
+#[test]
+fn test_expected_elo_change_falls_with_position() {
+    let players = vec![create_player(1, 1300), create_player(2, 1250)];
+
+    let predictions = predict_race(
+        &players,
+        &EloSettings::default(),
+        &MK8_POINTS,
+        10,
+        &mut StdRng::seed_from_u64(7),
+    );
+
+    for prediction in &predictions {
+        assert!(prediction.elo_change_if_first > 0);
+        assert!(prediction.elo_change_if_first > prediction.elo_change_if_median);
+        assert!(prediction.elo_change_if_median > prediction.elo_change_if_last);
+        assert!(prediction.elo_change_if_last < 0);
+    }
+}
+
+#[test]
+fn test_unbeatable_lead_wins_with_certainty() {
+    let (team_a, team_b) = (Uuid::from_u128(10), Uuid::from_u128(20));
+    let current_points = HashMap::from([(team_a, 60), (team_b, 0)]);
+    let remaining_rounds = vec![vec![
+        (Uuid::from_u128(1), team_a, 1200),
+        (Uuid::from_u128(2), team_b, 1200),
+    ]];
+
+    let predictions = predict_match_winner(
+        &current_points,
+        &remaining_rounds,
+        &EloSettings::default(),
+        &MK8_POINTS,
+        500,
+        &mut StdRng::seed_from_u64(7),
+    );
+
+    let team_a_prediction = predictions.iter().find(|p| p.team_id == team_a).unwrap();
+    assert_eq!(team_a_prediction.win_probability, 1.0);
+    assert_eq!(team_a_prediction.current_points, 60);
+}
+
+#[test]
+fn test_win_probabilities_sum_to_one_and_favour_stronger_team() {
+    let (team_a, team_b) = (Uuid::from_u128(10), Uuid::from_u128(20));
+    let current_points = HashMap::from([(team_a, 0), (team_b, 0)]);
+    let remaining_rounds: Vec<_> = (0..4)
+        .map(|_| {
+            vec![
+                (Uuid::from_u128(1), team_a, 1500),
+                (Uuid::from_u128(2), team_a, 1450),
+                (Uuid::from_u128(3), team_b, 1100),
+                (Uuid::from_u128(4), team_b, 1050),
+            ]
+        })
+        .collect();
+
+    let predictions = predict_match_winner(
+        &current_points,
+        &remaining_rounds,
+        &EloSettings::default(),
+        &MK8_POINTS,
+        2000,
+        &mut StdRng::seed_from_u64(7),
+    );
+
+    let total: f64 = predictions.iter().map(|p| p.win_probability).sum();
+    assert!((total - 1.0).abs() < 1e-9);
+    let team_a_prediction = predictions.iter().find(|p| p.team_id == team_a).unwrap();
+    let team_b_prediction = predictions.iter().find(|p| p.team_id == team_b).unwrap();
+    assert!(team_a_prediction.win_probability > team_b_prediction.win_probability);
+    assert!(team_a_prediction.expected_points > team_b_prediction.expected_points);
+}
+
+#[tokio::test]
+async fn test_predict_round_covers_round_players_and_match_teams() {
+    let ctx = setup::setup_test_db().await;
+    let group = fixtures::create_test_group(&ctx.pool, "Test Group", "password")
+        .await
+        .expect("Failed to create test group");
+    let tournament = fixtures::create_test_tournaments(&ctx.pool, group.id, 1)
+        .await
+        .expect("Failed to create test tournaments")
+        .remove(0);
+    let players = fixtures::create_test_players(&ctx.pool, group.id, 4)
+        .await
+        .expect("Failed to create test players");
+    let match_record = fixtures::create_test_match(&ctx.pool, group.id, tournament.id, 2)
+        .await
+        .expect("Failed to create test match");
+    let teams = fixtures::create_test_teams(&ctx.pool, group.id, match_record.id, 2)
+        .await
+        .expect("Failed to create test teams");
+    fixtures::create_test_rounds(&ctx.pool, match_record.id, 2)
+        .await
+        .expect("Failed to create test rounds");
+
+    for (team, team_players) in teams.iter().zip(players.chunks(2)) {
+        let team_player_ids: Vec<Uuid> = team_players.iter().map(|p| p.id).collect();
+        fixtures::add_players_to_team(&ctx.pool, group.id, team.id, &team_player_ids)
+            .await
+            .expect("Failed to add players to team");
+        for round_number in 1..=2 {
+            fixtures::add_players_to_round(
+                &ctx.pool,
+                group.id,
+                match_record.id,
+                round_number,
+                team.id,
+                &team_player_ids,
+            )
+            .await
+            .expect("Failed to add players to round");
+        }
+    }
+
+    let prediction = prediction::predict_round(&ctx.pool, &match_record, 1)
+        .await
+        .expect("Failed to predict round");
+
+    assert_eq!(prediction.players.len(), 4);
+    assert_eq!(
+        prediction.teams.iter().map(|t| t.team_id).collect::<Vec<_>>(),
+        teams.iter().map(|t| t.id).collect::<Vec<_>>()
+    );
+    let total: f64 = prediction.teams.iter().map(|t| t.win_probability).sum();
+    assert!((total - 1.0).abs() < 1e-9);
+
+    let missing_round = prediction::predict_round(&ctx.pool, &match_record, 3).await;
+    assert!(missing_round.is_err());
+}
@@ -0,0 +1,135 @@
+use mario_kart_leaderboard_backend::models::Player;
+use mario_kart_leaderboard_backend::services::race_allocation::{
+    allocate_races, check_teams_and_allocate_races,
+};
+use mario_kart_leaderboard_backend::services::team_allocation::{
+    PairConstraints, Team, allocate_teams,
+};
+use std::collections::HashMap;
+use uuid::Uuid;
+
+fn create_test_player(name: &str, elo_rating: i32) -> Player {
+    Player {
+        id: Uuid::new_v4(),
+        group_id: Uuid::new_v4(),
+        name: name.to_string(),
+        elo_rating,
+        rating_deviation: 350.0,
+        rating_volatility: 0.06,
+        avatar_filename: None,
+        disabled: false,
+        guest: false,
+    }
+}
+
+fn create_players(count: usize) -> Vec<Player> {
+    (0..count)
+        .map(|i| create_test_player(&format!("Player {}", i + 1), 1600 - 50 * i as i32))
+        .collect()
+}
+
+fn seats_per_team(teams: &[Team], race: &[Uuid]) -> Vec<usize> {
+    teams
+        .iter()
+        .map(|team| {
+            team.players
+                .iter()
+                .filter(|player| race.contains(&player.id))
+                .count()
+        })
+        .collect()
+}
+
+fn races_per_player(races: &[Vec<Uuid>]) -> HashMap<Uuid, usize> {
+    races.iter().flatten().fold(HashMap::new(), |mut counts, id| {
+        *counts.entry(*id).or_default() += 1;
+        counts
+    })
+}
+
+#[test]
+fn test_allocate_races_one_player_per_team() {
+    let players = create_players(6);
+    let teams = allocate_teams(&players, &2, &HashMap::new());
+
+    let races = allocate_races(&players, &teams, 6, 2, &HashMap::new()).expect("Allocation failed");
+
+    assert_eq!(races.len(), 6);
+    for race in &races {
+        assert_eq!(seats_per_team(&teams, &race.player_ids), vec![1, 1]);
+    }
+}
+
+#[test]
+fn test_allocate_races_several_teammates_per_race() {
+    // 2v2v2v2 with every player in every race
+    let players = create_players(8);
+    let teams = allocate_teams(&players, &4, &HashMap::new());
+
+    let races = allocate_races(&players, &teams, 3, 8, &HashMap::new()).expect("Allocation failed");
+
+    for race in &races {
+        assert_eq!(seats_per_team(&teams, &race.player_ids), vec![2, 2, 2, 2]);
+    }
+}
+
+#[test]
+fn test_allocate_races_rotates_remainder_seats_and_players() {
+    // 3v3 with four players per race
+    let players = create_players(6);
+    let teams = allocate_teams(&players, &2, &HashMap::new());
+
+    let races = allocate_races(&players, &teams, 6, 4, &HashMap::new()).expect("Allocation failed");
+    let race_ids: Vec<Vec<Uuid>> = races.iter().map(|race| race.player_ids.clone()).collect();
+
+    for race in &race_ids {
+        assert_eq!(race.len(), 4);
+        assert_eq!(seats_per_team(&teams, race), vec![2, 2]);
+    }
+    let counts = races_per_player(&race_ids);
+    assert!(players.iter().all(|player| counts[&player.id] == 4));
+}
+
+#[test]
+fn test_allocate_races_free_for_all_teams_sit_out_in_turn() {
+    // Every player on their own team, four seats per race
+    let players = create_players(6);
+    let teams = allocate_teams(&players, &6, &HashMap::new());
+
+    let races = allocate_races(&players, &teams, 3, 4, &HashMap::new()).expect("Allocation failed");
+    let race_ids: Vec<Vec<Uuid>> = races.iter().map(|race| race.player_ids.clone()).collect();
+
+    assert!(race_ids.iter().all(|race| race.len() == 4));
+    let counts = races_per_player(&race_ids);
+    assert!(players.iter().all(|player| counts[&player.id] == 2));
+}
+
+#[test]
+fn test_check_teams_and_allocate_races_only_constrains_teams_in_rotation() {
+    // 3v3 with four players per race
+    let players = create_players(6);
+    let teams = allocate_teams(&players, &2, &HashMap::new());
+    let opponents = (teams[0].players[0].id, teams[1].players[0].id);
+    let teammates = (teams[0].players[0].id, teams[0].players[1].id);
+
+    let mut constraints = PairConstraints::default();
+    constraints.keep_apart(opponents.0, opponents.1);
+    let races =
+        check_teams_and_allocate_races(&players, &teams, 6, 4, &HashMap::new(), &constraints)
+            .expect("Allocation failed");
+
+    assert!(
+        races
+            .iter()
+            .any(|race| race.player_ids.contains(&opponents.0)
+                && race.player_ids.contains(&opponents.1)),
+        "Keep-apart players on different teams can race each other"
+    );
+
+    let mut constraints = PairConstraints::default();
+    constraints.keep_apart(teammates.0, teammates.1);
+    let err = check_teams_and_allocate_races(&players, &teams, 6, 4, &HashMap::new(), &constraints)
+        .expect_err("Teams breaking a keep-apart constraint should be rejected");
+
+    assert!(err.to_string().contains("must be kept apart"));
+}
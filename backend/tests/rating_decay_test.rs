@@ -0,0 +1,229 @@
+mod common;
+
+use chrono::{Duration, Utc};
+use common::{fixtures, setup};
+use mario_kart_leaderboard_backend::models;
+use mario_kart_leaderboard_backend::services::elo::EloSettings;
+use mario_kart_leaderboard_backend::services::elo_replay::{self, STARTING_ELO};
+use mario_kart_leaderboard_backend::services::notification_manager::NotificationManager;
+use mario_kart_leaderboard_backend::services::rating_decay::{
+    self, RatingDecay, calculate_decay,
+};
+use mario_kart_leaderboard_backend::services::result_recording;
+use sqlx::PgPool;
+use std::collections::HashMap;
+use uuid::Uuid;
+
+fn decay_settings() -> EloSettings {
+    EloSettings {
+        inactivity_weeks: 2,
+        inactivity_decay_per_week: 10,
+        ..EloSettings::default()
+    }
+}
+
+#[test]
+fn test_no_decay_before_inactivity_weeks() {
+    assert_eq!(calculate_decay(1500, 1, 0, &decay_settings()), None);
+}
+
+#[test]
+fn test_decay_covers_every_inactive_week() {
+    assert_eq!(
+        calculate_decay(1500, 4, 0, &decay_settings()),
+        Some(RatingDecay {
+            weeks: 3,
+            elo_change: -30,
+            elo_after: 1470,
+        })
+    );
+}
+
+#[test]
+fn test_decay_skips_weeks_already_decayed() {
+    assert_eq!(calculate_decay(1470, 4, 3, &decay_settings()), None);
+    assert_eq!(
+        calculate_decay(1470, 5, 3, &decay_settings()).map(|decay| decay.elo_change),
+        Some(-10)
+    );
+}
+
+#[test]
+fn test_decay_stops_at_starting_elo() {
+    assert_eq!(
+        calculate_decay(STARTING_ELO + 5, 4, 0, &decay_settings()).map(|decay| decay.elo_after),
+        Some(STARTING_ELO)
+    );
+    assert_eq!(calculate_decay(STARTING_ELO - 20, 4, 0, &decay_settings()), None);
+}
+
+#[test]
+fn test_no_decay_when_disabled() {
+    assert_eq!(calculate_decay(1500, 20, 0, &EloSettings::default()), None);
+}
+
+/// Creates a single-team match and records one race for four players.
+async fn setup_recorded_race(pool: &PgPool) -> (models::Group, models::Tournament) {
+    let group = fixtures::create_test_group(pool, "Test Group", "password")
+        .await
+        .expect("Failed to create test group");
+    let tournament = fixtures::create_test_tournaments(pool, group.id, 1)
+        .await
+        .expect("Failed to create test tournaments")
+        .remove(0);
+    let players = fixtures::create_test_players(pool, group.id, 4)
+        .await
+        .expect("Failed to create test players");
+    let match_record = fixtures::create_test_match(pool, group.id, tournament.id, 1)
+        .await
+        .expect("Failed to create test match");
+    let team = fixtures::create_test_team(pool, group.id, match_record.id, 1)
+        .await
+        .expect("Failed to create test team");
+    fixtures::create_test_round(pool, match_record.id, 1, None)
+        .await
+        .expect("Failed to create test round");
+
+    let player_ids: Vec<Uuid> = players.iter().map(|p| p.id).collect();
+    fixtures::add_players_to_team(pool, group.id, team.id, &player_ids)
+        .await
+        .expect("Failed to add players to team");
+    fixtures::add_players_to_round(pool, group.id, match_record.id, 1, team.id, &player_ids)
+        .await
+        .expect("Failed to add players to round");
+
+    let results: Vec<(Uuid, i32)> = player_ids.iter().copied().zip([1, 4, 9, 12]).collect();
+    result_recording::record_race_results(
+        pool,
+        group.id,
+        &result_recording::RoundResults {
+            round_number: 1,
+            results: &results,
+            non_finishers: &[],
+            builds: &[],
+        },
+        &match_record,
+        &NotificationManager::new(),
+    )
+    .await
+    .expect("Failed to record results");
+
+    (group, tournament)
+}
+
+async fn fetch_all_time_elos(pool: &PgPool, group_id: Uuid) -> HashMap<Uuid, i32> {
+    sqlx::query_as::<_, (Uuid, i32)>("SELECT id, elo_rating FROM players WHERE group_id = $1")
+        .bind(group_id)
+        .fetch_all(pool)
+        .await
+        .expect("Failed to fetch players")
+        .into_iter()
+        .collect()
+}
+
+#[tokio::test]
+async fn test_apply_inactivity_decay_is_idempotent() {
+    let ctx = setup::setup_test_db().await;
+    let (group, _tournament) = setup_recorded_race(&ctx.pool).await;
+    models::GroupSettings::upsert(&ctx.pool, group.id, &decay_settings())
+        .await
+        .expect("Failed to store group settings");
+
+    let before = fetch_all_time_elos(&ctx.pool, group.id).await;
+    let now = Utc::now() + Duration::weeks(4) + Duration::days(1);
+
+    let report = rating_decay::apply_inactivity_decay(&ctx.pool, group.id, now, false)
+        .await
+        .expect("Failed to apply decay");
+
+    assert_eq!(report.inactive_players, 4);
+    let after = fetch_all_time_elos(&ctx.pool, group.id).await;
+    for (player_id, elo) in &before {
+        let expected = (elo - 30).max(STARTING_ELO).min(*elo);
+        assert_eq!(after.get(player_id), Some(&expected));
+    }
+    assert!(!report.decays.is_empty());
+
+    let report = rating_decay::apply_inactivity_decay(&ctx.pool, group.id, now, false)
+        .await
+        .expect("Failed to apply decay");
+    assert!(report.decays.is_empty());
+    assert_eq!(fetch_all_time_elos(&ctx.pool, group.id).await, after);
+}
+
+#[tokio::test]
+async fn test_apply_inactivity_decay_dry_run_writes_nothing() {
+    let ctx = setup::setup_test_db().await;
+    let (group, _tournament) = setup_recorded_race(&ctx.pool).await;
+    models::GroupSettings::upsert(&ctx.pool, group.id, &decay_settings())
+        .await
+        .expect("Failed to store group settings");
+
+    let before = fetch_all_time_elos(&ctx.pool, group.id).await;
+    let report = rating_decay::apply_inactivity_decay(
+        &ctx.pool,
+        group.id,
+        Utc::now() + Duration::weeks(4),
+        true,
+    )
+    .await
+    .expect("Failed to apply decay");
+
+    assert!(!report.decays.is_empty());
+    assert_eq!(fetch_all_time_elos(&ctx.pool, group.id).await, before);
+}
+
+#[tokio::test]
+async fn test_recompute_group_keeps_decay() {
+    let ctx = setup::setup_test_db().await;
+    let (group, _tournament) = setup_recorded_race(&ctx.pool).await;
+    models::GroupSettings::upsert(&ctx.pool, group.id, &decay_settings())
+        .await
+        .expect("Failed to store group settings");
+
+    rating_decay::apply_inactivity_decay(
+        &ctx.pool,
+        group.id,
+        Utc::now() + Duration::weeks(4),
+        false,
+    )
+    .await
+    .expect("Failed to apply decay");
+
+    let report = elo_replay::recompute_group(&ctx.pool, group.id, false)
+        .await
+        .expect("Failed to recompute group");
+
+    assert!(
+        report.rating_changes.is_empty(),
+        "Recomputing should replay decay events: {:?}",
+        report.rating_changes
+    );
+}
+
+#[tokio::test]
+async fn test_leaderboard_marks_provisional_players() {
+    let ctx = setup::setup_test_db().await;
+    let (group, tournament) = setup_recorded_race(&ctx.pool).await;
+
+    let leaderboard =
+        models::PlayerTournamentScore::get_tournament_leaderboard(&ctx.pool, tournament.id)
+            .await
+            .expect("Failed to fetch leaderboard");
+    assert_eq!(leaderboard.len(), 4);
+    assert!(leaderboard.iter().all(|row| row.races_played == 1 && row.provisional));
+
+    let settings = EloSettings {
+        provisional_race_count: 1,
+        ..EloSettings::default()
+    };
+    models::GroupSettings::upsert(&ctx.pool, group.id, &settings)
+        .await
+        .expect("Failed to store group settings");
+
+    let leaderboard =
+        models::PlayerTournamentScore::get_tournament_leaderboard(&ctx.pool, tournament.id)
+            .await
+            .expect("Failed to fetch leaderboard");
+    assert!(leaderboard.iter().all(|row| !row.provisional));
+}
@@ -1,6 +1,13 @@
-use mario_kart_leaderboard_backend::models::Player;
+mod common;
+
+use common::{fixtures, setup};
+use mario_kart_leaderboard_backend::models::{self, Player};
+use mario_kart_leaderboard_backend::services::elo_replay;
+use mario_kart_leaderboard_backend::services::match_service;
+use mario_kart_leaderboard_backend::services::notification_manager::NotificationManager;
+use mario_kart_leaderboard_backend::services::track_selection::TrackOptions;
 use mario_kart_leaderboard_backend::services::result_recording::{
-    create_player_elo_map, create_player_results,
+    self, create_player_elo_map, create_player_results,
 };
 use std::collections::HashMap;
 use uuid::Uuid;
@@ -11,8 +18,11 @@ fn create_test_player(id: Uuid, group_id: Uuid, name: &str, elo_rating: i32) ->
         group_id,
         name: name.to_string(),
         elo_rating,
+        rating_deviation: 350.0,
+        rating_volatility: 0.06,
         avatar_filename: None,
         disabled: false,
+        guest: false,
     }
 }
 
@@ -135,3 +145,376 @@ fn test_create_player_results_varied_positions() {
     assert_eq!(player_results[2].position, 24);
     assert_eq!(player_results[2].current_elo, 900);
 }
+
+#[tokio::test]
+async fn test_simulate_race_results_matches_recording_and_writes_nothing() {
+    let ctx = setup::setup_test_db().await;
+    let group = fixtures::create_test_group(&ctx.pool, "Test Group", "password")
+        .await
+        .expect("Failed to create test group");
+    let tournament = fixtures::create_test_tournaments(&ctx.pool, group.id, 1)
+        .await
+        .expect("Failed to create test tournaments")
+        .remove(0);
+    let players = fixtures::create_test_players(&ctx.pool, group.id, 4)
+        .await
+        .expect("Failed to create test players");
+    let match_record = fixtures::create_test_match(&ctx.pool, group.id, tournament.id, 1)
+        .await
+        .expect("Failed to create test match");
+    let teams = fixtures::create_test_teams(&ctx.pool, group.id, match_record.id, 2)
+        .await
+        .expect("Failed to create test teams");
+    fixtures::create_test_round(&ctx.pool, match_record.id, 1, None)
+        .await
+        .expect("Failed to create test round");
+
+    for (team, team_players) in teams.iter().zip(players.chunks(2)) {
+        let team_player_ids: Vec<Uuid> = team_players.iter().map(|p| p.id).collect();
+        fixtures::add_players_to_team(&ctx.pool, group.id, team.id, &team_player_ids)
+            .await
+            .expect("Failed to add players to team");
+        fixtures::add_players_to_round(
+            &ctx.pool,
+            group.id,
+            match_record.id,
+            1,
+            team.id,
+            &team_player_ids,
+        )
+        .await
+        .expect("Failed to add players to round");
+    }
+
+    let results: Vec<(Uuid, i32)> = players.iter().map(|p| p.id).zip([1, 5, 8, 12]).collect();
+
+    let simulation = result_recording::simulate_race_results(
+        &ctx.pool,
+        group.id,
+        1,
+        &results,
+        &[],
+        &match_record,
+    )
+    .await
+    .expect("Failed to simulate results");
+
+    let (race_scores,): (i64,) =
+        sqlx::query_as("SELECT COUNT(*) FROM player_race_scores WHERE match_id = $1")
+            .bind(match_record.id)
+            .fetch_one(&ctx.pool)
+            .await
+            .expect("Failed to count race scores");
+    assert_eq!(race_scores, 0);
+    let round = models::Round::find_one(&ctx.pool, match_record.id, 1)
+        .await
+        .expect("Failed to fetch round")
+        .expect("Round not found");
+    assert!(!round.completed);
+    let unchanged = models::Player::find_by_ids(&ctx.pool, &[players[0].id])
+        .await
+        .expect("Failed to fetch player");
+    assert_eq!(unchanged[0].elo_rating, players[0].elo_rating);
+
+    let recorded_match = result_recording::record_race_results(
+        &ctx.pool,
+        group.id,
+        &result_recording::RoundResults {
+            round_number: 1,
+            results: &results,
+            non_finishers: &[],
+            builds: &[],
+        },
+        &match_record,
+        &NotificationManager::new(),
+    )
+    .await
+    .expect("Failed to record results");
+
+    assert!(simulation.match_completed);
+    assert_eq!(simulation.match_completed, recorded_match.completed);
+
+    let player_ids: Vec<Uuid> = results.iter().map(|(id, _)| *id).collect();
+    let recorded_players = models::Player::find_by_ids(&ctx.pool, &player_ids)
+        .await
+        .expect("Failed to fetch players");
+    for player in &recorded_players {
+        let simulated = simulation
+            .players
+            .iter()
+            .find(|p| p.player_id == player.id)
+            .expect("Missing simulated player");
+        assert_eq!(simulated.all_time_elo_after, player.elo_rating);
+    }
+
+    let leaderboard =
+        models::PlayerTournamentScore::get_tournament_leaderboard(&ctx.pool, tournament.id)
+            .await
+            .expect("Failed to fetch leaderboard");
+    let simulated_leaderboard: Vec<(Uuid, i32)> = simulation
+        .leaderboard
+        .iter()
+        .map(|row| (row.player_id, row.elo_rating))
+        .collect();
+    let recorded_leaderboard: Vec<(Uuid, i32)> = leaderboard
+        .iter()
+        .map(|row| (row.player_id, row.elo_rating))
+        .collect();
+    assert_eq!(simulated_leaderboard, recorded_leaderboard);
+    for simulated in &simulation.players {
+        let row = leaderboard
+            .iter()
+            .find(|row| row.player_id == simulated.player_id)
+            .expect("Missing leaderboard row");
+        assert_eq!(simulated.tournament_elo_after, row.elo_rating);
+    }
+
+    let recorded_teams = models::Team::find_by_match_id(&ctx.pool, match_record.id)
+        .await
+        .expect("Failed to fetch teams");
+    for team in &recorded_teams {
+        let simulated = simulation
+            .teams
+            .iter()
+            .find(|t| t.team_id == team.id)
+            .expect("Missing simulated team");
+        assert_eq!(Some(simulated.score.round() as i32), team.score);
+    }
+}
+
+#[tokio::test]
+async fn test_team_scores_use_match_points_table() {
+    let ctx = setup::setup_test_db().await;
+    let group = fixtures::create_test_group(&ctx.pool, "Test Group", "password")
+        .await
+        .expect("Failed to create test group");
+    let tournament = fixtures::create_test_tournaments(&ctx.pool, group.id, 1)
+        .await
+        .expect("Failed to create test tournaments")
+        .remove(0);
+    let players = fixtures::create_test_players(&ctx.pool, group.id, 2)
+        .await
+        .expect("Failed to create test players");
+    let match_record = fixtures::create_test_match(&ctx.pool, group.id, tournament.id, 1)
+        .await
+        .expect("Failed to create test match");
+    assert_eq!(
+        match_record.points_table_id,
+        models::points_table::MK8_POINTS_TABLE_ID
+    );
+    let match_record = sqlx::query_as::<_, models::Match>(
+        "UPDATE matches SET points_table_id = $1 WHERE id = $2
+         RETURNING id, group_id, tournament_id, time, rounds, completed, points_table_id, mode, track_pool_id",
+    )
+    .bind(models::points_table::MK_WORLD_POINTS_TABLE_ID)
+    .bind(match_record.id)
+    .fetch_one(&ctx.pool)
+    .await
+    .expect("Failed to set points table");
+    let team = fixtures::create_test_team(&ctx.pool, group.id, match_record.id, 1)
+        .await
+        .expect("Failed to create test team");
+    fixtures::create_test_round(&ctx.pool, match_record.id, 1, None)
+        .await
+        .expect("Failed to create test round");
+
+    let player_ids: Vec<Uuid> = players.iter().map(|p| p.id).collect();
+    fixtures::add_players_to_team(&ctx.pool, group.id, team.id, &player_ids)
+        .await
+        .expect("Failed to add players to team");
+    fixtures::add_players_to_round(&ctx.pool, group.id, match_record.id, 1, team.id, &player_ids)
+        .await
+        .expect("Failed to add players to round");
+
+    let results: Vec<(Uuid, i32)> = player_ids.iter().copied().zip([13, 20]).collect();
+    result_recording::record_race_results(
+        &ctx.pool,
+        group.id,
+        &result_recording::RoundResults {
+            round_number: 1,
+            results: &results,
+            non_finishers: &[],
+            builds: &[],
+        },
+        &match_record,
+        &NotificationManager::new(),
+    )
+    .await
+    .expect("Failed to record results");
+
+    let teams = models::Team::find_by_match_id(&ctx.pool, match_record.id)
+        .await
+        .expect("Failed to fetch teams");
+    // Mario Kart World awards 5 and 3 points where Mario Kart 8 awards none
+    assert_eq!(teams[0].score, Some(8));
+}
+
+#[tokio::test]
+async fn test_free_for_all_match_scores_individual_points() {
+    let ctx = setup::setup_test_db().await;
+    let group = fixtures::create_test_group(&ctx.pool, "Test Group", "password")
+        .await
+        .expect("Failed to create test group");
+    let tournament = fixtures::create_test_tournaments(&ctx.pool, group.id, 1)
+        .await
+        .expect("Failed to create test tournaments")
+        .remove(0);
+    let players = fixtures::create_test_players(&ctx.pool, group.id, 3)
+        .await
+        .expect("Failed to create test players");
+    let player_ids: Vec<Uuid> = players.iter().map(|p| p.id).collect();
+
+    let mut match_record = match_service::create_free_for_all_match(
+        &ctx.pool,
+        &tournament,
+        &player_ids,
+        2,
+        None,
+        TrackOptions::default(),
+        &NotificationManager::new(),
+    )
+    .await
+    .expect("Failed to create free-for-all match");
+    assert_eq!(match_record.mode, models::MatchMode::FreeForAll);
+
+    for (round_number, positions) in [(1, [1, 2, 3]), (2, [3, 1, 2])] {
+        let results: Vec<(Uuid, i32)> = player_ids.iter().copied().zip(positions).collect();
+        match_record = result_recording::record_race_results(
+            &ctx.pool,
+            group.id,
+            &result_recording::RoundResults {
+                round_number,
+                results: &results,
+                non_finishers: &[],
+                builds: &[],
+            },
+            &match_record,
+            &NotificationManager::new(),
+        )
+        .await
+        .expect("Failed to record results");
+    }
+    assert!(match_record.completed);
+
+    let scores = models::PlayerMatchScore::find_by_match_id(&ctx.pool, match_record.id)
+        .await
+        .expect("Failed to fetch match scores");
+    let points: HashMap<Uuid, Option<i32>> =
+        scores.iter().map(|s| (s.player_id, s.points)).collect();
+    // Mario Kart 8 awards 15, 12 and 10 points for the top three
+    assert_eq!(points[&player_ids[0]], Some(25));
+    assert_eq!(points[&player_ids[1]], Some(27));
+    assert_eq!(points[&player_ids[2]], Some(22));
+    assert!(scores.iter().all(|s| s.tournament_elo_from_contributions == 0));
+
+    let contributions: i64 = sqlx::query_scalar(
+        "SELECT COUNT(*) FROM player_teammate_elo_contributions WHERE match_id = $1",
+    )
+    .bind(match_record.id)
+    .fetch_one(&ctx.pool)
+    .await
+    .expect("Failed to count contributions");
+    assert_eq!(contributions, 0);
+}
+
+#[tokio::test]
+async fn test_non_finishers_are_rated_and_scored_by_status() {
+    let ctx = setup::setup_test_db().await;
+    let group = fixtures::create_test_group(&ctx.pool, "Test Group", "password")
+        .await
+        .expect("Failed to create test group");
+    let tournament = fixtures::create_test_tournaments(&ctx.pool, group.id, 1)
+        .await
+        .expect("Failed to create test tournaments")
+        .remove(0);
+    let players = fixtures::create_test_players(&ctx.pool, group.id, 4)
+        .await
+        .expect("Failed to create test players");
+    let match_record = fixtures::create_test_match(&ctx.pool, group.id, tournament.id, 1)
+        .await
+        .expect("Failed to create test match");
+    let team = fixtures::create_test_team(&ctx.pool, group.id, match_record.id, 1)
+        .await
+        .expect("Failed to create test team");
+    fixtures::create_test_round(&ctx.pool, match_record.id, 1, None)
+        .await
+        .expect("Failed to create test round");
+
+    let player_ids: Vec<Uuid> = players.iter().map(|p| p.id).collect();
+    fixtures::add_players_to_team(&ctx.pool, group.id, team.id, &player_ids)
+        .await
+        .expect("Failed to add players to team");
+    fixtures::add_players_to_round(&ctx.pool, group.id, match_record.id, 1, team.id, &player_ids)
+        .await
+        .expect("Failed to add players to round");
+
+    let [winner, dnf, disqualified, disconnected] = player_ids[..] else {
+        panic!("Expected four players");
+    };
+    result_recording::record_race_results(
+        &ctx.pool,
+        group.id,
+        &result_recording::RoundResults {
+            round_number: 1,
+            results: &[(winner, 1)],
+            non_finishers: &[
+                (dnf, models::ResultStatus::Dnf),
+                (disqualified, models::ResultStatus::Disqualified),
+                (disconnected, models::ResultStatus::Disconnected),
+            ],
+            builds: &[],
+        },
+        &match_record,
+        &NotificationManager::new(),
+    )
+    .await
+    .expect("Failed to record results");
+
+    let scores: HashMap<Uuid, models::PlayerRaceScore> =
+        models::PlayerRaceScore::find_by_match_and_round(&ctx.pool, match_record.id, 1)
+            .await
+            .expect("Failed to fetch race scores")
+            .into_iter()
+            .map(|score| (score.player_id, score))
+            .collect();
+
+    assert_eq!(scores[&dnf].status, models::ResultStatus::Dnf);
+    assert_eq!(scores[&dnf].position, 24, "Non-finishers are placed last");
+    assert_eq!(scores[&disqualified].position, 24);
+    assert_eq!(scores[&disconnected].position, 24);
+    assert!(scores[&dnf].all_time_elo_change.unwrap() < 0);
+    assert_eq!(
+        scores[&disqualified].all_time_elo_change,
+        scores[&dnf].all_time_elo_change,
+        "DNFs and disqualified players are both rated as last place"
+    );
+    assert_eq!(scores[&disconnected].all_time_elo_change, Some(0));
+    assert_eq!(scores[&disconnected].tournament_elo_change, Some(0));
+
+    let disconnected_player = models::Player::find_by_id(&ctx.pool, disconnected)
+        .await
+        .expect("Failed to fetch player")
+        .expect("Player should exist");
+    assert_eq!(disconnected_player.elo_rating, 1200);
+
+    let teams = models::Team::find_by_match_id(&ctx.pool, match_record.id)
+        .await
+        .expect("Failed to fetch teams");
+    // 15 points for the win and 1 for the DNF's last place in the Mario Kart 8
+    // table; no points for the disqualified or disconnected players
+    assert_eq!(teams[0].score, Some(16));
+
+    // Replaying the group keeps the disconnected player unrated
+    elo_replay::recompute_group(&ctx.pool, group.id, false)
+        .await
+        .expect("Failed to recompute group");
+    let replayed = models::PlayerRaceScore::find_by_match_and_round(&ctx.pool, match_record.id, 1)
+        .await
+        .expect("Failed to fetch race scores");
+    let replayed_disconnected = replayed
+        .iter()
+        .find(|score| score.player_id == disconnected)
+        .expect("Missing race score");
+    assert_eq!(replayed_disconnected.all_time_elo_change, Some(0));
+    assert_eq!(replayed_disconnected.all_time_elo_after, Some(1200));
+}
@@ -4,7 +4,8 @@ use async_graphql::{Request, Variables, value};
 use common::{fixtures, setup};
 use mario_kart_leaderboard_backend::{
     graphql::context::GraphQLContext,
-    services::notification_manager::NotificationManager,
+    models,
+    services::{elo::EloSettings, notification_manager::NotificationManager},
 };
 
 #[tokio::test]
@@ -566,6 +567,90 @@ async fn test_record_round_results_validation_invalid_position() {
     assert!(response.errors[0].message.contains("between 1 and 24"));
 }
 
+#[tokio::test]
+async fn test_record_round_results_validation_position_past_race_size() {
+    let ctx = setup::setup_test_db().await;
+
+    let group = fixtures::create_test_group(&ctx.pool, "Test Group", "password")
+        .await
+        .expect("Failed to create test group");
+    models::GroupSettings::upsert(
+        &ctx.pool,
+        group.id,
+        &EloSettings {
+            total_race_size: 12,
+            ..EloSettings::default()
+        },
+    )
+    .await
+    .expect("Failed to store group settings");
+
+    let tournaments = fixtures::create_test_tournaments(&ctx.pool, group.id, 1)
+        .await
+        .expect("Failed to create test tournaments");
+    let tournament = &tournaments[0];
+
+    let players = fixtures::create_test_players(&ctx.pool, group.id, 2)
+        .await
+        .expect("Failed to create test players");
+
+    let match_record = fixtures::create_test_match(&ctx.pool, group.id, tournament.id, 1)
+        .await
+        .expect("Failed to create test match");
+
+    let teams = fixtures::create_test_teams(&ctx.pool, group.id, match_record.id, 1)
+        .await
+        .expect("Failed to create test teams");
+
+    let _rounds = fixtures::create_test_rounds(&ctx.pool, match_record.id, 1)
+        .await
+        .expect("Failed to create test rounds");
+
+    fixtures::add_players_to_round(
+        &ctx.pool,
+        group.id,
+        match_record.id,
+        1,
+        teams[0].id,
+        &players.iter().map(|p| p.id).collect::<Vec<_>>(),
+    )
+    .await
+    .expect("Failed to add players to round");
+
+    let mutation = r#"
+        mutation RecordResults($matchId: ID!, $roundNumber: Int!, $results: [PlayerResultInput!]!) {
+            recordRoundResults(matchId: $matchId, roundNumber: $roundNumber, results: $results) {
+                id
+            }
+        }
+    "#;
+
+    let request = Request::new(mutation)
+        .variables(Variables::from_value(value!({
+            "matchId": match_record.id.to_string(),
+            "roundNumber": 1,
+            "results": [
+                {"playerId": players[0].id.to_string(), "position": 1},
+                {"playerId": players[1].id.to_string(), "position": 20},
+            ]
+        })))
+        .data(ctx.config.clone());
+
+    let gql_ctx = GraphQLContext::new(ctx.pool.clone(), Some(group.id), NotificationManager::new());
+    let response = ctx.schema.execute(request.data(gql_ctx)).await;
+
+    assert!(!response.errors.is_empty());
+    assert!(response.errors[0].message.contains("between 1 and 12"));
+
+    let recorded: i64 =
+        sqlx::query_scalar("SELECT COUNT(*) FROM player_race_scores WHERE match_id = $1")
+            .bind(match_record.id)
+            .fetch_one(&ctx.pool)
+            .await
+            .expect("Failed to count race scores");
+    assert_eq!(recorded, 0);
+}
+
 #[tokio::test]
 async fn test_record_round_results_validation_duplicate_position() {
     let ctx = setup::setup_test_db().await;
@@ -1692,3 +1777,655 @@ async fn test_slot_assignments_updated_filters_by_group_match_and_round() {
     );
     assert_eq!(event.get("sourceClientId").and_then(|v| v.as_str()), Some("wanted"));
 }
+
+async fn setup_two_team_match(
+    pool: &sqlx::PgPool,
+    group_name: &str,
+) -> (
+    mario_kart_leaderboard_backend::models::Group,
+    Vec<mario_kart_leaderboard_backend::models::Player>,
+    mario_kart_leaderboard_backend::models::Match,
+) {
+    let group = fixtures::create_test_group(pool, group_name, "password")
+        .await
+        .expect("Failed to create test group");
+
+    let tournaments = fixtures::create_test_tournaments(pool, group.id, 1)
+        .await
+        .expect("Failed to create test tournaments");
+
+    let players = fixtures::create_test_players(pool, group.id, 4)
+        .await
+        .expect("Failed to create test players");
+
+    let match_record = fixtures::create_test_match(pool, group.id, tournaments[0].id, 2)
+        .await
+        .expect("Failed to create test match");
+
+    let teams = fixtures::create_test_teams(pool, group.id, match_record.id, 2)
+        .await
+        .expect("Failed to create test teams");
+
+    fixtures::create_test_rounds(pool, match_record.id, 2)
+        .await
+        .expect("Failed to create test rounds");
+
+    for (team, team_players) in teams.iter().zip(players.chunks(2)) {
+        let team_player_ids: Vec<_> = team_players.iter().map(|p| p.id).collect();
+        fixtures::add_players_to_team(pool, group.id, team.id, &team_player_ids)
+            .await
+            .expect("Failed to add players to team");
+        for round_num in 1..=2 {
+            fixtures::add_players_to_round(
+                pool,
+                group.id,
+                match_record.id,
+                round_num,
+                team.id,
+                &team_player_ids,
+            )
+            .await
+            .expect("Failed to add players to round");
+        }
+    }
+
+    (group, players, match_record)
+}
+
+async fn execute_round_results(
+    ctx: &setup::TestContext,
+    group_id: uuid::Uuid,
+    mutation_name: &str,
+    match_id: uuid::Uuid,
+    round_number: i32,
+    results: &[(uuid::Uuid, i32)],
+) -> async_graphql::Response {
+    let mutation = format!(
+        r#"
+        mutation Results($matchId: ID!, $roundNumber: Int!, $results: [PlayerResultInput!]!) {{
+            {mutation_name}(matchId: $matchId, roundNumber: $roundNumber, results: $results) {{
+                id
+                completed
+            }}
+        }}
+    "#
+    );
+
+    let results_value: Vec<async_graphql::Value> = results
+        .iter()
+        .map(|(player_id, position)| {
+            value!({"playerId": player_id.to_string(), "position": *position})
+        })
+        .collect();
+
+    let request = Request::new(mutation)
+        .variables(Variables::from_value(value!({
+            "matchId": match_id.to_string(),
+            "roundNumber": round_number,
+            "results": results_value,
+        })))
+        .data(ctx.config.clone());
+
+    let gql_ctx = GraphQLContext::new(ctx.pool.clone(), Some(group_id), NotificationManager::new());
+    ctx.schema.execute(request.data(gql_ctx)).await
+}
+
+async fn fetch_elo_snapshot(
+    pool: &sqlx::PgPool,
+    match_id: uuid::Uuid,
+    player_ids: &[uuid::Uuid],
+) -> Vec<(i32, i32, i32, i32, i32)> {
+    futures::future::join_all(player_ids.iter().map(|player_id| {
+        sqlx::query_as::<_, (i32, i32, i32, i32, i32)>(
+            "SELECT p.elo_rating, pts.elo_rating, prs.all_time_elo_after, prs.tournament_elo_after,
+                    COALESCE((SELECT SUM(contribution_amount)::int
+                              FROM player_teammate_elo_contributions
+                              WHERE match_id = $1 AND beneficiary_player_id = p.id), 0)
+             FROM players p
+             JOIN player_tournament_scores pts ON pts.player_id = p.id
+             JOIN player_race_scores prs ON prs.player_id = p.id
+                 AND prs.match_id = $1 AND prs.round_number = 2
+             WHERE p.id = $2",
+        )
+        .bind(match_id)
+        .bind(player_id)
+        .fetch_one(pool)
+    }))
+    .await
+    .into_iter()
+    .map(|row| row.expect("Failed to fetch ELO snapshot"))
+    .collect()
+}
+
+#[tokio::test]
+async fn test_amend_round_results_replays_later_rounds() {
+    let ctx = setup::setup_test_db().await;
+
+    let (amended_group, amended_players, amended_match) =
+        setup_two_team_match(&ctx.pool, "Amended Group").await;
+    let (direct_group, direct_players, direct_match) =
+        setup_two_team_match(&ctx.pool, "Direct Group").await;
+
+    let ids = |players: &[mario_kart_leaderboard_backend::models::Player]| {
+        players.iter().map(|p| p.id).collect::<Vec<_>>()
+    };
+    let amended_ids = ids(&amended_players);
+    let direct_ids = ids(&direct_players);
+
+    let mistaken_round_1 = [1, 2, 3, 4];
+    let corrected_round_1 = [4, 3, 2, 1];
+    let round_2 = [2, 1, 4, 3];
+    let with_positions = |ids: &[uuid::Uuid], positions: &[i32]| {
+        ids.iter().copied().zip(positions.iter().copied()).collect::<Vec<_>>()
+    };
+
+    for (round_number, positions) in [(1, mistaken_round_1), (2, round_2)] {
+        let response = execute_round_results(
+            &ctx,
+            amended_group.id,
+            "recordRoundResults",
+            amended_match.id,
+            round_number,
+            &with_positions(&amended_ids, &positions),
+        )
+        .await;
+        assert!(response.errors.is_empty(), "Expected no errors: {:?}", response.errors);
+    }
+
+    let response = execute_round_results(
+        &ctx,
+        amended_group.id,
+        "amendRoundResults",
+        amended_match.id,
+        1,
+        &with_positions(&amended_ids, &corrected_round_1),
+    )
+    .await;
+    assert!(response.errors.is_empty(), "Expected no errors: {:?}", response.errors);
+
+    for (round_number, positions) in [(1, corrected_round_1), (2, round_2)] {
+        let response = execute_round_results(
+            &ctx,
+            direct_group.id,
+            "recordRoundResults",
+            direct_match.id,
+            round_number,
+            &with_positions(&direct_ids, &positions),
+        )
+        .await;
+        assert!(response.errors.is_empty(), "Expected no errors: {:?}", response.errors);
+    }
+
+    let amended = fetch_elo_snapshot(&ctx.pool, amended_match.id, &amended_ids).await;
+    let direct = fetch_elo_snapshot(&ctx.pool, direct_match.id, &direct_ids).await;
+    assert_eq!(
+        amended, direct,
+        "Amending round 1 should produce the same ratings as recording the corrected results"
+    );
+
+    let positions: Vec<i32> = sqlx::query_scalar(
+        "SELECT position FROM player_race_scores
+         WHERE match_id = $1 AND round_number = 1 AND player_id = ANY($2)
+         ORDER BY position",
+    )
+    .bind(amended_match.id)
+    .bind(&amended_ids[..1])
+    .fetch_all(&ctx.pool)
+    .await
+    .expect("Failed to fetch positions");
+    assert_eq!(positions, vec![4]);
+}
+
+#[tokio::test]
+async fn test_amend_round_results_rejects_unrecorded_round() {
+    let ctx = setup::setup_test_db().await;
+
+    let (group, players, match_record) = setup_two_team_match(&ctx.pool, "Test Group").await;
+
+    let results: Vec<(uuid::Uuid, i32)> = players
+        .iter()
+        .enumerate()
+        .map(|(i, p)| (p.id, i as i32 + 1))
+        .collect();
+
+    let response =
+        execute_round_results(&ctx, group.id, "amendRoundResults", match_record.id, 1, &results)
+            .await;
+
+    assert!(!response.errors.is_empty(), "Expected an error");
+    assert!(
+        response.errors[0].message.contains("has not been recorded"),
+        "Unexpected error: {}",
+        response.errors[0].message
+    );
+}
+
+#[tokio::test]
+async fn test_amend_round_results_requires_all_round_players() {
+    let ctx = setup::setup_test_db().await;
+
+    let (group, players, match_record) = setup_two_team_match(&ctx.pool, "Test Group").await;
+
+    let results: Vec<(uuid::Uuid, i32)> = players
+        .iter()
+        .enumerate()
+        .map(|(i, p)| (p.id, i as i32 + 1))
+        .collect();
+
+    let response =
+        execute_round_results(&ctx, group.id, "recordRoundResults", match_record.id, 1, &results)
+            .await;
+    assert!(response.errors.is_empty(), "Expected no errors: {:?}", response.errors);
+
+    let response = execute_round_results(
+        &ctx,
+        group.id,
+        "amendRoundResults",
+        match_record.id,
+        1,
+        &results[..3],
+    )
+    .await;
+
+    assert!(!response.errors.is_empty(), "Expected an error");
+    assert!(
+        response.errors[0].message.contains("all players in this round"),
+        "Unexpected error: {}",
+        response.errors[0].message
+    );
+}
+
+async fn execute_undo_last_round(
+    ctx: &setup::TestContext,
+    group_id: uuid::Uuid,
+    match_id: uuid::Uuid,
+) -> async_graphql::Response {
+    let mutation = r#"
+        mutation UndoLastRound($matchId: ID!) {
+            undoLastRound(matchId: $matchId) {
+                id
+                completed
+            }
+        }
+    "#;
+
+    let request = Request::new(mutation)
+        .variables(Variables::from_value(value!({
+            "matchId": match_id.to_string(),
+        })))
+        .data(ctx.config.clone());
+
+    let gql_ctx = GraphQLContext::new(ctx.pool.clone(), Some(group_id), NotificationManager::new());
+    ctx.schema.execute(request.data(gql_ctx)).await
+}
+
+async fn fetch_ratings(pool: &sqlx::PgPool, player_ids: &[uuid::Uuid]) -> Vec<(uuid::Uuid, i32, i32)> {
+    sqlx::query_as::<_, (uuid::Uuid, i32, i32)>(
+        "SELECT p.id, p.elo_rating, COALESCE(pts.elo_rating, 1200)
+         FROM players p
+         LEFT JOIN player_tournament_scores pts ON pts.player_id = p.id
+         WHERE p.id = ANY($1)
+         ORDER BY p.id",
+    )
+    .bind(player_ids)
+    .fetch_all(pool)
+    .await
+    .expect("Failed to fetch ratings")
+}
+
+#[tokio::test]
+async fn test_undo_last_round_reverts_ratings_and_reopens_match() {
+    let ctx = setup::setup_test_db().await;
+
+    let (group, players, match_record) = setup_two_team_match(&ctx.pool, "Test Group").await;
+    let player_ids: Vec<uuid::Uuid> = players.iter().map(|p| p.id).collect();
+    let initial_ratings = fetch_ratings(&ctx.pool, &player_ids).await;
+
+    let round_1: Vec<(uuid::Uuid, i32)> = player_ids.iter().copied().zip([1, 2, 3, 4]).collect();
+    let round_2: Vec<(uuid::Uuid, i32)> = player_ids.iter().copied().zip([4, 3, 2, 1]).collect();
+
+    let response =
+        execute_round_results(&ctx, group.id, "recordRoundResults", match_record.id, 1, &round_1)
+            .await;
+    assert!(response.errors.is_empty(), "Expected no errors: {:?}", response.errors);
+    let ratings_after_round_1 = fetch_ratings(&ctx.pool, &player_ids).await;
+
+    let response =
+        execute_round_results(&ctx, group.id, "recordRoundResults", match_record.id, 2, &round_2)
+            .await;
+    assert!(response.errors.is_empty(), "Expected no errors: {:?}", response.errors);
+
+    let response = execute_undo_last_round(&ctx, group.id, match_record.id).await;
+    assert!(response.errors.is_empty(), "Expected no errors: {:?}", response.errors);
+    let data = response.data.into_json().expect("Failed to parse response");
+    assert_eq!(
+        data["undoLastRound"]["completed"].as_bool(),
+        Some(false),
+        "Undoing a round of a completed match should reopen it"
+    );
+
+    assert_eq!(fetch_ratings(&ctx.pool, &player_ids).await, ratings_after_round_1);
+
+    let (round_2_completed,): (bool,) =
+        sqlx::query_as("SELECT completed FROM rounds WHERE match_id = $1 AND round_number = 2")
+            .bind(match_record.id)
+            .fetch_one(&ctx.pool)
+            .await
+            .expect("Failed to fetch round");
+    assert!(!round_2_completed);
+
+    let (remaining_scores, remaining_contributions): (i64, i64) = sqlx::query_as(
+        "SELECT
+             (SELECT COUNT(*) FROM player_race_scores WHERE match_id = $1 AND round_number = 2),
+             (SELECT COUNT(*) FROM player_teammate_elo_contributions WHERE match_id = $1 AND round_number = 2)",
+    )
+    .bind(match_record.id)
+    .fetch_one(&ctx.pool)
+    .await
+    .expect("Failed to count round 2 rows");
+    assert_eq!((remaining_scores, remaining_contributions), (0, 0));
+
+    let response = execute_undo_last_round(&ctx, group.id, match_record.id).await;
+    assert!(response.errors.is_empty(), "Expected no errors: {:?}", response.errors);
+    assert_eq!(fetch_ratings(&ctx.pool, &player_ids).await, initial_ratings);
+
+    let response =
+        execute_round_results(&ctx, group.id, "recordRoundResults", match_record.id, 1, &round_1)
+            .await;
+    assert!(
+        response.errors.is_empty(),
+        "Undone round should be recordable again: {:?}",
+        response.errors
+    );
+    assert_eq!(fetch_ratings(&ctx.pool, &player_ids).await, ratings_after_round_1);
+}
+
+#[tokio::test]
+async fn test_undo_last_round_without_results_fails() {
+    let ctx = setup::setup_test_db().await;
+
+    let (group, _players, match_record) = setup_two_team_match(&ctx.pool, "Test Group").await;
+
+    let response = execute_undo_last_round(&ctx, group.id, match_record.id).await;
+
+    assert!(!response.errors.is_empty(), "Expected an error");
+    assert!(
+        response.errors[0].message.contains("No recorded rounds"),
+        "Unexpected error: {}",
+        response.errors[0].message
+    );
+}
+
+#[tokio::test]
+async fn test_undo_last_round_other_group_fails() {
+    let ctx = setup::setup_test_db().await;
+
+    let (_group, _players, match_record) = setup_two_team_match(&ctx.pool, "Test Group").await;
+    let other_group = fixtures::create_test_group(&ctx.pool, "Other Group", "password")
+        .await
+        .expect("Failed to create other group");
+
+    let response = execute_undo_last_round(&ctx, other_group.id, match_record.id).await;
+
+    assert!(!response.errors.is_empty(), "Expected an error");
+    assert_eq!(response.errors[0].message, "Match not found");
+}
+
+#[tokio::test]
+async fn test_record_round_results_with_builds() {
+    let ctx = setup::setup_test_db().await;
+
+    let group = fixtures::create_test_group(&ctx.pool, "Test Group", "password")
+        .await
+        .expect("Failed to create test group");
+
+    let tournaments = fixtures::create_test_tournaments(&ctx.pool, group.id, 1)
+        .await
+        .expect("Failed to create test tournaments");
+
+    let players = fixtures::create_test_players(&ctx.pool, group.id, 2)
+        .await
+        .expect("Failed to create test players");
+
+    let match_record = fixtures::create_test_match(&ctx.pool, group.id, tournaments[0].id, 2)
+        .await
+        .expect("Failed to create test match");
+
+    let teams = fixtures::create_test_teams(&ctx.pool, group.id, match_record.id, 2)
+        .await
+        .expect("Failed to create test teams");
+
+    let _rounds = fixtures::create_test_rounds(&ctx.pool, match_record.id, 2)
+        .await
+        .expect("Failed to create test rounds");
+
+    fixtures::add_players_to_round(
+        &ctx.pool,
+        group.id,
+        match_record.id,
+        1,
+        teams[0].id,
+        &players.iter().map(|p| p.id).collect::<Vec<_>>(),
+    )
+    .await
+    .expect("Failed to add players to round");
+
+    let part_id = |kind: &'static str, name: &'static str| {
+        let pool = ctx.pool.clone();
+        async move {
+            sqlx::query_scalar::<_, uuid::Uuid>(
+                "SELECT id FROM build_parts WHERE kind::text = $1 AND name = $2",
+            )
+            .bind(kind)
+            .bind(name)
+            .fetch_one(&pool)
+            .await
+            .expect("Failed to fetch build part")
+            .to_string()
+        }
+    };
+    let mario = part_id("character", "Mario").await;
+    let standard_kart = part_id("kart", "Standard Kart").await;
+    let standard_wheels = part_id("wheels", "Standard").await;
+    let super_glider = part_id("glider", "Super Glider").await;
+
+    let mutation = r#"
+        mutation RecordResults($matchId: ID!, $roundNumber: Int!, $results: [PlayerResultInput!]!) {
+            recordRoundResults(matchId: $matchId, roundNumber: $roundNumber, results: $results) {
+                id
+            }
+        }
+    "#;
+
+    // A kart ID given as the character is rejected before anything is recorded
+    let request = Request::new(mutation)
+        .variables(Variables::from_value(value!({
+            "matchId": match_record.id.to_string(),
+            "roundNumber": 1,
+            "results": [
+                {"playerId": players[0].id.to_string(), "position": 1,
+                 "build": {"characterId": standard_kart.clone()}},
+                {"playerId": players[1].id.to_string(), "position": 2},
+            ]
+        })))
+        .data(ctx.config.clone());
+
+    let gql_ctx = GraphQLContext::new(ctx.pool.clone(), Some(group.id), NotificationManager::new());
+    let response = ctx.schema.execute(request.data(gql_ctx)).await;
+
+    assert!(!response.errors.is_empty(), "Expected an error");
+    assert_eq!(response.errors[0].message, "Build part not found");
+
+    let request = Request::new(mutation)
+        .variables(Variables::from_value(value!({
+            "matchId": match_record.id.to_string(),
+            "roundNumber": 1,
+            "results": [
+                {"playerId": players[0].id.to_string(), "position": 1,
+                 "build": {
+                     "characterId": mario.clone(),
+                     "kartId": standard_kart.clone(),
+                     "wheelsId": standard_wheels.clone(),
+                     "gliderId": super_glider.clone(),
+                 }},
+                {"playerId": players[1].id.to_string(), "position": 2},
+            ]
+        })))
+        .data(ctx.config.clone());
+
+    let gql_ctx = GraphQLContext::new(ctx.pool.clone(), Some(group.id), NotificationManager::new());
+    let response = ctx.schema.execute(request.data(gql_ctx)).await;
+
+    assert!(
+        response.errors.is_empty(),
+        "Expected no errors: {:?}",
+        response.errors
+    );
+
+    let query = r#"
+        query Player($playerId: ID!) {
+            playerById(playerId: $playerId) {
+                characterStats {
+                    character { name }
+                    racesPlayed
+                    averagePosition
+                }
+                mostPickedBuild {
+                    character { name }
+                    kart { name }
+                    wheels { name }
+                    glider { name }
+                    racesPlayed
+                }
+            }
+        }
+    "#;
+
+    let request = Request::new(query)
+        .variables(Variables::from_value(value!({
+            "playerId": players[0].id.to_string(),
+        })))
+        .data(ctx.config.clone());
+
+    let gql_ctx = GraphQLContext::new(ctx.pool.clone(), Some(group.id), NotificationManager::new());
+    let response = ctx.schema.execute(request.data(gql_ctx)).await;
+
+    assert!(
+        response.errors.is_empty(),
+        "Expected no errors: {:?}",
+        response.errors
+    );
+
+    let data = response.data.into_json().expect("Failed to parse response");
+    let player = &data["playerById"];
+
+    assert_eq!(player["characterStats"][0]["character"]["name"], "Mario");
+    assert_eq!(player["characterStats"][0]["racesPlayed"], 1);
+    assert_eq!(player["characterStats"][0]["averagePosition"], 1.0);
+    assert_eq!(player["mostPickedBuild"]["kart"]["name"], "Standard Kart");
+    assert_eq!(player["mostPickedBuild"]["wheels"]["name"], "Standard");
+    assert_eq!(player["mostPickedBuild"]["glider"]["name"], "Super Glider");
+    assert_eq!(player["mostPickedBuild"]["racesPlayed"], 1);
+
+    let no_build: (Option<uuid::Uuid>,) = sqlx::query_as(
+        "SELECT character_id FROM player_race_scores WHERE match_id = $1 AND player_id = $2",
+    )
+    .bind(match_record.id)
+    .bind(players[1].id)
+    .fetch_one(&ctx.pool)
+    .await
+    .expect("Failed to fetch race score");
+
+    assert_eq!(no_build.0, None);
+}
+
+#[tokio::test]
+async fn test_amend_round_results_with_statuses() {
+    let ctx = setup::setup_test_db().await;
+
+    let (group, players, match_record) = setup_two_team_match(&ctx.pool, "Test Group").await;
+
+    let results: Vec<(uuid::Uuid, i32)> = players
+        .iter()
+        .enumerate()
+        .map(|(i, p)| (p.id, i as i32 + 1))
+        .collect();
+
+    let response =
+        execute_round_results(&ctx, group.id, "recordRoundResults", match_record.id, 1, &results)
+            .await;
+    assert!(response.errors.is_empty(), "Expected no errors: {:?}", response.errors);
+
+    let mutation = r#"
+        mutation AmendResults($matchId: ID!, $roundNumber: Int!, $results: [PlayerResultInput!]!) {
+            amendRoundResults(matchId: $matchId, roundNumber: $roundNumber, results: $results) {
+                id
+            }
+        }
+    "#;
+    let execute = |disconnected_position: Option<i32>| {
+        let mut disconnected = value!({
+            "playerId": players[3].id.to_string(),
+            "status": "DISCONNECTED",
+        });
+        if let (Some(position), async_graphql::Value::Object(fields)) =
+            (disconnected_position, &mut disconnected)
+        {
+            fields.insert(async_graphql::Name::new("position"), value!(position));
+        }
+
+        let request = Request::new(mutation)
+            .variables(Variables::from_value(value!({
+                "matchId": match_record.id.to_string(),
+                "roundNumber": 1,
+                "results": [
+                    {"playerId": players[0].id.to_string(), "position": 1},
+                    {"playerId": players[1].id.to_string(), "status": "DNF"},
+                    {"playerId": players[2].id.to_string(), "position": 2},
+                    disconnected,
+                ]
+            })))
+            .data(ctx.config.clone());
+        let gql_ctx =
+            GraphQLContext::new(ctx.pool.clone(), Some(group.id), NotificationManager::new());
+        ctx.schema.execute(request.data(gql_ctx))
+    };
+
+    let response = execute(Some(4)).await;
+    assert!(!response.errors.is_empty(), "Expected an error");
+    assert_eq!(
+        response.errors[0].message,
+        "Players who did not finish cannot have a position"
+    );
+
+    let response = execute(None).await;
+    assert!(response.errors.is_empty(), "Expected no errors: {:?}", response.errors);
+
+    let rows: Vec<(uuid::Uuid, i32, String, Option<i32>)> = sqlx::query_as(
+        "SELECT player_id, position, status::text, all_time_elo_change
+         FROM player_race_scores
+         WHERE match_id = $1 AND round_number = 1",
+    )
+    .bind(match_record.id)
+    .fetch_all(&ctx.pool)
+    .await
+    .expect("Failed to fetch race scores");
+    let row = |player_id: uuid::Uuid| {
+        rows.iter()
+            .find(|r| r.0 == player_id)
+            .cloned()
+            .expect("Missing race score")
+    };
+
+    assert_eq!(row(players[1].id).1, 24);
+    assert_eq!(row(players[1].id).2, "dnf");
+    assert_eq!(row(players[3].id).2, "disconnected");
+    assert_eq!(row(players[3].id).3, Some(0));
+
+    let elo_rating: i32 = sqlx::query_scalar("SELECT elo_rating FROM players WHERE id = $1")
+        .bind(players[3].id)
+        .fetch_one(&ctx.pool)
+        .await
+        .expect("Failed to fetch player");
+    assert_eq!(elo_rating, 1200, "Disconnected players keep their rating");
+}
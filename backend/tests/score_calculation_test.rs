@@ -1,4 +1,7 @@
-use mario_kart_leaderboard_backend::services::score_calculation::calculate_team_scores_from_positions;
+use mario_kart_leaderboard_backend::services::score_calculation::{
+    calculate_team_scores_from_positions, calculate_team_scores_with_points,
+};
+use mario_kart_leaderboard_backend::services::scoring::MK_WORLD_POINTS;
 use uuid::Uuid;
 
 // ============================================================================
@@ -166,3 +169,29 @@ fn test_calculate_team_scores_single_player_per_team() {
     assert_eq!(scores.get(&team1), Some(&15.0));
     assert_eq!(scores.get(&team2), Some(&1.0));
 }
+
+// ============================================================================
+// Tests for `calculate_team_scores_with_points`
+// ============================================================================
+
+#[test]
+fn test_calculate_team_scores_with_mk_world_points() {
+    let team1 = Uuid::new_v4();
+    let team2 = Uuid::new_v4();
+    let race_scores = vec![(team1, 5), (team1, 13), (team2, 24), (team2, 12)];
+
+    let scores = calculate_team_scores_with_points(&race_scores, 1, &MK_WORLD_POINTS);
+
+    assert_eq!(scores.get(&team1), Some(&(9.0 + 5.0)));
+    assert_eq!(scores.get(&team2), Some(&(1.0 + 6.0)));
+}
+
+#[test]
+fn test_calculate_team_scores_with_short_custom_table() {
+    let team_id = Uuid::new_v4();
+    let race_scores = vec![(team_id, 1), (team_id, 2), (team_id, 3)];
+
+    let scores = calculate_team_scores_with_points(&race_scores, 3, &[10, 5]);
+
+    assert_eq!(scores.get(&team_id), Some(&5.0));
+}
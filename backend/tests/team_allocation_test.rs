@@ -1,5 +1,13 @@
+use mario_kart_leaderboard_backend::error::AppError;
 use mario_kart_leaderboard_backend::models::Player;
-use mario_kart_leaderboard_backend::services::team_allocation::{allocate_teams, allocate_teams_randomly, calculate_team_sizes};
+use mario_kart_leaderboard_backend::services::elo::EloSettings;
+use mario_kart_leaderboard_backend::services::scoring::MK8_POINTS;
+use mario_kart_leaderboard_backend::services::team_allocation::{
+    EXACT_BALANCING_MAX_PLAYERS, PairConstraints, PairingHistory, RacePointsModel, Team,
+    allocate_teams, allocate_teams_optimally, allocate_teams_randomly,
+    allocate_teams_randomly_with_constraints, allocate_teams_with_constraints,
+    allocate_teams_with_history, calculate_team_sizes,
+};
 use std::collections::HashMap;
 use uuid::Uuid;
 
@@ -9,8 +17,11 @@ fn create_test_player(name: &str, elo_rating: i32) -> Player {
         group_id: Uuid::new_v4(),
         name: name.to_string(),
         elo_rating,
+        rating_deviation: 350.0,
+        rating_volatility: 0.06,
         avatar_filename: None,
         disabled: false,
+        guest: false,
     }
 }
 
@@ -343,3 +354,328 @@ fn test_allocate_teams_randomly_single_player() {
     assert_eq!(teams[0].total_elo, 1200);
     assert_eq!(teams[0].team_num, 1);
 }
+
+// ============================================================================
+// Tests for `allocate_teams_optimally`
+// ============================================================================
+
+fn average_elo_spread(teams: &[Team]) -> f64 {
+    let averages: Vec<f64> = teams
+        .iter()
+        .map(|team| team.total_elo as f64 / team.players.len() as f64)
+        .collect();
+    averages.iter().cloned().fold(f64::NEG_INFINITY, f64::max)
+        - averages.iter().cloned().fold(f64::INFINITY, f64::min)
+}
+
+fn allocate_optimally(players: &[Player], players_per_race: i32) -> Vec<Team> {
+    allocate_optimally_with_history(players, players_per_race, &PairingHistory::default(), 0)
+}
+
+fn allocate_optimally_with_history(
+    players: &[Player],
+    players_per_race: i32,
+    history: &PairingHistory,
+    repeat_penalty: i32,
+) -> Vec<Team> {
+    allocate_optimally_with_constraints(
+        players,
+        players_per_race,
+        history,
+        repeat_penalty,
+        &PairConstraints::default(),
+    )
+    .expect("Unconstrained allocation should succeed")
+}
+
+fn allocate_optimally_with_constraints(
+    players: &[Player],
+    players_per_race: i32,
+    history: &PairingHistory,
+    repeat_penalty: i32,
+    constraints: &PairConstraints,
+) -> Result<Vec<Team>, AppError> {
+    let settings = EloSettings::default();
+    let model = RacePointsModel {
+        num_races: 4,
+        players_per_race,
+        points: &MK8_POINTS,
+        settings: &settings,
+    };
+    allocate_teams_optimally(
+        players,
+        &players_per_race,
+        &HashMap::new(),
+        &model,
+        history,
+        repeat_penalty,
+        constraints,
+    )
+}
+
+#[test]
+fn test_allocate_teams_optimally_beats_greedy_gap() {
+    // Greedy ends with 4200 against 4000; 1600+1400+1100 and 1500+1300+1300
+    // are both 4100.
+    let players: Vec<Player> = [1600, 1500, 1400, 1300, 1300, 1100]
+        .iter()
+        .enumerate()
+        .map(|(i, elo)| create_test_player(&format!("Player {i}"), *elo))
+        .collect();
+
+    let greedy = allocate_teams(&players, &2, &HashMap::new());
+    let optimal = allocate_optimally(&players, 2);
+
+    assert!(average_elo_spread(&greedy) > 0.0);
+    assert_eq!(average_elo_spread(&optimal), 0.0);
+}
+
+#[test]
+fn test_allocate_teams_optimally_never_worse_than_greedy() {
+    let elos = [1850, 1720, 1610, 1555, 1490, 1420, 1380, 1300, 1240, 1180, 1090, 1010];
+    let players: Vec<Player> = elos
+        .iter()
+        .enumerate()
+        .map(|(i, elo)| create_test_player(&format!("Player {i}"), *elo))
+        .collect();
+
+    for players_per_race in [2, 3, 4] {
+        let greedy = allocate_teams(&players, &players_per_race, &HashMap::new());
+        let optimal = allocate_optimally(&players, players_per_race);
+        // Optimal balancing may trade a little ELO spread for expected points spread
+        assert!(average_elo_spread(&optimal) <= average_elo_spread(&greedy) + 20.0);
+    }
+}
+
+#[test]
+fn test_allocate_teams_optimally_keeps_sizes_and_players() {
+    let players: Vec<Player> = (0..10)
+        .map(|i| create_test_player(&format!("Player {i}"), 1000 + i * 73))
+        .collect();
+
+    let teams = allocate_optimally(&players, 3);
+
+    let sizes: Vec<usize> = teams.iter().map(|team| team.players.len()).collect();
+    assert_eq!(sizes, calculate_team_sizes(10, 3));
+    assert_eq!(
+        teams.iter().map(|team| team.team_num).collect::<Vec<_>>(),
+        vec![1, 2, 3]
+    );
+
+    let mut assigned: Vec<Uuid> = teams
+        .iter()
+        .flat_map(|team| team.players.iter().map(|p| p.id))
+        .collect();
+    let mut expected: Vec<Uuid> = players.iter().map(|p| p.id).collect();
+    assigned.sort();
+    expected.sort();
+    assert_eq!(assigned, expected);
+
+    for team in &teams {
+        assert_eq!(team.total_elo, team.players.iter().map(|p| p.elo_rating).sum::<i32>());
+    }
+}
+
+#[test]
+fn test_allocate_teams_optimally_local_search_for_large_matches() {
+    let players: Vec<Player> = (0..20)
+        .map(|i| create_test_player(&format!("Player {i}"), 900 + (i * i * 7) % 800))
+        .collect();
+
+    let greedy = allocate_teams(&players, &4, &HashMap::new());
+    let optimal = allocate_optimally(&players, 4);
+
+    assert_eq!(optimal.len(), 4);
+    assert!(optimal.iter().all(|team| team.players.len() == 5));
+    assert!(average_elo_spread(&optimal) <= average_elo_spread(&greedy) + 20.0);
+}
+
+#[test]
+fn test_allocate_teams_optimally_local_search_improves_on_greedy() {
+    // Greedy ends with 8200 against 8000 (1600+1300+1300 against
+    // 1500+1400+1100, plus four 1000s each); swapping 1600 and 1500 evens
+    // the teams out.
+    let players: Vec<Player> = [1600, 1500, 1400, 1300, 1300, 1100]
+        .into_iter()
+        .chain([1000; 8])
+        .enumerate()
+        .map(|(i, elo)| create_test_player(&format!("Player {i}"), elo))
+        .collect();
+    assert!(players.len() > EXACT_BALANCING_MAX_PLAYERS);
+
+    let greedy = allocate_teams(&players, &2, &HashMap::new());
+    let optimal = allocate_optimally(&players, 2);
+
+    assert_eq!(
+        greedy.iter().map(|team| team.total_elo).collect::<Vec<_>>(),
+        vec![8200, 8000]
+    );
+    assert_eq!(average_elo_spread(&optimal), 0.0);
+}
+
+// ============================================================================
+// Tests for teammate variety
+// ============================================================================
+
+fn team_ids(teams: &[Team]) -> Vec<Vec<Uuid>> {
+    teams
+        .iter()
+        .map(|team| team.players.iter().map(|p| p.id).collect())
+        .collect()
+}
+
+#[test]
+fn test_pairing_history_counts_and_freshness() {
+    let (a, b, c, d) = (Uuid::new_v4(), Uuid::new_v4(), Uuid::new_v4(), Uuid::new_v4());
+    let history = PairingHistory::from_counts([(a, b, 2), (b, a, 1), (c, d, 1)]);
+
+    assert_eq!(history.times_paired(b, a), 3);
+    assert_eq!(history.times_paired(a, c), 0);
+    assert_eq!(history.repeat_pairings(&[a, b, c]), 3);
+    assert_eq!(history.freshness(&[vec![a, b], vec![c, d]]), 0.0);
+    assert_eq!(history.freshness(&[vec![a, c], vec![b, d]]), 1.0);
+    assert_eq!(history.freshness(&[vec![a, b, c]]), 2.0 / 3.0);
+    assert_eq!(history.freshness(&[vec![a], vec![b]]), 1.0);
+}
+
+#[test]
+fn test_allocate_teams_with_history_avoids_repeat_teammates() {
+    let players = vec![
+        create_test_player("Player 1", 1500),
+        create_test_player("Player 2", 1400),
+        create_test_player("Player 3", 1300),
+        create_test_player("Player 4", 1200),
+    ];
+    let history = PairingHistory::from_counts([(players[1].id, players[2].id, 3)]);
+
+    let balanced = allocate_teams_with_history(&players, &2, &HashMap::new(), &history, 0);
+    assert_eq!(history.freshness(&team_ids(&balanced)), 0.5);
+
+    let varied = allocate_teams_with_history(&players, &2, &HashMap::new(), &history, 50);
+    assert_eq!(history.freshness(&team_ids(&varied)), 1.0);
+}
+
+#[test]
+fn test_allocate_teams_optimally_trades_balance_for_fresh_pairings() {
+    let players = vec![
+        create_test_player("Player 1", 1500),
+        create_test_player("Player 2", 1400),
+        create_test_player("Player 3", 1300),
+        create_test_player("Player 4", 1200),
+    ];
+    let history = PairingHistory::from_counts([(players[1].id, players[2].id, 3)]);
+
+    let balanced = allocate_optimally_with_history(&players, 2, &history, 0);
+    assert_eq!(average_elo_spread(&balanced), 0.0);
+    assert_eq!(history.freshness(&team_ids(&balanced)), 0.5);
+
+    let varied = allocate_optimally_with_history(&players, 2, &history, 200);
+    assert_eq!(history.freshness(&team_ids(&varied)), 1.0);
+}
+
+// ============================================================================
+// Tests for pair constraints
+// ============================================================================
+
+fn team_of(teams: &[Team], player: &Player) -> i32 {
+    teams
+        .iter()
+        .find(|team| team.players.iter().any(|p| p.id == player.id))
+        .map(|team| team.team_num)
+        .expect("Player should be on a team")
+}
+
+fn six_players() -> Vec<Player> {
+    [1600, 1500, 1400, 1300, 1200, 1100]
+        .iter()
+        .enumerate()
+        .map(|(i, &elo)| create_test_player(&format!("Player {}", i + 1), elo))
+        .collect()
+}
+
+#[test]
+fn test_allocate_teams_with_constraints_keeps_pairs_together_and_apart() {
+    let players = six_players();
+    let mut constraints = PairConstraints::default();
+    constraints.keep_together(players[0].id, players[1].id);
+    constraints.keep_apart(players[4].id, players[5].id);
+
+    let history = PairingHistory::default();
+    let greedy =
+        allocate_teams_with_constraints(&players, &2, &HashMap::new(), &history, 0, &constraints)
+            .expect("Constraints should be satisfiable");
+    let random =
+        allocate_teams_randomly_with_constraints(&players, &2, &HashMap::new(), &constraints)
+            .expect("Constraints should be satisfiable");
+    let optimal = allocate_optimally_with_constraints(&players, 2, &history, 0, &constraints)
+        .expect("Constraints should be satisfiable");
+
+    for teams in [greedy, random, optimal] {
+        assert!(teams.iter().all(|team| team.players.len() == 3));
+        assert_eq!(team_of(&teams, &players[0]), team_of(&teams, &players[1]));
+        assert_ne!(team_of(&teams, &players[4]), team_of(&teams, &players[5]));
+        constraints
+            .check_teams(&teams)
+            .expect("Teams should honour the constraints");
+    }
+}
+
+#[test]
+fn test_allocate_teams_with_constraints_rejects_oversized_unit() {
+    let players = six_players();
+    let mut constraints = PairConstraints::default();
+    constraints.keep_together(players[0].id, players[1].id);
+    constraints.keep_together(players[1].id, players[2].id);
+
+    let result = allocate_teams_with_constraints(
+        &players,
+        &3,
+        &HashMap::new(),
+        &PairingHistory::default(),
+        0,
+        &constraints,
+    );
+
+    match result {
+        Err(AppError::InvalidInput(message)) => {
+            assert!(message.contains("Player 1, Player 2, Player 3"), "{message}");
+        }
+        other => panic!("Expected InvalidInput, got {other:?}"),
+    }
+}
+
+#[test]
+fn test_allocate_teams_with_constraints_names_blocking_constraint() {
+    let players = six_players();
+    let mut constraints = PairConstraints::default();
+    constraints.keep_apart(players[0].id, players[1].id);
+    constraints.keep_apart(players[1].id, players[2].id);
+    constraints.keep_apart(players[0].id, players[2].id);
+
+    let result = allocate_teams_randomly_with_constraints(&players, &2, &HashMap::new(), &constraints);
+
+    match result {
+        Err(AppError::InvalidInput(message)) => {
+            assert!(message.contains("Player 1 and Player 2 apart"), "{message}");
+        }
+        other => panic!("Expected InvalidInput, got {other:?}"),
+    }
+}
+
+#[test]
+fn test_allocate_teams_with_constraints_rejects_contradiction() {
+    let players = six_players();
+    let mut constraints = PairConstraints::default();
+    constraints.keep_together(players[0].id, players[1].id);
+    constraints.keep_apart(players[1].id, players[0].id);
+
+    let result = allocate_optimally_with_constraints(
+        &players,
+        2,
+        &PairingHistory::default(),
+        0,
+        &constraints,
+    );
+
+    assert!(matches!(result, Err(AppError::InvalidInput(_))));
+}
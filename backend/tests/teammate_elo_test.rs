@@ -1,3 +1,4 @@
+use mario_kart_leaderboard_backend::services::elo::EloSettings;
 use mario_kart_leaderboard_backend::services::teammate_elo::calculate_teammate_contributions;
 use std::collections::HashMap;
 use uuid::Uuid;
@@ -20,8 +21,13 @@ fn test_contribution_is_10_percent() {
     let mut elo_changes = HashMap::new();
     elo_changes.insert(player1, 50);
 
-    let (contributions, adjustments) =
-        calculate_teammate_contributions(&results, &player_to_team, &team_to_players, &elo_changes);
+    let (contributions, adjustments) = calculate_teammate_contributions(
+        &results,
+        &player_to_team,
+        &team_to_players,
+        &elo_changes,
+        &EloSettings::default(),
+    );
 
     assert_eq!(contributions.len(), 1);
     assert_eq!(contributions[0].source_player_id, player1);
@@ -49,8 +55,13 @@ fn test_contribution_negative_elo() {
     let mut elo_changes = HashMap::new();
     elo_changes.insert(player1, -50);
 
-    let (contributions, adjustments) =
-        calculate_teammate_contributions(&results, &player_to_team, &team_to_players, &elo_changes);
+    let (contributions, adjustments) = calculate_teammate_contributions(
+        &results,
+        &player_to_team,
+        &team_to_players,
+        &elo_changes,
+        &EloSettings::default(),
+    );
 
     assert_eq!(contributions.len(), 1);
     assert_eq!(contributions[0].source_tournament_elo_change, -50);
@@ -74,8 +85,13 @@ fn test_no_self_contribution() {
     let mut elo_changes = HashMap::new();
     elo_changes.insert(player1, 50);
 
-    let (contributions, adjustments) =
-        calculate_teammate_contributions(&results, &player_to_team, &team_to_players, &elo_changes);
+    let (contributions, adjustments) = calculate_teammate_contributions(
+        &results,
+        &player_to_team,
+        &team_to_players,
+        &elo_changes,
+        &EloSettings::default(),
+    );
 
     assert_eq!(contributions.len(), 0);
     assert_eq!(adjustments.len(), 0);
@@ -101,8 +117,13 @@ fn test_multiple_teammates() {
     let mut elo_changes = HashMap::new();
     elo_changes.insert(player1, 100);
 
-    let (contributions, adjustments) =
-        calculate_teammate_contributions(&results, &player_to_team, &team_to_players, &elo_changes);
+    let (contributions, adjustments) = calculate_teammate_contributions(
+        &results,
+        &player_to_team,
+        &team_to_players,
+        &elo_changes,
+        &EloSettings::default(),
+    );
 
     assert_eq!(contributions.len(), 2);
     assert_eq!(adjustments.get(&player2), Some(&20));
@@ -135,8 +156,13 @@ fn test_contribution_rounding() {
     let mut elo_changes = HashMap::new();
     elo_changes.insert(player1, 47);
 
-    let (contributions, adjustments) =
-        calculate_teammate_contributions(&results, &player_to_team, &team_to_players, &elo_changes);
+    let (contributions, adjustments) = calculate_teammate_contributions(
+        &results,
+        &player_to_team,
+        &team_to_players,
+        &elo_changes,
+        &EloSettings::default(),
+    );
 
     assert_eq!(contributions.len(), 1);
     assert_eq!(contributions[0].contribution_amount, 9);
@@ -150,8 +176,13 @@ fn test_empty_results() {
     let team_to_players = HashMap::new();
     let elo_changes = HashMap::new();
 
-    let (contributions, adjustments) =
-        calculate_teammate_contributions(&results, &player_to_team, &team_to_players, &elo_changes);
+    let (contributions, adjustments) = calculate_teammate_contributions(
+        &results,
+        &player_to_team,
+        &team_to_players,
+        &elo_changes,
+        &EloSettings::default(),
+    );
 
     assert_eq!(contributions.len(), 0);
     assert_eq!(adjustments.len(), 0);
@@ -173,8 +204,13 @@ fn test_solo_player_no_teammates() {
     let mut elo_changes = HashMap::new();
     elo_changes.insert(player1, 50);
 
-    let (contributions, adjustments) =
-        calculate_teammate_contributions(&results, &player_to_team, &team_to_players, &elo_changes);
+    let (contributions, adjustments) = calculate_teammate_contributions(
+        &results,
+        &player_to_team,
+        &team_to_players,
+        &elo_changes,
+        &EloSettings::default(),
+    );
 
     assert_eq!(contributions.len(), 0);
     assert_eq!(adjustments.len(), 0);
@@ -201,8 +237,13 @@ fn test_multiple_source_players_same_team() {
     elo_changes.insert(player1, 50);
     elo_changes.insert(player2, 30);
 
-    let (contributions, adjustments) =
-        calculate_teammate_contributions(&results, &player_to_team, &team_to_players, &elo_changes);
+    let (contributions, adjustments) = calculate_teammate_contributions(
+        &results,
+        &player_to_team,
+        &team_to_players,
+        &elo_changes,
+        &EloSettings::default(),
+    );
 
     assert_eq!(contributions.len(), 4);
 
@@ -231,8 +272,13 @@ fn test_realistic_scenario_teammates_not_in_race() {
     let mut elo_changes = HashMap::new();
     elo_changes.insert(player1, 50);
 
-    let (contributions, adjustments) =
-        calculate_teammate_contributions(&results, &player_to_team, &team_to_players, &elo_changes);
+    let (contributions, adjustments) = calculate_teammate_contributions(
+        &results,
+        &player_to_team,
+        &team_to_players,
+        &elo_changes,
+        &EloSettings::default(),
+    );
 
     assert_eq!(contributions.len(), 2);
 
@@ -280,8 +326,13 @@ fn test_independent_teams() {
     elo_changes.insert(player1, 50);
     elo_changes.insert(player3, 30);
 
-    let (contributions, adjustments) =
-        calculate_teammate_contributions(&results, &player_to_team, &team_to_players, &elo_changes);
+    let (contributions, adjustments) = calculate_teammate_contributions(
+        &results,
+        &player_to_team,
+        &team_to_players,
+        &elo_changes,
+        &EloSettings::default(),
+    );
 
     assert_eq!(contributions.len(), 2);
 
@@ -300,3 +351,38 @@ fn test_independent_teams() {
     assert!(!beneficiaries.contains(&player1));
     assert!(!beneficiaries.contains(&player3));
 }
+
+#[test]
+fn test_contribution_uses_configured_ratio() {
+    let player1 = Uuid::new_v4();
+    let player2 = Uuid::new_v4();
+    let team = Uuid::new_v4();
+
+    let results = vec![(player1, 1)];
+
+    let mut player_to_team = HashMap::new();
+    player_to_team.insert(player1, team);
+    player_to_team.insert(player2, team);
+
+    let mut team_to_players = HashMap::new();
+    team_to_players.insert(team, vec![player1, player2]);
+
+    let mut elo_changes = HashMap::new();
+    elo_changes.insert(player1, 50);
+
+    let settings = EloSettings {
+        teammate_contribution_ratio: 0.5,
+        ..EloSettings::default()
+    };
+
+    let (contributions, adjustments) = calculate_teammate_contributions(
+        &results,
+        &player_to_team,
+        &team_to_players,
+        &elo_changes,
+        &settings,
+    );
+
+    assert_eq!(contributions[0].contribution_amount, 25);
+    assert_eq!(adjustments.get(&player2), Some(&25));
+}
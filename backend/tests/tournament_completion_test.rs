@@ -1,7 +1,13 @@
 mod common;
 
+use async_graphql::{Request, Variables, value};
 use common::{fixtures, setup};
+use mario_kart_leaderboard_backend::graphql::context::GraphQLContext;
+use mario_kart_leaderboard_backend::models::TournamentStatType;
+use mario_kart_leaderboard_backend::services::notification_manager::NotificationManager;
+use mario_kart_leaderboard_backend::services::track_selection::TrackOptions;
 use mario_kart_leaderboard_backend::services::tournament_completion::complete_tournament;
+use mario_kart_leaderboard_backend::services::{match_service, result_recording};
 use uuid::Uuid;
 
 async fn setup_tournament_with_data(
@@ -204,6 +210,96 @@ async fn test_complete_tournament_sets_correct_winner() {
     );
 }
 
+#[tokio::test]
+async fn test_complete_tournament_leaves_guests_out_of_stats() {
+    let ctx = setup::setup_test_db().await;
+
+    let group = fixtures::create_test_group(&ctx.pool, "Test Group", "password")
+        .await
+        .expect("Failed to create test group");
+
+    let tournaments = fixtures::create_test_tournaments(&ctx.pool, group.id, 1)
+        .await
+        .expect("Failed to create test tournaments");
+    let tournament = &tournaments[0];
+
+    let players = fixtures::create_test_players(&ctx.pool, group.id, 5)
+        .await
+        .expect("Failed to create test players");
+    let player_ids: Vec<Uuid> = players.iter().map(|p| p.id).collect();
+
+    setup_tournament_with_data(&ctx.pool, group.id, tournament.id, &player_ids)
+        .await
+        .expect("Failed to setup tournament data");
+
+    // The first player has the best race and match
+    let guest_id = player_ids[0];
+    sqlx::query("UPDATE players SET guest = TRUE WHERE id = $1")
+        .bind(guest_id)
+        .execute(&ctx.pool)
+        .await
+        .expect("Failed to make player a guest");
+
+    let completed_tournament = complete_tournament(&ctx.pool, tournament.id, group.id)
+        .await
+        .expect("Tournament completion should succeed");
+    assert_eq!(completed_tournament.winner, Some(player_ids[1]));
+
+    let stats: Vec<(TournamentStatType, Uuid)> = sqlx::query_as(
+        "SELECT stat_type, player_id FROM tournament_stats WHERE tournament_id = $1",
+    )
+    .bind(tournament.id)
+    .fetch_all(&ctx.pool)
+    .await
+    .expect("Should fetch stats");
+
+    assert!(!stats.is_empty(), "Tournament stats should be created");
+    assert!(
+        stats.iter().all(|(_, player_id)| *player_id != guest_id),
+        "Guests should not be awarded stats: {stats:?}"
+    );
+    assert!(stats.contains(&(TournamentStatType::BestRace, player_ids[1])));
+
+    let promote = r#"
+        mutation PromoteGuestPlayer($playerId: ID!) {
+            promoteGuestPlayer(playerId: $playerId) {
+                isGuest
+            }
+        }
+    "#;
+    let request = Request::new(promote)
+        .variables(Variables::from_value(value!({ "playerId": guest_id.to_string() })))
+        .data(ctx.config.clone());
+    let gql_ctx = GraphQLContext::new(ctx.pool.clone(), Some(group.id), NotificationManager::new());
+    let response = ctx.schema.execute(request.data(gql_ctx)).await;
+
+    assert!(
+        response.errors.is_empty(),
+        "Expected no errors: {:?}",
+        response.errors
+    );
+
+    let winner: Option<Uuid> = sqlx::query_scalar("SELECT winner FROM tournaments WHERE id = $1")
+        .bind(tournament.id)
+        .fetch_one(&ctx.pool)
+        .await
+        .expect("Should fetch winner");
+    assert_eq!(winner, Some(guest_id), "The promoted player should win");
+
+    let stats: Vec<(TournamentStatType, Uuid)> = sqlx::query_as(
+        "SELECT stat_type, player_id FROM tournament_stats WHERE tournament_id = $1",
+    )
+    .bind(tournament.id)
+    .fetch_all(&ctx.pool)
+    .await
+    .expect("Should fetch stats");
+
+    assert!(
+        stats.contains(&(TournamentStatType::BestRace, guest_id)),
+        "The promoted player should be awarded their stats: {stats:?}"
+    );
+}
+
 #[tokio::test]
 async fn test_complete_tournament_unauthorized() {
     let ctx = setup::setup_test_db().await;
@@ -321,3 +417,71 @@ async fn test_complete_tournament_not_found() {
 
     assert!(result.is_err(), "Should fail for non-existent tournament");
 }
+
+#[tokio::test]
+async fn test_complete_tournament_skips_teammate_stats_for_free_for_all() {
+    let ctx = setup::setup_test_db().await;
+
+    let group = fixtures::create_test_group(&ctx.pool, "Test Group", "password")
+        .await
+        .expect("Failed to create test group");
+
+    let tournament = fixtures::create_test_tournaments(&ctx.pool, group.id, 1)
+        .await
+        .expect("Failed to create test tournaments")
+        .remove(0);
+
+    let players = fixtures::create_test_players(&ctx.pool, group.id, 3)
+        .await
+        .expect("Failed to create test players");
+    let player_ids: Vec<Uuid> = players.iter().map(|p| p.id).collect();
+
+    let match_record = match_service::create_free_for_all_match(
+        &ctx.pool,
+        &tournament,
+        &player_ids,
+        1,
+        None,
+        TrackOptions::default(),
+        &NotificationManager::new(),
+    )
+    .await
+    .expect("Failed to create free-for-all match");
+
+    let results: Vec<(Uuid, i32)> = player_ids.iter().copied().zip([1, 2, 3]).collect();
+    result_recording::record_race_results(
+        &ctx.pool,
+        group.id,
+        &result_recording::RoundResults {
+            round_number: 1,
+            results: &results,
+            non_finishers: &[],
+            builds: &[],
+        },
+        &match_record,
+        &NotificationManager::new(),
+    )
+    .await
+    .expect("Failed to record results");
+
+    complete_tournament(&ctx.pool, tournament.id, group.id)
+        .await
+        .expect("Tournament completion should succeed without team matches");
+
+    let stat_types: Vec<TournamentStatType> = sqlx::query_scalar(
+        "SELECT stat_type FROM tournament_stats WHERE tournament_id = $1",
+    )
+    .bind(tournament.id)
+    .fetch_all(&ctx.pool)
+    .await
+    .expect("Should fetch stats");
+
+    assert!(stat_types.contains(&TournamentStatType::BestRace));
+    assert!(!stat_types.iter().any(|stat_type| matches!(
+        stat_type,
+        TournamentStatType::BestTeammate
+            | TournamentStatType::WorstTeammate
+            | TournamentStatType::MostHelped
+            | TournamentStatType::MostHurt
+    )));
+}
@@ -0,0 +1,187 @@
+mod common;
+
+use async_graphql::{Request, Variables, value};
+use common::{fixtures, setup};
+use mario_kart_leaderboard_backend::{
+    graphql::context::GraphQLContext,
+    services::notification_manager::NotificationManager,
+};
+use std::collections::HashSet;
+use uuid::Uuid;
+
+async fn find_track_id(pool: &sqlx::PgPool, name: &str) -> Uuid {
+    sqlx::query_scalar("SELECT id FROM tracks WHERE name = $1")
+        .bind(name)
+        .fetch_one(pool)
+        .await
+        .expect("Failed to find track")
+}
+
+#[tokio::test]
+async fn test_create_track_pool_and_match_draws_from_tournament_pool() {
+    let ctx = setup::setup_test_db().await;
+
+    let group = fixtures::create_test_group(&ctx.pool, "Test Group", "password")
+        .await
+        .expect("Failed to create test group");
+
+    let players = fixtures::create_test_players(&ctx.pool, group.id, 4)
+        .await
+        .expect("Failed to create test players");
+
+    let pool_names = ["Rainbow Road", "DK Pass", "Peach Beach", "Choco Mountain"];
+    let mut pool_tracks = Vec::new();
+    for name in pool_names {
+        let track_id = find_track_id(&ctx.pool, name).await;
+        pool_tracks.push(value!({ "trackId": track_id.to_string(), "weight": 2 }));
+    }
+
+    let create_pool = r#"
+        mutation CreatePool($tracks: [TrackPoolTrackInput!]!) {
+            createTrackPool(name: "Favourites", tracks: $tracks) {
+                id
+                name
+                tracks {
+                    weight
+                    track { name }
+                }
+            }
+        }
+    "#;
+
+    let request = Request::new(create_pool)
+        .variables(Variables::from_value(value!({ "tracks": pool_tracks })))
+        .data(ctx.config.clone());
+    let gql_ctx = GraphQLContext::new(ctx.pool.clone(), Some(group.id), NotificationManager::new());
+    let response = ctx.schema.execute(request.data(gql_ctx)).await;
+
+    assert!(response.errors.is_empty(), "Expected no errors: {:?}", response.errors);
+
+    let data = response.data.into_json().expect("Failed to parse response");
+    let track_pool = &data["createTrackPool"];
+    assert_eq!(track_pool["name"], "Favourites");
+    assert_eq!(track_pool["tracks"].as_array().unwrap().len(), 4);
+    assert_eq!(track_pool["tracks"][0]["weight"], 2);
+    let track_pool_id = track_pool["id"].as_str().unwrap().to_string();
+
+    let create_tournament = r#"
+        mutation CreateTournament($trackPoolId: ID) {
+            createTournament(trackPoolId: $trackPoolId) {
+                id
+                trackPool { id }
+            }
+        }
+    "#;
+
+    let request = Request::new(create_tournament)
+        .variables(Variables::from_value(value!({ "trackPoolId": track_pool_id.clone() })))
+        .data(ctx.config.clone());
+    let gql_ctx = GraphQLContext::new(ctx.pool.clone(), Some(group.id), NotificationManager::new());
+    let response = ctx.schema.execute(request.data(gql_ctx)).await;
+
+    assert!(response.errors.is_empty(), "Expected no errors: {:?}", response.errors);
+
+    let data = response.data.into_json().expect("Failed to parse response");
+    assert_eq!(data["createTournament"]["trackPool"]["id"], track_pool_id.as_str());
+    let tournament_id = data["createTournament"]["id"].as_str().unwrap().to_string();
+
+    let create_match = r#"
+        mutation CreateMatch($tournamentId: ID!, $playerIds: [ID!]!) {
+            createMatchWithRounds(
+                tournamentId: $tournamentId
+                playerIds: $playerIds
+                numRaces: 4
+                mode: FREE_FOR_ALL
+            ) {
+                trackPool { id }
+                rounds {
+                    track { name }
+                }
+            }
+        }
+    "#;
+
+    let player_ids: Vec<String> = players.iter().map(|p| p.id.to_string()).collect();
+    let request = Request::new(create_match)
+        .variables(Variables::from_value(value!({
+            "tournamentId": tournament_id,
+            "playerIds": player_ids,
+        })))
+        .data(ctx.config.clone());
+    let gql_ctx = GraphQLContext::new(ctx.pool.clone(), Some(group.id), NotificationManager::new());
+    let response = ctx.schema.execute(request.data(gql_ctx)).await;
+
+    assert!(response.errors.is_empty(), "Expected no errors: {:?}", response.errors);
+
+    let data = response.data.into_json().expect("Failed to parse response");
+    let created = &data["createMatchWithRounds"];
+    assert_eq!(created["trackPool"]["id"], track_pool_id.as_str());
+
+    let raced: HashSet<&str> = created["rounds"]
+        .as_array()
+        .expect("rounds should be an array")
+        .iter()
+        .map(|round| round["track"]["name"].as_str().unwrap())
+        .collect();
+    assert_eq!(raced, HashSet::from(pool_names));
+
+    let delete_pool = r#"
+        mutation DeletePool($trackPoolId: ID!) {
+            deleteTrackPool(trackPoolId: $trackPoolId)
+        }
+    "#;
+
+    let request = Request::new(delete_pool)
+        .variables(Variables::from_value(value!({ "trackPoolId": track_pool_id })))
+        .data(ctx.config.clone());
+    let gql_ctx = GraphQLContext::new(ctx.pool.clone(), Some(group.id), NotificationManager::new());
+    let response = ctx.schema.execute(request.data(gql_ctx)).await;
+
+    assert_eq!(response.errors.len(), 1);
+    assert!(response.errors[0].message.contains("used by a tournament or match"));
+}
+
+#[tokio::test]
+async fn test_create_track_pool_validation() {
+    let ctx = setup::setup_test_db().await;
+
+    let group = fixtures::create_test_group(&ctx.pool, "Test Group", "password")
+        .await
+        .expect("Failed to create test group");
+
+    let track_id = find_track_id(&ctx.pool, "Rainbow Road").await.to_string();
+
+    let create_pool = r#"
+        mutation CreatePool($tracks: [TrackPoolTrackInput!]!) {
+            createTrackPool(name: "Pool", tracks: $tracks) {
+                id
+            }
+        }
+    "#;
+
+    let cases = [
+        (value!([]), "at least one track"),
+        (
+            value!([{ "trackId": track_id.clone() }, { "trackId": track_id.clone() }]),
+            "only appear once",
+        ),
+        (value!([{ "trackId": track_id.clone(), "weight": 0 }]), "between 1 and 100"),
+        (value!([{ "trackId": Uuid::new_v4().to_string() }]), "Track not found"),
+    ];
+
+    for (tracks, expected) in cases {
+        let request = Request::new(create_pool)
+            .variables(Variables::from_value(value!({ "tracks": tracks })))
+            .data(ctx.config.clone());
+        let gql_ctx =
+            GraphQLContext::new(ctx.pool.clone(), Some(group.id), NotificationManager::new());
+        let response = ctx.schema.execute(request.data(gql_ctx)).await;
+
+        assert_eq!(response.errors.len(), 1);
+        assert!(
+            response.errors[0].message.contains(expected),
+            "Expected '{expected}', got: {}",
+            response.errors[0].message
+        );
+    }
+}
@@ -0,0 +1,154 @@
+mod common;
+
+use common::{fixtures, setup};
+use mario_kart_leaderboard_backend::models;
+use mario_kart_leaderboard_backend::services::elo::EloSettings;
+use mario_kart_leaderboard_backend::services::match_service;
+use mario_kart_leaderboard_backend::services::notification_manager::NotificationManager;
+use mario_kart_leaderboard_backend::services::result_recording;
+use mario_kart_leaderboard_backend::services::track_rating::{
+    STARTING_TRACK_ELO, calculate_track_elos, replay_track_ratings,
+};
+use mario_kart_leaderboard_backend::services::track_selection::TrackOptions;
+use std::collections::HashMap;
+use uuid::Uuid;
+
+#[test]
+fn test_replay_track_ratings_keeps_tracks_separate() {
+    let rainbow_road = Uuid::new_v4();
+    let dk_pass = Uuid::new_v4();
+    let specialist = Uuid::new_v4();
+    let rival = Uuid::new_v4();
+
+    let races = vec![
+        (rainbow_road, vec![(specialist, 1), (rival, 2)]),
+        (rainbow_road, vec![(specialist, 1), (rival, 2)]),
+        (dk_pass, vec![(rival, 1), (specialist, 2)]),
+    ];
+
+    let ratings = replay_track_ratings(&races, &EloSettings::default());
+
+    let (specialist_rr, specialist_rr_races) = ratings[&(specialist, rainbow_road)];
+    let (rival_rr, _) = ratings[&(rival, rainbow_road)];
+    let (specialist_dk, specialist_dk_races) = ratings[&(specialist, dk_pass)];
+    let (rival_dk, _) = ratings[&(rival, dk_pass)];
+
+    assert_eq!(specialist_rr_races, 2);
+    assert_eq!(specialist_dk_races, 1);
+    assert!(specialist_rr > rival_rr);
+    assert!(rival_dk > specialist_dk);
+
+    let fresh = calculate_track_elos(
+        &[(rival, 1), (specialist, 2)],
+        &HashMap::new(),
+        &EloSettings::default(),
+    );
+    assert_eq!(
+        (rival_dk, specialist_dk),
+        (fresh[0].1, fresh[1].1),
+        "Results on other tracks should not carry over"
+    );
+}
+
+#[tokio::test]
+async fn test_recording_results_updates_track_ratings_and_chaos_index() {
+    let ctx = setup::setup_test_db().await;
+    let group = fixtures::create_test_group(&ctx.pool, "Test Group", "password")
+        .await
+        .expect("Failed to create test group");
+    let tournament = fixtures::create_test_tournaments(&ctx.pool, group.id, 1)
+        .await
+        .expect("Failed to create test tournaments")
+        .remove(0);
+    let players = fixtures::create_test_players(&ctx.pool, group.id, 3)
+        .await
+        .expect("Failed to create test players");
+    let player_ids: Vec<Uuid> = players.iter().map(|p| p.id).collect();
+
+    let mut match_record = match_service::create_free_for_all_match(
+        &ctx.pool,
+        &tournament,
+        &player_ids,
+        2,
+        None,
+        TrackOptions::default(),
+        &NotificationManager::new(),
+    )
+    .await
+    .expect("Failed to create free-for-all match");
+
+    let track_ids: Vec<Uuid> = sqlx::query_scalar(
+        "SELECT track_id FROM rounds WHERE match_id = $1 ORDER BY round_number",
+    )
+    .bind(match_record.id)
+    .fetch_all(&ctx.pool)
+    .await
+    .expect("Failed to fetch round tracks");
+
+    // The first race finishes in rating order (everyone starts level); the
+    // second reverses the order the first race produced.
+    for (round_number, positions) in [(1, [1, 2, 3]), (2, [3, 1, 2])] {
+        let results: Vec<(Uuid, i32)> = player_ids.iter().copied().zip(positions).collect();
+        match_record = result_recording::record_race_results(
+            &ctx.pool,
+            group.id,
+            &result_recording::RoundResults {
+                round_number,
+                results: &results,
+                non_finishers: &[],
+                builds: &[],
+            },
+            &match_record,
+            &NotificationManager::new(),
+        )
+        .await
+        .expect("Failed to record results");
+    }
+
+    let specialists =
+        models::PlayerTrackRating::find_top_by_track(&ctx.pool, track_ids[0], group.id, 5)
+            .await
+            .expect("Failed to fetch track ratings");
+    assert_eq!(specialists.len(), 3);
+    assert_eq!(specialists[0].player_id, player_ids[0]);
+    assert!(specialists[0].elo_rating > STARTING_TRACK_ELO);
+    assert!(specialists.iter().all(|r| r.races_played == 1));
+
+    let stats = models::PlayerRaceScore::find_track_stats_by_player(&ctx.pool, player_ids[1])
+        .await
+        .expect("Failed to fetch track stats");
+    assert_eq!(stats.len(), 2);
+    assert!(stats.iter().all(|s| s.track_elo.is_some()));
+
+    let chaos = models::PlayerRaceScore::find_track_chaos_indexes(
+        &ctx.pool,
+        &track_ids,
+        Some(group.id),
+    )
+    .await
+    .expect("Failed to fetch chaos indexes");
+    let chaos_of = |track_id: Uuid| {
+        chaos
+            .iter()
+            .find(|c| c.track_id == track_id)
+            .map(|c| c.chaos_index)
+            .expect("Missing chaos index")
+    };
+    assert_eq!(chaos_of(track_ids[0]), 0.0);
+    assert_eq!(chaos_of(track_ids[1]), 1.0);
+
+    result_recording::undo_last_round(
+        &ctx.pool,
+        group.id,
+        &match_record,
+        &NotificationManager::new(),
+    )
+    .await
+    .expect("Failed to undo round");
+
+    let undone =
+        models::PlayerTrackRating::find_top_by_track(&ctx.pool, track_ids[1], group.id, 5)
+            .await
+            .expect("Failed to fetch track ratings");
+    assert!(undone.is_empty(), "Undoing a race should remove its track ratings");
+}
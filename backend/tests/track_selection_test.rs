@@ -1,10 +1,19 @@
 mod common;
 
 use common::{fixtures, setup};
+use mario_kart_leaderboard_backend::error::AppError;
 use mario_kart_leaderboard_backend::models;
-use mario_kart_leaderboard_backend::services::track_selection::select_tracks;
+use mario_kart_leaderboard_backend::services::track_selection::{
+    RoundTrack, TrackFormat, TrackOptions, select_round_tracks, select_tracks,
+    select_tracks_avoiding_vetoes,
+};
 use std::collections::HashSet;
 
+const GRAND_PRIX: TrackOptions = TrackOptions {
+    format: TrackFormat::GrandPrix,
+    track_pool_id: None,
+};
+
 #[tokio::test]
 async fn test_select_tracks_returns_correct_number() {
     let ctx = setup::setup_test_db().await;
@@ -18,7 +27,7 @@ async fn test_select_tracks_returns_correct_number() {
         .expect("Failed to create test tournaments");
     let tournament = &tournaments[0];
 
-    let tracks = select_tracks(&ctx.pool, tournament.id, 4)
+    let tracks = select_tracks(&ctx.pool, tournament.id, None, 4)
         .await
         .expect("Failed to select tracks");
 
@@ -38,7 +47,7 @@ async fn test_select_tracks_returns_unique_tracks() {
         .expect("Failed to create test tournaments");
     let tournament = &tournaments[0];
 
-    let tracks = select_tracks(&ctx.pool, tournament.id, 8)
+    let tracks = select_tracks(&ctx.pool, tournament.id, None, 8)
         .await
         .expect("Failed to select tracks");
 
@@ -63,7 +72,7 @@ async fn test_select_tracks_first_match_no_history() {
         .expect("Failed to create test tournaments");
     let tournament = &tournaments[0];
 
-    let tracks = select_tracks(&ctx.pool, tournament.id, 4)
+    let tracks = select_tracks(&ctx.pool, tournament.id, None, 4)
         .await
         .expect("Failed to select tracks");
 
@@ -87,7 +96,7 @@ async fn test_select_tracks_avoids_recently_used() {
         .expect("Failed to create test tournaments");
     let tournament = &tournaments[0];
 
-    let first_tracks = select_tracks(&ctx.pool, tournament.id, 4)
+    let first_tracks = select_tracks(&ctx.pool, tournament.id, None, 4)
         .await
         .expect("Failed to select first tracks");
 
@@ -108,7 +117,7 @@ async fn test_select_tracks_avoids_recently_used() {
         .expect("Failed to insert round");
     }
 
-    let second_tracks = select_tracks(&ctx.pool, tournament.id, 4)
+    let second_tracks = select_tracks(&ctx.pool, tournament.id, None, 4)
         .await
         .expect("Failed to select second tracks");
 
@@ -136,7 +145,7 @@ async fn test_select_tracks_different_tournaments_isolated() {
     let tournament1 = &tournaments[0];
     let tournament2 = &tournaments[1];
 
-    let tracks_for_t1 = select_tracks(&ctx.pool, tournament1.id, 4)
+    let tracks_for_t1 = select_tracks(&ctx.pool, tournament1.id, None, 4)
         .await
         .expect("Failed to select tracks for tournament 1");
 
@@ -157,7 +166,7 @@ async fn test_select_tracks_different_tournaments_isolated() {
         .expect("Failed to insert round");
     }
 
-    let tracks_for_t2 = select_tracks(&ctx.pool, tournament2.id, 4)
+    let tracks_for_t2 = select_tracks(&ctx.pool, tournament2.id, None, 4)
         .await
         .expect("Failed to select tracks for tournament 2");
 
@@ -181,7 +190,7 @@ async fn test_select_tracks_request_single_track() {
         .expect("Failed to create test tournaments");
     let tournament = &tournaments[0];
 
-    let tracks = select_tracks(&ctx.pool, tournament.id, 1)
+    let tracks = select_tracks(&ctx.pool, tournament.id, None, 1)
         .await
         .expect("Failed to select tracks");
 
@@ -201,7 +210,7 @@ async fn test_select_tracks_all_tracks_have_names() {
         .expect("Failed to create test tournaments");
     let tournament = &tournaments[0];
 
-    let tracks = select_tracks(&ctx.pool, tournament.id, 4)
+    let tracks = select_tracks(&ctx.pool, tournament.id, None, 4)
         .await
         .expect("Failed to select tracks");
 
@@ -258,7 +267,7 @@ async fn test_full_cycle_no_duplicates() {
 
     // Play a full cycle (5 matches x 6 rounds = 30 tracks)
     for _ in 0..matches_per_cycle {
-        let tracks = select_tracks(&ctx.pool, tournament.id, rounds_per_match as i32)
+        let tracks = select_tracks(&ctx.pool, tournament.id, None, rounds_per_match as i32)
             .await
             .expect("Failed to select tracks");
 
@@ -296,7 +305,7 @@ async fn test_full_cycle_no_duplicates() {
     );
 
     // Start of a new cycle (6th match) — should still produce valid tracks
-    let new_cycle_tracks = select_tracks(&ctx.pool, tournament.id, rounds_per_match as i32)
+    let new_cycle_tracks = select_tracks(&ctx.pool, tournament.id, None, rounds_per_match as i32)
         .await
         .expect("Failed to select tracks for new cycle");
 
@@ -304,3 +313,437 @@ async fn test_full_cycle_no_duplicates() {
     let new_ids: HashSet<_> = new_cycle_tracks.iter().map(|t| t.id).collect();
     assert_eq!(new_ids.len(), rounds_per_match, "New cycle tracks should be unique");
 }
+
+async fn persist_round_tracks(
+    pool: &sqlx::PgPool,
+    group_id: uuid::Uuid,
+    tournament_id: uuid::Uuid,
+    round_tracks: &[RoundTrack],
+) {
+    let test_match =
+        fixtures::create_test_match(pool, group_id, tournament_id, round_tracks.len() as i32)
+            .await
+            .expect("Failed to create test match");
+
+    for (i, round_track) in round_tracks.iter().enumerate() {
+        sqlx::query(
+            "INSERT INTO rounds (match_id, round_number, track_id, cup_id, completed)
+             VALUES ($1, $2, $3, $4, false)",
+        )
+        .bind(test_match.id)
+        .bind(i as i32 + 1)
+        .bind(round_track.track.id)
+        .bind(round_track.cup_id)
+        .execute(pool)
+        .await
+        .expect("Failed to insert round");
+    }
+}
+
+#[tokio::test]
+async fn test_grand_prix_races_whole_cups_in_order() {
+    let ctx = setup::setup_test_db().await;
+
+    let group = fixtures::create_test_group(&ctx.pool, "Test Group", "password")
+        .await
+        .expect("Failed to create test group");
+
+    let tournament = fixtures::create_test_tournaments(&ctx.pool, group.id, 1)
+        .await
+        .expect("Failed to create test tournaments")
+        .remove(0);
+
+    let round_tracks = select_round_tracks(&ctx.pool, tournament.id, &vec![Vec::new(); 8], GRAND_PRIX)
+        .await
+        .expect("Failed to select Grand Prix tracks");
+
+    assert_eq!(round_tracks.len(), 8);
+
+    for cup_rounds in round_tracks.chunks(4) {
+        let cup_id = cup_rounds[0].cup_id.expect("Grand Prix rounds should have a cup");
+        assert!(cup_rounds.iter().all(|round| round.cup_id == Some(cup_id)));
+
+        let cup_tracks: Vec<uuid::Uuid> =
+            models::Cup::find_tracks_by_cup_ids(&ctx.pool, &[cup_id], None)
+                .await
+                .expect("Failed to fetch cup tracks")
+                .into_iter()
+                .map(|(_, track)| track.id)
+                .collect();
+        let round_track_ids: Vec<uuid::Uuid> =
+            cup_rounds.iter().map(|round| round.track.id).collect();
+        assert_eq!(round_track_ids, cup_tracks, "Cup tracks should be raced in order");
+    }
+
+    assert_ne!(round_tracks[0].cup_id, round_tracks[4].cup_id);
+
+    let result = select_round_tracks(&ctx.pool, tournament.id, &vec![Vec::new(); 6], GRAND_PRIX).await;
+    assert!(
+        matches!(result, Err(AppError::InvalidInput(_))),
+        "Grand Prix matches should race whole cups"
+    );
+}
+
+#[tokio::test]
+async fn test_grand_prix_full_cup_cycle_no_duplicates() {
+    let ctx = setup::setup_test_db().await;
+
+    let group = fixtures::create_test_group(&ctx.pool, "Test Group", "password")
+        .await
+        .expect("Failed to create test group");
+
+    let tournament = fixtures::create_test_tournaments(&ctx.pool, group.id, 1)
+        .await
+        .expect("Failed to create test tournaments")
+        .remove(0);
+
+    let total_cups = models::Cup::find_all(&ctx.pool)
+        .await
+        .expect("Failed to fetch cups")
+        .len();
+    let mut cycle_cup_ids: HashSet<uuid::Uuid> = HashSet::new();
+
+    // Two cups per match across a full cycle of cups
+    for _ in 0..total_cups / 2 {
+        let round_tracks =
+            select_round_tracks(&ctx.pool, tournament.id, &vec![Vec::new(); 8], GRAND_PRIX)
+                .await
+                .expect("Failed to select Grand Prix tracks");
+
+        for cup_rounds in round_tracks.chunks(4) {
+            let cup_id = cup_rounds[0].cup_id.expect("Grand Prix rounds should have a cup");
+            assert!(
+                cycle_cup_ids.insert(cup_id),
+                "Cups should not repeat within a cycle"
+            );
+        }
+
+        persist_round_tracks(&ctx.pool, group.id, tournament.id, &round_tracks).await;
+    }
+
+    assert_eq!(cycle_cup_ids.len(), total_cups, "A full cycle should cover every cup");
+}
+
+#[tokio::test]
+async fn test_grand_prix_skips_cups_with_retired_tracks() {
+    let ctx = setup::setup_test_db().await;
+
+    let group = fixtures::create_test_group(&ctx.pool, "Test Group", "password")
+        .await
+        .expect("Failed to create test group");
+
+    let tournament = fixtures::create_test_tournaments(&ctx.pool, group.id, 1)
+        .await
+        .expect("Failed to create test tournaments")
+        .remove(0);
+
+    let cup_ids: Vec<uuid::Uuid> = models::Cup::find_all(&ctx.pool)
+        .await
+        .expect("Failed to fetch cups")
+        .into_iter()
+        .map(|cup| cup.id)
+        .collect();
+    let cup_tracks = models::Cup::find_tracks_by_cup_ids(&ctx.pool, &cup_ids, None)
+        .await
+        .expect("Failed to fetch cup tracks");
+    let first_track = |cup_id: uuid::Uuid| {
+        cup_tracks
+            .iter()
+            .find(|(id, _)| *id == cup_id)
+            .map(|(_, track)| track.id)
+            .expect("Cup should have tracks")
+    };
+
+    // Keep the first three cups intact, then retire one of the third cup's
+    // tracks for this group only
+    for &cup_id in &cup_ids[3..] {
+        sqlx::query("UPDATE tracks SET retired = TRUE WHERE id = $1")
+            .bind(first_track(cup_id))
+            .execute(&ctx.pool)
+            .await
+            .expect("Failed to retire track");
+    }
+    models::Track::set_retired_for_group(&ctx.pool, first_track(cup_ids[2]), group.id, true)
+        .await
+        .expect("Failed to retire track for group");
+
+    let round_tracks =
+        select_round_tracks(&ctx.pool, tournament.id, &vec![Vec::new(); 8], GRAND_PRIX)
+            .await
+            .expect("Failed to select Grand Prix tracks");
+
+    let selected_cups: HashSet<uuid::Uuid> =
+        round_tracks.iter().filter_map(|round| round.cup_id).collect();
+    assert_eq!(
+        selected_cups,
+        HashSet::from([cup_ids[0], cup_ids[1]]),
+        "Only cups with four active tracks should be raced"
+    );
+
+    let result =
+        select_round_tracks(&ctx.pool, tournament.id, &vec![Vec::new(); 12], GRAND_PRIX).await;
+    assert!(
+        matches!(result, Err(AppError::InvalidInput(_))),
+        "Requesting more cups than are complete should fail"
+    );
+}
+
+#[tokio::test]
+async fn test_select_tracks_skips_retired_and_includes_group_tracks() {
+    let ctx = setup::setup_test_db().await;
+
+    let group = fixtures::create_test_group(&ctx.pool, "Test Group", "password")
+        .await
+        .expect("Failed to create test group");
+    let other_group = fixtures::create_test_group(&ctx.pool, "Other Group", "password")
+        .await
+        .expect("Failed to create test group");
+
+    let tournaments = fixtures::create_test_tournaments(&ctx.pool, group.id, 1)
+        .await
+        .expect("Failed to create test tournaments");
+    let tournament = &tournaments[0];
+
+    sqlx::query("UPDATE tracks SET retired = TRUE WHERE name NOT IN ('Rainbow Road', 'DK Pass')")
+        .execute(&ctx.pool)
+        .await
+        .expect("Failed to retire tracks");
+
+    let details = |name: &str| models::TrackDetails {
+        name: name.to_string(),
+        game_edition: None,
+        cup: None,
+        retro: false,
+        tags: Vec::new(),
+    };
+    let group_track = models::Track::create(&ctx.pool, group.id, &details("Baby Park"))
+        .await
+        .expect("Failed to create track");
+    models::Track::create(&ctx.pool, other_group.id, &details("Yoshi Circuit"))
+        .await
+        .expect("Failed to create track");
+
+    let tracks = select_tracks(&ctx.pool, tournament.id, None, 3)
+        .await
+        .expect("Failed to select tracks");
+
+    let names: HashSet<_> = tracks.iter().map(|t| t.name.as_str()).collect();
+    assert_eq!(
+        names,
+        HashSet::from(["Rainbow Road", "DK Pass", "Baby Park"]),
+        "Only active tracks available to the group should be selected"
+    );
+    assert!(tracks.iter().any(|t| t.id == group_track.id));
+}
+
+async fn create_track_pool(
+    pool: &sqlx::PgPool,
+    group_id: uuid::Uuid,
+    track_names: &[(&str, i32)],
+) -> models::TrackPool {
+    let mut tracks = Vec::new();
+    for (name, weight) in track_names {
+        let track_id: uuid::Uuid = sqlx::query_scalar("SELECT id FROM tracks WHERE name = $1")
+            .bind(name)
+            .fetch_one(pool)
+            .await
+            .expect("Failed to find track");
+        tracks.push((track_id, *weight));
+    }
+
+    let mut tx = pool.begin().await.expect("Failed to start transaction");
+    let track_pool = models::TrackPool::create(&mut tx, group_id, "Test Pool", &tracks)
+        .await
+        .expect("Failed to create track pool");
+    tx.commit().await.expect("Failed to commit");
+
+    track_pool
+}
+
+#[tokio::test]
+async fn test_select_tracks_from_pool_cycles_within_pool() {
+    let ctx = setup::setup_test_db().await;
+
+    let group = fixtures::create_test_group(&ctx.pool, "Test Group", "password")
+        .await
+        .expect("Failed to create test group");
+
+    let tournaments = fixtures::create_test_tournaments(&ctx.pool, group.id, 1)
+        .await
+        .expect("Failed to create test tournaments");
+    let tournament = &tournaments[0];
+
+    let pool_names = ["Rainbow Road", "DK Pass", "Peach Beach", "Choco Mountain"];
+    let track_pool = create_track_pool(
+        &ctx.pool,
+        group.id,
+        &pool_names.map(|name| (name, 1)),
+    )
+    .await;
+
+    // A match on the full catalogue does not count towards the pool's cycle
+    let catalogue_tracks = select_tracks(&ctx.pool, tournament.id, None, 4)
+        .await
+        .expect("Failed to select tracks");
+    let outside_pool: Vec<_> = catalogue_tracks
+        .into_iter()
+        .filter(|t| !pool_names.contains(&t.name.as_str()))
+        .collect();
+    persist_tracks_as_rounds(&ctx.pool, group.id, tournament.id, &outside_pool).await;
+
+    let first = select_tracks(&ctx.pool, tournament.id, Some(track_pool.id), 2)
+        .await
+        .expect("Failed to select tracks");
+    persist_tracks_as_rounds(&ctx.pool, group.id, tournament.id, &first).await;
+
+    let second = select_tracks(&ctx.pool, tournament.id, Some(track_pool.id), 2)
+        .await
+        .expect("Failed to select tracks");
+
+    let names: HashSet<_> = first
+        .iter()
+        .chain(second.iter())
+        .map(|t| t.name.as_str())
+        .collect();
+    assert_eq!(
+        names,
+        HashSet::from(pool_names),
+        "Two matches should cover the whole pool without repeats"
+    );
+
+    let result = select_tracks(&ctx.pool, tournament.id, Some(track_pool.id), 5).await;
+    assert!(
+        matches!(result, Err(AppError::InvalidInput(_))),
+        "A pool smaller than the match should be rejected"
+    );
+}
+
+#[tokio::test]
+async fn test_select_tracks_from_pool_skips_retired_and_prefers_heavy_tracks() {
+    let ctx = setup::setup_test_db().await;
+
+    let group = fixtures::create_test_group(&ctx.pool, "Test Group", "password")
+        .await
+        .expect("Failed to create test group");
+
+    let tournaments = fixtures::create_test_tournaments(&ctx.pool, group.id, 1)
+        .await
+        .expect("Failed to create test tournaments");
+    let tournament = &tournaments[0];
+
+    let track_pool = create_track_pool(
+        &ctx.pool,
+        group.id,
+        &[("Rainbow Road", 100), ("DK Pass", 1), ("Peach Beach", 1)],
+    )
+    .await;
+
+    sqlx::query("UPDATE tracks SET retired = TRUE WHERE name = 'Peach Beach'")
+        .execute(&ctx.pool)
+        .await
+        .expect("Failed to retire track");
+
+    let mut heavy_picks = 0;
+    for _ in 0..50 {
+        let tracks = select_tracks(&ctx.pool, tournament.id, Some(track_pool.id), 1)
+            .await
+            .expect("Failed to select tracks");
+
+        assert_ne!(tracks[0].name, "Peach Beach", "Retired tracks should be skipped");
+        if tracks[0].name == "Rainbow Road" {
+            heavy_picks += 1;
+        }
+    }
+
+    assert!(
+        heavy_picks >= 40,
+        "A track with weight 100 should almost always be drawn first, got {heavy_picks}/50"
+    );
+}
+
+#[tokio::test]
+async fn test_select_tracks_avoids_vetoed_tracks_and_falls_back() {
+    let ctx = setup::setup_test_db().await;
+
+    let group = fixtures::create_test_group(&ctx.pool, "Test Group", "password")
+        .await
+        .expect("Failed to create test group");
+
+    let tournaments = fixtures::create_test_tournaments(&ctx.pool, group.id, 1)
+        .await
+        .expect("Failed to create test tournaments");
+    let tournament = &tournaments[0];
+
+    let vetoer = fixtures::create_test_player(&ctx.pool, group.id, "Vetoer")
+        .await
+        .expect("Failed to create player");
+    let other = fixtures::create_test_player(&ctx.pool, group.id, "Other")
+        .await
+        .expect("Failed to create player");
+
+    let track_pool = create_track_pool(
+        &ctx.pool,
+        group.id,
+        &[("Rainbow Road", 1), ("DK Pass", 1)],
+    )
+    .await;
+
+    let rainbow_road: uuid::Uuid =
+        sqlx::query_scalar("SELECT id FROM tracks WHERE name = 'Rainbow Road'")
+            .fetch_one(&ctx.pool)
+            .await
+            .expect("Failed to find track");
+    models::TrackVeto::create(&ctx.pool, tournament.id, vetoer.id, rainbow_road)
+        .await
+        .expect("Failed to create veto");
+
+    for _ in 0..10 {
+        let round_tracks = select_tracks_avoiding_vetoes(
+            &ctx.pool,
+            tournament.id,
+            Some(track_pool.id),
+            &[vec![vetoer.id, other.id]],
+        )
+        .await
+        .expect("Failed to select tracks");
+
+        assert_eq!(
+            round_tracks[0].track.name, "DK Pass",
+            "A track vetoed by a player in the race should be avoided"
+        );
+        for note in &round_tracks[0].selection_notes {
+            assert_eq!(note, "Skipped Rainbow Road: vetoed by Vetoer");
+        }
+    }
+
+    // Races without the vetoer may still get the vetoed track
+    let round_tracks = select_tracks_avoiding_vetoes(
+        &ctx.pool,
+        tournament.id,
+        Some(track_pool.id),
+        &[vec![vetoer.id], vec![other.id]],
+    )
+    .await
+    .expect("Failed to select tracks");
+    assert_eq!(round_tracks[0].track.name, "DK Pass");
+    assert_eq!(round_tracks[1].track.name, "Rainbow Road");
+    assert!(round_tracks[1].selection_notes.is_empty());
+
+    // When the pool runs dry the vetoed track is raced anyway, with a note
+    let round_tracks = select_tracks_avoiding_vetoes(
+        &ctx.pool,
+        tournament.id,
+        Some(track_pool.id),
+        &[vec![vetoer.id], vec![vetoer.id]],
+    )
+    .await
+    .expect("Failed to select tracks");
+    assert_eq!(round_tracks[1].track.name, "Rainbow Road");
+    assert_eq!(
+        round_tracks[1].selection_notes,
+        vec![
+            "Every remaining track was vetoed; raced Rainbow Road despite vetoes by Vetoer"
+                .to_string()
+        ]
+    );
+}
@@ -1,11 +1,13 @@
 mod common;
 
-use async_graphql::Request;
-use common::setup;
+use async_graphql::{Request, Variables, value};
+use common::{fixtures, setup};
 use mario_kart_leaderboard_backend::{
     graphql::context::GraphQLContext,
+    models,
     services::notification_manager::NotificationManager,
 };
+use uuid::Uuid;
 
 #[tokio::test]
 async fn test_tracks_query() {
@@ -41,3 +43,423 @@ async fn test_tracks_query() {
     // Tracks are populated via migrations, so we should have some
     assert!(!tracks.is_empty(), "Expected tracks from migrations");
 }
+
+async fn query_track_ids(ctx: &setup::TestContext, group_id: Uuid, include_retired: bool) -> Vec<String> {
+    let query = r#"
+        query Tracks($includeRetired: Boolean) {
+            tracks(includeRetired: $includeRetired) {
+                id
+            }
+        }
+    "#;
+
+    let request = Request::new(query)
+        .variables(Variables::from_value(value!({ "includeRetired": include_retired })))
+        .data(ctx.config.clone());
+    let gql_ctx = GraphQLContext::new(ctx.pool.clone(), Some(group_id), NotificationManager::new());
+    let response = ctx.schema.execute(request.data(gql_ctx)).await;
+
+    let data = response.data.into_json().expect("Failed to parse response");
+    data["tracks"]
+        .as_array()
+        .expect("tracks should be an array")
+        .iter()
+        .map(|track| track["id"].as_str().unwrap().to_string())
+        .collect()
+}
+
+#[tokio::test]
+async fn test_create_update_and_retire_group_track() {
+    let ctx = setup::setup_test_db().await;
+
+    let group = fixtures::create_test_group(&ctx.pool, "Test Group", "password")
+        .await
+        .expect("Failed to create test group");
+
+    let create = r#"
+        mutation CreateTrack($input: TrackInput!) {
+            createTrack(input: $input) {
+                id
+                name
+                gameEdition
+                retro
+                tags
+                retired
+                builtIn
+            }
+        }
+    "#;
+
+    let request = Request::new(create)
+        .variables(Variables::from_value(value!({
+            "input": {
+                "name": " Baby Park ",
+                "gameEdition": "Mario Kart: Double Dash!!",
+                "cup": "  ",
+                "tags": ["short", " chaotic", "short"]
+            }
+        })))
+        .data(ctx.config.clone());
+    let gql_ctx = GraphQLContext::new(ctx.pool.clone(), Some(group.id), NotificationManager::new());
+    let response = ctx.schema.execute(request.data(gql_ctx)).await;
+
+    assert!(response.errors.is_empty(), "Expected no errors: {:?}", response.errors);
+
+    let data = response.data.into_json().expect("Failed to parse response");
+    let track = &data["createTrack"];
+    assert_eq!(track["name"], "Baby Park");
+    assert_eq!(track["gameEdition"], "Mario Kart: Double Dash!!");
+    assert_eq!(track["tags"], serde_json::json!(["short", "chaotic"]));
+    assert_eq!(track["retro"], false);
+    assert_eq!(track["builtIn"], false);
+    let track_id = track["id"].as_str().unwrap().to_string();
+
+    let update = r#"
+        mutation UpdateTrack($trackId: ID!, $input: TrackInput!) {
+            updateTrack(trackId: $trackId, input: $input) {
+                name
+                retro
+            }
+        }
+    "#;
+
+    let request = Request::new(update)
+        .variables(Variables::from_value(value!({
+            "trackId": track_id.clone(),
+            "input": { "name": "GCN Baby Park", "retro": true }
+        })))
+        .data(ctx.config.clone());
+    let gql_ctx = GraphQLContext::new(ctx.pool.clone(), Some(group.id), NotificationManager::new());
+    let response = ctx.schema.execute(request.data(gql_ctx)).await;
+
+    assert!(response.errors.is_empty(), "Expected no errors: {:?}", response.errors);
+    let data = response.data.into_json().expect("Failed to parse response");
+    assert_eq!(data["updateTrack"]["name"], "GCN Baby Park");
+    assert_eq!(data["updateTrack"]["retro"], true);
+
+    let retire = r#"
+        mutation Retire($trackId: ID!) {
+            setTrackRetired(trackId: $trackId, retired: true) {
+                retired
+            }
+        }
+    "#;
+
+    let request = Request::new(retire)
+        .variables(Variables::from_value(value!({ "trackId": track_id.clone() })))
+        .data(ctx.config.clone());
+    let gql_ctx = GraphQLContext::new(ctx.pool.clone(), Some(group.id), NotificationManager::new());
+    let response = ctx.schema.execute(request.data(gql_ctx)).await;
+
+    assert!(response.errors.is_empty(), "Expected no errors: {:?}", response.errors);
+
+    assert!(!query_track_ids(&ctx, group.id, false).await.contains(&track_id));
+    assert!(query_track_ids(&ctx, group.id, true).await.contains(&track_id));
+}
+
+#[tokio::test]
+async fn test_track_mutations_reject_built_in_and_other_group_tracks() {
+    let ctx = setup::setup_test_db().await;
+
+    let group = fixtures::create_test_group(&ctx.pool, "Test Group", "password")
+        .await
+        .expect("Failed to create test group");
+    let other_group = fixtures::create_test_group(&ctx.pool, "Other Group", "password")
+        .await
+        .expect("Failed to create test group");
+
+    let other_track = models::Track::create(
+        &ctx.pool,
+        other_group.id,
+        &models::TrackDetails {
+            name: "Baby Park".to_string(),
+            game_edition: None,
+            cup: None,
+            retro: false,
+            tags: Vec::new(),
+        },
+    )
+    .await
+    .expect("Failed to create track");
+
+    let built_in_id: Uuid =
+        sqlx::query_scalar("SELECT id FROM tracks WHERE name = 'Rainbow Road'")
+            .fetch_one(&ctx.pool)
+            .await
+            .expect("Failed to find built-in track");
+
+    let update = r#"
+        mutation UpdateTrack($trackId: ID!) {
+            updateTrack(trackId: $trackId, input: { name: "Renamed" }) {
+                id
+            }
+        }
+    "#;
+
+    for (track_id, expected) in [
+        (built_in_id, "Built-in tracks cannot be changed"),
+        (other_track.id, "Track not found"),
+    ] {
+        let request = Request::new(update)
+            .variables(Variables::from_value(value!({ "trackId": track_id.to_string() })))
+            .data(ctx.config.clone());
+        let gql_ctx =
+            GraphQLContext::new(ctx.pool.clone(), Some(group.id), NotificationManager::new());
+        let response = ctx.schema.execute(request.data(gql_ctx)).await;
+
+        assert_eq!(response.errors.len(), 1);
+        assert_eq!(response.errors[0].message, expected);
+    }
+
+    let create = r#"
+        mutation {
+            createTrack(input: { name: "rainbow road" }) {
+                id
+            }
+        }
+    "#;
+
+    let request = Request::new(create).data(ctx.config.clone());
+    let gql_ctx = GraphQLContext::new(ctx.pool.clone(), Some(group.id), NotificationManager::new());
+    let response = ctx.schema.execute(request.data(gql_ctx)).await;
+
+    assert_eq!(response.errors.len(), 1);
+    assert_eq!(response.errors[0].message, "A track with this name already exists");
+}
+
+#[tokio::test]
+async fn test_group_can_retire_built_in_track_for_itself() {
+    let ctx = setup::setup_test_db().await;
+
+    let group = fixtures::create_test_group(&ctx.pool, "Test Group", "password")
+        .await
+        .expect("Failed to create test group");
+    let other_group = fixtures::create_test_group(&ctx.pool, "Other Group", "password")
+        .await
+        .expect("Failed to create test group");
+
+    let built_in_id: Uuid =
+        sqlx::query_scalar("SELECT id FROM tracks WHERE name = 'Rainbow Road'")
+            .fetch_one(&ctx.pool)
+            .await
+            .expect("Failed to find built-in track");
+    let track_id = built_in_id.to_string();
+
+    let set_retired = r#"
+        mutation SetRetired($trackId: ID!, $retired: Boolean!) {
+            setTrackRetired(trackId: $trackId, retired: $retired) {
+                retired
+                builtIn
+            }
+        }
+    "#;
+
+    for retired in [true, false] {
+        let request = Request::new(set_retired)
+            .variables(Variables::from_value(value!({
+                "trackId": track_id.clone(),
+                "retired": retired,
+            })))
+            .data(ctx.config.clone());
+        let gql_ctx =
+            GraphQLContext::new(ctx.pool.clone(), Some(group.id), NotificationManager::new());
+        let response = ctx.schema.execute(request.data(gql_ctx)).await;
+
+        assert!(response.errors.is_empty(), "Expected no errors: {:?}", response.errors);
+        let data = response.data.into_json().expect("Failed to parse response");
+        assert_eq!(data["setTrackRetired"]["retired"], retired);
+        assert_eq!(data["setTrackRetired"]["builtIn"], true);
+
+        assert_eq!(
+            !query_track_ids(&ctx, group.id, false).await.contains(&track_id),
+            retired,
+            "The built-in track should only be hidden while the group has it retired"
+        );
+        assert!(query_track_ids(&ctx, group.id, true).await.contains(&track_id));
+        assert!(
+            query_track_ids(&ctx, other_group.id, false).await.contains(&track_id),
+            "Retiring a built-in track should not affect other groups"
+        );
+    }
+}
+
+#[tokio::test]
+async fn test_delete_track_only_when_unraced() {
+    let ctx = setup::setup_test_db().await;
+
+    let group = fixtures::create_test_group(&ctx.pool, "Test Group", "password")
+        .await
+        .expect("Failed to create test group");
+    let tournament = fixtures::create_test_tournament(&ctx.pool, group.id, None, None)
+        .await
+        .expect("Failed to create test tournament");
+    let match_record = fixtures::create_test_match(&ctx.pool, group.id, tournament.id, 1)
+        .await
+        .expect("Failed to create test match");
+
+    let details = |name: &str| models::TrackDetails {
+        name: name.to_string(),
+        game_edition: None,
+        cup: None,
+        retro: false,
+        tags: Vec::new(),
+    };
+    let raced = models::Track::create(&ctx.pool, group.id, &details("Baby Park"))
+        .await
+        .expect("Failed to create track");
+    let unraced = models::Track::create(&ctx.pool, group.id, &details("Yoshi Circuit"))
+        .await
+        .expect("Failed to create track");
+
+    fixtures::create_test_round(&ctx.pool, match_record.id, 1, Some(raced.id))
+        .await
+        .expect("Failed to create test round");
+
+    let delete = r#"
+        mutation Delete($trackId: ID!) {
+            deleteTrack(trackId: $trackId)
+        }
+    "#;
+
+    let request = Request::new(delete)
+        .variables(Variables::from_value(value!({ "trackId": raced.id.to_string() })))
+        .data(ctx.config.clone());
+    let gql_ctx = GraphQLContext::new(ctx.pool.clone(), Some(group.id), NotificationManager::new());
+    let response = ctx.schema.execute(request.data(gql_ctx)).await;
+
+    assert_eq!(response.errors.len(), 1);
+    assert!(response.errors[0].message.contains("Retire it instead"));
+
+    let request = Request::new(delete)
+        .variables(Variables::from_value(value!({ "trackId": unraced.id.to_string() })))
+        .data(ctx.config.clone());
+    let gql_ctx = GraphQLContext::new(ctx.pool.clone(), Some(group.id), NotificationManager::new());
+    let response = ctx.schema.execute(request.data(gql_ctx)).await;
+
+    assert!(response.errors.is_empty(), "Expected no errors: {:?}", response.errors);
+    assert!(
+        models::Track::find_by_id(&ctx.pool, unraced.id, None)
+            .await
+            .unwrap()
+            .is_none()
+    );
+}
+
+#[tokio::test]
+async fn test_retired_track_still_resolves_for_rounds() {
+    let ctx = setup::setup_test_db().await;
+
+    let group = fixtures::create_test_group(&ctx.pool, "Test Group", "password")
+        .await
+        .expect("Failed to create test group");
+
+    let track_id: Uuid = sqlx::query_scalar("SELECT id FROM tracks WHERE name = 'Rainbow Road'")
+        .fetch_one(&ctx.pool)
+        .await
+        .expect("Failed to find built-in track");
+    models::Track::set_retired(&ctx.pool, track_id, true)
+        .await
+        .expect("Failed to retire track");
+
+    let gql_ctx = GraphQLContext::new(ctx.pool.clone(), Some(group.id), NotificationManager::new());
+    let track = gql_ctx
+        .track_loader
+        .load_one(track_id)
+        .await
+        .expect("Failed to load track")
+        .expect("Retired track should still load");
+
+    assert_eq!(track.name, "Rainbow Road");
+    assert!(track.retired);
+}
+
+#[tokio::test]
+async fn test_veto_tracks_up_to_limit() {
+    let ctx = setup::setup_test_db().await;
+
+    let group = fixtures::create_test_group(&ctx.pool, "Test Group", "password")
+        .await
+        .expect("Failed to create test group");
+    let tournament = fixtures::create_test_tournament(&ctx.pool, group.id, None, None)
+        .await
+        .expect("Failed to create test tournament");
+    let player = fixtures::create_test_player(&ctx.pool, group.id, "Vetoer")
+        .await
+        .expect("Failed to create test player");
+
+    let track_ids: Vec<Uuid> =
+        sqlx::query_scalar("SELECT id FROM tracks WHERE group_id IS NULL ORDER BY name LIMIT 4")
+            .fetch_all(&ctx.pool)
+            .await
+            .expect("Failed to find built-in tracks");
+
+    let veto = r#"
+        mutation Veto($tournamentId: ID!, $playerId: ID!, $trackId: ID!) {
+            vetoTrack(tournamentId: $tournamentId, playerId: $playerId, trackId: $trackId) {
+                player { name }
+                track { id }
+            }
+        }
+    "#;
+    let veto_track = |track_id: Uuid| {
+        let request = Request::new(veto)
+            .variables(Variables::from_value(value!({
+                "tournamentId": tournament.id.to_string(),
+                "playerId": player.id.to_string(),
+                "trackId": track_id.to_string(),
+            })))
+            .data(ctx.config.clone());
+        let gql_ctx =
+            GraphQLContext::new(ctx.pool.clone(), Some(group.id), NotificationManager::new());
+        ctx.schema.execute(request.data(gql_ctx))
+    };
+
+    for track_id in &track_ids[..3] {
+        let response = veto_track(*track_id).await;
+        assert!(response.errors.is_empty(), "Expected no errors: {:?}", response.errors);
+    }
+
+    // Repeating a veto is not counted twice
+    let response = veto_track(track_ids[0]).await;
+    assert!(response.errors.is_empty(), "Expected no errors: {:?}", response.errors);
+
+    let response = veto_track(track_ids[3]).await;
+    assert_eq!(response.errors.len(), 1);
+    assert!(response.errors[0].message.contains("at most 3 tracks"));
+
+    let query = r#"
+        query {
+            activeTournament {
+                trackVetoes { player { name } track { id } }
+            }
+        }
+    "#;
+    let request = Request::new(query).data(ctx.config.clone());
+    let gql_ctx = GraphQLContext::new(ctx.pool.clone(), Some(group.id), NotificationManager::new());
+    let response = ctx.schema.execute(request.data(gql_ctx)).await;
+
+    assert!(response.errors.is_empty(), "Expected no errors: {:?}", response.errors);
+    let data = response.data.into_json().expect("Failed to parse response");
+    let vetoes = data["activeTournament"]["trackVetoes"].as_array().unwrap();
+    assert_eq!(vetoes.len(), 3);
+    assert!(vetoes.iter().all(|veto| veto["player"]["name"] == "Vetoer"));
+
+    let remove = r#"
+        mutation Remove($tournamentId: ID!, $playerId: ID!, $trackId: ID!) {
+            removeTrackVeto(tournamentId: $tournamentId, playerId: $playerId, trackId: $trackId)
+        }
+    "#;
+    let request = Request::new(remove)
+        .variables(Variables::from_value(value!({
+            "tournamentId": tournament.id.to_string(),
+            "playerId": player.id.to_string(),
+            "trackId": track_ids[0].to_string(),
+        })))
+        .data(ctx.config.clone());
+    let gql_ctx = GraphQLContext::new(ctx.pool.clone(), Some(group.id), NotificationManager::new());
+    let response = ctx.schema.execute(request.data(gql_ctx)).await;
+    assert!(response.errors.is_empty(), "Expected no errors: {:?}", response.errors);
+
+    let response = veto_track(track_ids[3]).await;
+    assert!(response.errors.is_empty(), "Expected no errors: {:?}", response.errors);
+}
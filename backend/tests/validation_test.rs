@@ -1,3 +1,4 @@
+use mario_kart_leaderboard_backend::services::elo::EloSettings;
 use mario_kart_leaderboard_backend::services::validation::*;
 
 const MAX_NAME_LENGTH: usize = 100;
@@ -43,3 +44,93 @@ fn test_validate_password_too_short() {
 fn test_validate_password_empty() {
     assert!(validate_password("").is_err());
 }
+
+#[test]
+fn test_validate_elo_settings_defaults() {
+    assert!(validate_elo_settings(&EloSettings::default()).is_ok());
+}
+
+#[test]
+fn test_validate_elo_settings_invalid() {
+    let invalid = [
+        EloSettings {
+            k_factor: 0.0,
+            ..EloSettings::default()
+        },
+        EloSettings {
+            total_race_size: 25,
+            ..EloSettings::default()
+        },
+        EloSettings {
+            position_score_exponent: -1.0,
+            ..EloSettings::default()
+        },
+        EloSettings {
+            min_average_elo_for_cpu: 1500,
+            ..EloSettings::default()
+        },
+        EloSettings {
+            teammate_contribution_ratio: 1.5,
+            ..EloSettings::default()
+        },
+        EloSettings {
+            inactivity_weeks: 0,
+            ..EloSettings::default()
+        },
+        EloSettings {
+            inactivity_decay_per_week: -10,
+            ..EloSettings::default()
+        },
+        EloSettings {
+            repeat_teammate_penalty: -1,
+            ..EloSettings::default()
+        },
+    ];
+
+    for settings in &invalid {
+        assert!(
+            validate_elo_settings(settings).is_err(),
+            "Expected {settings:?} to be rejected"
+        );
+    }
+}
+
+#[test]
+fn test_validate_points_table_success() {
+    assert!(validate_points_table(&[15, 12, 10, 9, 8, 7, 6, 5, 4, 3, 2, 1]).is_ok());
+    assert!(validate_points_table(&[10, 10, 0]).is_ok());
+    assert!(validate_points_table(&[1; 24]).is_ok());
+}
+
+#[test]
+fn test_validate_points_table_invalid() {
+    assert!(validate_points_table(&[]).is_err());
+    assert!(validate_points_table(&[1; 25]).is_err());
+    assert!(validate_points_table(&[10, -1]).is_err());
+    assert!(validate_points_table(&[10, 12, 8]).is_err());
+}
+
+#[test]
+fn test_validate_track_name() {
+    assert!(validate_track_name("Great ? Block Ruins").is_ok());
+    assert!(validate_track_name("Wario's Galleon").is_ok());
+    assert!(validate_track_name("   ").is_err());
+    assert!(validate_track_name("Track <script>").is_err());
+}
+
+#[test]
+fn test_validate_track_tags() {
+    assert!(validate_track_tags(&["night".to_string(), "water".to_string()]).is_ok());
+    assert!(validate_track_tags(&[" ".to_string()]).is_err());
+    assert!(validate_track_tags(&vec!["tag".to_string(); 21]).is_err());
+}
+
+#[test]
+fn test_validate_track_pool() {
+    let track_id = uuid::Uuid::new_v4();
+
+    assert!(validate_track_pool(&[(track_id, 1), (uuid::Uuid::new_v4(), 100)]).is_ok());
+    assert!(validate_track_pool(&[]).is_err());
+    assert!(validate_track_pool(&[(track_id, 1), (track_id, 2)]).is_err());
+    assert!(validate_track_pool(&[(track_id, 101)]).is_err());
+}
@@ -2,7 +2,7 @@ import { graphql } from 'gql.tada'
 
 export const playersQuery = graphql(`
   query Players {
-    players {
+    players(includeGuests: true) {
       id
       name
       avatarFilename